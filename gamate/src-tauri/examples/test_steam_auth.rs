@@ -65,7 +65,7 @@ fn test_extract_steamid() {
     println!("\n📦 测试 2: 从回调 URL 提取 Steam ID\n");
 
     // 模拟回调 URL
-    let test_callback = "http://localhost:1420/auth/steam/callback?openid.ns=http://specs.openid.net/auth/2.0&openid.mode=id_res&openid.claimed_id=https://steamcommunity.com/openid/id/76561198123456789&openid.identity=https://steamcommunity.com/openid/id/76561198123456789";
+    let test_callback = "http://localhost:1420/auth/steam/callback?openid.ns=http%3A%2F%2Fspecs.openid.net%2Fauth%2F2.0&openid.mode=id_res&openid.op_endpoint=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Flogin&openid.claimed_id=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Fid%2F76561198123456789&openid.identity=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Fid%2F76561198123456789&openid.return_to=http%3A%2F%2Flocalhost%3A1420%2Fauth%2Fsteam%2Fcallback&openid.response_nonce=2025-01-01T00%3A00%3A00Zabc123&openid.assoc_handle=1234567890&openid.signed=signed%2Cop_endpoint%2Cclaimed_id%2Cidentity%2Creturn_to%2Cresponse_nonce%2Cassoc_handle&openid.sig=W0v2b3JrZWQrc2lnbmF0dXJlPQ%3D%3D";
 
     match openid::extract_steamid_from_callback(test_callback) {
         Ok(steamid) => {
@@ -79,7 +79,7 @@ fn test_extract_steamid() {
 
     println!("\n💡 实际使用时:");
     println!("   前端接收到回调 URL 后，调用 handle_steam_callback 命令");
-    println!("   Tauri 会自动提取 Steam ID 并获取用户信息");
+    println!("   Tauri 会校验签名参数并向 Steam 确认后再提取 Steam ID");
 }
 
 async fn test_get_user_info(api_key: &str) {
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use base64;
+use base64::Engine;
 use chrono::Utc;
 use futures::{SinkExt, StreamExt};
 use hmac::{Hmac, Mac};
@@ -9,11 +9,14 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sha1::Sha1;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 use url::Url;
@@ -21,6 +24,21 @@ use uuid::Uuid;
 
 type HmacSha1 = Hmac<Sha1>;
 
+/// 阿里云 TTS 默认发音人
+const DEFAULT_TTS_VOICE: &str = "xiaoyun";
+
+/// 阿里云 TTS 默认采样率
+const DEFAULT_TTS_SAMPLE_RATE: u32 = 16000;
+
+/// 单次合成请求的最大字符数 (超出时按句子拆分)
+const MAX_TTS_CHARS: usize = 300;
+
+/// 等待单次合成结果的超时时间
+const TTS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 实时转写发送 StopTranscription 后等待最终结果的超时时间
+const TRANSCRIPTION_FINAL_TIMEOUT: Duration = Duration::from_secs(10);
+
 const ENCODE_SET: &percent_encoding::AsciiSet = &NON_ALPHANUMERIC
     .remove(b'-')
     .remove(b'_')
@@ -64,6 +82,21 @@ fn token_cache() -> &'static Mutex<Option<CachedToken>> {
     TOKEN_CACHE.get_or_init(|| Mutex::new(None))
 }
 
+/// 进行中的实时转写会话
+struct TranscriptionSession {
+    /// 音频块发送通道 (关闭后结束转写)
+    chunk_tx: mpsc::UnboundedSender<Vec<u8>>,
+    /// 转写任务 (返回完整文本)
+    task: JoinHandle<Result<String, String>>,
+}
+
+static TRANSCRIPTION_SESSIONS: OnceCell<Mutex<HashMap<String, TranscriptionSession>>> =
+    OnceCell::new();
+
+fn transcription_sessions() -> &'static Mutex<HashMap<String, TranscriptionSession>> {
+    TRANSCRIPTION_SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 // 用于去重的请求缓存 (存储音频数据的哈希)
 static RECOGNIZE_REQUEST_CACHE: OnceCell<Arc<Mutex<HashSet<String>>>> = OnceCell::new();
 
@@ -86,12 +119,12 @@ fn percent_encode_str(s: &str) -> String {
 }
 
 /// 将PCM数据保存为WAV文件
-fn save_pcm_as_wav(pcm_data: &[u8], filename: &str, sample_rate: u32) -> std::io::Result<()> {
-    use std::fs::File;
-    use std::io::Write;
-
-    let mut file = File::create(filename)?;
+fn save_pcm_as_wav(pcm_data: &[u8], filename: &Path, sample_rate: u32) -> std::io::Result<()> {
+    std::fs::write(filename, pcm_to_wav(pcm_data, sample_rate))
+}
 
+/// 为 16-bit 单声道 PCM 数据加上 WAV 文件头
+fn pcm_to_wav(pcm_data: &[u8], sample_rate: u32) -> Vec<u8> {
     // WAV文件头
     let bits_per_sample = 16u16;
     let channels = 1u16;
@@ -99,27 +132,29 @@ fn save_pcm_as_wav(pcm_data: &[u8], filename: &str, sample_rate: u32) -> std::io
     let block_align = channels * bits_per_sample / 8;
     let data_size = pcm_data.len() as u32;
 
+    let mut wav = Vec::with_capacity(44 + pcm_data.len());
+
     // RIFF header
-    file.write_all(b"RIFF")?;
-    file.write_all(&(36 + data_size).to_le_bytes())?;
-    file.write_all(b"WAVE")?;
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
 
     // fmt chunk
-    file.write_all(b"fmt ")?;
-    file.write_all(&16u32.to_le_bytes())?; // chunk size
-    file.write_all(&1u16.to_le_bytes())?; // audio format (PCM)
-    file.write_all(&channels.to_le_bytes())?;
-    file.write_all(&sample_rate.to_le_bytes())?;
-    file.write_all(&byte_rate.to_le_bytes())?;
-    file.write_all(&block_align.to_le_bytes())?;
-    file.write_all(&bits_per_sample.to_le_bytes())?;
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // audio format (PCM)
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&bits_per_sample.to_le_bytes());
 
     // data chunk
-    file.write_all(b"data")?;
-    file.write_all(&data_size.to_le_bytes())?;
-    file.write_all(pcm_data)?;
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    wav.extend_from_slice(pcm_data);
 
-    Ok(())
+    wav
 }
 
 /// 根据阿里云 OpenAPI 签名机制，构造 CreateToken 请求并返回 Token
@@ -356,34 +391,29 @@ pub async fn aliyun_one_sentence_recognize(
         log::warn!("⚠️ 音频数据较小: {} 字节", pcm_data.len());
     }
 
-    // // 保存为WAV文件用于调试 - 使用时间戳避免覆盖
-    // let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-
-    // // 音频应该已经被重采样到 16kHz，所以这里使用 16000
-    // let actual_sample_rate = 16000u32;
-
-    // // 尝试多个可能的下载目录
-    // let possible_paths = vec![
-    //     std::env::var("USERPROFILE").ok().map(|p| format!("{}\\Downloads\\debug_audio_{}.wav", p, timestamp)),
-    //     Some(format!("debug_audio_{}.wav", timestamp)), // 当前目录作为后备
-    // ];
-
-    // let mut saved = false;
-    // for path_option in possible_paths {
-    //     if let Some(debug_filename) = path_option {
-    //         if let Ok(_) = save_pcm_as_wav(&pcm_data, &debug_filename, actual_sample_rate) {
-    //             log::info!("💾 已保存调试音频: {}", debug_filename);
-    //             log::info!("📊 音频信息: {} 字节, {}Hz, 16-bit PCM, 单声道", pcm_data.len(), actual_sample_rate);
-    //             log::info!("⏱️ 音频时长: 约 {:.2} 秒", pcm_data.len() as f32 / (actual_sample_rate as f32 * 2.0));
-    //             saved = true;
-    //             break;
-    //         }
-    //     }
-    // }
-
-    // if !saved {
-    //     log::warn!("⚠️ 无法保存调试音频文件到任何位置");
-    // }
+    // 保存为WAV文件用于调试 (需在设置中开启 save_debug_audio)
+    if let Some(dir) = crate::settings::AppSettings::load()
+        .ok()
+        .and_then(|settings| settings.general.debug_audio_output_dir())
+    {
+        // 使用时间戳避免覆盖
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let debug_filename = dir.join(format!("debug_audio_{}.wav", timestamp));
+
+        // 音频应该已经被重采样到 16kHz，所以这里使用 16000
+        let actual_sample_rate = 16000u32;
+
+        match save_pcm_as_wav(&pcm_data, &debug_filename, actual_sample_rate) {
+            Ok(()) => {
+                log::info!("💾 已保存调试音频: {}", debug_filename.display());
+                log::info!(
+                    "⏱️ 音频时长: 约 {:.2} 秒",
+                    pcm_data.len() as f32 / (actual_sample_rate as f32 * 2.0)
+                );
+            }
+            Err(e) => log::warn!("⚠️ 保存调试音频失败: {}", e),
+        }
+    }
 
     let region = region.unwrap_or_else(|| "cn-shanghai".to_string());
 
@@ -565,6 +595,8 @@ pub async fn aliyun_one_sentence_recognize(
                                         payload.get("result").and_then(|r| r.as_str())
                                     {
                                         log::info!("   中间结果: {}", result);
+                                        // 实时字幕: 只发送识别文本
+                                        let _ = app.emit("asr_partial", result.to_string());
                                     }
                                 }
                             }
@@ -623,8 +655,523 @@ pub async fn aliyun_one_sentence_recognize(
     }
 }
 
-/// TTS 合成占位
+/// 开始实时转写会话 (适用于超过一句话识别时长限制的长语音)
+///
+/// 返回会话 ID; 之后通过 `aliyun_realtime_transcribe_push` 发送 16kHz PCM 音频块,
+/// 最后调用 `aliyun_realtime_transcribe_stop` 获取完整文本
+#[tauri::command]
+pub async fn aliyun_realtime_transcribe_start(
+    app: AppHandle,
+    appkey: String,
+    access_key: String,
+    access_secret: String,
+    region: Option<String>,
+) -> Result<String, String> {
+    let session_id = Uuid::new_v4().simple().to_string();
+    let (chunk_tx, chunk_rx) = mpsc::unbounded_channel();
+
+    let task = tokio::spawn(async move {
+        aliyun_realtime_transcribe(&app, appkey, access_key, access_secret, region, chunk_rx).await
+    });
+
+    transcription_sessions()
+        .lock()
+        .unwrap()
+        .insert(session_id.clone(), TranscriptionSession { chunk_tx, task });
+
+    log::info!("🎙️ 开始实时转写会话: {}", session_id);
+    Ok(session_id)
+}
+
+/// 向实时转写会话发送一块 PCM 音频
 #[tauri::command]
-pub async fn aliyun_tts_synthesize(_text: String) -> Result<String, String> {
-    Ok("(aliyun tts) base64-audio-placeholder".to_string())
+pub async fn aliyun_realtime_transcribe_push(
+    session_id: String,
+    pcm_data: Vec<u8>,
+) -> Result<(), String> {
+    let sessions = transcription_sessions().lock().unwrap();
+    let session = sessions
+        .get(&session_id)
+        .ok_or_else(|| format!("实时转写会话不存在: {}", session_id))?;
+
+    session
+        .chunk_tx
+        .send(pcm_data)
+        .map_err(|_| "实时转写已结束".to_string())
+}
+
+/// 结束实时转写会话, 返回完整的转写文本
+#[tauri::command]
+pub async fn aliyun_realtime_transcribe_stop(session_id: String) -> Result<String, String> {
+    let session = transcription_sessions()
+        .lock()
+        .unwrap()
+        .remove(&session_id)
+        .ok_or_else(|| format!("实时转写会话不存在: {}", session_id))?;
+
+    // 关闭音频通道, 转写任务随后发送 StopTranscription
+    drop(session.chunk_tx);
+
+    session
+        .task
+        .await
+        .map_err(|e| format!("实时转写任务异常: {}", e))?
+}
+
+/// 实时转写 (SpeechTranscriber): 从通道读取 PCM 音频块直到通道关闭,
+/// 将每句话的最终结果 (SentenceEnd) 拼接为完整文本
+///
+/// 中间结果通过 `asr_partial` 事件发送给前端
+pub async fn aliyun_realtime_transcribe(
+    app: &AppHandle,
+    appkey: String,
+    access_key: String,
+    access_secret: String,
+    region: Option<String>,
+    mut chunks: mpsc::UnboundedReceiver<Vec<u8>>,
+) -> Result<String, String> {
+    let region = region.unwrap_or_else(|| "cn-shanghai".to_string());
+
+    let token = aliyun_get_cached_token(access_key, access_secret, Some(region.clone()))
+        .await?
+        .token;
+
+    let ws_url = format!(
+        "wss://nls-gateway-{}.aliyuncs.com/ws/v1?token={}",
+        region, token
+    );
+
+    log::info!("🔌 正在连接 WebSocket (实时转写)...");
+    let (ws_stream, _resp) = connect_async(&ws_url)
+        .await
+        .map_err(|e| format!("WS连接失败: {}", e))?;
+
+    let (mut write, mut read) = ws_stream.split();
+
+    let task_id = Uuid::new_v4().simple().to_string();
+    log::info!("📋 任务 ID: {}", task_id);
+
+    // 1. 发送 StartTranscription
+    let start_msg = json!({
+        "header": {
+            "message_id": Uuid::new_v4().simple().to_string(),
+            "task_id": task_id.clone(),
+            "namespace": "SpeechTranscriber",
+            "name": "StartTranscription",
+            "appkey": appkey.clone()
+        },
+        "payload": {
+            "format": "pcm",
+            "sample_rate": 16000,
+            "enable_intermediate_result": true,
+            "enable_punctuation_prediction": true,
+            "enable_inverse_text_normalization": true
+        }
+    });
+
+    let start_text = serde_json::to_string(&start_msg).map_err(|e| e.to_string())?;
+    write
+        .send(Message::Text(start_text))
+        .await
+        .map_err(|e| format!("发送失败: {}", e))?;
+
+    // 2. 等待 TranscriptionStarted 确认
+    loop {
+        match read.next().await {
+            Some(Ok(Message::Text(txt))) => {
+                let (name, _) = check_nls_header(&txt)?;
+                if name == "TranscriptionStarted" {
+                    log::info!("✅ 实时转写已启动");
+                    break;
+                }
+            }
+            Some(Ok(Message::Close(_))) | None => {
+                return Err("服务器在启动转写前关闭连接".to_string());
+            }
+            Some(Err(e)) => return Err(format!("等待确认时出错: {}", e)),
+            _ => {}
+        }
+    }
+
+    // 3. 发送音频 (直到通道关闭) 的同时接收转写结果
+    let (stopped_tx, stopped_rx) = oneshot::channel::<()>();
+
+    let send_task = async move {
+        let mut total = 0usize;
+        while let Some(chunk) = chunks.recv().await {
+            total += chunk.len();
+            write
+                .send(Message::Binary(chunk))
+                .await
+                .map_err(|e| format!("发送音频块失败: {}", e))?;
+        }
+        log::info!("✅ 音频发送完成，共发送 {} 字节", total);
+
+        let stop_msg = json!({
+            "header": {
+                "message_id": Uuid::new_v4().simple().to_string(),
+                "task_id": task_id,
+                "namespace": "SpeechTranscriber",
+                "name": "StopTranscription",
+                "appkey": appkey
+            },
+            "payload": {}
+        });
+        let stop_text = serde_json::to_string(&stop_msg).map_err(|e| e.to_string())?;
+        write
+            .send(Message::Text(stop_text))
+            .await
+            .map_err(|e| format!("发送失败: {}", e))?;
+        let _ = stopped_tx.send(());
+
+        Ok::<_, String>(write)
+    };
+
+    let receive_task = async {
+        let mut sentences: Vec<String> = Vec::new();
+
+        // 发送 StopTranscription (或发送失败) 后最多再等待一段时间
+        let deadline = async {
+            let _ = stopped_rx.await;
+            tokio::time::sleep(TRANSCRIPTION_FINAL_TIMEOUT).await;
+        };
+        tokio::pin!(deadline);
+
+        loop {
+            let msg = tokio::select! {
+                msg = read.next() => msg,
+                _ = &mut deadline => {
+                    log::warn!("⏱️ 等待转写结果超时");
+                    break;
+                }
+            };
+
+            match msg {
+                Some(Ok(Message::Text(txt))) => {
+                    let (name, payload) = check_nls_header(&txt)?;
+                    let result = payload
+                        .as_ref()
+                        .and_then(|p| p.get("result"))
+                        .and_then(|r| r.as_str())
+                        .unwrap_or("");
+
+                    match name.as_str() {
+                        "SentenceBegin" => log::debug!("🗣️ 句子开始"),
+                        "TranscriptionResultChanged" => {
+                            let _ = app.emit("asr_partial", result.to_string());
+                        }
+                        "SentenceEnd" => {
+                            log::info!("📝 句子结果: {}", result);
+                            if !result.is_empty() {
+                                sentences.push(result.to_string());
+                            }
+                        }
+                        "TranscriptionCompleted" => {
+                            log::info!("✅ 实时转写完成");
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    log::info!("🔌 WebSocket 连接关闭");
+                    break;
+                }
+                Some(Err(e)) => return Err(format!("接收消息错误: {}", e)),
+                _ => {}
+            }
+        }
+
+        Ok::<_, String>(sentences.concat())
+    };
+
+    let (send_result, receive_result) = tokio::join!(send_task, receive_task);
+    if let Ok(mut write) = send_result {
+        let _ = write.close().await;
+    }
+
+    let transcript = receive_result?;
+    log::info!("🎉 实时转写结果: {}", transcript);
+    Ok(transcript)
+}
+
+/// 解析 NLS 消息头, 返回 (消息名称, payload); 服务端返回错误状态时返回 Err
+fn check_nls_header(txt: &str) -> Result<(String, Option<serde_json::Value>), String> {
+    let mut v: serde_json::Value =
+        serde_json::from_str(txt).map_err(|e| format!("无法解析服务端消息: {}", e))?;
+
+    let header = v.get("header").cloned().unwrap_or_default();
+    let name = header
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or("")
+        .to_string();
+    let status = header.get("status").and_then(|s| s.as_i64()).unwrap_or(0);
+
+    if name == "TaskFailed" || (status != 20000000 && status != 0) {
+        let status_text = header
+            .get("status_text")
+            .and_then(|s| s.as_str())
+            .unwrap_or("未知错误");
+        return Err(format!("服务端错误: {} - {}", status, status_text));
+    }
+
+    Ok((name, v.get_mut("payload").map(serde_json::Value::take)))
+}
+
+/// TTS 合成, 返回 base64 编码的 WAV 音频
+///
+/// - `voice`: 发音人, 不传时使用 TTS 设置中的音色 (默认 xiaoyun)
+/// - `sample_rate`: 采样率 (8000 / 16000 / 24000), 默认 16000
+///
+/// 前端播放返回的音频时需用 `begin_tts_playback` / `end_tts_playback` 包住, 避免持续监听识别到播报声音
+#[tauri::command]
+pub async fn aliyun_tts_synthesize(
+    text: String,
+    voice: Option<String>,
+    sample_rate: Option<u32>,
+) -> Result<String, String> {
+    let wav = aliyun_synthesize_wav(&text, voice.as_deref(), sample_rate).await?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(wav))
+}
+
+/// 使用 TTS 设置中的阿里云凭据合成语音, 返回 WAV 数据
+///
+/// 文本超过单次请求上限时按句子拆分, 逐段合成后拼接为一个音频
+pub async fn aliyun_synthesize_wav(
+    text: &str,
+    voice: Option<&str>,
+    sample_rate: Option<u32>,
+) -> Result<Vec<u8>, String> {
+    let settings = crate::settings::AppSettings::load()
+        .map_err(|e| format!("加载设置失败: {}", e))?
+        .tts;
+
+    let access_key = settings
+        .aliyun_access_key
+        .filter(|k| !k.is_empty())
+        .ok_or("未配置阿里云 Access Key")?;
+    let access_secret = settings
+        .aliyun_access_secret
+        .filter(|k| !k.is_empty())
+        .ok_or("未配置阿里云 Access Secret")?;
+    let appkey = settings
+        .aliyun_appkey
+        .filter(|k| !k.is_empty())
+        .ok_or("未配置阿里云 AppKey")?;
+
+    // 设置中的音色只有在使用阿里云时才是阿里云发音人
+    let voice = voice
+        .map(str::to_string)
+        .or_else(|| settings.voice.filter(|_| settings.provider == "aliyun"))
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_TTS_VOICE.to_string());
+    let sample_rate = sample_rate.unwrap_or(DEFAULT_TTS_SAMPLE_RATE);
+
+    let region = "cn-shanghai".to_string();
+    let token = aliyun_get_cached_token(access_key, access_secret, Some(region.clone()))
+        .await?
+        .token;
+
+    let mut pcm_data = Vec::new();
+    for segment in split_tts_text(text, MAX_TTS_CHARS) {
+        let params = json!({
+            "text": segment,
+            "voice": voice,
+            "format": "pcm",
+            "sample_rate": sample_rate,
+            "volume": (settings.volume.clamp(0.0, 1.0) * 100.0).round() as i32,
+            "speech_rate": to_speech_rate(settings.rate),
+            "pitch_rate": 0
+        });
+        let segment_pcm = synthesize_segment(&region, &token, &appkey, params).await?;
+        pcm_data.extend_from_slice(&segment_pcm);
+    }
+
+    if pcm_data.is_empty() {
+        return Err("未收到合成音频".to_string());
+    }
+
+    log::info!(
+        "✅ 阿里云 TTS 合成完成: {} 字节, 约 {:.1} 秒",
+        pcm_data.len(),
+        pcm_data.len() as f32 / (sample_rate as f32 * 2.0)
+    );
+
+    Ok(pcm_to_wav(&pcm_data, sample_rate))
+}
+
+/// 通过 WebSocket 合成一段文本, 返回拼接后的 PCM 数据
+async fn synthesize_segment(
+    region: &str,
+    token: &str,
+    appkey: &str,
+    params: serde_json::Value,
+) -> Result<Vec<u8>, String> {
+    let ws_url = format!(
+        "wss://nls-gateway-{}.aliyuncs.com/ws/v1?token={}",
+        region, token
+    );
+
+    let (ws_stream, _resp) = connect_async(&ws_url)
+        .await
+        .map_err(|e| format!("WS连接失败: {}", e))?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let task_id = Uuid::new_v4().simple().to_string();
+    log::info!("🔊 阿里云 TTS 任务: {}", task_id);
+
+    // 1. 发送 StartSynthesis
+    let start_msg = json!({
+        "header": {
+            "message_id": Uuid::new_v4().simple().to_string(),
+            "task_id": task_id,
+            "namespace": "SpeechSynthesizer",
+            "name": "StartSynthesis",
+            "appkey": appkey
+        },
+        "payload": params
+    });
+
+    let start_text = serde_json::to_string(&start_msg).map_err(|e| e.to_string())?;
+    write
+        .send(Message::Text(start_text))
+        .await
+        .map_err(|e| format!("发送失败: {}", e))?;
+
+    // 2. 接收音频帧 (二进制), 直到 SynthesisCompleted
+    let receive_task = async {
+        let mut pcm_data = Vec::new();
+        while let Some(msg) = read.next().await {
+            match msg {
+                Ok(Message::Binary(data)) => pcm_data.extend_from_slice(&data),
+                Ok(Message::Text(txt)) => {
+                    let v: serde_json::Value = match serde_json::from_str(&txt) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    let Some(header) = v.get("header") else {
+                        continue;
+                    };
+                    let name = header.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                    let status = header.get("status").and_then(|s| s.as_i64()).unwrap_or(0);
+
+                    if name == "TaskFailed" || (status != 20000000 && status != 0) {
+                        let status_text = header
+                            .get("status_text")
+                            .and_then(|s| s.as_str())
+                            .unwrap_or("未知错误");
+                        return Err(format!("语音合成失败: {} - {}", status, status_text));
+                    }
+
+                    if name == "SynthesisCompleted" {
+                        break;
+                    }
+                }
+                Ok(Message::Close(_)) => break,
+                Err(e) => return Err(format!("接收音频失败: {}", e)),
+                _ => {}
+            }
+        }
+        Ok(pcm_data)
+    };
+
+    let result = tokio::time::timeout(TTS_TIMEOUT, receive_task)
+        .await
+        .map_err(|_| "语音合成超时".to_string())?;
+
+    let _ = write.close().await;
+
+    result
+}
+
+/// 将语速倍数 (1.0 为正常, 0.5 ~ 2.0) 换算为阿里云 speech_rate (-500 ~ 500)
+fn to_speech_rate(rate: f32) -> i32 {
+    let rate = rate.clamp(0.5, 2.0);
+    let value = if rate >= 1.0 {
+        (1.0 - 1.0 / rate) / 0.001
+    } else {
+        (1.0 - 1.0 / rate) / 0.002
+    };
+    (value.round() as i32).clamp(-500, 500)
+}
+
+/// 按句子拆分文本, 每段不超过 `max_chars` 个字符 (单句过长时强制截断)
+fn split_tts_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut sentence = String::new();
+
+    let mut push_sentence = |sentence: &mut String, current: &mut String| {
+        if current.chars().count() + sentence.chars().count() > max_chars && !current.is_empty() {
+            segments.push(std::mem::take(current));
+        }
+        current.push_str(sentence);
+        sentence.clear();
+    };
+
+    for c in text.trim().chars() {
+        sentence.push(c);
+        if "。！？!?；;\n".contains(c) || sentence.chars().count() >= max_chars {
+            push_sentence(&mut sentence, &mut current);
+        }
+    }
+    push_sentence(&mut sentence, &mut current);
+
+    if !current.trim().is_empty() {
+        segments.push(current);
+    }
+    segments.retain(|s| !s.trim().is_empty());
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_rate() {
+        assert_eq!(to_speech_rate(1.0), 0);
+        assert_eq!(to_speech_rate(2.0), 500);
+        assert_eq!(to_speech_rate(0.5), -500);
+        assert_eq!(to_speech_rate(5.0), 500);
+    }
+
+    #[test]
+    fn test_split_tts_text() {
+        assert_eq!(split_tts_text("你好。", 300), vec!["你好。"]);
+        assert!(split_tts_text("  ", 300).is_empty());
+
+        let segments = split_tts_text("第一句。第二句！第三句", 8);
+        assert_eq!(segments, vec!["第一句。第二句！", "第三句"]);
+
+        let long = "长".repeat(20);
+        let segments = split_tts_text(&long, 8);
+        assert!(segments.iter().all(|s| s.chars().count() <= 8));
+        assert_eq!(segments.concat(), long);
+    }
+
+    #[test]
+    fn test_check_nls_header() {
+        let (name, payload) = check_nls_header(
+            r#"{"header":{"name":"SentenceEnd","status":20000000},"payload":{"result":"你好"}}"#,
+        )
+        .unwrap();
+        assert_eq!(name, "SentenceEnd");
+        assert_eq!(payload.unwrap()["result"], "你好");
+
+        let err = check_nls_header(
+            r#"{"header":{"name":"TaskFailed","status":40000001,"status_text":"Gateway:ACCESS_DENIED"}}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("ACCESS_DENIED"));
+    }
+
+    #[test]
+    fn test_pcm_to_wav() {
+        let wav = pcm_to_wav(&[0u8; 3200], 16000);
+        assert_eq!(wav.len(), 44 + 3200);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+    }
 }
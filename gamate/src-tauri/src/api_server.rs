@@ -0,0 +1,399 @@
+/// 本地 HTTP API
+///
+/// 在 `127.0.0.1:{port}` 提供 JSON 接口, 供 Stream Deck、命令行脚本等外部工具调用后端功能,
+/// 与 Tauri 命令共用同一套实现. 除 `/api/health` 外所有请求都需要携带 `Authorization: Bearer <token>`:
+/// - `GET  /api/health`: 服务状态
+/// - `GET  /api/games`: 游戏列表及知识库状态
+/// - `POST /api/search`: 搜索 Wiki 知识 `{ "gameId", "query", "topK"?, "allowSpoilers"? }`
+/// - `POST /api/ask`: 基于知识库回答问题 `{ "gameId", "message", "screenshot"? }`
+/// - `POST /api/crawl`: 按 games.toml 配置重新爬取技能库 `{ "gameId" }` (爬取完成后才返回)
+use crate::commands::ai_commands::generate_ai_response_impl;
+use crate::commands::vector_commands::{get_games_with_status_impl, search_wiki_impl};
+use crate::commands::wiki_commands::download_skill_library;
+use crate::config::Config;
+use crate::settings::AppSettings;
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// 请求头大小上限
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// 请求体大小上限 (截图以 base64 传入)
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// API 服务
+pub struct ApiServer {
+    port: u16,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl ApiServer {
+    /// 启动服务
+    pub async fn start(app: AppHandle, port: u16, token: String) -> Result<Self> {
+        if token.is_empty() {
+            bail!("访问令牌为空");
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| format!("无法监听端口 {}", port))?;
+
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            let app = app.clone();
+                            let token = token.clone();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = handle_connection(&app, stream, &token).await {
+                                    log::debug!("API 连接结束: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => log::warn!("⚠️ API 服务接受连接失败: {}", e),
+                    },
+                }
+            }
+            log::info!("⏹️ API 服务已停止");
+        });
+
+        log::info!("🔌 API 服务已启动: http://127.0.0.1:{}/api", port);
+
+        Ok(Self {
+            port,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// 停止服务
+    pub fn stop(mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+/// HTTP 请求
+struct Request {
+    method: String,
+    path: String,
+    /// 请求头 (名称为小写)
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 解析 JSON 请求体
+    fn json<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| ApiError::new(400, format!("无效的请求体: {}", e)))
+    }
+}
+
+/// 接口错误 (HTTP 状态码 + 错误信息)
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl ToString) -> Self {
+        Self::new(500, message.to_string())
+    }
+}
+
+type ApiResult = Result<Value, ApiError>;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchParams {
+    game_id: String,
+    query: String,
+    top_k: Option<usize>,
+    #[serde(default)]
+    allow_spoilers: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AskParams {
+    game_id: String,
+    message: String,
+    screenshot: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CrawlParams {
+    game_id: String,
+}
+
+/// 处理单个连接 (每个连接只处理一个请求)
+async fn handle_connection(app: &AppHandle, mut stream: TcpStream, token: &str) -> Result<()> {
+    let (status, body) = match read_request(&mut stream).await {
+        Ok(request) => handle_request(app, token, &request).await,
+        Err(e) => error_body(ApiError::new(400, e.to_string())),
+    };
+
+    write_response(&mut stream, status, body).await
+}
+
+/// 路由请求, 返回 (状态码, 响应体)
+async fn handle_request(app: &AppHandle, token: &str, request: &Request) -> (u16, Option<Value>) {
+    // CORS 预检 (浏览器环境中的插件)
+    if request.method == "OPTIONS" {
+        return (204, None);
+    }
+
+    if request.method == "GET" && request.path == "/api/health" {
+        return (
+            200,
+            Some(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") })),
+        );
+    }
+
+    if !is_authorized(request, token) {
+        return error_body(ApiError::new(401, "缺少或错误的访问令牌"));
+    }
+
+    log::info!("🔌 API 请求: {} {}", request.method, request.path);
+
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/api/games") => list_games(app).await,
+        ("POST", "/api/search") => search(request).await,
+        ("POST", "/api/ask") => ask(request).await,
+        ("POST", "/api/crawl") => crawl(app, request).await,
+        (method, path) => Err(ApiError::new(404, format!("未知接口: {} {}", method, path))),
+    };
+
+    match result {
+        Ok(body) => (200, Some(body)),
+        Err(e) => {
+            log::warn!(
+                "⚠️ API 请求失败: {} {} - {}",
+                request.method,
+                request.path,
+                e.message
+            );
+            error_body(e)
+        }
+    }
+}
+
+async fn list_games(app: &AppHandle) -> ApiResult {
+    let config = app.state::<Config>();
+    let games = get_games_with_status_impl(config.inner())
+        .await
+        .map_err(ApiError::internal)?;
+
+    to_value(games)
+}
+
+async fn search(request: &Request) -> ApiResult {
+    let params: SearchParams = request.json()?;
+    let results = search_wiki_impl(
+        params.query,
+        params.game_id,
+        params.top_k,
+        params.allow_spoilers,
+    )
+    .await
+    .map_err(ApiError::internal)?;
+
+    to_value(results)
+}
+
+async fn ask(request: &Request) -> ApiResult {
+    let params: AskParams = request.json()?;
+    let response = generate_ai_response_impl(params.message, params.game_id, params.screenshot)
+        .await
+        .map_err(ApiError::internal)?;
+
+    to_value(response)
+}
+
+async fn crawl(app: &AppHandle, request: &Request) -> ApiResult {
+    let params: CrawlParams = request.json()?;
+
+    let skill = app
+        .state::<Config>()
+        .find_game(&params.game_id)
+        .and_then(|game| game.skill_configs.first())
+        .cloned()
+        .ok_or_else(|| ApiError::new(404, format!("未找到游戏 {} 的技能库配置", params.game_id)))?;
+
+    let settings = AppSettings::load().map_err(ApiError::internal)?;
+    let base_path = PathBuf::from(&settings.skill_library.storage_base_path);
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let result = download_skill_library(&params.game_id, &skill, &base_path, timestamp)
+        .await
+        .map_err(ApiError::internal)?;
+
+    to_value(result)
+}
+
+fn to_value<T: Serialize>(value: T) -> ApiResult {
+    serde_json::to_value(value).map_err(ApiError::internal)
+}
+
+fn error_body(error: ApiError) -> (u16, Option<Value>) {
+    (error.status, Some(json!({ "error": error.message })))
+}
+
+fn is_authorized(request: &Request, token: &str) -> bool {
+    !token.is_empty()
+        && request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|value| value.trim() == token)
+}
+
+/// 读取请求头和请求体 (按 Content-Length)
+async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = find_header_end(&buf) {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            bail!("请求头过大");
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("连接已关闭");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let mut request = parse_head(&buf[..header_end])?;
+
+    let content_length = match request.header("content-length") {
+        Some(value) => value.parse::<usize>().context("无效的 Content-Length")?,
+        None => 0,
+    };
+    if content_length > MAX_BODY_BYTES {
+        bail!("请求体过大");
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            bail!("请求体不完整");
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+    request.body = body;
+
+    Ok(request)
+}
+
+fn find_header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// 解析请求行和请求头
+fn parse_head(head: &[u8]) -> Result<Request> {
+    let head = std::str::from_utf8(head).context("请求头不是有效的 UTF-8")?;
+    let mut lines = head.split("\r\n");
+
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        bail!("无效的请求行");
+    };
+    let path = target.split('?').next().unwrap_or(target);
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(Request {
+        method: method.to_ascii_uppercase(),
+        path: path.to_string(),
+        headers,
+        body: Vec::new(),
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: Option<Value>) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_head() {
+        let raw = b"POST /api/search?x=1 HTTP/1.1\r\nHost: 127.0.0.1\r\nAuthorization: Bearer secret\r\nContent-Length: 2\r\n\r\n{}";
+        let header_end = find_header_end(raw).unwrap();
+        let request = parse_head(&raw[..header_end]).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/search");
+        assert_eq!(request.header("content-length"), Some("2"));
+        assert_eq!(&raw[header_end + 4..], b"{}");
+
+        assert!(is_authorized(&request, "secret"));
+        assert!(!is_authorized(&request, "other"));
+        assert!(!is_authorized(&request, ""));
+
+        assert!(parse_head(b"garbage").is_err());
+    }
+}
@@ -3,12 +3,16 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::runtime::Handle;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use super::recorder::{AudioRecorder, RecorderConfig};
+use super::speech_activity;
 use super::vad::{VadConfig, VadState, VoiceActivityDetector};
 use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
@@ -17,6 +21,51 @@ use rubato::{
 #[cfg(windows)]
 use super::stt_windows::WindowsSttEngine;
 
+#[cfg(feature = "whisper")]
+use super::stt_whisper::WhisperSttEngine;
+
+/// 语音识别后端
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SttBackend {
+    /// Windows 系统语音识别 (仅 Windows)
+    Windows,
+    /// 本地 whisper.cpp (需要 whisper 编译特性)
+    Whisper { model_path: Option<String> },
+    /// 阿里云一句话识别 (通过 AliyunRecognizeRequest 事件交给前端命令处理)
+    Aliyun,
+}
+
+impl SttBackend {
+    /// 按名称解析后端, 未知名称时使用阿里云
+    pub fn parse(name: &str, whisper_model_path: Option<String>) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "windows" => Self::Windows,
+            "whisper" => Self::Whisper {
+                model_path: whisper_model_path,
+            },
+            "aliyun" => Self::Aliyun,
+            other => {
+                log::warn!("⚠️ 未知的语音识别后端: {}, 使用阿里云", other);
+                Self::Aliyun
+            }
+        }
+    }
+
+    /// 使用设置中选择的语音识别后端
+    pub fn from_settings() -> Self {
+        match crate::settings::AppSettings::load() {
+            Ok(settings) => Self::parse(
+                &settings.general.stt_backend,
+                settings.general.whisper_model_path,
+            ),
+            Err(_) => Self::Aliyun,
+        }
+    }
+}
+
+#[cfg(feature = "whisper")]
+static WHISPER_ENGINE: Mutex<Option<WhisperSttEngine>> = Mutex::new(None);
+
 /// 监听器状态信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListenerState {
@@ -24,6 +73,9 @@ pub struct ListenerState {
     pub vad_state: VadState,
     /// 是否正在监听
     pub is_listening: bool,
+    /// 是否已暂停 (录音流保持运行, 音频被丢弃)
+    #[serde(default)]
+    pub paused: bool,
     /// 当前录音时长(秒)
     pub recording_duration: f32,
     /// 音频缓冲区大小(采样点数)
@@ -60,6 +112,8 @@ pub struct ContinuousListener {
     vad_config: VadConfig,
     /// 录音器配置
     recorder_config: RecorderConfig,
+    /// 语音识别后端
+    stt_backend: SttBackend,
 
     /// 共享状态
     state: Arc<Mutex<ListenerStateInternal>>,
@@ -70,6 +124,9 @@ pub struct ContinuousListener {
     /// 事件发送器
     event_tx: Option<mpsc::UnboundedSender<ListenerEvent>>,
 
+    /// 强制停止标记 (不经过状态锁, 监听线程卡住时也能生效)
+    cancelled: Arc<AtomicBool>,
+
     /// 实际的设备采样率（在 start_listening 时设置）
     actual_sample_rate: Option<u32>,
 }
@@ -78,6 +135,7 @@ pub struct ContinuousListener {
 struct ListenerStateInternal {
     vad: VoiceActivityDetector,
     is_listening: bool,
+    paused: bool,
     last_transcription: Option<String>,
 }
 
@@ -89,19 +147,38 @@ impl ContinuousListener {
         let state = Arc::new(Mutex::new(ListenerStateInternal {
             vad,
             is_listening: false,
+            paused: false,
             last_transcription: None,
         }));
 
         Self {
             vad_config,
             recorder_config,
+            stt_backend: SttBackend::Aliyun,
             state,
             listen_task: None,
             event_tx: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
             actual_sample_rate: None,
         }
     }
 
+    /// 设置回声屏蔽标记: 标记为 true (TTS 播放中) 时 VAD 忽略音频
+    pub fn with_echo_gate(self, echo_gate: Arc<AtomicBool>) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .vad
+            .set_echo_gate(Some(echo_gate));
+        self
+    }
+
+    /// 设置语音识别后端 (默认阿里云)
+    pub fn with_stt_backend(mut self, stt_backend: SttBackend) -> Self {
+        self.stt_backend = stt_backend;
+        self
+    }
+
     /// 开始持续监听
     pub fn start_listening(
         &mut self,
@@ -127,16 +204,22 @@ impl ContinuousListener {
         {
             let mut state = self.state.lock().unwrap();
             state.is_listening = true;
+            state.paused = false;
             state.vad.reset();
         }
+        self.cancelled = Arc::new(AtomicBool::new(false));
 
         // 启动监听循环
         let state = Arc::clone(&self.state);
+        let cancelled = Arc::clone(&self.cancelled);
         let recorder_config = self.recorder_config.clone();
+        let stt_backend = self.stt_backend.clone();
         let event_tx = tx.clone();
 
         let handle = tokio::spawn(async move {
-            if let Err(e) = Self::listen_loop(state, recorder_config, event_tx).await {
+            if let Err(e) =
+                Self::listen_loop(state, cancelled, recorder_config, stt_backend, event_tx).await
+            {
                 log::error!("监听循环错误: {}", e);
             }
         });
@@ -169,13 +252,27 @@ impl ContinuousListener {
                 // 计算实际采样率: 样本数 / 时长
                 let actual_sample_rate = (audio_samples.len() as f32 / duration) as u32;
 
+                // 本地识别后端: 直接在后台识别, 不走阿里云请求
+                if self.stt_backend != SttBackend::Aliyun {
+                    drop(state);
+                    match (Handle::try_current(), event_tx.clone()) {
+                        (Ok(runtime), Some(tx)) => Self::spawn_local_recognition(
+                            &runtime,
+                            self.stt_backend.clone(),
+                            Arc::clone(&self.state),
+                            audio_samples,
+                            actual_sample_rate,
+                            tx,
+                        ),
+                        _ => log::warn!("⚠️ 无法启动本地识别, 丢弃最后一段语音"),
+                    }
+                    return self.finish_stop();
+                }
+
                 // 重采样到16kHz
                 match Self::resample_to_16khz(&audio_samples, actual_sample_rate) {
                     Ok(pcm_data) => {
-                        // // 保存 WAV 文件到下载目录
-                        // if let Err(e) = Self::save_wav_file(&pcm_data, 16000, duration) {
-                        //     log::error!("❌ 保存 WAV 文件失败: {}", e);
-                        // }
+                        Self::save_debug_audio(&pcm_data, duration);
                         Some((pcm_data, actual_sample_rate, duration))
                     }
                     Err(e) => {
@@ -235,6 +332,11 @@ impl ContinuousListener {
             log::warn!("⚠️ 没有触发识别（音频可能过短或重采样失败）");
         }
 
+        self.finish_stop()
+    }
+
+    /// 标记停止并结束监听任务
+    fn finish_stop(&mut self) -> Result<()> {
         // 标记为停止监听
         {
             let mut state = self.state.lock().unwrap();
@@ -252,12 +354,63 @@ impl ContinuousListener {
         Ok(())
     }
 
+    /// 暂停监听: 保持录音流, 监听循环丢弃音频并保持 VadState::Idle
+    ///
+    /// 暂停时正在录制的语音片段会被丢弃, 不触发识别 (例如 TTS 播放时避免识别到自己的声音)
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.paused {
+            state.paused = true;
+            state.vad.reset();
+            log::info!("⏸️ 暂停持续监听");
+        }
+    }
+
+    /// 恢复监听 (保留 VAD 的噪声基线)
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.paused {
+            state.paused = false;
+            state.vad.reset();
+            log::info!("▶️ 恢复持续监听");
+        }
+    }
+
+    /// 是否已暂停
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    /// 强制停止监听 (用于设备断开等卡死场景)
+    ///
+    /// 不等待状态锁, 不触发最后一段语音的识别; 监听线程在下一次检查时退出并释放录音设备
+    pub fn abort(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+
+        if let Ok(mut state) = self.state.try_lock() {
+            state.is_listening = false;
+        }
+
+        if let Some(handle) = self.listen_task.take() {
+            handle.abort();
+        }
+
+        self.event_tx = None;
+        log::warn!("⏹️ 已强制停止持续监听");
+    }
+
+    /// 强制停止标记, 可在监听器被其他线程持有时用于中止监听
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+
     /// 获取当前状态
     pub fn get_state(&self) -> ListenerState {
         let state = self.state.lock().unwrap();
         ListenerState {
             vad_state: state.vad.state(),
             is_listening: state.is_listening,
+            paused: state.paused,
             recording_duration: state.vad.recording_duration(),
             buffer_size: state.vad.buffer_size(),
             last_transcription: state.last_transcription.clone(),
@@ -267,12 +420,24 @@ impl ContinuousListener {
     /// 监听循环 (异步任务)
     async fn listen_loop(
         state: Arc<Mutex<ListenerStateInternal>>,
+        cancelled: Arc<AtomicBool>,
         recorder_config: RecorderConfig,
+        stt_backend: SttBackend,
         event_tx: mpsc::UnboundedSender<ListenerEvent>,
     ) -> Result<()> {
+        // 本地识别任务需要在阻塞线程中提交到 tokio runtime
+        let runtime = Handle::current();
+
         // 在 spawn_blocking 中运行,因为 cpal Stream 不是 Send
         tokio::task::spawn_blocking(move || {
-            Self::listen_loop_blocking(state, recorder_config, event_tx)
+            Self::listen_loop_blocking(
+                state,
+                cancelled,
+                recorder_config,
+                stt_backend,
+                runtime,
+                event_tx,
+            )
         })
         .await
         .map_err(|e| anyhow::anyhow!("监听任务失败: {}", e))??;
@@ -283,7 +448,10 @@ impl ContinuousListener {
     /// 监听循环 (阻塞版本,在单独线程中运行)
     fn listen_loop_blocking(
         state: Arc<Mutex<ListenerStateInternal>>,
+        cancelled: Arc<AtomicBool>,
         recorder_config: RecorderConfig,
+        stt_backend: SttBackend,
+        runtime: Handle,
         event_tx: mpsc::UnboundedSender<ListenerEvent>,
     ) -> Result<()> {
         // 创建录音器
@@ -297,6 +465,13 @@ impl ContinuousListener {
             recorder_config.sample_rate
         );
 
+        // 前导缓冲按每秒采样点数换算 (交错的多声道数据)
+        state
+            .lock()
+            .unwrap()
+            .vad
+            .set_sample_rate(actual_sample_rate * recorder.actual_channels() as u32);
+
         // 开始录音
         recorder.start_recording()?;
 
@@ -305,6 +480,10 @@ impl ContinuousListener {
 
         loop {
             // 检查是否应该继续监听
+            if cancelled.load(Ordering::SeqCst) {
+                log::warn!("⏹️ 监听已被强制停止");
+                break;
+            }
             {
                 let state = state.lock().unwrap();
                 if !state.is_listening {
@@ -326,6 +505,12 @@ impl ContinuousListener {
             // VAD 处理
             let (should_trigger_stt, speech_ended_with_audio) = {
                 let mut state = state.lock().unwrap();
+
+                // 暂停时丢弃音频, VAD 保持空闲
+                if state.paused {
+                    continue;
+                }
+
                 let old_vad_state = state.vad.state();
 
                 let should_trigger = state.vad.process_audio(&audio_chunk);
@@ -366,10 +551,12 @@ impl ContinuousListener {
                         VadState::Speaking => {
                             println!("🎤 检测到开始说话");
                             log::info!("🎤 检测到开始说话");
+                            speech_activity::mark_speech_started();
                             let _ = event_tx.send(ListenerEvent::SpeechStarted);
                         }
                         VadState::Processing => {
                             let duration = state.vad.recording_duration();
+                            speech_activity::mark_speech_ended();
                             let _ = event_tx.send(ListenerEvent::SpeechEnded {
                                 duration_secs: duration,
                             });
@@ -401,13 +588,23 @@ impl ContinuousListener {
                     duration
                 );
 
+                // 本地识别后端: 在后台识别, 完成后发送 VoiceTranscribed
+                if stt_backend != SttBackend::Aliyun {
+                    Self::spawn_local_recognition(
+                        &runtime,
+                        stt_backend.clone(),
+                        Arc::clone(&state),
+                        audio_samples,
+                        actual_sample_rate,
+                        event_tx.clone(),
+                    );
+                    continue;
+                }
+
                 // 重采样到16kHz
                 match Self::resample_to_16khz(&audio_samples, actual_sample_rate) {
                     Ok(pcm_data) => {
-                        // // 保存 WAV 文件到下载目录
-                        // if let Err(e) = Self::save_wav_file(&pcm_data, 16000, duration) {
-                        //     log::error!("保存 WAV 文件失败: {}", e);
-                        // }
+                        Self::save_debug_audio(&pcm_data, duration);
 
                         // 发送识别请求
                         if let Err(e) = event_tx.send(ListenerEvent::AliyunRecognizeRequest {
@@ -431,9 +628,69 @@ impl ContinuousListener {
         Ok(())
     }
 
+    /// 在后台用本地 STT 识别一段语音, 识别完成后发送 VoiceTranscribed 事件
+    fn spawn_local_recognition(
+        runtime: &Handle,
+        stt_backend: SttBackend,
+        state: Arc<Mutex<ListenerStateInternal>>,
+        audio_samples: Vec<f32>,
+        sample_rate: u32,
+        event_tx: mpsc::UnboundedSender<ListenerEvent>,
+    ) {
+        let handle = runtime.clone();
+        // 识别是计算密集型的 (Windows STT 的 future 也不是 Send), 放在阻塞线程中执行
+        runtime.spawn_blocking(move || {
+            let result = handle.block_on(Self::process_voice_segment(
+                &stt_backend,
+                &audio_samples,
+                sample_rate,
+            ));
+
+            let event = match result {
+                Ok(text) if text.trim().is_empty() => {
+                    log::info!("🔇 未识别到文字");
+                    return;
+                }
+                Ok(text) => {
+                    state.lock().unwrap().last_transcription = Some(text.clone());
+                    ListenerEvent::VoiceTranscribed { text }
+                }
+                Err(e) => {
+                    log::error!("❌ 语音识别失败: {}", e);
+                    ListenerEvent::Error {
+                        message: format!("语音识别失败: {}", e),
+                    }
+                }
+            };
+
+            if let Err(e) = event_tx.send(event) {
+                log::error!("发送识别结果失败: {}", e);
+            }
+        });
+    }
+
     /// 处理语音片段:STT 识别
+    async fn process_voice_segment(
+        stt_backend: &SttBackend,
+        audio_data: &[f32],
+        sample_rate: u32,
+    ) -> Result<String> {
+        match stt_backend {
+            SttBackend::Windows => Self::recognize_windows(audio_data, sample_rate).await,
+            SttBackend::Whisper { model_path } => {
+                let model_path = model_path
+                    .as_deref()
+                    .filter(|p| !p.trim().is_empty())
+                    .context("未配置 Whisper 模型路径")?;
+                Self::recognize_whisper(model_path, audio_data, sample_rate)
+            }
+            SttBackend::Aliyun => anyhow::bail!("阿里云识别需通过 AliyunRecognizeRequest 事件处理"),
+        }
+    }
+
+    /// Windows 系统语音识别
     #[cfg(windows)]
-    async fn process_voice_segment(audio_data: &[f32], sample_rate: u32) -> Result<String> {
+    async fn recognize_windows(audio_data: &[f32], sample_rate: u32) -> Result<String> {
         let mut stt_engine = WindowsSttEngine::new()?;
         let text = stt_engine
             .recognize_from_audio(audio_data, sample_rate)
@@ -443,52 +700,39 @@ impl ContinuousListener {
 
     /// 非 Windows 平台的占位实现
     #[cfg(not(windows))]
-    async fn process_voice_segment(_audio_data: &[f32], _sample_rate: u32) -> Result<String> {
-        anyhow::bail!("STT 仅支持 Windows 平台");
+    async fn recognize_windows(_audio_data: &[f32], _sample_rate: u32) -> Result<String> {
+        anyhow::bail!("Windows 语音识别仅支持 Windows 平台");
+    }
+
+    /// Whisper 本地识别 (复用已加载的模型, 模型路径变化时重新加载)
+    #[cfg(feature = "whisper")]
+    fn recognize_whisper(model_path: &str, audio_data: &[f32], sample_rate: u32) -> Result<String> {
+        let mut engine = WHISPER_ENGINE.lock().unwrap_or_else(|e| e.into_inner());
+        if engine.as_ref().map(|e| e.model_path()) != Some(model_path) {
+            *engine = Some(WhisperSttEngine::new(model_path)?);
+        }
+        engine
+            .as_mut()
+            .expect("Whisper 引擎已加载")
+            .transcribe(audio_data, sample_rate)
+    }
+
+    /// 未启用 whisper 特性时的占位实现
+    #[cfg(not(feature = "whisper"))]
+    fn recognize_whisper(
+        _model_path: &str,
+        _audio_data: &[f32],
+        _sample_rate: u32,
+    ) -> Result<String> {
+        anyhow::bail!("未启用 Whisper 语音识别, 请使用 --features whisper 重新编译");
     }
 
     /// 重采样音频数据到16kHz
     /// 输入: f32样本数据, 原始采样率
     /// 输出: 16kHz PCM u8数据 (16-bit little-endian)
     fn resample_to_16khz(samples: &[f32], from_rate: u32) -> Result<Vec<u8>> {
-        const TARGET_RATE: u32 = 16000;
-
-        if from_rate == TARGET_RATE {
-            // 不需要重采样,直接转换为PCM
-            let pcm_data: Vec<u8> = samples
-                .iter()
-                .flat_map(|&s| {
-                    let sample_i16 = (s.clamp(-1.0, 1.0) * 32767.0) as i16;
-                    sample_i16.to_le_bytes()
-                })
-                .collect();
-            return Ok(pcm_data);
-        }
-        // 创建重采样器
-        let params = SincInterpolationParameters {
-            sinc_len: 256,
-            f_cutoff: 0.95,
-            interpolation: SincInterpolationType::Linear,
-            oversampling_factor: 256,
-            window: WindowFunction::BlackmanHarris2,
-        };
-
-        let mut resampler = SincFixedIn::<f32>::new(
-            TARGET_RATE as f64 / from_rate as f64,
-            2.0,
-            params,
-            samples.len(),
-            1, // mono
-        )
-        .context("创建重采样器失败")?;
-
-        // 重采样 (需要 Vec<Vec<f32>> 格式)
-        let input = vec![samples.to_vec()];
-        let output = resampler.process(&input, None).context("重采样失败")?;
-
         // 转换为PCM (16-bit little-endian)
-        let resampled_samples = &output[0];
-        let pcm_data: Vec<u8> = resampled_samples
+        let pcm_data: Vec<u8> = resample_to_16khz_f32(samples, from_rate)?
             .iter()
             .flat_map(|&s| {
                 let sample_i16 = (s.clamp(-1.0, 1.0) * 32767.0) as i16;
@@ -499,19 +743,33 @@ impl ContinuousListener {
         Ok(pcm_data)
     }
 
-    /// 保存 WAV 文件到下载目录
-    fn save_wav_file(pcm_data: &[u8], sample_rate: u32, duration: f32) -> Result<()> {
+    /// 开启 save_debug_audio 时保存 16kHz 语音片段, 关闭时不写文件
+    fn save_debug_audio(pcm_data: &[u8], duration: f32) {
+        let Some(dir) = crate::settings::AppSettings::load()
+            .ok()
+            .and_then(|settings| settings.general.debug_audio_output_dir())
+        else {
+            return;
+        };
+
+        if let Err(e) = Self::save_wav_file(&dir, pcm_data, 16000, duration) {
+            log::error!("❌ 保存 WAV 文件失败: {}", e);
+        }
+    }
+
+    /// 保存 WAV 文件到调试音频目录
+    fn save_wav_file(dir: &Path, pcm_data: &[u8], sample_rate: u32, duration: f32) -> Result<()> {
         use std::fs::File;
         use std::io::Write;
 
         // 生成文件名（时间戳）
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
         let filename = format!("voice_{}_{:.1}s.wav", timestamp, duration);
-        let filepath = format!(r"C:\Users\Administrator\Downloads\{}", filename);
+        let filepath = dir.join(filename);
 
         // 创建 WAV 文件
-        let mut file =
-            File::create(&filepath).context(format!("创建 WAV 文件失败: {}", filepath))?;
+        let mut file = File::create(&filepath)
+            .with_context(|| format!("创建 WAV 文件失败: {}", filepath.display()))?;
 
         // 写入 WAV 头
         let num_samples = pcm_data.len() / 2; // 16-bit = 2 bytes per sample
@@ -541,7 +799,7 @@ impl ContinuousListener {
 
         log::info!(
             "已保存语音文件: {} ({:.1}s, {} bytes)",
-            filepath,
+            filepath.display(),
             duration,
             pcm_data.len()
         );
@@ -550,6 +808,39 @@ impl ContinuousListener {
     }
 }
 
+/// 重采样音频数据到16kHz (单声道 f32 样本)
+pub(crate) fn resample_to_16khz_f32(samples: &[f32], from_rate: u32) -> Result<Vec<f32>> {
+    const TARGET_RATE: u32 = 16000;
+
+    if from_rate == TARGET_RATE || samples.is_empty() {
+        // 不需要重采样
+        return Ok(samples.to_vec());
+    }
+    // 创建重采样器
+    let params = SincInterpolationParameters {
+        sinc_len: 256,
+        f_cutoff: 0.95,
+        interpolation: SincInterpolationType::Linear,
+        oversampling_factor: 256,
+        window: WindowFunction::BlackmanHarris2,
+    };
+
+    let mut resampler = SincFixedIn::<f32>::new(
+        TARGET_RATE as f64 / from_rate as f64,
+        2.0,
+        params,
+        samples.len(),
+        1, // mono
+    )
+    .context("创建重采样器失败")?;
+
+    // 重采样 (需要 Vec<Vec<f32>> 格式)
+    let input = vec![samples.to_vec()];
+    let mut output = resampler.process(&input, None).context("重采样失败")?;
+
+    Ok(output.swap_remove(0))
+}
+
 impl Drop for ContinuousListener {
     fn drop(&mut self) {
         let _ = self.stop_listening();
@@ -570,4 +861,31 @@ mod tests {
         assert!(!state.is_listening);
         assert_eq!(state.vad_state, VadState::Idle);
     }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let listener = ContinuousListener::new(VadConfig::default(), RecorderConfig::default());
+
+        listener.pause();
+        assert!(listener.is_paused());
+        assert!(listener.get_state().paused);
+        assert_eq!(listener.get_state().vad_state, VadState::Idle);
+
+        listener.resume();
+        assert!(!listener.is_paused());
+        assert!(!listener.get_state().paused);
+    }
+
+    #[test]
+    fn test_stt_backend_parse() {
+        assert_eq!(SttBackend::parse("windows", None), SttBackend::Windows);
+        assert_eq!(
+            SttBackend::parse(" Whisper ", Some("ggml-base.bin".to_string())),
+            SttBackend::Whisper {
+                model_path: Some("ggml-base.bin".to_string())
+            }
+        );
+        assert_eq!(SttBackend::parse("aliyun", None), SttBackend::Aliyun);
+        assert_eq!(SttBackend::parse("unknown", None), SttBackend::Aliyun);
+    }
 }
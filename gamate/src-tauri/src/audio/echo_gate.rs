@@ -0,0 +1,54 @@
+// Echo gate - TTS 播报期间屏蔽语音输入
+// TTS 播放时设置共享标记, 持续监听的 VAD 在标记为 true 时忽略音频, 避免 AI 识别到自己的声音
+// 每次播放开始时分配新的代数, 只有最近一次播放结束时才清除标记, 避免较早的播放结束时误清除
+
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 全局的 TTS 播放标记
+static TTS_PLAYING: Lazy<Arc<AtomicBool>> = Lazy::new(|| Arc::new(AtomicBool::new(false)));
+
+/// 最近一次播放的代数 (与标记一起在锁内更新)
+static PLAYBACK_GENERATION: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+
+/// 获取共享的 TTS 播放标记 (传给监听器的 VAD)
+pub fn tts_playing_flag() -> Arc<AtomicBool> {
+    Arc::clone(&TTS_PLAYING)
+}
+
+/// 开始播放: 设置播放标记, 返回本次播放的代数 (结束时传给 `end_playback`)
+pub fn begin_playback() -> u64 {
+    let mut generation = PLAYBACK_GENERATION.lock().unwrap_or_else(|e| e.into_inner());
+    *generation += 1;
+    TTS_PLAYING.store(true, Ordering::SeqCst);
+    *generation
+}
+
+/// 播放结束: 期间没有新的播放开始时才清除播放标记
+pub fn end_playback(generation: u64) {
+    let current = PLAYBACK_GENERATION.lock().unwrap_or_else(|e| e.into_inner());
+    if *current == generation {
+        TTS_PLAYING.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_older_playback_does_not_clear_newer() {
+        let flag = tts_playing_flag();
+
+        let first = begin_playback();
+        let second = begin_playback();
+
+        // 较早的播放先结束: 较新的播放仍在进行, 保持屏蔽
+        end_playback(first);
+        assert!(flag.load(Ordering::SeqCst));
+
+        end_playback(second);
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+}
@@ -1,8 +1,14 @@
 // Audio module for voice input functionality
 
 pub mod continuous_listener;
+pub mod echo_gate;
+pub mod player;
 pub mod recorder;
+pub mod speech_activity;
 pub mod vad;
 
 #[cfg(windows)]
 pub mod stt_windows;
+
+#[cfg(feature = "whisper")]
+pub mod stt_whisper;
@@ -0,0 +1,263 @@
+// Audio playback module using cpal
+// 负责把音频播放到指定的输出设备 (例如虚拟声卡), 供 TTS 路由到直播混音
+
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, SizedSample, StreamConfig};
+use serde::Serialize;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 输出设备信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDeviceInfo {
+    /// 设备名称
+    pub name: String,
+    /// 是否为系统默认输出设备
+    pub is_default: bool,
+}
+
+/// 列出所有输出设备
+pub fn list_output_devices() -> Result<Vec<OutputDeviceInfo>> {
+    let host = cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .output_devices()
+        .context("无法枚举输出设备")?
+        .filter_map(|d| d.name().ok())
+        .map(|name| OutputDeviceInfo {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect();
+
+    Ok(devices)
+}
+
+/// 查找输出设备
+///
+/// - `device_name`: 设备名称, None 时使用默认输出设备
+pub fn find_output_device(device_name: Option<&str>) -> Result<Device> {
+    let host = cpal::default_host();
+
+    match device_name {
+        Some(name) => host
+            .output_devices()
+            .context("无法枚举输出设备")?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .with_context(|| format!("未找到输出设备: {}", name)),
+        None => host.default_output_device().context("未找到默认输出设备"),
+    }
+}
+
+/// 播放单声道 f32 音频 (阻塞直到播放完成或被取消)
+///
+/// 音频会被重采样到设备采样率并复制到所有声道
+pub fn play_samples(
+    device_name: Option<&str>,
+    samples: &[f32],
+    sample_rate: u32,
+    cancel: Option<&AtomicBool>,
+) -> Result<()> {
+    let device = find_output_device(device_name)?;
+    let default_config = device
+        .default_output_config()
+        .context("无法获取输出设备默认配置")?;
+
+    let config = StreamConfig {
+        channels: default_config.channels(),
+        sample_rate: default_config.sample_rate(),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    let resampled = resample_linear(samples, sample_rate, config.sample_rate.0);
+    let frames = resampled.len();
+    let interleaved: Arc<Vec<f32>> = Arc::new(
+        resampled
+            .into_iter()
+            .flat_map(|s| std::iter::repeat(s).take(config.channels as usize))
+            .collect(),
+    );
+    let position = Arc::new(AtomicUsize::new(0));
+
+    let stream = match default_config.sample_format() {
+        cpal::SampleFormat::F32 => {
+            build_stream::<f32>(&device, &config, interleaved.clone(), position.clone())?
+        }
+        cpal::SampleFormat::I16 => {
+            build_stream::<i16>(&device, &config, interleaved.clone(), position.clone())?
+        }
+        cpal::SampleFormat::U16 => {
+            build_stream::<u16>(&device, &config, interleaved.clone(), position.clone())?
+        }
+        format => anyhow::bail!("不支持的采样格式: {:?}", format),
+    };
+
+    log::debug!(
+        "🔈 播放到设备 {:?}: {} 帧 @ {} Hz",
+        device.name(),
+        frames,
+        config.sample_rate.0
+    );
+
+    stream.play()?;
+
+    while position.load(Ordering::Relaxed) < interleaved.len() {
+        if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            log::debug!("⏹️ 播放已取消");
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    // 等待设备缓冲区中的最后一段音频播放完
+    std::thread::sleep(Duration::from_millis(100));
+
+    Ok(())
+}
+
+/// 播放 WAV 数据 (多声道会被混为单声道)
+pub fn play_wav_bytes(
+    device_name: Option<&str>,
+    wav: &[u8],
+    cancel: Option<&AtomicBool>,
+) -> Result<()> {
+    let (samples, sample_rate) = decode_wav(wav)?;
+    play_samples(device_name, &samples, sample_rate, cancel)
+}
+
+/// 生成正弦测试音 (单声道)
+pub fn sine_tone(frequency: f32, duration_ms: u32, sample_rate: u32) -> Vec<f32> {
+    let count = (sample_rate as u64 * duration_ms as u64 / 1000) as usize;
+    // 首尾 10ms 淡入淡出, 避免爆音
+    let fade = (sample_rate as usize / 100).max(1);
+
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            let envelope = (i.min(count - 1 - i) as f32 / fade as f32).min(1.0);
+            (2.0 * std::f32::consts::PI * frequency * t).sin() * 0.3 * envelope
+        })
+        .collect()
+}
+
+/// 解码 WAV 为单声道 f32 样本, 返回 (样本, 采样率)
+fn decode_wav(wav: &[u8]) -> Result<(Vec<f32>, u32)> {
+    let mut reader = hound::WavReader::new(Cursor::new(wav)).context("无法解析 WAV 数据")?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok((mono, spec.sample_rate))
+}
+
+/// 线性插值重采样
+fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (samples.len() as f64 / ratio) as usize;
+
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let index = pos as usize;
+            let frac = (pos - index as f64) as f32;
+            let a = samples[index.min(samples.len() - 1)];
+            let b = samples[(index + 1).min(samples.len() - 1)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+fn build_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    data: Arc<Vec<f32>>,
+    position: Arc<AtomicUsize>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let stream = device.build_output_stream(
+        config,
+        move |output: &mut [T], _: &cpal::OutputCallbackInfo| {
+            let start = position.load(Ordering::Relaxed);
+            for (i, sample) in output.iter_mut().enumerate() {
+                let value = data.get(start + i).copied().unwrap_or(0.0);
+                *sample = T::from_sample(value);
+            }
+            position.store((start + output.len()).min(data.len()), Ordering::Relaxed);
+        },
+        |err| {
+            log::error!("❌ 音频输出流错误: {}", err);
+        },
+        None,
+    )?;
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_tone_and_resample() {
+        let tone = sine_tone(440.0, 500, 16000);
+        assert_eq!(tone.len(), 8000);
+        assert!(tone.iter().all(|s| s.abs() <= 0.3));
+        // 淡入: 第一个样本为静音
+        assert_eq!(tone[0], 0.0);
+
+        let resampled = resample_linear(&tone, 16000, 48000);
+        assert_eq!(resampled.len(), 24000);
+        assert_eq!(resample_linear(&tone, 16000, 16000).len(), tone.len());
+    }
+
+    #[test]
+    fn test_decode_wav() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 22050,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+            for _ in 0..100 {
+                writer.write_sample(16384i16).unwrap();
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let (samples, sample_rate) = decode_wav(buffer.get_ref()).unwrap();
+        assert_eq!(sample_rate, 22050);
+        assert_eq!(samples.len(), 100);
+        assert!((samples[0] - 0.25).abs() < 1e-3);
+    }
+}
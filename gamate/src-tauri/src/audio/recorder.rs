@@ -4,6 +4,7 @@
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Host, Stream, StreamConfig};
+use serde::Serialize;
 use std::sync::{Arc, Mutex};
 
 /// 音频录制器配置
@@ -13,6 +14,8 @@ pub struct RecorderConfig {
     pub sample_rate: u32,
     /// 声道数
     pub channels: u16,
+    /// 输入设备名称 (None 或未找到时使用默认输入设备)
+    pub device_name: Option<String>,
 }
 
 impl Default for RecorderConfig {
@@ -20,6 +23,19 @@ impl Default for RecorderConfig {
         Self {
             sample_rate: 16000, // 16kHz 适合语音识别
             channels: 1,        // 单声道
+            device_name: None,
+        }
+    }
+}
+
+impl RecorderConfig {
+    /// 使用设置中选择的输入设备
+    pub fn from_settings() -> Self {
+        Self {
+            device_name: crate::settings::AppSettings::load()
+                .ok()
+                .and_then(|s| s.general.input_device),
+            ..Default::default()
         }
     }
 }
@@ -44,10 +60,21 @@ impl AudioRecorder {
         // 获取默认音频主机
         let host = cpal::default_host();
 
-        // 获取默认输入设备 (麦克风)
-        let device = host
-            .default_input_device()
-            .context("未找到默认输入设备 (麦克风)")?;
+        // 获取指定的输入设备 (未找到时使用默认麦克风)
+        let device = match recorder_config
+            .device_name
+            .as_deref()
+            .and_then(|name| find_input_device(&host, name))
+        {
+            Some(device) => device,
+            None => {
+                if let Some(name) = &recorder_config.device_name {
+                    log::warn!("⚠️ 未找到输入设备 {}, 使用默认输入设备", name);
+                }
+                host.default_input_device()
+                    .context("未找到默认输入设备 (麦克风)")?
+            }
+        };
 
         log::info!("🎙️ 使用音频设备: {:?}", device.name());
 
@@ -77,6 +104,7 @@ impl AudioRecorder {
         let actual_config = RecorderConfig {
             sample_rate: config.sample_rate.0, // 使用实际设备采样率
             channels: config.channels,
+            device_name: device.name().ok(),
         };
 
         Ok(Self {
@@ -202,6 +230,114 @@ impl AudioRecorder {
     pub fn actual_sample_rate(&self) -> u32 {
         self.actual_config.sample_rate
     }
+
+    /// 获取实际的设备声道数 (缓冲区中的采样为交错排列)
+    pub fn actual_channels(&self) -> u16 {
+        self.actual_config.channels
+    }
+}
+
+/// 音频输入设备能力信息 (用于前端诊断采样率不匹配等问题)
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDeviceInfo {
+    /// 设备名称
+    pub name: String,
+    /// 是否为系统默认输入设备
+    pub is_default: bool,
+    /// 默认配置的采样率 (Hz)
+    pub default_sample_rate: u32,
+    /// 默认配置的声道数
+    pub default_channels: u16,
+    /// 默认配置的采样格式
+    pub default_sample_format: String,
+    /// 支持的采样率范围 (min, max)
+    pub supported_sample_rates: Vec<(u32, u32)>,
+    /// 支持的声道数 (去重、升序)
+    pub supported_channels: Vec<u16>,
+}
+
+/// 按名称查找输入设备
+fn find_input_device(host: &Host, name: &str) -> Option<Device> {
+    host.input_devices()
+        .ok()?
+        .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+}
+
+/// 列出所有输入设备 (跳过无法读取配置的设备)
+pub fn list_input_devices() -> Result<Vec<AudioDeviceInfo>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .input_devices()
+        .context("无法枚举输入设备")?
+        .filter_map(
+            |device| match describe_device(&device, default_name.as_deref()) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    log::debug!("跳过输入设备 {:?}: {}", device.name(), e);
+                    None
+                }
+            },
+        )
+        .collect();
+
+    Ok(devices)
+}
+
+/// 获取输入设备的能力信息
+///
+/// - `device_name`: 设备名称, None 时使用默认输入设备
+pub fn get_input_device_info(device_name: Option<&str>) -> Result<AudioDeviceInfo> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let device = match device_name {
+        Some(name) => {
+            find_input_device(&host, name).with_context(|| format!("未找到输入设备: {}", name))?
+        }
+        None => host
+            .default_input_device()
+            .context("未找到默认输入设备 (麦克风)")?,
+    };
+
+    describe_device(&device, default_name.as_deref())
+}
+
+/// 读取设备的默认配置和支持的配置范围
+fn describe_device(device: &Device, default_name: Option<&str>) -> Result<AudioDeviceInfo> {
+    let name = device.name().unwrap_or_else(|_| "未知设备".to_string());
+
+    let default_config = device
+        .default_input_config()
+        .context("无法获取设备默认配置")?;
+
+    let mut supported_sample_rates = Vec::new();
+    let mut supported_channels = Vec::new();
+    for range in device
+        .supported_input_configs()
+        .context("无法获取设备支持的配置")?
+    {
+        let rates = (range.min_sample_rate().0, range.max_sample_rate().0);
+        if !supported_sample_rates.contains(&rates) {
+            supported_sample_rates.push(rates);
+        }
+        if !supported_channels.contains(&range.channels()) {
+            supported_channels.push(range.channels());
+        }
+    }
+    supported_sample_rates.sort();
+    supported_channels.sort();
+
+    Ok(AudioDeviceInfo {
+        is_default: default_name == Some(name.as_str()),
+        name,
+        default_sample_rate: default_config.sample_rate().0,
+        default_channels: default_config.channels(),
+        default_sample_format: format!("{:?}", default_config.sample_format()),
+        supported_sample_rates,
+        supported_channels,
+    })
 }
 
 impl Drop for AudioRecorder {
@@ -0,0 +1,62 @@
+// Speech activity - 共享的语音活动状态
+// 持续监听的 VAD 检测到说话开始/结束时更新, 定时截图据此在活跃/闲置间隔之间切换
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 语音活动状态
+#[derive(Debug, Clone, Copy, Default)]
+struct SpeechActivity {
+    /// 是否正在说话
+    speaking: bool,
+    /// 最近一次说话结束的时间
+    last_speech: Option<Instant>,
+}
+
+/// 全局的语音活动状态
+static ACTIVITY: Lazy<Mutex<SpeechActivity>> = Lazy::new(|| Mutex::new(SpeechActivity::default()));
+
+/// 标记检测到开始说话
+pub fn mark_speech_started() {
+    let mut activity = ACTIVITY.lock().unwrap_or_else(|e| e.into_inner());
+    activity.speaking = true;
+}
+
+/// 标记说话结束
+pub fn mark_speech_ended() {
+    let mut activity = ACTIVITY.lock().unwrap_or_else(|e| e.into_inner());
+    activity.speaking = false;
+    activity.last_speech = Some(Instant::now());
+}
+
+/// 距离上次说话的时长 (正在说话时为 0, 从未检测到语音时为 None)
+pub fn silence_duration() -> Option<Duration> {
+    let activity = *ACTIVITY.lock().unwrap_or_else(|e| e.into_inner());
+    if activity.speaking {
+        return Some(Duration::ZERO);
+    }
+    activity.last_speech.map(|at| at.elapsed())
+}
+
+/// 静音时长是否达到闲置阈值
+pub fn is_silent(silence: Duration, threshold: Duration) -> bool {
+    silence >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_activity() {
+        mark_speech_started();
+        assert_eq!(silence_duration(), Some(Duration::ZERO));
+
+        mark_speech_ended();
+        let silence = silence_duration().unwrap();
+        assert!(silence < Duration::from_secs(1));
+        assert!(!is_silent(silence, Duration::from_secs(30)));
+        assert!(is_silent(Duration::from_secs(30), Duration::from_secs(30)));
+    }
+}
@@ -0,0 +1,84 @@
+// Whisper STT implementation
+// 使用 whisper.cpp (whisper-rs) 在本地离线识别, 跨平台可用
+
+#![cfg(feature = "whisper")]
+
+use anyhow::Result;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use super::continuous_listener::resample_to_16khz_f32;
+
+/// Whisper STT 引擎
+pub struct WhisperSttEngine {
+    context: WhisperContext,
+    model_path: String,
+    language: String,
+}
+
+impl WhisperSttEngine {
+    /// 加载 ggml 模型创建 STT 引擎 (模型较大时加载较慢, 应复用同一个引擎)
+    pub fn new(model_path: &str) -> Result<Self> {
+        let context =
+            WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+                .map_err(|e| anyhow::anyhow!("加载 Whisper 模型失败 ({}): {}", model_path, e))?;
+
+        log::info!("🗣️ Whisper STT 初始化成功 (模型: {})", model_path);
+
+        Ok(Self {
+            context,
+            model_path: model_path.to_string(),
+            language: "zh".to_string(),
+        })
+    }
+
+    /// 当前加载的模型路径
+    pub fn model_path(&self) -> &str {
+        &self.model_path
+    }
+
+    /// 从音频数据识别文字 (计算密集, 应在阻塞线程中调用)
+    ///
+    /// # 参数
+    /// - `audio_data`: 音频数据 (f32 样本, 单声道)
+    /// - `sample_rate`: 采样率 (非 16kHz 时先重采样)
+    pub fn transcribe(&mut self, audio_data: &[f32], sample_rate: u32) -> Result<String> {
+        let samples = resample_to_16khz_f32(audio_data, sample_rate)?;
+
+        let mut state = self
+            .context
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("创建 Whisper 状态失败: {}", e))?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(&self.language));
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+
+        log::info!(
+            "🎤 Whisper 开始识别 ({:.2}s 音频)",
+            samples.len() as f32 / 16000.0
+        );
+
+        state
+            .full(params, &samples)
+            .map_err(|e| anyhow::anyhow!("Whisper 识别失败: {}", e))?;
+
+        let segments = state
+            .full_n_segments()
+            .map_err(|e| anyhow::anyhow!("读取 Whisper 识别结果失败: {}", e))?;
+
+        let mut text = String::new();
+        for i in 0..segments {
+            let segment = state
+                .full_get_segment_text(i)
+                .map_err(|e| anyhow::anyhow!("读取 Whisper 识别结果失败: {}", e))?;
+            text.push_str(segment.trim());
+        }
+
+        log::info!("✅ 识别结果: {}", text);
+
+        Ok(text)
+    }
+}
@@ -2,8 +2,34 @@
 // 基于 RMS 音量的语音活动检测
 
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// 自适应阈值的默认倍数 (阈值 = 噪声底 * 倍数)
+pub const DEFAULT_THRESHOLD_MULTIPLIER: f32 = 3.0;
+
+/// 估算噪声底时保留的空闲音频块数量 (约几秒的音频)
+const NOISE_HISTORY_CHUNKS: usize = 50;
+
+/// 自适应阈值的下限, 避免完全静音时任何声音都触发
+const MIN_ADAPTIVE_THRESHOLD: f32 = 0.005;
+
+/// 默认的语音前导缓冲时长 (毫秒)
+pub const DEFAULT_PRE_ROLL_MS: u32 = 300;
+
+/// 未设置采样率时假定的每秒采样点数
+const DEFAULT_SAMPLES_PER_SEC: u32 = 16000;
+
+fn default_threshold_multiplier() -> f32 {
+    DEFAULT_THRESHOLD_MULTIPLIER
+}
+
+fn default_pre_roll_ms() -> u32 {
+    DEFAULT_PRE_ROLL_MS
+}
+
 /// VAD 配置参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VadConfig {
@@ -21,6 +47,18 @@ pub struct VadConfig {
 
     /// RMS 计算窗口大小 (采样点数)
     pub rms_window_size: usize,
+
+    /// 是否根据背景噪声自动调整阈值
+    #[serde(default)]
+    pub adaptive_threshold: bool,
+
+    /// 自适应阈值倍数 (阈值 = 空闲时 RMS 的中位数 * 倍数)
+    #[serde(default = "default_threshold_multiplier")]
+    pub threshold_multiplier: f32,
+
+    /// 语音前导缓冲时长(毫秒),开始说话时把之前的这段音频一起保留,避免截掉第一个字
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
 }
 
 impl Default for VadConfig {
@@ -31,6 +69,9 @@ impl Default for VadConfig {
             min_speech_duration_secs: 0.3,  // 至少0.3秒才算有效语音
             max_speech_duration_secs: 30.0, // 最长30秒
             rms_window_size: 1024,          // 1024个采样点计算RMS
+            adaptive_threshold: false,
+            threshold_multiplier: DEFAULT_THRESHOLD_MULTIPLIER,
+            pre_roll_ms: DEFAULT_PRE_ROLL_MS,
         }
     }
 }
@@ -59,6 +100,24 @@ pub struct VoiceActivityDetector {
 
     /// 累积的音频缓冲区
     audio_buffer: Vec<f32>,
+
+    /// 空闲时最近音频块的 RMS (用于估算噪声底)
+    noise_history: VecDeque<f32>,
+
+    /// 自适应模式下的当前阈值 (尚未采集到噪声时为 None)
+    adaptive_threshold: Option<f32>,
+
+    /// 空闲时最近的音频 (语音开始时拼接到缓冲区开头)
+    pre_roll: VecDeque<f32>,
+
+    /// 每秒采样点数 (多声道时为采样率 * 声道数)
+    samples_per_sec: u32,
+
+    /// 当前录音中前导音频的时长(秒)
+    pre_roll_secs: f32,
+
+    /// 回声屏蔽标记 (TTS 播放时为 true, 此时忽略所有音频)
+    echo_gate: Option<Arc<AtomicBool>>,
 }
 
 impl VoiceActivityDetector {
@@ -69,6 +128,44 @@ impl VoiceActivityDetector {
             speech_start_time: None,
             last_voice_time: None,
             audio_buffer: Vec::new(),
+            noise_history: VecDeque::with_capacity(NOISE_HISTORY_CHUNKS),
+            adaptive_threshold: None,
+            pre_roll: VecDeque::new(),
+            samples_per_sec: DEFAULT_SAMPLES_PER_SEC,
+            pre_roll_secs: 0.0,
+            echo_gate: None,
+        }
+    }
+
+    /// 设置回声屏蔽标记 (None 表示不屏蔽)
+    pub fn set_echo_gate(&mut self, echo_gate: Option<Arc<AtomicBool>>) {
+        self.echo_gate = echo_gate;
+    }
+
+    /// 当前是否处于回声屏蔽中
+    fn is_echo_suppressed(&self) -> bool {
+        self.echo_gate
+            .as_ref()
+            .is_some_and(|gate| gate.load(Ordering::SeqCst))
+    }
+
+    /// 设置输入音频的每秒采样点数 (用于换算前导缓冲长度)
+    pub fn set_sample_rate(&mut self, samples_per_sec: u32) {
+        self.samples_per_sec = samples_per_sec.max(1);
+    }
+
+    /// 前导缓冲最多保留的采样点数
+    fn pre_roll_capacity(&self) -> usize {
+        (self.config.pre_roll_ms as u64 * self.samples_per_sec as u64 / 1000) as usize
+    }
+
+    /// 空闲时保留最近的音频
+    fn push_pre_roll(&mut self, audio_chunk: &[f32]) {
+        let capacity = self.pre_roll_capacity();
+        self.pre_roll.extend(audio_chunk.iter().copied());
+        if self.pre_roll.len() > capacity {
+            let excess = self.pre_roll.len() - capacity;
+            self.pre_roll.drain(..excess);
         }
     }
 
@@ -94,6 +191,31 @@ impl VoiceActivityDetector {
         (sum_squares / samples.len() as f32).sqrt()
     }
 
+    /// 当前生效的音量阈值 (自适应模式下随背景噪声变化)
+    pub fn effective_threshold(&self) -> f32 {
+        if self.config.adaptive_threshold {
+            self.adaptive_threshold
+                .unwrap_or(self.config.volume_threshold)
+        } else {
+            self.config.volume_threshold
+        }
+    }
+
+    /// 记录空闲时的音量, 以中位数作为噪声底更新自适应阈值
+    fn update_noise_floor(&mut self, rms: f32) {
+        if self.noise_history.len() == NOISE_HISTORY_CHUNKS {
+            self.noise_history.pop_front();
+        }
+        self.noise_history.push_back(rms);
+
+        let mut sorted: Vec<f32> = self.noise_history.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let noise_floor = sorted[sorted.len() / 2];
+
+        self.adaptive_threshold =
+            Some((noise_floor * self.config.threshold_multiplier).max(MIN_ADAPTIVE_THRESHOLD));
+    }
+
     /// 处理新的音频数据,返回是否应该触发 STT
     ///
     /// # 参数
@@ -103,22 +225,38 @@ impl VoiceActivityDetector {
     /// - `true`: 检测到语音结束,应该触发 STT
     /// - `false`: 继续监听
     pub fn process_audio(&mut self, audio_chunk: &[f32]) -> bool {
+        // TTS 播放中: 丢弃音频并保持空闲 (不更新噪声底和前导缓冲)
+        if self.is_echo_suppressed() {
+            if self.state != VadState::Idle || !self.pre_roll.is_empty() {
+                self.reset();
+            }
+            return false;
+        }
+
         let now = Instant::now();
 
         // 计算当前音频块的 RMS 音量
         let rms = self.calculate_rms(audio_chunk);
+        let threshold = self.effective_threshold();
 
         match self.state {
             VadState::Idle => {
                 // 空闲状态:检测是否有语音输入
-                if rms > self.config.volume_threshold {
+                if rms > threshold {
                     // 检测到语音,切换到 Speaking 状态
                     self.state = VadState::Speaking;
                     self.speech_start_time = Some(now);
                     self.last_voice_time = Some(now);
                     self.audio_buffer.clear();
+                    self.pre_roll_secs = self.pre_roll.len() as f32 / self.samples_per_sec as f32;
+                    self.audio_buffer.extend(self.pre_roll.drain(..));
                     self.audio_buffer.extend_from_slice(audio_chunk);
                     log::info!("🎤 VAD: 检测到语音开始 (RMS: {:.4})", rms);
+                } else {
+                    self.push_pre_roll(audio_chunk);
+                    if self.config.adaptive_threshold {
+                        self.update_noise_floor(rms);
+                    }
                 }
                 false
             }
@@ -128,7 +266,7 @@ impl VoiceActivityDetector {
                 self.audio_buffer.extend_from_slice(audio_chunk);
 
                 // 检查是否还有语音
-                if rms > self.config.volume_threshold {
+                if rms > threshold {
                     self.last_voice_time = Some(now);
                 }
 
@@ -168,11 +306,12 @@ impl VoiceActivityDetector {
 
             VadState::Processing => {
                 // 处理状态:检测新的语音输入以重新开始
-                if rms > self.config.volume_threshold {
+                if rms > threshold {
                     // 检测到新语音,清空旧缓冲区并重新开始
                     self.state = VadState::Speaking;
                     self.speech_start_time = Some(now);
                     self.last_voice_time = Some(now);
+                    self.pre_roll_secs = 0.0;
                     self.audio_buffer.clear(); // 清空旧音频
                     self.audio_buffer.extend_from_slice(audio_chunk);
                 } else {
@@ -222,6 +361,8 @@ impl VoiceActivityDetector {
         self.speech_start_time = None;
         self.last_voice_time = None;
         self.audio_buffer.clear();
+        self.pre_roll.clear();
+        self.pre_roll_secs = 0.0;
     }
 
     /// 获取当前音频缓冲区大小 (采样点数)
@@ -229,10 +370,10 @@ impl VoiceActivityDetector {
         self.audio_buffer.len()
     }
 
-    /// 获取当前录音时长 (秒)
+    /// 获取当前录音时长 (秒, 包含前导音频)
     pub fn recording_duration(&self) -> f32 {
         self.speech_start_time
-            .map(|start| Instant::now().duration_since(start).as_secs_f32())
+            .map(|start| Instant::now().duration_since(start).as_secs_f32() + self.pre_roll_secs)
             .unwrap_or(0.0)
     }
 
@@ -272,6 +413,7 @@ mod tests {
             min_speech_duration_secs: 0.1,
             max_speech_duration_secs: 5.0,
             rms_window_size: 512,
+            ..Default::default()
         };
 
         let mut vad = VoiceActivityDetector::new(config);
@@ -289,4 +431,95 @@ mod tests {
         assert!(!vad.process_audio(&voice));
         assert_eq!(vad.state(), VadState::Speaking);
     }
+
+    #[test]
+    fn test_adaptive_threshold_follows_noise() {
+        let config = VadConfig {
+            volume_threshold: 0.02,
+            adaptive_threshold: true,
+            threshold_multiplier: 3.0,
+            ..Default::default()
+        };
+        let mut vad = VoiceActivityDetector::new(config);
+
+        // 还没有噪声样本时使用固定阈值
+        assert_eq!(vad.effective_threshold(), 0.02);
+
+        // 背景噪声逐渐变大: 阈值跟随上升, 不会误触发
+        let mut thresholds = Vec::new();
+        for noise in [0.01, 0.02, 0.04] {
+            for _ in 0..NOISE_HISTORY_CHUNKS {
+                assert!(!vad.process_audio(&vec![noise; 512]));
+                assert_eq!(vad.state(), VadState::Idle);
+            }
+            thresholds.push(vad.effective_threshold());
+        }
+        assert!((thresholds[0] - 0.03).abs() < 1e-4);
+        assert!((thresholds[1] - 0.06).abs() < 1e-4);
+        assert!((thresholds[2] - 0.12).abs() < 1e-4);
+
+        // 明显高于噪声的语音仍然能触发
+        vad.process_audio(&vec![0.5; 512]);
+        assert_eq!(vad.state(), VadState::Speaking);
+
+        // 关闭自适应时, 同样的噪声会超过固定阈值
+        let mut vad = VoiceActivityDetector::new(VadConfig::default());
+        vad.process_audio(&vec![0.04; 512]);
+        assert_eq!(vad.state(), VadState::Speaking);
+    }
+
+    #[test]
+    fn test_pre_roll_keeps_speech_onset() {
+        let config = VadConfig {
+            volume_threshold: 0.1,
+            pre_roll_ms: 300,
+            ..Default::default()
+        };
+        let mut vad = VoiceActivityDetector::new(config);
+        vad.set_sample_rate(1000);
+
+        // 空闲时的音频只保留最近 300ms (300 个采样点)
+        for _ in 0..5 {
+            vad.process_audio(&vec![0.01; 100]);
+        }
+        assert_eq!(vad.buffer_size(), 0);
+
+        let voice = vec![0.5; 100];
+        vad.process_audio(&voice);
+        assert_eq!(vad.state(), VadState::Speaking);
+        assert_eq!(vad.buffer_size(), 300 + voice.len());
+        assert!(vad.recording_duration() >= 0.3);
+
+        let audio = vad.take_audio_buffer();
+        assert_eq!(audio[0], 0.01);
+        assert_eq!(audio[audio.len() - 1], 0.5);
+    }
+
+    #[test]
+    fn test_echo_gate_holds_idle() {
+        let mut vad = VoiceActivityDetector::new(VadConfig {
+            volume_threshold: 0.1,
+            ..Default::default()
+        });
+        let gate = Arc::new(AtomicBool::new(true));
+        vad.set_echo_gate(Some(Arc::clone(&gate)));
+
+        let loud = vec![0.8; 1024];
+        for _ in 0..10 {
+            assert!(!vad.process_audio(&loud));
+            assert_eq!(vad.state(), VadState::Idle);
+        }
+        assert_eq!(vad.buffer_size(), 0);
+
+        // 播报结束后恢复检测
+        gate.store(false, Ordering::SeqCst);
+        vad.process_audio(&loud);
+        assert_eq!(vad.state(), VadState::Speaking);
+
+        // 说话过程中开始播报, 丢弃当前片段
+        gate.store(true, Ordering::SeqCst);
+        vad.process_audio(&loud);
+        assert_eq!(vad.state(), VadState::Idle);
+        assert_eq!(vad.buffer_size(), 0);
+    }
 }
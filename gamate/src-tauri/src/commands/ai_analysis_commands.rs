@@ -12,6 +12,16 @@ pub struct AIAnalysisRequest {
     pub screenshot_after: String,   // Base64
 }
 
+/// AI 分析结果
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AnalysisOutcome {
+    /// 已完成分析并执行员工行为
+    Sent { action_count: usize },
+    /// 因冷却或已有分析进行中而跳过
+    Skipped { reason: String },
+}
+
 /// 判断礼物是否为大礼物
 fn is_big_gift(gift_name: &str) -> bool {
     // 大礼物列表（可以根据实际情况调整）
@@ -53,9 +63,9 @@ pub async fn trigger_ai_analysis(
     _app: AppHandle,
     state: State<'_, SimulationState>,
     request: AIAnalysisRequest,
-) -> Result<String, String> {
+) -> Result<AnalysisOutcome, String> {
     // 获取必要的数据并在锁外调用
-    let (app, employees, memory, ai_analyzer, tts_engine, game_id) = {
+    let (app, employees, memory, ai_analyzer, tts_engine, game_id, analysis_gate) = {
         let engine_lock = state.engine.lock().unwrap();
         if let Some(engine) = engine_lock.as_ref() {
             (
@@ -65,6 +75,7 @@ pub async fn trigger_ai_analysis(
                 engine.ai_analyzer.clone(),
                 engine.tts_engine.clone(),
                 engine.game_id.clone(),
+                engine.analysis_gate.clone(),
             )
         } else {
             log::warn!("⚠️ 直播间已停止，忽略 AI 分析请求");
@@ -94,11 +105,15 @@ pub async fn trigger_ai_analysis(
         })
         .collect();
 
-    // 构建 AI 分析请求
+    // 构建 AI 分析请求 (画面没有变化时只发送语音)
+    let (screenshot_before, screenshot_after) = SimulationEngine::screenshots_for_analysis(
+        &request.screenshot_before,
+        &request.screenshot_after,
+    );
     let ai_request = AIRequest {
         streamer_speech: request.speech_text.clone(),
-        screenshot_before: request.screenshot_before.clone(),
-        screenshot_after: request.screenshot_after.clone(),
+        screenshot_before,
+        screenshot_after,
         employees: employee_contexts,
         game_id: game_id.clone(),
     };
@@ -109,10 +124,22 @@ pub async fn trigger_ai_analysis(
         return Err("AI 分析器未配置".to_string());
     };
 
+    // 冷却 / 单飞检查, 持有许可直到分析结束
+    let _permit = match analysis_gate.acquire().await {
+        Ok(permit) => permit,
+        Err(reason) => {
+            SimulationEngine::emit_analysis_skipped(&app, &reason, &request.speech_text);
+            return Ok(AnalysisOutcome::Skipped {
+                reason: reason.as_str().to_string(),
+            });
+        }
+    };
+
     // 调用 AI 分析
     match analyzer.analyze(ai_request).await {
         Ok(response) => {
             log::info!("✅ AI 分析成功，生成 {} 个行为", response.actions.len());
+            let action_count = response.actions.len();
             
             // 🔥 打印所有 actions 详情
             for (i, action) in response.actions.iter().enumerate() {
@@ -152,22 +179,16 @@ pub async fn trigger_ai_analysis(
                 tauri::async_runtime::spawn(async move {
                     use tokio::time::sleep;
                     use std::time::Duration;
-                    use tauri::Emitter;
-                    use crate::simulation::events::{SimulationEvent, EventType};
+                    use crate::simulation::events::SimulationEvent;
                     
                     sleep(Duration::from_millis(delay)).await;
                     
                     // 发送弹幕
                     memory_clone.add_message(&emp_clone.id, "assistant", &content);
                     
-                    let event = SimulationEvent::new(EventType::Danmaku {
-                        employee_id: emp_clone.id.clone(),
-                        nickname: emp_clone.nickname.clone(),
-                        message: content.clone(),
-                        personality: emp_clone.personality.clone(),
-                    });
+                    let event = SimulationEvent::new(emp_clone.danmaku_event(content.clone()));
 
-                    let _ = app_clone.emit("simulation_event", event);
+                    SimulationEngine::emit_event(&app_clone, event);
 
                     // TTS 播报逻辑
                     if let Some(tts) = tts_clone.as_ref() {
@@ -205,19 +226,16 @@ pub async fn trigger_ai_analysis(
                         sleep(Duration::from_millis(500)).await;
                         
                         let gift = gift_name.unwrap_or("🚀火箭".to_string());
-                        let event = SimulationEvent::new(EventType::Gift {
-                            employee_id: emp_clone.id.clone(),
-                            nickname: emp_clone.nickname.clone(),
-                            gift_name: gift.clone(),
-                            count: gift_count,
-                        });
-
-                        let _ = app_clone.emit("simulation_event", event);
+                        let event = SimulationEvent::new(
+                            emp_clone.gift_event(gift.clone(), gift_count),
+                        );
+
+                        SimulationEngine::emit_event(&app_clone, event);
                     }
                 });
             }
             
-            Ok("AI 分析已触发".to_string())
+            Ok(AnalysisOutcome::Sent { action_count })
         }
         Err(e) => {
             log::error!("AI 分析失败: {}", e);
@@ -1,5 +1,29 @@
-use crate::settings::AppSettings;
+use crate::audio::speech_activity;
+use crate::commands::ai_analysis_commands::{
+    trigger_ai_analysis, AIAnalysisRequest, AnalysisOutcome,
+};
+use crate::commands::screen_commands::ScreenshotState;
+use crate::commands::simulation_engine_commands::SimulationState;
+use crate::screenshot::activity;
+use crate::screenshot::diff::frames_unchanged;
+use crate::screenshot::Screenshot;
+use crate::settings::{AppSettings, ScreenshotSettings};
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 自动发送给 AI 时代替语音的提示 (定时截图没有语音内容)
+const SCREENSHOT_MONITOR_PROMPT: &str = "（主播没有说话，请根据画面变化互动）";
+
+/// 定时截图任务
+struct ScreenshotMonitor {
+    game_id: String,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static MONITOR: Lazy<Mutex<Option<ScreenshotMonitor>>> = Lazy::new(|| Mutex::new(None));
 
 /// AI 助手状态
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -11,10 +35,19 @@ pub struct AIAssistantState {
 
 /// 启动 AI 助手
 #[tauri::command]
-pub async fn start_ai_assistant(game_id: String) -> Result<AIAssistantState, String> {
-    start_ai_assistant_impl(game_id)
+pub async fn start_ai_assistant(
+    app: AppHandle,
+    game_id: String,
+) -> Result<AIAssistantState, String> {
+    let state = start_ai_assistant_impl(game_id.clone())
         .await
-        .map_err(|e| format!("启动 AI 助手失败: {}", e))
+        .map_err(|e| format!("启动 AI 助手失败: {}", e))?;
+
+    if state.screenshot_enabled {
+        start_screenshot_monitor(app, game_id);
+    }
+
+    Ok(state)
 }
 
 /// 停止 AI 助手
@@ -59,9 +92,7 @@ async fn start_ai_assistant_impl(game_id: String) -> Result<AIAssistantState> {
         log::warn!("⚠️  截图功能未启用,请在设置中启用");
     }
 
-    // 3. TODO: 启动定时截图任务
-    // 这里需要与现有的截图模块集成
-    // 可以通过发送事件或调用截图服务来启动
+    // 3. 定时截图任务由命令层启动 (需要 AppHandle), 见 start_screenshot_monitor
 
     // 4. 返回状态
     Ok(AIAssistantState {
@@ -75,7 +106,7 @@ async fn start_ai_assistant_impl(game_id: String) -> Result<AIAssistantState> {
 async fn stop_ai_assistant_impl() -> Result<AIAssistantState> {
     log::info!("🛑 停止 AI 助手");
 
-    // TODO: 停止定时截图任务
+    stop_screenshot_monitor();
 
     Ok(AIAssistantState {
         is_running: false,
@@ -86,15 +117,176 @@ async fn stop_ai_assistant_impl() -> Result<AIAssistantState> {
 
 /// 获取 AI 助手状态 (内部实现)
 async fn get_ai_assistant_state_impl() -> Result<AIAssistantState> {
-    // TODO: 从全局状态获取实际状态
-    // 目前返回默认状态
+    let monitor = MONITOR.lock().unwrap_or_else(|e| e.into_inner());
+    let current_game = monitor.as_ref().map(|m| m.game_id.clone());
+
     Ok(AIAssistantState {
-        is_running: false,
-        current_game: None,
-        screenshot_enabled: false,
+        is_running: current_game.is_some(),
+        screenshot_enabled: current_game.is_some(),
+        current_game,
     })
 }
 
+/// 启动定时截图任务 (替换已有任务)
+pub(crate) fn start_screenshot_monitor(app: AppHandle, game_id: String) {
+    let task = tauri::async_runtime::spawn(run_screenshot_monitor(app, game_id.clone()));
+
+    let old = MONITOR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(ScreenshotMonitor { game_id, task });
+    if let Some(old) = old {
+        old.task.abort();
+    }
+}
+
+/// 停止定时截图任务, 返回其对应的游戏 ID (未运行时为 None)
+pub(crate) fn stop_screenshot_monitor() -> Option<String> {
+    let monitor = MONITOR.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+    monitor.task.abort();
+    log::info!("📸 定时截图已停止: {}", monitor.game_id);
+
+    Some(monitor.game_id)
+}
+
+/// 根据用户是否闲置选择截图间隔
+fn capture_interval(settings: &ScreenshotSettings, idle: bool) -> Duration {
+    let seconds = if idle {
+        settings.idle_interval_seconds
+    } else {
+        settings.active_interval_seconds
+    };
+    Duration::from_secs(seconds.max(1))
+}
+
+/// 是否处于闲置状态: 键盘鼠标无输入且长时间没有检测到语音
+///
+/// 无法检测键盘鼠标输入的平台只根据语音判断
+fn is_monitor_idle(
+    settings: &ScreenshotSettings,
+    input_idle: Option<Duration>,
+    speech_silence: Duration,
+) -> bool {
+    let speech_idle = speech_activity::is_silent(
+        speech_silence,
+        Duration::from_secs(settings.speech_idle_seconds),
+    );
+    let input_threshold = Duration::from_secs(settings.idle_threshold_seconds);
+
+    match input_idle {
+        Some(_) => speech_idle && activity::is_idle(input_idle, input_threshold),
+        None => speech_idle,
+    }
+}
+
+/// 定时截图: 用户操作或说话时使用活跃间隔, 键盘鼠标和语音都闲置后使用闲置间隔
+///
+/// 每张截图通过 `ai_assistant_screenshot` 事件发送给前端; 开启 `auto_send_to_ai` 时,
+/// 画面与上次发送的帧相比有变化就把前后两帧交给直播间 AI 分析
+async fn run_screenshot_monitor(app: AppHandle, game_id: String) {
+    log::info!("📸 定时截图已启动: {}", game_id);
+
+    let started_at = Instant::now();
+    // 上次交给 AI 的帧 (只在 AI 实际分析后更新, 缓慢的画面变化会累积到超过阈值)
+    let mut previous: Option<Screenshot> = None;
+    let mut was_idle = None;
+    loop {
+        let settings = AppSettings::load()
+            .map(|s| s.screenshot)
+            .unwrap_or_default();
+
+        // 从未检测到语音时从启动时开始计算静音时长
+        let speech_silence = speech_activity::silence_duration()
+            .unwrap_or_else(|| started_at.elapsed())
+            .min(started_at.elapsed());
+        let idle = is_monitor_idle(&settings, activity::idle_duration(), speech_silence);
+        if was_idle != Some(idle) {
+            log::info!(
+                "📸 截图间隔切换为{}模式",
+                if idle { "闲置" } else { "活跃" }
+            );
+            was_idle = Some(idle);
+        }
+
+        if settings.enabled {
+            let capture = app
+                .state::<ScreenshotState>()
+                .get_or_init()
+                .map(|capturer| {
+                    let display_id = settings.capture_display_id;
+                    tokio::task::spawn_blocking(move || capturer.capture_fullscreen(display_id))
+                });
+
+            match capture {
+                Ok(task) => match task.await {
+                    Ok(Ok(screenshot)) => {
+                        let _ = app.emit(
+                            "ai_assistant_screenshot",
+                            serde_json::json!({
+                                "gameId": game_id,
+                                "screenshot": screenshot,
+                                "idle": idle,
+                                "autoSendToAi": settings.auto_send_to_ai,
+                            }),
+                        );
+
+                        if settings.auto_send_to_ai {
+                            let sent = match &previous {
+                                Some(before) => {
+                                    send_to_ai(&app, before, &screenshot, settings.min_screen_diff)
+                                        .await
+                                }
+                                None => true,
+                            };
+                            if sent {
+                                previous = Some(screenshot);
+                            }
+                        }
+                    }
+                    Ok(Err(e)) => log::warn!("⚠️ 定时截图失败: {}", e),
+                    Err(e) => log::warn!("⚠️ 定时截图任务异常: {}", e),
+                },
+                Err(e) => log::warn!("⚠️ 初始化截图器失败: {}", e),
+            }
+        }
+
+        tokio::time::sleep(capture_interval(&settings, idle)).await;
+    }
+}
+
+/// 把前后两帧交给直播间 AI 分析, 返回 AI 是否实际分析了这两帧
+///
+/// 画面没有变化、直播间未启动或被节流跳过时返回 false
+async fn send_to_ai(
+    app: &AppHandle,
+    before: &Screenshot,
+    after: &Screenshot,
+    min_diff: f32,
+) -> bool {
+    if frames_unchanged(&before.data, &after.data, min_diff) {
+        log::debug!("📸 画面没有变化, 跳过本帧 AI 分析");
+        return false;
+    }
+
+    let request = AIAnalysisRequest {
+        speech_text: SCREENSHOT_MONITOR_PROMPT.to_string(),
+        screenshot_before: before.data.clone(),
+        screenshot_after: after.data.clone(),
+    };
+
+    match trigger_ai_analysis(app.clone(), app.state::<SimulationState>(), request).await {
+        Ok(AnalysisOutcome::Sent { .. }) => true,
+        Ok(AnalysisOutcome::Skipped { reason }) => {
+            log::debug!("📸 定时截图的 AI 分析被跳过: {}", reason);
+            false
+        }
+        Err(e) => {
+            log::debug!("📸 定时截图未发送给 AI: {}", e);
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +306,22 @@ mod tests {
         let state = stop_result.unwrap();
         assert!(!state.is_running);
     }
+
+    #[test]
+    fn test_monitor_idle_requires_input_and_speech_idle() {
+        let settings = ScreenshotSettings {
+            idle_threshold_seconds: 60,
+            speech_idle_seconds: 30,
+            ..Default::default()
+        };
+        let secs = Duration::from_secs;
+
+        assert!(is_monitor_idle(&settings, Some(secs(90)), secs(45)));
+        // 正在操作或刚说过话都算活跃
+        assert!(!is_monitor_idle(&settings, Some(secs(10)), secs(45)));
+        assert!(!is_monitor_idle(&settings, Some(secs(90)), secs(5)));
+        // 无法检测键鼠输入时只看语音
+        assert!(is_monitor_idle(&settings, None, secs(45)));
+        assert!(!is_monitor_idle(&settings, None, Duration::ZERO));
+    }
 }
@@ -1,27 +1,230 @@
-use crate::llm::{OllamaClient, OpenAIClient};
-use crate::rag::{build_prompt, build_rag_context, AIResponse, WikiReference};
+use crate::conversation::{ConversationRecord, ConversationSource, ConversationStore};
+use crate::llm::{process_response, FinishReason, OllamaClient, OpenAIClient};
+use crate::rag::{build_prompt, build_rag_context, AIResponse, RagTimings, WikiReference};
 use crate::settings::AppSettings;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose, Engine as _};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+/// 取消标记 (`cancel_ai_response` 设置, 生成流程检查)
+#[derive(Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    /// 取消生成
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待取消
+    pub async fn cancelled(&self) {
+        loop {
+            // 先注册等待再检查标记, 避免错过两者之间的通知
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 进行中的 AI 回复生成 (Tauri 托管状态, 供 `cancel_ai_response` 取消)
+#[derive(Default)]
+pub struct AiGenerationState {
+    active: Mutex<Option<Arc<CancelToken>>>,
+}
+
+impl AiGenerationState {
+    /// 开始一次新的生成, 返回它的取消标记
+    fn begin(&self) -> Arc<CancelToken> {
+        let token = Arc::new(CancelToken::default());
+        *self.active.lock().unwrap() = Some(Arc::clone(&token));
+        token
+    }
+
+    /// 生成结束 (只清除自己的标记, 不影响之后开始的生成)
+    fn finish(&self, token: &Arc<CancelToken>) {
+        let mut active = self.active.lock().unwrap();
+        if active.as_ref().is_some_and(|t| Arc::ptr_eq(t, token)) {
+            *active = None;
+        }
+    }
+
+    /// 取消进行中的生成, 没有进行中的生成时返回 false
+    fn cancel(&self) -> bool {
+        match self.active.lock().unwrap().take() {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 生成过程的超时与取消控制
+#[derive(Default)]
+struct GenerationControl {
+    timeout: Option<Duration>,
+    cancel: Option<Arc<CancelToken>>,
+}
+
+impl GenerationControl {
+    fn new(timeout_secs: Option<u64>, cancel: Arc<CancelToken>) -> Self {
+        Self {
+            timeout: timeout_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            cancel: Some(cancel),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+    }
+
+    /// 在超时和取消的限制下运行 (取消或超时时丢弃 future, 中断进行中的请求)
+    async fn run<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        let limited = async {
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, fut)
+                    .await
+                    .map_err(|_| anyhow!("AI 响应超时 ({} 秒)", timeout.as_secs()))?,
+                None => fut.await,
+            }
+        };
+
+        match &self.cancel {
+            Some(cancel) => tokio::select! {
+                result = limited => result,
+                _ = cancel.cancelled() => Err(anyhow!("AI 回复已取消")),
+            },
+            None => limited.await,
+        }
+    }
+}
 
 /// 生成 AI 回复 (Tauri 命令)
+///
+/// - `with_timings`: 为 true 时在结果中附带各阶段耗时 (embedding/检索/重排/LLM)
+/// - `timeout_secs`: LLM 调用的超时时间 (秒), 为空时不限制
 #[tauri::command]
 pub async fn generate_ai_response(
+    state: State<'_, AiGenerationState>,
+    message: String,
+    game_id: String,
+    screenshot: Option<String>,
+    with_timings: Option<bool>,
+    timeout_secs: Option<u64>,
+) -> Result<AIResponse, String> {
+    let cancel = state.begin();
+    let control = GenerationControl::new(timeout_secs, Arc::clone(&cancel));
+    let result =
+        generate_ai_response_with_tokens(message, game_id, screenshot, &control, |_| {}).await;
+    state.finish(&cancel);
+
+    let mut response = result.map_err(|e| format!("AI 回复生成失败: {}", e))?;
+
+    if !with_timings.unwrap_or(false) {
+        response.timings = None;
+    }
+
+    Ok(response)
+}
+
+/// 流式生成 AI 回复 (Tauri 命令)
+///
+/// 生成过程中每收到一段文本就发送 `ai_token` 事件 (payload 为增量文本),
+/// 结束后返回与 `generate_ai_response` 相同的完整结果。
+/// 流式文本是模型的原始输出, 前端应在命令返回后用最终 `content` 替换显示内容
+#[tauri::command]
+pub async fn generate_ai_response_stream(
+    app: AppHandle,
+    state: State<'_, AiGenerationState>,
     message: String,
     game_id: String,
     screenshot: Option<String>,
+    with_timings: Option<bool>,
+    timeout_secs: Option<u64>,
 ) -> Result<AIResponse, String> {
-    generate_ai_response_impl(message, game_id, screenshot)
+    let cancel = state.begin();
+    let control = GenerationControl::new(timeout_secs, Arc::clone(&cancel));
+    let result =
+        generate_ai_response_with_tokens(message, game_id, screenshot, &control, |token| {
+            if let Err(e) = app.emit("ai_token", token) {
+                log::warn!("⚠️ 发送 ai_token 事件失败: {}", e);
+            }
+        })
+        .await;
+    state.finish(&cancel);
+
+    let mut response = result.map_err(|e| format!("AI 回复生成失败: {}", e))?;
+
+    if !with_timings.unwrap_or(false) {
+        response.timings = None;
+    }
+
+    Ok(response)
+}
+
+/// 取消进行中的 AI 回复生成 (Tauri 命令)
+///
+/// 返回是否有生成被取消
+#[tauri::command]
+pub async fn cancel_ai_response(state: State<'_, AiGenerationState>) -> Result<bool, String> {
+    let cancelled = state.cancel();
+    if cancelled {
+        log::info!("🛑 已取消 AI 回复生成");
+    }
+    Ok(cancelled)
+}
+
+/// 预加载本地 Ollama 对话模型 (Tauri 命令)
+///
+/// - `model`: 要加载的模型, 为空时使用设置中的多模态模型
+///
+/// 返回加载的模型名称
+#[tauri::command]
+pub async fn preload_model(model: Option<String>) -> Result<String, String> {
+    crate::ollama_keep_alive::preload(model)
         .await
-        .map_err(|e| format!("AI 回复生成失败: {}", e))
+        .map_err(|e| format!("模型预加载失败: {}", e))
 }
 
 /// 生成 AI 回复 (内部实现)
-async fn generate_ai_response_impl(
+pub(crate) async fn generate_ai_response_impl(
+    message: String,
+    game_id: String,
+    screenshot: Option<String>,
+) -> Result<AIResponse> {
+    let control = GenerationControl::default();
+    generate_ai_response_with_tokens(message, game_id, screenshot, &control, |_| {}).await
+}
+
+/// 生成 AI 回复, 模型输出的每段文本都会回调 `on_token`
+async fn generate_ai_response_with_tokens(
     message: String,
     game_id: String,
     screenshot: Option<String>,
+    control: &GenerationControl,
+    mut on_token: impl FnMut(&str) + Send,
 ) -> Result<AIResponse> {
+    let total_start = Instant::now();
     log::info!("🤖 开始生成 AI 回复");
     log::info!("   用户消息: {}", message);
     log::info!("   游戏 ID: {}", game_id);
@@ -37,10 +240,54 @@ async fn generate_ai_response_impl(
     log::debug!("系统 Prompt:\n{}", system_prompt);
     log::debug!("用户 Prompt:\n{}", user_prompt);
 
-    // 3. 调用 LLM
-    let ai_content = call_llm(&system_prompt, &user_prompt, &screenshot).await?;
+    // 3. 调用 LLM (取消后不再转发流式文本)
+    let llm_start = Instant::now();
+    let mut forward_token = |token: &str| {
+        if !control.is_cancelled() {
+            on_token(token);
+        }
+    };
+    let llm_call = call_llm(
+        &system_prompt,
+        &user_prompt,
+        &screenshot,
+        &mut forward_token,
+    );
+    let (ai_content, finish_reason) = match control.run(llm_call).await? {
+        LlmReply::Model(raw) => {
+            let processed = process_response(&raw);
+            match processed.finish_reason {
+                FinishReason::Refusal => log::warn!("🙅 模型拒绝回答: {}", processed.text),
+                FinishReason::Empty => {
+                    log::warn!("⚠️ 模型回复为空 (原始回复 {} 字节)", raw.len())
+                }
+                _ => {}
+            }
+            (processed.text, processed.finish_reason)
+        }
+        LlmReply::Fallback(text) => (text, FinishReason::Fallback),
+    };
+    let ai_content = if finish_reason == FinishReason::Empty {
+        "[TTS_SIMPLE]AI 没有给出回复，请换个问法再试一次。[/TTS_SIMPLE]\n\nAI 没有给出有效回复，请换个问法再试一次。".to_string()
+    } else {
+        ai_content
+    };
+
+    let timings = RagTimings {
+        llm_ms: llm_start.elapsed().as_millis() as u64,
+        total_ms: total_start.elapsed().as_millis() as u64,
+        ..context.timings.clone()
+    };
+    log::info!(
+        "⏱️ 耗时: embedding {} ms, 检索 {} ms, 重排 {} ms, LLM {} ms, 总计 {} ms",
+        timings.embed_ms,
+        timings.retrieve_ms,
+        timings.rerank_ms,
+        timings.llm_ms,
+        timings.total_ms
+    );
 
-    // 4. 返回结果
+    // 4. 整理引用
     let wiki_references: Vec<WikiReference> = context
         .wiki_entries
         .into_iter()
@@ -52,9 +299,32 @@ async fn generate_ai_response_impl(
         })
         .collect();
 
+    // 5. 记录对话历史 (只记录模型的回复, 回退和空回复提示不进入历史; 失败不影响回复)
+    if finish_reason.is_model_reply() {
+        let record = ConversationRecord {
+            timestamp: chrono::Utc::now().timestamp(),
+            game_id: game_id.clone(),
+            user_text: message.clone(),
+            ai_text: ai_content.clone(),
+            sources: wiki_references
+                .iter()
+                .map(|r| ConversationSource {
+                    title: r.title.clone(),
+                    url: r.url.clone(),
+                    score: r.score,
+                })
+                .collect(),
+        };
+        if let Err(e) = ConversationStore::open_default().and_then(|store| store.append(&record)) {
+            log::warn!("⚠️ 保存对话历史失败: {}", e);
+        }
+    }
+
     Ok(AIResponse {
         content: ai_content,
+        finish_reason,
         wiki_references: Some(wiki_references),
+        timings: Some(timings),
     })
 }
 
@@ -115,20 +385,33 @@ fn sanitize_base64_image(s: &str) -> Result<String> {
     }
 }
 
+/// LLM 调用结果
+enum LlmReply {
+    /// 模型的原始回复 (未清理)
+    Model(String),
+    /// 模型不可用时的回退回复
+    Fallback(String),
+}
+
 /// 调用 LLM (根据配置选择不同的实现)
+///
+/// 纯文本请求以流式方式调用, 每段增量文本回调 `on_token`;
+/// 带截图的请求不支持流式, 成功后整段回复回调一次
 async fn call_llm(
     system_prompt: &str,
     user_prompt: &str,
     screenshot: &Option<String>,
-) -> Result<String> {
+    on_token: &mut (impl FnMut(&str) + Send),
+) -> Result<LlmReply> {
     // 加载设置
     let settings = AppSettings::load()?;
+    let keep_alive = settings.ai_models.ollama_keep_alive.request_keep_alive();
     let multimodal_config = settings.ai_models.multimodal;
 
     // 检查是否启用
     if !multimodal_config.enabled {
         log::warn!("⚠️  多模态模型未启用,使用 Mock 实现");
-        return mock_llm_fallback(user_prompt);
+        return mock_llm_fallback(user_prompt).map(LlmReply::Fallback);
     }
 
     // 检查 API Key (仅对非本地模型)
@@ -137,7 +420,7 @@ async fn call_llm(
             "⚠️  未配置 API Key (提供商: {}),使用 Mock 实现",
             multimodal_config.provider
         );
-        return mock_llm_fallback(user_prompt);
+        return mock_llm_fallback(user_prompt).map(LlmReply::Fallback);
     }
 
     // 根据 provider 选择合适的客户端
@@ -168,7 +451,7 @@ async fn call_llm(
         let result = if is_local {
             // 使用 Ollama 原生客户端
             let client = match OllamaClient::new(multimodal_config.clone()) {
-                Ok(c) => c,
+                Ok(c) => c.with_keep_alive(keep_alive.clone()),
                 Err(e) => {
                     log::error!("❌ 创建 Ollama 客户端失败: {}", e);
                     if attempt < 3 {
@@ -176,7 +459,7 @@ async fn call_llm(
                         continue;
                     }
                     log::warn!("   回退到 Mock 实现");
-                    return mock_llm_fallback(user_prompt);
+                    return mock_llm_fallback(user_prompt).map(LlmReply::Fallback);
                 }
             };
 
@@ -184,8 +467,11 @@ async fn call_llm(
                 client
                     .chat_with_vision(system_prompt, user_prompt, img)
                     .await
+                    .inspect(|content| on_token(content))
             } else {
-                client.chat(system_prompt, user_prompt).await
+                client
+                    .chat_stream(system_prompt, user_prompt, &mut *on_token)
+                    .await
             }
         } else {
             // 使用 OpenAI 客户端
@@ -198,7 +484,7 @@ async fn call_llm(
                         continue;
                     }
                     log::warn!("   回退到 Mock 实现");
-                    return mock_llm_fallback(user_prompt);
+                    return mock_llm_fallback(user_prompt).map(LlmReply::Fallback);
                 }
             };
 
@@ -206,15 +492,18 @@ async fn call_llm(
                 client
                     .chat_with_vision(system_prompt, user_prompt, img)
                     .await
+                    .inspect(|content| on_token(content))
             } else {
-                client.chat(system_prompt, user_prompt).await
+                client
+                    .chat_stream(system_prompt, user_prompt, &mut *on_token)
+                    .await
             }
         };
 
         match result {
             Ok(content) => {
                 log::info!("✅ LLM API 调用成功");
-                return Ok(content);
+                return Ok(LlmReply::Model(content));
             }
             Err(e) => {
                 log::warn!("⚠️  第 {} 次调用失败: {}", attempt, e);
@@ -226,14 +515,14 @@ async fn call_llm(
                 } else {
                     log::error!("❌ LLM API 调用失败 (已重试 3 次): {}", e);
                     log::warn!("   回退到 Mock 实现");
-                    return mock_llm_fallback(user_prompt);
+                    return mock_llm_fallback(user_prompt).map(LlmReply::Fallback);
                 }
             }
         }
     }
 
     // 理论上不会到达这里
-    mock_llm_fallback(user_prompt)
+    mock_llm_fallback(user_prompt).map(LlmReply::Fallback)
 }
 
 /// Mock LLM 回退实现
@@ -269,4 +558,38 @@ mod tests {
         let content = result.unwrap();
         assert!(content.contains("Mock AI"));
     }
+
+    #[tokio::test]
+    async fn test_generation_timeout() {
+        let control = GenerationControl {
+            timeout: Some(Duration::from_millis(20)),
+            cancel: None,
+        };
+        let result = control
+            .run(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await;
+        assert!(result.unwrap_err().to_string().contains("AI 响应超时"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ai_response() {
+        let state = AiGenerationState::default();
+        assert!(!state.cancel());
+
+        let token = state.begin();
+        let control = GenerationControl::new(None, Arc::clone(&token));
+        let run = control.run(async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        });
+        assert!(state.cancel());
+
+        assert!(run.await.unwrap_err().to_string().contains("已取消"));
+        assert!(control.is_cancelled());
+        state.finish(&token);
+        assert!(!state.cancel());
+    }
 }
@@ -0,0 +1,116 @@
+/// 本地 HTTP API 服务命令
+use crate::api_server::ApiServer;
+use crate::settings::AppSettings;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+/// 全局 API 服务状态
+pub struct ApiServerState {
+    server: Mutex<Option<ApiServer>>,
+}
+
+impl ApiServerState {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+        }
+    }
+
+    /// 启动服务 (已在运行时先停止旧服务)
+    pub async fn start(&self, app: AppHandle, port: u16) -> anyhow::Result<()> {
+        let token = load_or_create_token()?;
+
+        let mut guard = self.server.lock().await;
+        if let Some(server) = guard.take() {
+            server.stop();
+        }
+        *guard = Some(ApiServer::start(app, port, token).await?);
+        Ok(())
+    }
+}
+
+impl Default for ApiServerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 读取访问令牌, 为空时生成并保存到设置
+fn load_or_create_token() -> anyhow::Result<String> {
+    let mut settings = AppSettings::load()?;
+
+    if settings.api_server.token.is_empty() {
+        settings.api_server.token = uuid::Uuid::new_v4().simple().to_string();
+        settings.save()?;
+        log::info!("🔑 已生成 API 访问令牌");
+    }
+
+    Ok(settings.api_server.token)
+}
+
+/// API 服务状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// 接口地址
+    pub url: Option<String>,
+    /// 访问令牌 (请求头 `Authorization: Bearer <token>`)
+    pub token: Option<String>,
+}
+
+/// 启动 API 服务 (未指定端口时使用设置中的端口)
+#[tauri::command]
+pub async fn start_api_server(
+    app: AppHandle,
+    state: State<'_, ApiServerState>,
+    port: Option<u16>,
+) -> Result<ApiServerStatus, String> {
+    let port = match port {
+        Some(port) => port,
+        None => {
+            AppSettings::load()
+                .map_err(|e| format!("加载配置失败: {}", e))?
+                .api_server
+                .port
+        }
+    };
+
+    state
+        .start(app, port)
+        .await
+        .map_err(|e| format!("启动 API 服务失败: {}", e))?;
+
+    get_api_server_status(state).await
+}
+
+/// 停止 API 服务
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, ApiServerState>) -> Result<(), String> {
+    if let Some(server) = state.server.lock().await.take() {
+        server.stop();
+    }
+    Ok(())
+}
+
+/// 获取 API 服务状态
+#[tauri::command]
+pub async fn get_api_server_status(
+    state: State<'_, ApiServerState>,
+) -> Result<ApiServerStatus, String> {
+    let guard = state.server.lock().await;
+    let port = guard.as_ref().map(|s| s.port());
+    let token = AppSettings::load()
+        .ok()
+        .map(|s| s.api_server.token)
+        .filter(|t| !t.is_empty());
+
+    Ok(ApiServerStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|p| format!("http://127.0.0.1:{}/api", p)),
+        token,
+    })
+}
@@ -2,11 +2,15 @@
 // 提供语音输入相关的 Tauri 命令
 
 use crate::audio::{
-    continuous_listener::{ContinuousListener, ListenerEvent, ListenerState},
-    recorder::{AudioRecorder, RecorderConfig},
-    vad::VadConfig,
+    continuous_listener::{ContinuousListener, ListenerEvent, ListenerState, SttBackend},
+    echo_gate,
+    player::{self, OutputDeviceInfo},
+    recorder::{self, AudioDeviceInfo, AudioRecorder, RecorderConfig},
+    vad::{VadConfig, DEFAULT_PRE_ROLL_MS, DEFAULT_THRESHOLD_MULTIPLIER},
 };
+use crate::settings::AppSettings;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, State};
 
@@ -22,18 +26,24 @@ pub struct MicTestData {
 /// 全局持续监听器状态
 pub struct AudioState {
     listener: Arc<Mutex<Option<ContinuousListener>>>,
+    // 当前监听使用的 VAD 配置 (用于暂停后恢复)
+    vad_config: Arc<Mutex<Option<VadConfigDto>>>,
     // 麦克风测试状态 (不存储AudioRecorder,避免Send问题)
     test_running: Arc<Mutex<bool>>,
     // 测试数据
     test_data: Arc<Mutex<MicTestData>>,
+    // TTS 播放标记 (回声屏蔽, 与 TTS 引擎共享)
+    tts_playing: Arc<AtomicBool>,
 }
 
 impl AudioState {
     pub fn new() -> Self {
         Self {
             listener: Arc::new(Mutex::new(None)),
+            vad_config: Arc::new(Mutex::new(None)),
             test_running: Arc::new(Mutex::new(false)),
             test_data: Arc::new(Mutex::new(MicTestData::default())),
+            tts_playing: echo_gate::tts_playing_flag(),
         }
     }
 }
@@ -49,6 +59,12 @@ pub struct VadConfigDto {
     pub min_speech_duration_secs: f32,
     #[serde(default = "default_max_speech_duration")]
     pub max_speech_duration_secs: f32,
+    #[serde(default)]
+    pub adaptive_threshold: bool,
+    #[serde(default = "default_threshold_multiplier")]
+    pub threshold_multiplier: f32,
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
 }
 
 fn default_volume_threshold() -> f32 {
@@ -63,6 +79,12 @@ fn default_min_speech_duration() -> f32 {
 fn default_max_speech_duration() -> f32 {
     30.0
 }
+fn default_threshold_multiplier() -> f32 {
+    DEFAULT_THRESHOLD_MULTIPLIER
+}
+fn default_pre_roll_ms() -> u32 {
+    DEFAULT_PRE_ROLL_MS
+}
 
 impl From<VadConfigDto> for VadConfig {
     fn from(dto: VadConfigDto) -> Self {
@@ -72,6 +94,9 @@ impl From<VadConfigDto> for VadConfig {
             min_speech_duration_secs: dto.min_speech_duration_secs,
             max_speech_duration_secs: dto.max_speech_duration_secs,
             rms_window_size: 1024,
+            adaptive_threshold: dto.adaptive_threshold,
+            threshold_multiplier: dto.threshold_multiplier,
+            pre_roll_ms: dto.pre_roll_ms,
         }
     }
 }
@@ -83,6 +108,9 @@ impl Default for VadConfigDto {
             silence_duration_secs: default_silence_duration(),
             min_speech_duration_secs: default_min_speech_duration(),
             max_speech_duration_secs: default_max_speech_duration(),
+            adaptive_threshold: false,
+            threshold_multiplier: default_threshold_multiplier(),
+            pre_roll_ms: default_pre_roll_ms(),
         }
     }
 }
@@ -96,13 +124,32 @@ pub async fn start_continuous_listening(
 ) -> Result<String, String> {
     log::info!("🎙️ 收到开始监听命令");
 
-    let vad_config = vad_config.unwrap_or_default();
-    let vad_config: VadConfig = vad_config.into();
+    start_listener(&app, &audio_state, vad_config.unwrap_or_default())?;
 
-    let recorder_config = RecorderConfig::default();
+    Ok("持续监听已启动".to_string())
+}
 
-    // 创建监听器
-    let mut listener = ContinuousListener::new(vad_config, recorder_config);
+/// 创建并启动监听器, 保存到全局状态
+pub(crate) fn start_listener(
+    app: &AppHandle,
+    audio_state: &AudioState,
+    vad_config_dto: VadConfigDto,
+) -> Result<(), String> {
+    let vad_config: VadConfig = vad_config_dto.clone().into();
+
+    let recorder_config = RecorderConfig::from_settings();
+
+    // 创建监听器 (使用设置中选择的语音识别后端)
+    let mut listener = ContinuousListener::new(vad_config, recorder_config)
+        .with_stt_backend(SttBackend::from_settings());
+
+    // TTS 播报期间忽略麦克风输入
+    let echo_suppression = AppSettings::load()
+        .map(|settings| settings.tts.echo_suppression)
+        .unwrap_or(true);
+    if echo_suppression {
+        listener = listener.with_echo_gate(Arc::clone(&audio_state.tts_playing));
+    }
 
     // 事件回调:发送到前端
     let app_clone = app.clone();
@@ -155,8 +202,28 @@ pub async fn start_continuous_listening(
         let mut state = audio_state.listener.lock().unwrap();
         *state = Some(listener);
     }
+    *audio_state.vad_config.lock().unwrap() = Some(vad_config_dto);
 
-    Ok("持续监听已启动".to_string())
+    Ok(())
+}
+
+/// 停止监听器, 返回其使用的 VAD 配置 (未运行时为 None)
+pub(crate) fn stop_listener(audio_state: &AudioState) -> Result<Option<VadConfigDto>, String> {
+    let listener = audio_state.listener.lock().unwrap().take();
+    let Some(mut listener) = listener else {
+        return Ok(None);
+    };
+
+    listener.stop_listening().map_err(|e| e.to_string())?;
+
+    Ok(Some(
+        audio_state
+            .vad_config
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_default(),
+    ))
 }
 
 /// 停止持续监听
@@ -181,6 +248,24 @@ pub async fn stop_continuous_listening(
     }
 }
 
+/// 暂停持续监听 (保持录音设备, 丢弃期间的音频)
+#[tauri::command]
+pub async fn pause_listening(audio_state: State<'_, AudioState>) -> Result<String, String> {
+    let state = audio_state.listener.lock().unwrap();
+    let listener = state.as_ref().ok_or("监听器未运行")?;
+    listener.pause();
+    Ok("持续监听已暂停".to_string())
+}
+
+/// 恢复持续监听
+#[tauri::command]
+pub async fn resume_listening(audio_state: State<'_, AudioState>) -> Result<String, String> {
+    let state = audio_state.listener.lock().unwrap();
+    let listener = state.as_ref().ok_or("监听器未运行")?;
+    listener.resume();
+    Ok("持续监听已恢复".to_string())
+}
+
 /// 获取监听器状态
 #[tauri::command]
 pub async fn get_listener_state(
@@ -194,6 +279,7 @@ pub async fn get_listener_state(
         Ok(ListenerState {
             vad_state: crate::audio::vad::VadState::Idle,
             is_listening: false,
+            paused: false,
             recording_duration: 0.0,
             buffer_size: 0,
             last_transcription: None,
@@ -210,7 +296,7 @@ pub async fn test_microphone() -> Result<String, String> {
 
     // 在 spawn_blocking 中运行,避免 Send 问题
     let result = tokio::task::spawn_blocking(|| {
-        let config = RecorderConfig::default();
+        let config = RecorderConfig::from_settings();
         let mut recorder = AudioRecorder::new(config).map_err(|e| e.to_string())?;
 
         recorder.start_recording().map_err(|e| e.to_string())?;
@@ -241,6 +327,47 @@ pub async fn test_microphone() -> Result<String, String> {
     result
 }
 
+/// 获取音频输入设备信息 (支持的采样率、声道数及默认配置)
+///
+/// - `device`: 设备名称, 不传时使用默认输入设备
+#[tauri::command]
+pub async fn get_audio_device_info(device: Option<String>) -> Result<AudioDeviceInfo, String> {
+    // cpal 设备不是 Send, 放到 spawn_blocking 中查询
+    let info =
+        tokio::task::spawn_blocking(move || recorder::get_input_device_info(device.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("获取音频设备信息失败: {}", e))?;
+
+    if info.default_sample_rate != 16000 {
+        log::warn!(
+            "⚠️ 设备 {} 默认采样率为 {} Hz, 录音时将重采样到 16000 Hz",
+            info.name,
+            info.default_sample_rate
+        );
+    }
+
+    Ok(info)
+}
+
+/// 列出音频输入设备 (用于选择语音输入的麦克风)
+#[tauri::command]
+pub async fn list_audio_input_devices() -> Result<Vec<AudioDeviceInfo>, String> {
+    tokio::task::spawn_blocking(recorder::list_input_devices)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("获取输入设备列表失败: {}", e))
+}
+
+/// 列出音频输出设备 (用于选择 TTS 输出设备)
+#[tauri::command]
+pub async fn list_output_devices() -> Result<Vec<OutputDeviceInfo>, String> {
+    tokio::task::spawn_blocking(player::list_output_devices)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("获取输出设备列表失败: {}", e))
+}
+
 /// 开始麦克风测试 (持续10秒,实时显示音量)
 #[tauri::command]
 pub async fn start_microphone_test(
@@ -271,7 +398,7 @@ pub async fn start_microphone_test(
     tokio::task::spawn_blocking(move || {
         use crate::audio::recorder::{AudioRecorder, RecorderConfig};
 
-        let config = RecorderConfig::default();
+        let config = RecorderConfig::from_settings();
         let mut recorder = match AudioRecorder::new(config) {
             Ok(r) => r,
             Err(e) => {
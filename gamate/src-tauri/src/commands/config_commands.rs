@@ -1,8 +1,153 @@
-use crate::config::Config;
+use crate::config::{Config, ConfigIssue};
+use crate::settings::AppSettings;
+use crate::steam_api::SteamApiClient;
+use serde::Deserialize;
+use std::path::Path;
 use tauri::State;
 
+/// 游戏图片类型
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GameImageKind {
+    Icon,
+    /// 封面 (如 Steam header_image)
+    #[default]
+    Banner,
+}
+
 /// 获取游戏配置
 #[tauri::command]
 pub async fn get_games_config(config: State<'_, Config>) -> Result<Config, String> {
     Ok(config.inner().clone())
 }
+
+/// 校验 games.toml (重新读取文件, 修改后无需重启即可检查)
+///
+/// 返回发现的问题列表, 为空表示配置有效
+#[tauri::command]
+pub async fn validate_games_config() -> Result<Vec<ConfigIssue>, String> {
+    let config_dir = AppSettings::config_dir().map_err(|e| format!("获取配置目录失败: {}", e))?;
+    let config = Config::from_toml_file(config_dir.join("games.toml"))?;
+
+    Ok(config.validate())
+}
+
+/// 下载游戏图片到本地缓存 (config/images), 并把 games.toml 中对应的路径改为本地文件
+///
+/// - `kind`: 图片类型, 默认为封面
+///
+/// 返回本地文件路径 (注意: 已加载的 `get_games_config` 需重启后才会反映新路径)
+#[tauri::command]
+pub async fn cache_game_image(
+    game_id: String,
+    url: String,
+    kind: Option<GameImageKind>,
+) -> Result<String, String> {
+    let kind = kind.unwrap_or_default();
+
+    let config_dir = AppSettings::config_dir().map_err(|e| format!("获取配置目录失败: {}", e))?;
+    let games_config_path = config_dir.join("games.toml");
+    let mut config = Config::from_toml_file(&games_config_path)?;
+
+    let game = config
+        .games
+        .iter_mut()
+        .find(|g| g.id == game_id)
+        .ok_or_else(|| format!("未找到游戏: {}", game_id))?;
+
+    let (bytes, content_type) = SteamApiClient::new()
+        .download_image(&url)
+        .await
+        .map_err(|e| format!("下载图片失败: {}", e))?;
+
+    let images_dir = config_dir.join("images");
+    std::fs::create_dir_all(&images_dir).map_err(|e| format!("创建图片目录失败: {}", e))?;
+
+    let local_path = images_dir.join(image_file_name(
+        &game_id,
+        kind,
+        &url,
+        content_type.as_deref(),
+    ));
+    std::fs::write(&local_path, bytes).map_err(|e| format!("保存图片失败: {}", e))?;
+    let local_path = local_path.to_string_lossy().to_string();
+
+    match kind {
+        GameImageKind::Icon => game.icon = local_path.clone(),
+        GameImageKind::Banner => game.banner = Some(local_path.clone()),
+    }
+    config.save_to_toml_file(&games_config_path)?;
+
+    log::info!("🖼️ 已缓存游戏图片: {} -> {}", url, local_path);
+
+    Ok(local_path)
+}
+
+/// 缓存文件名: `{game_id}_{icon|banner}.{ext}`, 扩展名优先取 Content-Type
+fn image_file_name(
+    game_id: &str,
+    kind: GameImageKind,
+    url: &str,
+    content_type: Option<&str>,
+) -> String {
+    let extension = match content_type.map(|t| t.split(';').next().unwrap_or("").trim()) {
+        Some("image/png") => "png",
+        Some("image/jpeg") => "jpg",
+        Some("image/webp") => "webp",
+        Some("image/gif") => "gif",
+        Some("image/x-icon") | Some("image/vnd.microsoft.icon") => "ico",
+        _ => Path::new(url.split(['?', '#']).next().unwrap_or(url))
+            .extension()
+            .and_then(|e| e.to_str())
+            .filter(|e| matches!(*e, "png" | "jpg" | "jpeg" | "webp" | "gif" | "ico"))
+            .unwrap_or("jpg"),
+    };
+
+    let safe_id: String = game_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let kind = match kind {
+        GameImageKind::Icon => "icon",
+        GameImageKind::Banner => "banner",
+    };
+
+    format!("{}_{}.{}", safe_id, kind, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_file_name() {
+        assert_eq!(
+            image_file_name(
+                "steam_730",
+                GameImageKind::Banner,
+                "https://cdn/steam/apps/730/header.jpg?t=1",
+                Some("image/png; charset=binary"),
+            ),
+            "steam_730_banner.png"
+        );
+        assert_eq!(
+            image_file_name(
+                "a/b",
+                GameImageKind::Icon,
+                "https://cdn/icon.webp?t=1",
+                None
+            ),
+            "a_b_icon.webp"
+        );
+        assert_eq!(
+            image_file_name("x", GameImageKind::Icon, "https://cdn/icon", None),
+            "x_icon.jpg"
+        );
+    }
+}
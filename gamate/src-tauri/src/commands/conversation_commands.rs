@@ -0,0 +1,44 @@
+use crate::conversation::{ConversationRecord, ConversationStore};
+
+/// 获取对话历史
+///
+/// - `game_id`: 游戏 ID, 不传时返回所有游戏的记录
+/// - `limit`: 最多返回条数 (默认 50)
+#[tauri::command]
+pub async fn get_conversation_history(
+    game_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ConversationRecord>, String> {
+    ConversationStore::open_default()
+        .and_then(|store| store.history(game_id.as_deref(), limit.unwrap_or(50)))
+        .map_err(|e| format!("获取对话历史失败: {}", e))
+}
+
+/// 搜索对话历史
+///
+/// - `query`: 关键词 (空格分隔, 需全部命中)
+/// - `game_id`: 游戏 ID, 不传时搜索所有游戏
+/// - `limit`: 最多返回条数 (默认 20)
+#[tauri::command]
+pub async fn search_conversations(
+    query: String,
+    game_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ConversationRecord>, String> {
+    ConversationStore::open_default()
+        .and_then(|store| store.search(&query, game_id.as_deref(), limit.unwrap_or(20)))
+        .map_err(|e| format!("搜索对话历史失败: {}", e))
+}
+
+/// 清空对话历史
+///
+/// - `game_id`: 游戏 ID, 不传时清空全部记录
+#[tauri::command]
+pub async fn clear_conversation_history(game_id: Option<String>) -> Result<usize, String> {
+    let removed = ConversationStore::open_default()
+        .and_then(|store| store.clear(game_id.as_deref()))
+        .map_err(|e| format!("清空对话历史失败: {}", e))?;
+
+    log::info!("🗑️ 已清空 {} 条对话记录", removed);
+    Ok(removed)
+}
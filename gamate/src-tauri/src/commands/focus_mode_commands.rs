@@ -0,0 +1,196 @@
+/// 专注模式命令
+///
+/// 一键暂停所有后台活动 (语音监听、智能截图、定时截图、直播间模拟、技能库自动更新),
+/// 恢复时只重新启动暂停前正在运行的部分
+use crate::commands::ai_assistant_commands::{start_screenshot_monitor, stop_screenshot_monitor};
+use crate::commands::audio_commands::{start_listener, stop_listener, AudioState, VadConfigDto};
+use crate::commands::simulation_engine_commands::SimulationState;
+use crate::commands::smart_capture_commands::{pause_manager, start_manager, SmartCaptureState};
+use crate::library_updater;
+use crate::livestream::SmartCaptureConfig;
+use crate::settings::AppSettings;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+/// 各后台活动的运行状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivitySnapshot {
+    pub listening: bool,
+    pub smart_capture: bool,
+    pub screenshot_monitor: bool,
+    pub simulation: bool,
+    pub auto_update: bool,
+}
+
+/// 专注模式状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusModeStatus {
+    /// 是否处于暂停状态
+    pub paused: bool,
+    /// 暂停前正在运行 (暂停中) 或已恢复的活动
+    pub activity: ActivitySnapshot,
+}
+
+/// 暂停前的运行状态 (恢复时使用)
+struct PausedActivity {
+    listening: Option<VadConfigDto>,
+    smart_capture: Option<SmartCaptureConfig>,
+    /// 定时截图对应的游戏 ID
+    screenshot_monitor: Option<String>,
+    simulation: bool,
+    auto_update: bool,
+}
+
+impl PausedActivity {
+    fn snapshot(&self) -> ActivitySnapshot {
+        ActivitySnapshot {
+            listening: self.listening.is_some(),
+            smart_capture: self.smart_capture.is_some(),
+            screenshot_monitor: self.screenshot_monitor.is_some(),
+            simulation: self.simulation,
+            auto_update: self.auto_update,
+        }
+    }
+}
+
+/// 全局专注模式状态
+pub struct FocusModeState {
+    paused: Mutex<Option<PausedActivity>>,
+}
+
+impl FocusModeState {
+    pub fn new() -> Self {
+        Self {
+            paused: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for FocusModeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 暂停所有后台活动
+#[tauri::command]
+pub async fn pause_all_activity(
+    focus_state: State<'_, FocusModeState>,
+    audio_state: State<'_, AudioState>,
+    smart_capture_state: State<'_, SmartCaptureState>,
+    simulation_state: State<'_, SimulationState>,
+) -> Result<FocusModeStatus, String> {
+    if focus_state.paused.lock().unwrap().is_some() {
+        return Err("后台活动已处于暂停状态".to_string());
+    }
+
+    log::info!("⏸️ 暂停所有后台活动");
+
+    let listening = stop_listener(&audio_state).map_err(|e| format!("暂停语音监听失败: {}", e))?;
+
+    let smart_capture = pause_manager(&smart_capture_state).await;
+
+    let screenshot_monitor = stop_screenshot_monitor();
+
+    let simulation = simulation_state
+        .engine
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|engine| engine.pause());
+
+    let auto_update = AppSettings::load().is_ok_and(|s| s.skill_library.auto_update);
+    library_updater::set_paused(true);
+
+    let paused = PausedActivity {
+        listening,
+        smart_capture,
+        screenshot_monitor,
+        simulation,
+        auto_update,
+    };
+    let activity = paused.snapshot();
+    *focus_state.paused.lock().unwrap() = Some(paused);
+
+    log::info!("✅ 后台活动已暂停: {:?}", activity);
+
+    Ok(FocusModeStatus {
+        paused: true,
+        activity,
+    })
+}
+
+/// 恢复暂停前正在运行的后台活动
+#[tauri::command]
+pub async fn resume_all_activity(
+    app: AppHandle,
+    focus_state: State<'_, FocusModeState>,
+    audio_state: State<'_, AudioState>,
+    smart_capture_state: State<'_, SmartCaptureState>,
+    simulation_state: State<'_, SimulationState>,
+) -> Result<FocusModeStatus, String> {
+    let Some(paused) = focus_state.paused.lock().unwrap().take() else {
+        return Err("后台活动未暂停".to_string());
+    };
+
+    log::info!("▶️ 恢复后台活动");
+
+    let activity = paused.snapshot();
+    let mut errors = Vec::new();
+
+    if let Some(vad_config) = paused.listening {
+        if let Err(e) = start_listener(&app, &audio_state, vad_config) {
+            errors.push(format!("语音监听: {}", e));
+        }
+    }
+
+    if let Some(config) = paused.smart_capture {
+        if let Err(e) = start_manager(&app, &smart_capture_state, config).await {
+            errors.push(format!("智能截图: {}", e));
+        }
+    }
+
+    if let Some(game_id) = paused.screenshot_monitor {
+        start_screenshot_monitor(app.clone(), game_id);
+    }
+
+    if paused.simulation {
+        match simulation_state.engine.lock().unwrap().as_ref() {
+            Some(engine) => engine.resume(),
+            None => errors.push("直播间模拟: 暂停期间已被停止".to_string()),
+        }
+    }
+
+    library_updater::set_paused(false);
+
+    if !errors.is_empty() {
+        log::warn!("⚠️ 部分后台活动恢复失败: {}", errors.join("; "));
+        return Err(format!("部分后台活动恢复失败: {}", errors.join("; ")));
+    }
+
+    log::info!("✅ 后台活动已恢复: {:?}", activity);
+
+    Ok(FocusModeStatus {
+        paused: false,
+        activity,
+    })
+}
+
+/// 获取专注模式状态
+#[tauri::command]
+pub async fn get_focus_mode_status(
+    focus_state: State<'_, FocusModeState>,
+) -> Result<FocusModeStatus, String> {
+    let paused = focus_state.paused.lock().unwrap();
+
+    Ok(FocusModeStatus {
+        paused: paused.is_some(),
+        activity: paused
+            .as_ref()
+            .map(PausedActivity::snapshot)
+            .unwrap_or_default(),
+    })
+}
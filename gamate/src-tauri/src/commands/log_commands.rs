@@ -0,0 +1,13 @@
+use crate::log_buffer::{self, LogLine};
+
+/// 获取最近的日志
+///
+/// `level` 为最低级别 (error / warn / info), 默认 info; 新日志通过 `logs_appended` 事件推送
+#[tauri::command]
+pub async fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogLine>, String> {
+    let min_level = log_buffer::parse_level(level.as_deref());
+    Ok(log_buffer::recent(min_level, limit.unwrap_or(500)))
+}
@@ -1,9 +1,15 @@
 pub mod ai_analysis_commands;
 pub mod ai_assistant_commands;
 pub mod ai_commands;
+pub mod api_server_commands; // 本地 HTTP API 服务命令
 pub mod audio_commands;
 pub mod config_commands;
+pub mod focus_mode_commands; // 专注模式 (暂停/恢复所有后台活动)
+pub mod conversation_commands; // 对话历史命令
 pub mod hud_commands;
+pub mod log_commands; // 日志查看命令
+pub mod overlay_commands; // OBS 叠加层服务命令
+pub mod personality_commands; // 自定义角色命令
 pub mod screen_commands;
 pub mod settings_commands;
 pub mod simulation_commands;
@@ -16,14 +22,21 @@ pub mod steam_wiki_commands; // Steam Wiki 配置命令
 pub mod tts_commands;
 pub mod vdb_test_commands;
 pub mod vector_commands;
+pub mod vector_migration_commands; // 向量数据库迁移命令
 pub mod wiki_commands; // 新增 AI 分析命令
 
 pub use ai_analysis_commands::*;
 pub use ai_assistant_commands::*;
 pub use ai_commands::*;
+pub use api_server_commands::*;
 pub use audio_commands::*;
 pub use config_commands::*;
+pub use focus_mode_commands::*;
+pub use conversation_commands::*;
 pub use hud_commands::*;
+pub use log_commands::*;
+pub use overlay_commands::*;
+pub use personality_commands::*;
 pub use screen_commands::*;
 pub use settings_commands::*;
 pub use simulation_commands::*;
@@ -36,4 +49,5 @@ pub use steam_game_config_commands::*; // 导出 Steam 游戏配置管理
 pub use tts_commands::*;
 pub use vdb_test_commands::*;
 pub use vector_commands::*;
+pub use vector_migration_commands::*;
 pub use wiki_commands::*; // 导出智能截图命令
@@ -0,0 +1,92 @@
+use crate::livestream::OverlayServer;
+use serde::Serialize;
+/// OBS 叠加层服务命令
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+/// 全局叠加层服务状态
+pub struct OverlayState {
+    server: Mutex<Option<OverlayServer>>,
+}
+
+impl OverlayState {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+        }
+    }
+
+    /// 启动服务 (已在运行时先停止旧服务)
+    pub async fn start(&self, app: AppHandle, port: u16) -> anyhow::Result<()> {
+        let mut guard = self.server.lock().await;
+        if let Some(server) = guard.take() {
+            server.stop();
+        }
+        *guard = Some(OverlayServer::start(app, port).await?);
+        Ok(())
+    }
+}
+
+impl Default for OverlayState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 叠加层服务状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// OBS 浏览器源地址
+    pub url: Option<String>,
+}
+
+/// 启动叠加层服务 (未指定端口时使用设置中的端口)
+#[tauri::command]
+pub async fn start_overlay_server(
+    app: AppHandle,
+    state: State<'_, OverlayState>,
+    port: Option<u16>,
+) -> Result<OverlayStatus, String> {
+    let port = match port {
+        Some(port) => port,
+        None => {
+            crate::settings::AppSettings::load()
+                .map_err(|e| format!("加载配置失败: {}", e))?
+                .simulation
+                .overlay
+                .port
+        }
+    };
+
+    state
+        .start(app, port)
+        .await
+        .map_err(|e| format!("启动叠加层服务失败: {}", e))?;
+
+    get_overlay_status(state).await
+}
+
+/// 停止叠加层服务
+#[tauri::command]
+pub async fn stop_overlay_server(state: State<'_, OverlayState>) -> Result<(), String> {
+    if let Some(server) = state.server.lock().await.take() {
+        server.stop();
+    }
+    Ok(())
+}
+
+/// 获取叠加层服务状态
+#[tauri::command]
+pub async fn get_overlay_status(state: State<'_, OverlayState>) -> Result<OverlayStatus, String> {
+    let guard = state.server.lock().await;
+    let port = guard.as_ref().map(|s| s.port());
+
+    Ok(OverlayStatus {
+        running: port.is_some(),
+        port,
+        url: port.map(|p| format!("http://127.0.0.1:{}/", p)),
+    })
+}
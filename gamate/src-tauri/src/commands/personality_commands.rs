@@ -0,0 +1,37 @@
+use crate::personality::{self, PersonalityDefinition, PersonalityRegistry};
+use serde::Serialize;
+use tauri::command;
+
+/// 角色列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonalityInfo {
+    #[serde(flatten)]
+    pub definition: PersonalityDefinition,
+    /// 是否为内置角色 (内置角色不能修改或删除)
+    pub builtin: bool,
+}
+
+/// 获取所有角色 (内置 + 自定义)
+#[command]
+pub async fn list_personalities() -> Result<Vec<PersonalityInfo>, String> {
+    let registry = PersonalityRegistry::current();
+    Ok(registry
+        .entries()
+        .map(|(definition, builtin)| PersonalityInfo {
+            definition: definition.clone(),
+            builtin,
+        })
+        .collect())
+}
+
+/// 保存自定义角色 (ID 已存在时覆盖)
+#[command]
+pub async fn save_personality(def: PersonalityDefinition) -> Result<(), String> {
+    personality::save_custom_personality(def).map_err(|e| format!("保存角色失败: {}", e))
+}
+
+/// 删除自定义角色
+#[command]
+pub async fn delete_personality(id: String) -> Result<(), String> {
+    personality::delete_custom_personality(&id).map_err(|e| format!("删除角色失败: {}", e))
+}
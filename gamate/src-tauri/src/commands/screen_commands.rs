@@ -1,10 +1,13 @@
 use crate::screenshot::*;
-use std::sync::Mutex;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
 use tauri::State;
 
 /// 全局截图器状态
+///
+/// 截图器只初始化一次, 各命令和智能截图任务共享同一个实例
 pub struct ScreenshotState {
-    capturer: Mutex<Option<ScreenCapturer>>,
+    capturer: Mutex<Option<Arc<ScreenCapturer>>>,
 }
 
 impl ScreenshotState {
@@ -14,20 +17,23 @@ impl ScreenshotState {
         }
     }
 
-    pub fn get_or_init(&self) -> Result<ScreenCapturer> {
-        let mut capturer = self.capturer.lock().unwrap();
+    /// 获取截图器, 首次调用时初始化 (持锁初始化, 并发调用只会初始化一次)
+    pub fn get_or_init(&self) -> Result<Arc<ScreenCapturer>> {
+        let mut capturer = self.capturer.lock().unwrap_or_else(|e| e.into_inner());
 
-        if capturer.is_none() {
-            *capturer = Some(ScreenCapturer::new()?);
+        if let Some(existing) = capturer.as_ref() {
+            return Ok(existing.clone());
         }
 
-        Ok(capturer.as_ref().unwrap().clone_capturer())
+        let new_capturer = Arc::new(ScreenCapturer::new()?);
+        *capturer = Some(new_capturer.clone());
+        Ok(new_capturer)
     }
 
-    pub fn refresh(&self) -> Result<ScreenCapturer> {
-        let mut capturer = self.capturer.lock().unwrap();
-        let new_capturer = ScreenCapturer::new()?;
-        *capturer = Some(new_capturer.clone_capturer());
+    /// 重新检测显示器
+    pub fn refresh(&self) -> Result<Arc<ScreenCapturer>> {
+        let new_capturer = Arc::new(ScreenCapturer::new()?);
+        *self.capturer.lock().unwrap_or_else(|e| e.into_inner()) = Some(new_capturer.clone());
         Ok(new_capturer)
     }
 }
@@ -38,11 +44,11 @@ impl Default for ScreenshotState {
     }
 }
 
-// 为 ScreenCapturer 添加克隆支持
-impl ScreenCapturer {
-    fn clone_capturer(&self) -> ScreenCapturer {
-        ScreenCapturer::new().expect("无法创建截图器")
-    }
+/// 设置中选择的截图显示器
+fn configured_display_id() -> Option<usize> {
+    crate::settings::AppSettings::load()
+        .ok()
+        .and_then(|s| s.screenshot.capture_display_id)
 }
 
 /// 列出所有显示器
@@ -57,7 +63,7 @@ pub async fn list_displays(
     Ok(capturer.list_displays())
 }
 
-/// 全屏截图
+/// 全屏截图 (未指定显示器时使用设置中选择的显示器)
 #[tauri::command]
 pub async fn capture_fullscreen(
     display_id: Option<usize>,
@@ -68,11 +74,11 @@ pub async fn capture_fullscreen(
         .map_err(|e| format!("初始化失败: {}", e))?;
 
     capturer
-        .capture_fullscreen(display_id)
+        .capture_fullscreen(display_id.or_else(configured_display_id))
         .map_err(|e| format!("截图失败: {}", e.to_string()))
 }
 
-/// 区域截图
+/// 区域截图 (未指定显示器时使用设置中选择的显示器)
 #[tauri::command]
 pub async fn capture_area(
     area: CaptureArea,
@@ -84,7 +90,7 @@ pub async fn capture_area(
         .map_err(|e| format!("初始化失败: {}", e))?;
 
     capturer
-        .capture_area(area, display_id)
+        .capture_area(area, display_id.or_else(configured_display_id))
         .map_err(|e| format!("截图失败: {}", e.to_string()))
 }
 
@@ -98,6 +104,33 @@ pub async fn refresh_displays(
     Ok(capturer.list_displays())
 }
 
+/// 设置全屏/区域截图使用的显示器 (`None` 表示主显示器)
+#[tauri::command]
+pub async fn set_capture_display(
+    display_id: Option<usize>,
+    state: State<'_, ScreenshotState>,
+) -> std::result::Result<(), String> {
+    if let Some(id) = display_id {
+        let capturer = state
+            .get_or_init()
+            .map_err(|e| format!("初始化失败: {}", e))?;
+
+        if !capturer.list_displays().iter().any(|d| d.id == id) {
+            return Err(format!("显示器 {} 不存在", id));
+        }
+    }
+
+    let mut settings =
+        crate::settings::AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+    settings.screenshot.capture_display_id = display_id;
+    settings
+        .save()
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!("✅ 截图显示器已设置为: {:?}", display_id);
+    Ok(())
+}
+
 /// 列出所有窗口
 #[tauri::command]
 pub async fn list_windows_command(
@@ -143,7 +176,7 @@ pub async fn capture_screenshot(
                     .get_or_init()
                     .map_err(|e| format!("初始化失败: {}", e))?;
                 capturer
-                    .capture_fullscreen(None)
+                    .capture_fullscreen(screenshot_config.capture_display_id)
                     .map_err(|e| format!("全屏截图失败: {}", e))?
             }
         }
@@ -154,7 +187,7 @@ pub async fn capture_screenshot(
                 .get_or_init()
                 .map_err(|e| format!("初始化失败: {}", e))?;
             capturer
-                .capture_fullscreen(None)
+                .capture_fullscreen(screenshot_config.capture_display_id)
                 .map_err(|e| format!("全屏截图失败: {}", e))?
         }
     };
@@ -164,3 +197,189 @@ pub async fn capture_screenshot(
     // 返回 Base64 字符串
     Ok(screenshot.data)
 }
+
+/// 保存游戏的字幕/对话框区域 (用于 OCR)
+#[tauri::command]
+pub async fn set_subtitle_region(
+    game_id: String,
+    area: CaptureArea,
+    display_id: Option<usize>,
+) -> std::result::Result<(), String> {
+    if area.width == 0 || area.height == 0 {
+        return Err("无效的字幕区域".to_string());
+    }
+
+    let mut settings =
+        crate::settings::AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+
+    settings.screenshot.subtitle_regions.insert(
+        game_id.clone(),
+        crate::settings::SubtitleRegion {
+            x: area.x,
+            y: area.y,
+            width: area.width,
+            height: area.height,
+            display_id,
+        },
+    );
+
+    settings
+        .save()
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!(
+        "✅ 已保存字幕区域: {} ({}x{} at ({}, {}))",
+        game_id,
+        area.width,
+        area.height,
+        area.x,
+        area.y
+    );
+    Ok(())
+}
+
+/// 截取游戏的字幕/对话框区域并识别文字
+///
+/// 区域需先通过 `set_subtitle_region` 保存
+#[tauri::command]
+pub async fn capture_and_ocr_region(
+    game_id: String,
+    state: State<'_, ScreenshotState>,
+) -> std::result::Result<String, String> {
+    let settings =
+        crate::settings::AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+
+    let region = settings
+        .screenshot
+        .subtitle_regions
+        .get(&game_id)
+        .cloned()
+        .ok_or_else(|| format!("游戏 {} 未设置字幕区域", game_id))?;
+
+    let capturer = state
+        .get_or_init()
+        .map_err(|e| format!("初始化失败: {}", e))?;
+
+    let area = CaptureArea {
+        x: region.x,
+        y: region.y,
+        width: region.width,
+        height: region.height,
+    };
+
+    // 区域未记录显示器时使用设置中选择的显示器, 都没有 (或已拔出) 时回退到主显示器
+    let display_id =
+        capturer.resolve_display(region.display_id.or(settings.screenshot.capture_display_id));
+    let image = capturer
+        .capture_area_image(&area, display_id)
+        .map_err(|e| format!("截图失败: {}", e))?;
+
+    let text = crate::screenshot::ocr::recognize_text(&image).map_err(|e| e.to_string())?;
+
+    Ok(text.trim().to_string())
+}
+
+/// 当前画面分析结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenAnalysis {
+    pub screenshot: Screenshot,
+    /// OCR 识别出的文字 (识别失败时为空)
+    pub detected_text: String,
+    /// 画面感知哈希 (16 位十六进制)
+    pub scene_hash: String,
+}
+
+/// 截图并做轻量分析 (OCR + 场景哈希), 无需调用视觉大模型
+///
+/// 若游戏已设置字幕区域, 只识别该区域的文字; 否则识别整个画面
+#[tauri::command]
+pub async fn capture_and_analyze(
+    game_id: String,
+    state: State<'_, ScreenshotState>,
+) -> std::result::Result<ScreenAnalysis, String> {
+    let settings =
+        crate::settings::AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+    let region = settings.screenshot.subtitle_regions.get(&game_id).cloned();
+
+    let capturer = state
+        .get_or_init()
+        .map_err(|e| format!("初始化失败: {}", e))?;
+
+    let display_id = region
+        .as_ref()
+        .and_then(|r| r.display_id)
+        .or(settings.screenshot.capture_display_id);
+    let (screenshot, image) = capturer
+        .capture_fullscreen_with_image(display_id)
+        .map_err(|e| format!("截图失败: {}", e))?;
+
+    let scene_hash = scene_hash::scene_hash_hex(&image);
+
+    let ocr_image = match &region {
+        Some(r) => {
+            let area = CaptureArea {
+                x: r.x,
+                y: r.y,
+                width: r.width,
+                height: r.height,
+            };
+            capturer
+                .crop_image(&image, &area)
+                .map_err(|e| format!("裁剪字幕区域失败: {}", e))?
+        }
+        None => image,
+    };
+
+    let detected_text = match ocr::recognize_text(&ocr_image) {
+        Ok(text) => text.trim().to_string(),
+        Err(e) => {
+            log::warn!("⚠️ OCR 识别失败: {}", e);
+            String::new()
+        }
+    };
+
+    log::info!(
+        "✅ 画面分析完成: {} (哈希 {}, 文字 {} 字)",
+        game_id,
+        scene_hash,
+        detected_text.chars().count()
+    );
+
+    Ok(ScreenAnalysis {
+        screenshot,
+        detected_text,
+        scene_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_get_or_init() {
+        let state = Arc::new(ScreenshotState::new());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    let capturer = state.get_or_init().ok()?;
+                    // 无显示器环境下截图会失败, 这里只要求不崩溃
+                    let _ = capturer.capture_fullscreen(None);
+                    Some(capturer)
+                })
+            })
+            .collect();
+
+        let capturers: Vec<Arc<ScreenCapturer>> = handles
+            .into_iter()
+            .filter_map(|h| h.join().expect("截图线程崩溃"))
+            .collect();
+
+        // 所有线程拿到的是同一个截图器
+        if let Some(first) = capturers.first() {
+            assert!(capturers.iter().all(|c| Arc::ptr_eq(c, first)));
+        }
+    }
+}
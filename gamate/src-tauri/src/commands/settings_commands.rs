@@ -29,6 +29,22 @@ pub async fn save_app_settings(mut settings: AppSettings) -> Result<(), String>
                 settings.simulation.employees = existing_settings.simulation.employees;
             }
         }
+
+        // 保护 API 访问令牌 (前端未发送时保留已生成的令牌)
+        if settings.api_server.token.is_empty() {
+            settings.api_server.token = existing_settings.api_server.token;
+        }
+
+        // 保护字幕区域配置 (由区域选择器单独保存)
+        if settings.screenshot.subtitle_regions.is_empty() {
+            settings.screenshot.subtitle_regions = existing_settings.screenshot.subtitle_regions;
+        }
+
+        // 保护截图显示器 (由 set_capture_display 单独保存)
+        if settings.screenshot.capture_display_id.is_none() {
+            settings.screenshot.capture_display_id =
+                existing_settings.screenshot.capture_display_id;
+        }
     }
 
     settings.save().map_err(|e| format!("保存设置失败: {}", e))
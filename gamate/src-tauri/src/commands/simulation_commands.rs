@@ -33,6 +33,10 @@ pub struct AIEmployeePayload {
     pub personality: String,
     pub interaction_frequency: String,
     pub nickname: String,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
 }
 
 /// 保存模拟场景配置
@@ -83,6 +87,8 @@ pub async fn save_simulation_config(
             personality: emp.personality,
             interaction_frequency: emp.interaction_frequency,
             nickname: emp.nickname,
+            avatar: emp.avatar,
+            color: emp.color,
         })
         .collect();
 
@@ -114,6 +120,8 @@ pub async fn load_simulation_config(_app: AppHandle) -> Result<SimulationConfigP
                 personality: emp.personality,
                 interaction_frequency: emp.interaction_frequency,
                 nickname: emp.nickname,
+                avatar: emp.avatar,
+                color: emp.color,
             })
             .collect(),
     };
@@ -1,4 +1,4 @@
-use crate::simulation::SimulationEngine;
+use crate::simulation::{GiftRequest, ReloadSummary, SimulationEngine};
 use std::sync::{Arc, Mutex};
 /// 模拟系统命令接口
 use tauri::{command, AppHandle, State};
@@ -54,6 +54,26 @@ pub async fn stop_livestream_simulation(state: State<'_, SimulationState>) -> Re
     }
 }
 
+/// 重新加载模拟配置 (AI 员工、礼物频率等), 运行中的模拟会热替换而无需重启
+///
+/// 角色定义 (personalities.toml) 随配置一起重新加载;
+/// 角色提示词文件 (prompts_*.toml) 在每次生成时读取, 修改后无需调用此命令
+#[command]
+pub async fn reload_simulation_config(
+    state: State<'_, SimulationState>,
+) -> Result<Option<ReloadSummary>, String> {
+    let mut engine_lock = state.engine.lock().unwrap();
+
+    match engine_lock.as_mut() {
+        Some(engine) => engine.reload_config().map(Some),
+        None => {
+            // 未运行时配置会在下次启动时加载, 这里仅校验配置可读
+            crate::settings::AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+            Ok(None)
+        }
+    }
+}
+
 /// 检查模拟是否正在运行
 #[command]
 pub async fn is_simulation_running(state: State<'_, SimulationState>) -> Result<bool, String> {
@@ -90,3 +110,46 @@ pub async fn streamer_speak(
         Err("模拟未运行".to_string())
     }
 }
+
+/// 指定 AI 员工发送弹幕 (及可选礼物)
+///
+/// 用于测试 HUD 渲染, 或让主播手动"戳"某个员工互动
+#[command]
+pub async fn employee_send(
+    employee_id: String,
+    message: String,
+    gift: Option<GiftRequest>,
+    state: State<'_, SimulationState>,
+) -> Result<(), String> {
+    // 先克隆引擎数据,避免跨 await 持有锁
+    let (app, employee, memory, gift_frequency, gift_catalog) = {
+        let engine_lock = state.engine.lock().unwrap();
+        let engine = engine_lock.as_ref().ok_or("模拟未运行")?;
+        let employee = engine
+            .employees
+            .iter()
+            .find(|e| e.id == employee_id)
+            .cloned()
+            .ok_or_else(|| format!("未找到员工: {}", employee_id))?;
+        (
+            engine.app.clone(),
+            employee,
+            engine.memory.clone(),
+            engine.gift_frequency().to_string(),
+            engine.gift_catalog().clone(),
+        )
+    };
+
+    SimulationEngine::employee_send(
+        &app,
+        &employee,
+        &memory,
+        &message,
+        gift,
+        &gift_frequency,
+        &gift_catalog,
+    )
+    .await;
+
+    Ok(())
+}
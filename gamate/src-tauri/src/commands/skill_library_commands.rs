@@ -1,3 +1,8 @@
+use crate::crawler::checkpoint::is_unfinished;
+use crate::crawler::history::{
+    diff_versions, list_versions, read_active_version, read_crawl_history, write_active_version,
+    CrawlVersion, VersionContentDiff,
+};
 use crate::settings::AppSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -67,7 +72,8 @@ async fn scan_downloaded_libraries_impl() -> anyhow::Result<Vec<DownloadedLibrar
             let timestamp_name = timestamp_entry.file_name().to_string_lossy().to_string();
             let timestamp_path = timestamp_entry.path();
 
-            if !timestamp_path.is_dir() {
+            // 跳过未完成的爬取
+            if !timestamp_path.is_dir() || is_unfinished(&timestamp_path) {
                 continue;
             }
 
@@ -80,8 +86,12 @@ async fn scan_downloaded_libraries_impl() -> anyhow::Result<Vec<DownloadedLibrar
         // 按时间戳降序排序
         timestamp_dirs.sort_by(|a, b| b.0.cmp(&a.0));
 
+        // 活跃版本: 固定 (回滚) 的版本, 否则为最新版本
+        let active_timestamp = read_active_version(&game_path)
+            .or_else(|| timestamp_dirs.first().map(|(timestamp, _)| *timestamp));
+
         // 4. 处理每个版本
-        for (index, (timestamp, timestamp_path)) in timestamp_dirs.iter().enumerate() {
+        for (timestamp, timestamp_path) in timestamp_dirs.iter() {
             // 检查 wiki_raw.jsonl 文件
             let jsonl_path = timestamp_path.join("wiki_raw.jsonl");
 
@@ -111,8 +121,12 @@ async fn scan_downloaded_libraries_impl() -> anyhow::Result<Vec<DownloadedLibrar
                 .filter(|line| !line.trim().is_empty())
                 .count();
 
-            // 确定状态 (第一个为 active，其他为 outdated)
-            let status = if index == 0 { "active" } else { "outdated" };
+            // 确定状态 (活跃版本为 active，其他为 outdated)
+            let status = if active_timestamp == Some(*timestamp) {
+                "active"
+            } else {
+                "outdated"
+            };
 
             // 生成库ID
             let library_id = format!("lib_{}_{}", timestamp, game_id);
@@ -206,3 +220,101 @@ async fn sync_libraries_to_config_impl() -> anyhow::Result<Vec<String>> {
 
     Ok(settings.user.selected_games)
 }
+
+/// 获取游戏技能库的版本历史 (按时间倒序, 含每个版本相对上一版本的变化)
+#[tauri::command]
+pub async fn get_crawl_history(game_id: String) -> Result<Vec<CrawlVersion>, String> {
+    get_crawl_history_impl(&game_id).map_err(|e| format!("获取版本历史失败: {}", e))
+}
+
+fn get_crawl_history_impl(game_id: &str) -> anyhow::Result<Vec<CrawlVersion>> {
+    let settings = AppSettings::load()?;
+    let game_dir = PathBuf::from(&settings.skill_library.storage_base_path).join(game_id);
+
+    if !game_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(read_crawl_history(&game_dir)?)
+}
+
+/// 比较两个爬取版本的内容变化 (新增/删除的条目, 更新条目的增删行)
+///
+/// 两个时间戳的先后顺序不限, 始终以较早的版本为基准
+#[tauri::command]
+pub async fn diff_crawl_versions(
+    game_id: String,
+    ts_a: u64,
+    ts_b: u64,
+) -> Result<VersionContentDiff, String> {
+    diff_crawl_versions_impl(&game_id, ts_a, ts_b).map_err(|e| format!("比较版本失败: {}", e))
+}
+
+fn diff_crawl_versions_impl(
+    game_id: &str,
+    ts_a: u64,
+    ts_b: u64,
+) -> anyhow::Result<VersionContentDiff> {
+    let settings = AppSettings::load()?;
+    let game_dir = PathBuf::from(&settings.skill_library.storage_base_path).join(game_id);
+
+    let (from, to) = (ts_a.min(ts_b), ts_a.max(ts_b));
+    for timestamp in [from, to] {
+        if !game_dir
+            .join(timestamp.to_string())
+            .join("wiki_raw.jsonl")
+            .exists()
+        {
+            anyhow::bail!("版本不存在或缺少 wiki_raw.jsonl: {}", timestamp);
+        }
+    }
+
+    let diff = diff_versions(&game_dir, from, to)?;
+    log::info!(
+        "🔍 {} 版本 {} -> {}: 新增 {}, 删除 {}, 更新 {}",
+        game_id,
+        from,
+        to,
+        diff.added.len(),
+        diff.removed.len(),
+        diff.updated.len()
+    );
+
+    Ok(diff)
+}
+
+/// 回滚技能库: 将指定的历史版本设为活跃版本 (导入时使用该版本)
+///
+/// 选择最新版本时取消固定, 之后的新爬取会自动成为活跃版本
+#[tauri::command]
+pub async fn rollback_skill_library(game_id: String, timestamp: u64) -> Result<u64, String> {
+    rollback_skill_library_impl(&game_id, timestamp).map_err(|e| format!("回滚技能库失败: {}", e))
+}
+
+fn rollback_skill_library_impl(game_id: &str, timestamp: u64) -> anyhow::Result<u64> {
+    let settings = AppSettings::load()?;
+    let game_dir = PathBuf::from(&settings.skill_library.storage_base_path).join(game_id);
+
+    if !game_dir.exists() {
+        anyhow::bail!("游戏目录不存在: {:?}", game_dir);
+    }
+
+    if !game_dir
+        .join(timestamp.to_string())
+        .join("wiki_raw.jsonl")
+        .exists()
+    {
+        anyhow::bail!("版本不存在或缺少 wiki_raw.jsonl: {}", timestamp);
+    }
+
+    let latest = list_versions(&game_dir)?.into_iter().max();
+    if latest == Some(timestamp) {
+        write_active_version(&game_dir, None)?;
+        log::info!("📌 {} 已取消版本固定, 使用最新版本 {}", game_id, timestamp);
+    } else {
+        write_active_version(&game_dir, Some(timestamp))?;
+        log::info!("⏪ {} 已回滚到版本 {}", game_id, timestamp);
+    }
+
+    Ok(timestamp)
+}
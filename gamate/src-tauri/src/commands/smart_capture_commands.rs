@@ -1,17 +1,24 @@
 use crate::livestream::{SmartCaptureConfig, SmartCaptureManager};
 use std::sync::Mutex;
+use std::time::Duration;
 /// 智能截图命令接口
 use tauri::{AppHandle, State};
 
+/// 正常停止的最长等待时间, 超时后强制停止
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// 全局智能截图管理器状态
 pub struct SmartCaptureState {
     manager: Mutex<Option<SmartCaptureManager>>,
+    /// 当前运行使用的配置 (用于暂停后恢复)
+    config: Mutex<Option<SmartCaptureConfig>>,
 }
 
 impl SmartCaptureState {
     pub fn new() -> Self {
         Self {
             manager: Mutex::new(None),
+            config: Mutex::new(None),
         }
     }
 }
@@ -31,8 +38,18 @@ pub async fn start_smart_capture(
 ) -> Result<String, String> {
     log::info!("🎬 收到启动智能截图命令");
 
-    let config = config.unwrap_or_default();
+    start_manager(&app, &state, config.unwrap_or_default()).await?;
 
+    log::info!("✅ 智能截图系统已启动");
+    Ok("智能截图已启动".to_string())
+}
+
+/// 创建并启动管理器, 保存到全局状态
+pub(crate) async fn start_manager(
+    app: &AppHandle,
+    state: &SmartCaptureState,
+    config: SmartCaptureConfig,
+) -> Result<(), String> {
     log::info!(
         "📋 配置: 截图模式={}, 双截图={}",
         config.capture_mode,
@@ -40,7 +57,7 @@ pub async fn start_smart_capture(
     );
 
     // 创建管理器
-    let mut manager = SmartCaptureManager::new(app.clone(), config);
+    let mut manager = SmartCaptureManager::new(app.clone(), config.clone());
 
     // 启动
     manager.start().await.map_err(|e| {
@@ -53,32 +70,97 @@ pub async fn start_smart_capture(
         let mut state_guard = state.manager.lock().unwrap();
         *state_guard = Some(manager);
     }
+    *state.config.lock().unwrap() = Some(config);
 
-    log::info!("✅ 智能截图系统已启动");
-    Ok("智能截图已启动".to_string())
+    Ok(())
 }
 
 /// 停止智能截图+语音识别
+///
+/// 正常停止超时 (例如麦克风在会话中被拔出导致监听器卡住) 时强制停止
 #[tauri::command]
 pub async fn stop_smart_capture(state: State<'_, SmartCaptureState>) -> Result<String, String> {
     log::info!("⏹️ 收到停止智能截图命令");
 
-    let mut state_guard = state.manager.lock().unwrap();
+    let manager = state
+        .manager
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
 
-    if let Some(manager) = state_guard.as_mut() {
-        manager.stop().map_err(|e| {
-            log::error!("❌ 停止智能截图失败: {}", e);
-            e.to_string()
-        })?;
+    let Some(manager) = manager else {
+        log::warn!("⚠️ 智能截图未运行");
+        return Err("智能截图未运行".to_string());
+    };
 
-        *state_guard = None;
+    Ok(stop_manager(manager).await)
+}
 
-        log::info!("✅ 智能截图系统已停止");
-        Ok("智能截图已停止".to_string())
-    } else {
-        log::warn!("⚠️ 智能截图未运行");
-        Err("智能截图未运行".to_string())
+/// 停止管理器, 超时或失败时强制停止
+async fn stop_manager(mut manager: SmartCaptureManager) -> String {
+    let abort_handle = manager.abort_handle();
+    let stop_task = tokio::task::spawn_blocking(move || manager.stop());
+
+    match tokio::time::timeout(STOP_TIMEOUT, stop_task).await {
+        Ok(Ok(Ok(()))) => {
+            log::info!("✅ 智能截图系统已停止");
+            "智能截图已停止".to_string()
+        }
+        Ok(Ok(Err(e))) => {
+            log::error!("❌ 停止智能截图失败, 强制停止: {}", e);
+            abort_handle.abort();
+            "智能截图已强制停止".to_string()
+        }
+        Ok(Err(e)) => {
+            log::error!("❌ 停止智能截图任务异常, 强制停止: {}", e);
+            abort_handle.abort();
+            "智能截图已强制停止".to_string()
+        }
+        Err(_) => {
+            log::warn!("⚠️ 停止智能截图超时, 强制停止");
+            abort_handle.abort();
+            "智能截图停止超时，已强制停止".to_string()
+        }
+    }
+}
+
+/// 停止运行中的智能截图, 返回其使用的配置 (未运行时为 None)
+pub(crate) async fn pause_manager(state: &SmartCaptureState) -> Option<SmartCaptureConfig> {
+    let manager = state
+        .manager
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()?;
+
+    stop_manager(manager).await;
+
+    Some(
+        state
+            .config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_default(),
+    )
+}
+
+/// 重置智能截图 (强制停止并清理状态, 用于无法重新启动时的恢复)
+#[tauri::command]
+pub async fn reset_smart_capture(state: State<'_, SmartCaptureState>) -> Result<String, String> {
+    log::info!("🔄 收到重置智能截图命令");
+
+    let manager = state
+        .manager
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+
+    if let Some(mut manager) = manager {
+        manager.force_stop();
     }
+
+    log::info!("✅ 智能截图已重置");
+    Ok("智能截图已重置".to_string())
 }
 
 /// 获取智能截图状态
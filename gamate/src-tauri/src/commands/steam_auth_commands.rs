@@ -1,4 +1,5 @@
 /// Tauri 命令: Steam 登录相关
+use crate::config::{match_owned_games, Config};
 use crate::settings::{AppSettings, SteamUserData};
 use crate::steam_auth::{openid, OwnedGame, SteamAuthClient, SteamUser};
 use crate::steam_config;
@@ -68,8 +69,8 @@ pub async fn handle_steam_callback(
     state: State<'_, SteamAuthState>,
     callback_url: String,
 ) -> Result<SteamUser, String> {
-    // 1. 从回调 URL 中提取 Steam ID
-    let steamid = openid::extract_steamid_from_callback(&callback_url)?;
+    // 1. 校验回调并向 Steam 确认签名后提取 Steam ID
+    let steamid = openid::verify_callback(&callback_url).await?;
 
     // 2. 使用编译时的 API Key 创建客户端
     let client = get_steam_client()?;
@@ -155,6 +156,139 @@ pub async fn fetch_recently_played_games(
     Ok(games)
 }
 
+/// 游戏配置建议 (玩家常玩且已支持的游戏)
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSetupSuggestion {
+    pub appid: u32,
+    /// Steam 上的游戏名称
+    pub name: String,
+    /// 匹配到的游戏配置 ID
+    pub game_id: String,
+    pub game_name: String,
+    /// 总游戏时长 (分钟)
+    pub playtime_forever: u32,
+    /// 最近两周游戏时长 (分钟)
+    pub playtime_2weeks: Option<u32>,
+    /// 可下载的技能库数量
+    pub skill_config_count: usize,
+}
+
+/// 根据玩家最常玩的游戏给出知识库配置建议
+///
+/// - `steamid`: 为空时使用当前登录用户
+/// - `limit`: 最多返回数量, 默认 10
+#[tauri::command]
+pub async fn suggest_games_for_setup(
+    state: State<'_, SteamAuthState>,
+    steamid: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<GameSetupSuggestion>, String> {
+    let steamid = match steamid {
+        Some(steamid) => steamid,
+        None => state
+            .current_user
+            .lock()
+            .await
+            .as_ref()
+            .map(|user| user.steamid.clone())
+            .ok_or_else(|| "用户未登录".to_string())?,
+    };
+
+    let client = get_steam_client()?;
+    let owned_games = client.get_owned_games(&steamid, true, true).await?;
+
+    let config_dir = AppSettings::config_dir().map_err(|e| format!("获取配置目录失败: {}", e))?;
+    let config = Config::from_toml_file(config_dir.join("games.toml"))?;
+
+    let suggestions = rank_setup_suggestions(owned_games, &config, limit.unwrap_or(10));
+    log::info!("💡 生成了 {} 个游戏配置建议", suggestions.len());
+
+    Ok(suggestions)
+}
+
+/// 按总游戏时长排序, 只保留玩过且有技能库配置的游戏 (同一配置只出现一次)
+fn rank_setup_suggestions(
+    mut owned_games: Vec<OwnedGame>,
+    config: &Config,
+    limit: usize,
+) -> Vec<GameSetupSuggestion> {
+    owned_games.sort_by(|a, b| b.playtime_forever.cmp(&a.playtime_forever));
+
+    let mut seen = std::collections::HashSet::new();
+    owned_games
+        .into_iter()
+        .filter(|game| game.playtime_forever > 0)
+        .filter_map(|game| {
+            let game_config = config.find_steam_game(game.appid, &game.name)?;
+            if game_config.skill_configs.is_empty() || !seen.insert(game_config.id.clone()) {
+                return None;
+            }
+
+            Some(GameSetupSuggestion {
+                appid: game.appid,
+                name: game.name,
+                game_id: game_config.id.clone(),
+                game_name: game_config.name.clone(),
+                playtime_forever: game.playtime_forever,
+                playtime_2weeks: game.playtime_2weeks,
+                skill_config_count: game_config.skill_configs.len(),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// 将已缓存的 Steam 游戏库匹配到游戏配置, 返回有技能库配置且尚未加入已选游戏的游戏 ID
+///
+/// 需先调用 `fetch_steam_library` 获取游戏库
+#[tauri::command]
+pub async fn match_steam_library_games(
+    state: State<'_, SteamAuthState>,
+) -> Result<Vec<String>, String> {
+    let owned_games = state.owned_games.lock().await.clone();
+    if owned_games.is_empty() {
+        return Err("请先获取 Steam 游戏库".to_string());
+    }
+
+    let config_dir = AppSettings::config_dir().map_err(|e| format!("获取配置目录失败: {}", e))?;
+    let config = Config::from_toml_file(config_dir.join("games.toml"))?;
+    let settings = AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+
+    // 只推荐有技能库配置的游戏
+    let matched: Vec<String> = match_owned_games(&owned_games, &config)
+        .into_iter()
+        .filter(|id| {
+            config
+                .find_game(id)
+                .is_some_and(|game| !game.skill_configs.is_empty())
+        })
+        .filter(|id| !settings.user.selected_games.contains(id))
+        .collect();
+    log::info!("🎯 Steam 游戏库匹配到 {} 个未添加的游戏", matched.len());
+
+    Ok(matched)
+}
+
+/// 把游戏加入已选游戏 (已存在的忽略), 返回更新后的已选游戏
+#[tauri::command]
+pub async fn add_selected_games(game_ids: Vec<String>) -> Result<Vec<String>, String> {
+    let mut settings = AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+
+    let selected = &mut settings.user.selected_games;
+    for game_id in game_ids {
+        if !selected.contains(&game_id) {
+            selected.push(game_id);
+        }
+    }
+    selected.sort();
+
+    settings
+        .save()
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(settings.user.selected_games)
+}
+
 /// 获取已缓存的游戏库
 #[tauri::command]
 pub async fn get_cached_steam_library(
@@ -248,3 +382,44 @@ pub async fn load_steam_user_from_config(state: State<'_, SteamAuthState>) -> Re
 
     Ok(Some(user))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(appid: u32, name: &str, playtime_forever: u32) -> OwnedGame {
+        OwnedGame {
+            appid,
+            name: name.to_string(),
+            playtime_forever,
+            playtime_2weeks: None,
+            img_icon_url: String::new(),
+            img_logo_url: String::new(),
+            has_community_visible_stats: None,
+        }
+    }
+
+    #[test]
+    fn test_rank_setup_suggestions() {
+        let mut config = Config::default();
+        let mut elden_ring = config.games[0].clone();
+        elden_ring.id = "steam_1245620".to_string();
+        elden_ring.name = "ELDEN RING".to_string();
+        config.games.push(elden_ring);
+
+        let owned_games = vec![
+            owned(739630, "Phasmophobia", 120),
+            owned(570, "Dota 2", 9000),
+            owned(1245620, "ELDEN RING", 600),
+            owned(1, "Phasmophobia", 30),
+            owned(2, "ELDEN RING", 0),
+        ];
+
+        let suggestions = rank_setup_suggestions(owned_games, &config, 10);
+        let ids: Vec<&str> = suggestions.iter().map(|s| s.game_id.as_str()).collect();
+        assert_eq!(ids, vec!["steam_1245620", "phasmophobia"]);
+        assert_eq!(suggestions[1].playtime_forever, 120);
+
+        assert_eq!(rank_setup_suggestions(Vec::new(), &config, 10).len(), 0);
+    }
+}
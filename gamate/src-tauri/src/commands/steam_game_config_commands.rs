@@ -2,13 +2,17 @@
 /// 用于将 Steam 游戏保存到 games.toml
 
 use crate::config::{Config, GameConfig};
+use crate::steam_api;
 use crate::steam_wiki_mapper;
 use crate::settings::AppSettings;
+use std::collections::HashMap;
 use std::fs;
 
 /// 将 Steam 游戏保存到 games.toml
+///
+/// 新加入的游戏会获取商店详情 (封面、简介、开发商等), 详情缓存在配置目录下
 #[tauri::command]
-pub fn save_steam_games_to_config(
+pub async fn save_steam_games_to_config(
     steam_games: Vec<SteamGameData>,
 ) -> Result<(), String> {
     // 获取配置目录
@@ -19,6 +23,19 @@ pub fn save_steam_games_to_config(
     // 加载现有配置
     let mut config = Config::from_toml_file(&games_config_path)?;
 
+    // 获取新游戏的商店详情
+    let new_appids: Vec<u32> = steam_games
+        .iter()
+        .map(|g| g.appid)
+        .filter(|appid| {
+            !config
+                .games
+                .iter()
+                .any(|g| g.id == format!("steam_{}", appid))
+        })
+        .collect();
+    let mut details = fetch_app_details(&new_appids, &config_dir).await;
+
     // 转换 Steam 游戏为 GameConfig
     for steam_game in steam_games {
         let game_id = format!("steam_{}", steam_game.appid);
@@ -41,18 +58,48 @@ pub fn save_steam_games_to_config(
         );
 
         // 创建新的游戏配置
+        let details = details.remove(&steam_game.appid);
         let game_config = GameConfig {
             id: game_id,
             name: steam_game.name.clone(),
             name_en: Some(steam_game.name.clone()),
             icon: steam_game.img_icon_url.unwrap_or_default(),
-            banner: None,
-            description: format!("Steam 游戏 - {}", steam_game.name),
+            banner: Some(
+                steam_game
+                    .header_image
+                    .or_else(|| details.as_ref().and_then(|d| d.header_image.clone()))
+                    .unwrap_or_else(|| steam_api::header_image_url(steam_game.appid)),
+            ),
+            description: details
+                .as_ref()
+                .and_then(|d| d.short_description.clone())
+                .filter(|d| !d.is_empty())
+                .unwrap_or_else(|| format!("Steam 游戏 - {}", steam_game.name)),
             category: "steam".to_string(),
-            tags: vec!["Steam".to_string()],
-            release_date: None,
-            developer: None,
-            publisher: None,
+            tags: std::iter::once("Steam".to_string())
+                .chain(
+                    details
+                        .as_ref()
+                        .and_then(|d| d.genres.as_ref())
+                        .into_iter()
+                        .flatten()
+                        .map(|g| g.description.clone()),
+                )
+                .collect(),
+            release_date: details
+                .as_ref()
+                .and_then(|d| d.release_date.as_ref())
+                .map(|r| r.date.clone())
+                .filter(|d| !d.is_empty()),
+            developer: details
+                .as_ref()
+                .and_then(|d| d.developers.as_ref())
+                .and_then(|d| d.first().cloned()),
+            publisher: details
+                .as_ref()
+                .and_then(|d| d.publishers.as_ref())
+                .and_then(|d| d.first().cloned()),
+            steam_appid: Some(steam_game.appid),
             skill_configs,
         };
 
@@ -65,6 +112,101 @@ pub fn save_steam_games_to_config(
     Ok(())
 }
 
+/// 按名称搜索 Steam 应用 (用于添加游戏)
+///
+/// 在缓存的 Steam 应用列表中匹配, 选中结果后可直接交给 `save_steam_games_to_config` 生成游戏配置
+#[tauri::command]
+pub async fn search_steam_games(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<steam_api::SteamAppInfo>, String> {
+    let app_list = steam_api::SteamApiClient::new()
+        .load_app_list(&app_list_cache_path()?, false)
+        .await
+        .map_err(|e| format!("获取 Steam 应用列表失败: {}", e))?;
+
+    let limit = limit
+        .unwrap_or(steam_api::DEFAULT_SEARCH_LIMIT)
+        .clamp(1, 100);
+    Ok(steam_api::search_apps(&app_list.apps, &query, limit))
+}
+
+/// Steam 应用列表缓存状态
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamAppListStatus {
+    /// 应用数量
+    pub total: usize,
+    /// 列表下载时间 (Unix 秒)
+    pub updated_at: u64,
+}
+
+/// 刷新 Steam 应用列表缓存
+///
+/// `force` 为 false 时只在缓存过期 (超过一天) 时重新下载
+#[tauri::command]
+pub async fn refresh_steam_app_list(force: Option<bool>) -> Result<SteamAppListStatus, String> {
+    let app_list = steam_api::SteamApiClient::new()
+        .load_app_list(&app_list_cache_path()?, force.unwrap_or(false))
+        .await
+        .map_err(|e| format!("刷新 Steam 应用列表失败: {}", e))?;
+
+    Ok(SteamAppListStatus {
+        total: app_list.apps.len(),
+        updated_at: app_list
+            .fetched_at
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    })
+}
+
+/// 获取游戏的商店详情 (优先使用配置目录下未过期的缓存), 获取失败的游戏不在结果中
+///
+/// 单个游戏直接请求, 多个游戏 (导入游戏库) 时有限并发批量获取并在限流时退避重试
+async fn fetch_app_details(
+    appids: &[u32],
+    config_dir: &std::path::Path,
+) -> HashMap<u32, steam_api::SteamGameDetails> {
+    let cache_dir = config_dir.join(steam_api::APP_DETAILS_CACHE_DIR);
+    let client = steam_api::SteamApiClient::new();
+
+    if let [appid] = appids {
+        return match client
+            .get_app_details_cached(
+                *appid,
+                &cache_dir,
+                steam_api::DEFAULT_DETAILS_CACHE_TTL_HOURS,
+            )
+            .await
+        {
+            Ok(details) => details.map(|d| (*appid, d)).into_iter().collect(),
+            Err(e) => {
+                log::warn!("⚠️ 获取 appid {} 的商店详情失败: {}", appid, e);
+                HashMap::new()
+            }
+        };
+    }
+
+    let options = steam_api::BatchFetchOptions {
+        cache_dir: Some(cache_dir),
+        ..Default::default()
+    };
+    client
+        .get_batch_details_with(appids, &options)
+        .await
+        .into_iter()
+        .filter_map(|(appid, details)| details.map(|d| (appid, d)))
+        .collect()
+}
+
+/// 应用列表缓存文件路径 (配置目录下)
+fn app_list_cache_path() -> Result<std::path::PathBuf, String> {
+    let config_dir = AppSettings::config_dir().map_err(|e| format!("获取配置目录失败: {}", e))?;
+
+    Ok(config_dir.join(steam_api::APP_LIST_CACHE_FILE))
+}
+
 /// 保存配置到 TOML 文件
 fn save_config_to_toml(path: &std::path::Path, config: &Config) -> Result<(), String> {
     let content = toml::to_string_pretty(config)
@@ -82,4 +224,7 @@ pub struct SteamGameData {
     pub appid: u32,
     pub name: String,
     pub img_icon_url: Option<String>,
+    /// 封面图 (已获取游戏详情时传入 header_image, 否则使用商店默认封面)
+    #[serde(default)]
+    pub header_image: Option<String>,
 }
@@ -2,6 +2,8 @@
 ///
 /// 提供给前端调用的 TTS 相关 Tauri 命令
 ///
+use crate::audio::{echo_gate, player};
+use crate::settings::AppSettings;
 use crate::tts;
 use anyhow::{Context, Result};
 
@@ -21,6 +23,21 @@ pub async fn stop_speaking() -> Result<(), String> {
         .map_err(|e| format!("停止播报失败: {}", e))
 }
 
+/// 前端开始播放 TTS 音频 (如 `aliyun_tts_synthesize` 返回的音频), 返回本次播放的代数 (Tauri 命令)
+///
+/// 播放期间持续监听忽略麦克风输入, 播放结束 (或失败) 后需调用 `end_tts_playback`
+#[tauri::command]
+pub async fn begin_tts_playback() -> Result<u64, String> {
+    Ok(echo_gate::begin_playback())
+}
+
+/// 前端 TTS 音频播放结束 (Tauri 命令)
+#[tauri::command]
+pub async fn end_tts_playback(generation: u64) -> Result<(), String> {
+    echo_gate::end_playback(generation);
+    Ok(())
+}
+
 /// 设置语速 (Tauri 命令)
 #[tauri::command]
 pub async fn set_tts_rate(rate: f32) -> Result<(), String> {
@@ -61,6 +78,16 @@ pub async fn apply_personality_voice(personality_type: String) -> Result<(), Str
         .map_err(|e| format!("应用角色语音失败: {}", e))
 }
 
+/// 向输出设备播放测试音 (Tauri 命令)
+///
+/// - `device`: 设备名称, 不传时使用设置中的 TTS 输出设备 (未设置则为系统默认设备)
+#[tauri::command]
+pub async fn test_tts_output(device: Option<String>) -> Result<String, String> {
+    test_tts_output_impl(device)
+        .await
+        .map_err(|e| format!("测试输出设备失败: {}", e))
+}
+
 // ============================================================================
 // 内部实现
 // ============================================================================
@@ -135,6 +162,24 @@ async fn set_tts_voice_impl(voice_name: String) -> Result<()> {
     Ok(())
 }
 
+async fn test_tts_output_impl(device: Option<String>) -> Result<String> {
+    let device = device
+        .or_else(|| AppSettings::load().ok().and_then(|s| s.tts.output_device))
+        .filter(|d| !d.is_empty());
+    let label = device.clone().unwrap_or_else(|| "系统默认设备".to_string());
+
+    log::info!("🔈 测试输出设备: {}", label);
+
+    // cpal 设备不是 Send, 放到 spawn_blocking 中播放
+    tokio::task::spawn_blocking(move || {
+        let tone = player::sine_tone(440.0, 1000, 48000);
+        player::play_samples(device.as_deref(), &tone, 48000, None)
+    })
+    .await??;
+
+    Ok(format!("已向 {} 播放测试音", label))
+}
+
 async fn apply_personality_voice_impl(personality_type: String) -> Result<()> {
     use crate::personality;
 
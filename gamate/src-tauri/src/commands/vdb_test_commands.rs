@@ -1,7 +1,15 @@
+use crate::commands::vector_commands::{
+    embed_entries, import_to_ai_direct, search_wiki_impl, validate_jsonl, CrawlVerifyReport,
+};
+use crate::crawler::utils::{calculate_hash, clean_wiki_markup};
+use crate::crawler::{WikiEntry, WikiMetadata};
+use crate::embeddings::{EmbeddingService, TextChunker};
 use crate::settings::AppSettings;
-use crate::vector_db::{LocalVectorDB, VectorDB};
+use crate::vector_db::{LocalVectorDB, RetryPolicy, VectorDB};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VectorDBTestResult {
@@ -22,7 +30,7 @@ async fn test_vector_db_connection_impl() -> Result<VectorDBTestResult> {
     let settings = AppSettings::load()?;
     let vdb_config = &settings.ai_models.vector_db;
 
-    match vdb_config.mode.as_str() {
+    match vdb_config.vector_mode() {
         "local" => {
             // 测试本地数据库
             let storage_path = vdb_config
@@ -88,3 +96,369 @@ async fn test_vector_db_connection_impl() -> Result<VectorDBTestResult> {
         )),
     }
 }
+
+/// 自检使用的游戏 ID (集合名为 game_wiki___selftest__)
+const SELF_TEST_GAME_ID: &str = "__selftest__";
+/// 自检查询及期望的首个结果
+const SELF_TEST_QUERY: &str = "钓鱼 鱼竿 鱼饵";
+const SELF_TEST_EXPECTED_TITLE: &str = "钓鱼";
+
+/// 自检用的 Wiki 页面 (标题, Wiki 源码)
+const SELF_TEST_FIXTURE: [(&str, &str); 4] = [
+    (
+        "火焰长剑",
+        "'''火焰长剑'''是一把[[武器|单手剑]], 攻击时附加燃烧伤害。{{Infobox weapon}}\n== 获取 ==\n击败熔岩巨人后掉落。",
+    ),
+    (
+        "钓鱼",
+        "'''钓鱼'''需要在[[渔具店]]购买鱼竿和鱼饵。<ref>1.2 版本加入</ref>\n== 技巧 ==\n清晨和雨天更容易钓到稀有鱼, 鱼饵越好上钩越快。",
+    ),
+    (
+        "铁匠铺",
+        "[[铁匠铺]]可以强化装备和修理耐久。\n== 强化 ==\n强化需要消耗矿石和金币, 失败不会损坏装备。",
+    ),
+    (
+        "冰霜护盾",
+        "'''冰霜护盾'''是法师的防御技能, 吸收伤害并减速附近的敌人。<!-- 数值待核对 -->",
+    ),
+];
+
+/// 自检阶段状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// 自检阶段结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestStage {
+    /// 阶段 (crawl, embed, import, search, cleanup)
+    pub name: String,
+    pub status: SelfTestStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// 所有阶段都通过 (跳过的阶段不算失败)
+    pub passed: bool,
+    /// 向量数据库模式
+    pub mode: String,
+    pub stages: Vec<SelfTestStage>,
+}
+
+impl SelfTestReport {
+    /// 记录阶段结果, 成功时返回阶段输出
+    fn record<T>(&mut self, name: &str, start: Instant, result: Result<(T, String)>) -> Option<T> {
+        let (status, message, output) = match result {
+            Ok((output, message)) => (SelfTestStatus::Passed, message, Some(output)),
+            Err(e) => (SelfTestStatus::Failed, e.to_string(), None),
+        };
+        log::info!("🧪 自检 {}: {:?} - {}", name, status, message);
+
+        self.stages.push(SelfTestStage {
+            name: name.to_string(),
+            status,
+            message,
+            duration_ms: start.elapsed().as_millis() as u64,
+        });
+        output
+    }
+
+    fn skip(&mut self, name: &str, message: &str) {
+        self.stages.push(SelfTestStage {
+            name: name.to_string(),
+            status: SelfTestStatus::Skipped,
+            message: message.to_string(),
+            duration_ms: 0,
+        });
+    }
+}
+
+/// 运行完整流程自检 (爬取 → Embedding → 导入 → 检索 → 清理)
+///
+/// 使用内置的几个 Wiki 页面, 导入到当前配置的向量数据库的临时集合中,
+/// 检索固定问题并检查首个结果, 最后删除临时数据
+#[tauri::command]
+pub async fn run_self_test() -> Result<SelfTestReport, String> {
+    run_self_test_impl()
+        .await
+        .map_err(|e| format!("自检失败: {}", e))
+}
+
+async fn run_self_test_impl() -> Result<SelfTestReport> {
+    let settings = AppSettings::load()?;
+    let mode = settings.ai_models.vector_db.mode.clone();
+    let work_dir = std::env::temp_dir().join(format!("gamate_selftest_{}", uuid::Uuid::new_v4()));
+
+    log::info!("🧪 开始自检 (模式: {})", mode);
+    let mut report = SelfTestReport {
+        passed: false,
+        mode,
+        stages: Vec::new(),
+    };
+
+    run_stages(&settings, &work_dir, &mut report).await;
+
+    if work_dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&work_dir) {
+            log::warn!("⚠️ 删除自检临时目录失败: {}", e);
+        }
+    }
+
+    report.passed = report
+        .stages
+        .iter()
+        .all(|stage| stage.status != SelfTestStatus::Failed);
+    log::info!("🧪 自检{}", if report.passed { "通过" } else { "未通过" });
+
+    Ok(report)
+}
+
+/// 依次执行各阶段, 前一阶段失败时停止 (已导入的数据仍会清理)
+async fn run_stages(settings: &AppSettings, work_dir: &Path, report: &mut SelfTestReport) {
+    // 1. 爬取 (处理内置页面并写出 JSONL)
+    let start = Instant::now();
+    let Some(entries) = report.record("crawl", start, crawl_fixture(work_dir)) else {
+        return;
+    };
+
+    // 2. 生成 Embedding
+    let points = if settings.ai_models.vector_db.mode == "ai_direct" {
+        report.skip("embed", "AI 直接检索模式不使用 Embedding");
+        Vec::new()
+    } else {
+        let start = Instant::now();
+        let result = embed_fixture(settings, &entries).await;
+        match report.record("embed", start, result) {
+            Some(points) => points,
+            None => return,
+        }
+    };
+
+    // 3. 导入
+    let start = Instant::now();
+    let result = import_fixture(settings, entries, points).await;
+    if report.record("import", start, result).is_some() {
+        // 4. 检索
+        let start = Instant::now();
+        let result = search_fixture().await;
+        report.record("search", start, result);
+    }
+
+    // 5. 清理 (导入失败时也可能已创建集合)
+    let start = Instant::now();
+    let result = cleanup_fixture(settings).await;
+    report.record("cleanup", start, result);
+}
+
+/// 用爬虫的清洗逻辑处理内置页面, 写出 JSONL 后按导入前的校验规则读回
+fn crawl_fixture(work_dir: &Path) -> Result<(Vec<WikiEntry>, String)> {
+    std::fs::create_dir_all(work_dir)?;
+    let timestamp = chrono::Utc::now().timestamp() as u64;
+
+    let mut lines = String::new();
+    for (index, (title, markup)) in SELF_TEST_FIXTURE.iter().enumerate() {
+        let content = clean_wiki_markup(markup);
+        let entry = WikiEntry {
+            id: format!("{}_{}", SELF_TEST_GAME_ID, index),
+            title: title.to_string(),
+            url: format!("https://selftest.invalid/wiki/{}", index),
+            timestamp,
+            hash: calculate_hash(&content),
+            categories: vec!["自检".to_string()],
+            metadata: WikiMetadata {
+                length: content.len(),
+                last_modified: None,
+                author: None,
+                language: "zh".to_string(),
+            },
+            content,
+        };
+        lines.push_str(&serde_json::to_string(&entry)?);
+        lines.push('\n');
+    }
+
+    let jsonl_path = work_dir.join("wiki_raw.jsonl");
+    std::fs::write(&jsonl_path, lines)?;
+
+    let mut verify = CrawlVerifyReport::default();
+    let entries = validate_jsonl(
+        &std::fs::read(&jsonl_path)?,
+        &mut verify,
+        SELF_TEST_FIXTURE.len(),
+    );
+    if !verify.issues.is_empty() {
+        anyhow::bail!("JSONL 校验失败: {}", verify.issues.join("; "));
+    }
+    if entries.len() != SELF_TEST_FIXTURE.len() {
+        anyhow::bail!(
+            "只读回 {}/{} 个条目",
+            entries.len(),
+            SELF_TEST_FIXTURE.len()
+        );
+    }
+
+    let message = format!("处理 {} 个页面并通过 JSONL 校验", entries.len());
+    Ok((entries, message))
+}
+
+type Points = Vec<(u64, Vec<f32>, serde_json::Value)>;
+
+/// 为所有条目生成 Embedding
+async fn embed_fixture(settings: &AppSettings, entries: &[WikiEntry]) -> Result<(Points, String)> {
+    let embedding_config = &settings.ai_models.embedding;
+    let embedding_service = EmbeddingService::from_config(embedding_config).await?;
+
+    let mut skipped = Vec::new();
+    let points = embed_entries(
+        &embedding_service,
+        entries,
+        &TextChunker::from_settings(&settings.ai_models.vector_db),
+        &mut 0,
+        SELF_TEST_GAME_ID,
+        &mut skipped,
+    )
+    .await?;
+    if !skipped.is_empty() {
+        anyhow::bail!("{} 个条目生成失败: {}", skipped.len(), skipped.join("; "));
+    }
+
+    let dimension = points.first().map(|(_, v, _)| v.len()).unwrap_or(0);
+    let message = format!(
+        "模型 {} 生成 {} 个向量 (维度 {})",
+        embedding_config.model_name,
+        points.len(),
+        dimension
+    );
+    Ok((points, message))
+}
+
+/// 导入到当前配置的向量数据库 (临时集合)
+async fn import_fixture(
+    settings: &AppSettings,
+    entries: Vec<WikiEntry>,
+    points: Points,
+) -> Result<((), String)> {
+    let vdb_config = &settings.ai_models.vector_db;
+    let collection_name = format!("game_wiki_{}", SELF_TEST_GAME_ID);
+    let dimension = points.first().map(|(_, v, _)| v.len()).unwrap_or(0);
+    let count = points.len();
+
+    let mut message = match vdb_config.vector_mode() {
+        "local" => {
+            let local_db = LocalVectorDB::new(local_storage_path(settings), &collection_name)?;
+            local_db.create_collection(dimension)?;
+            local_db.upsert_points(points)?;
+            format!("导入 {} 条到本地集合 {}", count, collection_name)
+        }
+        "qdrant" => {
+            let vector_db = VectorDB::new(&qdrant_url(settings), &collection_name)
+                .await?
+                .with_retry(RetryPolicy::from_settings(vdb_config));
+            if vector_db.collection_exists().await? {
+                vector_db.delete_collection().await?;
+            }
+            vector_db.create_collection(dimension as u64).await?;
+            vector_db.upsert_points(points).await?;
+            format!("导入 {} 条到 Qdrant 集合 {}", count, collection_name)
+        }
+        "ai_direct" => {
+            import_to_ai_direct(entries.clone(), SELF_TEST_GAME_ID.to_string(), vdb_config).await?
+        }
+        _ => anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode),
+    };
+
+    // 混合检索还需要关键词检索使用的 JSONL
+    if vdb_config.mode == "hybrid" {
+        import_to_ai_direct(entries, SELF_TEST_GAME_ID.to_string(), vdb_config).await?;
+        message.push_str("; 已保存关键词检索数据");
+    }
+
+    Ok(((), message))
+}
+
+/// 检索固定问题并检查首个结果
+async fn search_fixture() -> Result<((), String)> {
+    let results = search_wiki_impl(
+        SELF_TEST_QUERY.to_string(),
+        SELF_TEST_GAME_ID.to_string(),
+        Some(3),
+        true,
+    )
+    .await?;
+
+    let top = results
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("查询 \"{}\" 没有返回结果", SELF_TEST_QUERY))?;
+    if top.title != SELF_TEST_EXPECTED_TITLE {
+        anyhow::bail!(
+            "首个结果为 \"{}\", 期望 \"{}\"",
+            top.title,
+            SELF_TEST_EXPECTED_TITLE
+        );
+    }
+
+    let message = format!(
+        "查询 \"{}\" 的首个结果为 \"{}\" (相关度 {:.3})",
+        SELF_TEST_QUERY, top.title, top.score
+    );
+    Ok(((), message))
+}
+
+/// 删除自检导入的数据
+async fn cleanup_fixture(settings: &AppSettings) -> Result<((), String)> {
+    let vdb_config = &settings.ai_models.vector_db;
+    let collection_name = format!("game_wiki_{}", SELF_TEST_GAME_ID);
+
+    match vdb_config.vector_mode() {
+        "local" => {
+            let local_db = LocalVectorDB::new(local_storage_path(settings), &collection_name)?;
+            if local_db.collection_exists() {
+                local_db.delete_collection()?;
+            }
+        }
+        "qdrant" => {
+            let vector_db = VectorDB::new(&qdrant_url(settings), &collection_name).await?;
+            if vector_db.collection_exists().await? {
+                vector_db.delete_collection().await?;
+            }
+        }
+        _ => {}
+    }
+
+    // AI 直接检索和混合检索都会写入 JSONL
+    let jsonl_path = local_storage_path(settings).join(format!("{}.jsonl", SELF_TEST_GAME_ID));
+    if jsonl_path.exists() {
+        std::fs::remove_file(&jsonl_path)?;
+    }
+
+    Ok(((), format!("已删除临时数据 {}", collection_name)))
+}
+
+fn local_storage_path(settings: &AppSettings) -> PathBuf {
+    PathBuf::from(
+        settings
+            .ai_models
+            .vector_db
+            .local_storage_path
+            .as_deref()
+            .unwrap_or("./data/vector_db"),
+    )
+}
+
+fn qdrant_url(settings: &AppSettings) -> String {
+    settings
+        .ai_models
+        .vector_db
+        .qdrant_url
+        .clone()
+        .unwrap_or_else(|| "http://localhost:6333".to_string())
+}
@@ -1,13 +1,22 @@
 use crate::{
-    crawler::WikiEntry,
-    embeddings::EmbeddingService,
+    config::{Config, GameConfig},
+    crawler::{
+        checkpoint::is_unfinished,
+        history::{read_active_version, record_crawl_version},
+        utils::calculate_hash,
+        EntryProcessor, SpoilerFilter, WikiEntry,
+    },
+    embeddings::{CacheStats, EmbeddingService, TextChunker, CACHE_FILE_NAME},
     settings::AppSettings,
-    vector_db::{AIDirectSearch, LocalVectorDB, VectorDB},
+    vector_db::{AIDirectSearch, LocalVectorDB, RetryPolicy, VectorDB},
 };
 use anyhow::Result;
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::State;
 
 /// 导入 Wiki 数据到向量数据库
 #[tauri::command]
@@ -41,19 +50,58 @@ async fn import_wiki_to_vector_db_impl(jsonl_path: String, game_id: String) -> R
 
     // 2. 加载应用配置
     let settings = AppSettings::load()?;
+
+    // 导入前剧透过滤 (仅在配置为导入时丢弃)
+    let entries = match SpoilerFilter::from_settings(&settings, &game_id) {
+        Some(filter) if settings.skill_library.spoiler_filter.drop_on_import => {
+            filter.process(entries)
+        }
+        _ => entries,
+    };
     let embedding_config = &settings.ai_models.embedding;
     let vdb_config = &settings.ai_models.vector_db;
 
+    let batch_size = vdb_config.batch_size();
+
     log::info!("🔧 向量数据库模式: {}", vdb_config.mode);
 
     // 3. 根据模式选择不同的导入逻辑
     match vdb_config.mode.as_str() {
-        "local" => import_to_local_db(entries, game_id, embedding_config).await,
-        "qdrant" => import_to_qdrant(entries, game_id, embedding_config, vdb_config).await,
+        "local" => import_to_local_db(entries, game_id, embedding_config, batch_size).await,
+        "qdrant" => {
+            import_to_qdrant(entries, game_id, embedding_config, vdb_config, batch_size).await
+        }
         "ai_direct" => {
             // AI 直接检索模式不需要导入向量数据库,只需要保存原始数据
             import_to_ai_direct(entries, game_id, vdb_config).await
         }
+        "hybrid" => {
+            // 混合检索同时需要向量数据库和关键词检索使用的 JSONL
+            let summary = match vdb_config.vector_mode() {
+                "local" => {
+                    import_to_local_db(
+                        entries.clone(),
+                        game_id.clone(),
+                        embedding_config,
+                        batch_size,
+                    )
+                    .await?
+                }
+                "qdrant" => {
+                    import_to_qdrant(
+                        entries.clone(),
+                        game_id.clone(),
+                        embedding_config,
+                        vdb_config,
+                        batch_size,
+                    )
+                    .await?
+                }
+                mode => anyhow::bail!("不支持的混合检索向量模式: {}", mode),
+            };
+            import_to_ai_direct(entries, game_id, vdb_config).await?;
+            Ok(format!("{}; 已保存关键词检索数据", summary))
+        }
         _ => {
             anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode);
         }
@@ -65,16 +113,12 @@ async fn import_to_local_db(
     entries: Vec<WikiEntry>,
     game_id: String,
     embedding_config: &crate::settings::ModelConfig,
+    batch_size: usize,
 ) -> Result<String> {
     log::info!("📦 使用本地文件型数据库");
 
     // 1. 初始化 Embedding 服务
-    let embedding_service = EmbeddingService::new(
-        embedding_config.api_base.clone(),
-        embedding_config.api_key.clone(),
-        embedding_config.model_name.clone(),
-    )
-    .await?;
+    let embedding_service = EmbeddingService::from_config(embedding_config).await?;
 
     // 2. 初始化本地数据库
     let settings = AppSettings::load()?;
@@ -88,50 +132,46 @@ async fn import_to_local_db(
 
     let collection_name = format!("game_wiki_{}", game_id);
     let mut local_db = LocalVectorDB::new(PathBuf::from(&storage_path), &collection_name)?;
+    let embedding_service =
+        embedding_service.with_cache(embedding_cache_path(&settings.ai_models.vector_db));
+    let chunker = TextChunker::from_settings(&settings.ai_models.vector_db);
 
     // 3. 创建集合
-    let vector_size = embedding_service.dimension();
+    let vector_size = embedding_service.resolve_dimension().await?;
     local_db.create_collection(vector_size)?;
 
     // 4. 批量生成 Embedding 并插入
-    let batch_size = 50;
     let mut total_imported = 0;
+    let mut total_points = 0;
+    let mut next_id = 0;
+    let mut skipped = Vec::new();
 
-    for (batch_idx, chunk) in entries.chunks(batch_size).enumerate() {
+    for (batch_idx, batch) in entries.chunks(batch_size).enumerate() {
         log::info!(
             "📝 处理批次 {}/{} ({} 条)...",
             batch_idx + 1,
             (entries.len() + batch_size - 1) / batch_size,
-            chunk.len()
+            batch.len()
         );
 
-        // 生成 embedding
-        let texts: Vec<&str> = chunk.iter().map(|e| e.content.as_str()).collect();
-        let vectors = embedding_service.embed_batch(texts).await?;
-
-        // 准备插入数据
-        let points: Vec<_> = chunk
-            .iter()
-            .zip(vectors)
-            .enumerate()
-            .map(|(i, (entry, vector))| {
-                let id = (batch_idx * batch_size + i) as u64;
-                let payload = json!({
-                    "id": entry.id,
-                    "title": entry.title,
-                    "content": entry.content,
-                    "url": entry.url,
-                    "timestamp": entry.timestamp,
-                    "categories": entry.categories,
-                    "game_id": game_id,
-                });
-                (id, vector, payload)
-            })
-            .collect();
+        // 生成 embedding 并准备插入数据 (失败的分块跳过)
+        let points = embed_entries(
+            &embedding_service,
+            batch,
+            &chunker,
+            &mut next_id,
+            &game_id,
+            &mut skipped,
+        )
+        .await?;
+        let imported = imported_entry_count(&points);
+        total_points += points.len();
 
         // 插入向量
-        local_db.upsert_points(points)?;
-        total_imported += chunk.len();
+        if !points.is_empty() {
+            local_db.upsert_points(points)?;
+        }
+        total_imported += imported;
 
         log::info!(
             "✅ 批次 {} 完成，累计导入 {} 条",
@@ -140,10 +180,12 @@ async fn import_to_local_db(
         );
     }
 
-    let summary = format!(
-        "成功导入 {} 条 Wiki 条目到本地向量数据库 (集合: {})",
-        total_imported, collection_name
+    let mut summary = format!(
+        "成功导入 {} 条 Wiki 条目 ({} 个分块) 到本地向量数据库 (集合: {}, 每批 {} 条)",
+        total_imported, total_points, collection_name, batch_size
     );
+    summary.push_str(&cache_summary(embedding_service.cache_stats()));
+    summary.push_str(&skipped_summary(&skipped));
 
     log::info!("🎉 {}", summary);
     Ok(summary)
@@ -155,16 +197,15 @@ async fn import_to_qdrant(
     game_id: String,
     embedding_config: &crate::settings::ModelConfig,
     vdb_config: &crate::settings::VectorDBSettings,
+    batch_size: usize,
 ) -> Result<String> {
     log::info!("🚀 使用 Qdrant 服务器");
 
     // 1. 初始化 Embedding 服务
-    let embedding_service = EmbeddingService::new(
-        embedding_config.api_base.clone(),
-        embedding_config.api_key.clone(),
-        embedding_config.model_name.clone(),
-    )
-    .await?;
+    let embedding_service = EmbeddingService::from_config(embedding_config)
+        .await?
+        .with_cache(embedding_cache_path(vdb_config));
+    let chunker = TextChunker::from_settings(vdb_config);
 
     // 2. 连接 Qdrant
     let qdrant_url = vdb_config
@@ -173,7 +214,9 @@ async fn import_to_qdrant(
         .cloned()
         .unwrap_or_else(|| "http://localhost:6333".to_string());
     let collection_name = format!("game_wiki_{}", game_id);
-    let vector_db = VectorDB::new(&qdrant_url, &collection_name).await?;
+    let vector_db = VectorDB::new(&qdrant_url, &collection_name)
+        .await?
+        .with_retry(RetryPolicy::from_settings(vdb_config));
 
     // 3. 如果集合已存在，删除它
     if vector_db.collection_exists().await? {
@@ -182,48 +225,41 @@ async fn import_to_qdrant(
     }
 
     // 4. 创建集合
-    let vector_size = embedding_service.dimension() as u64;
+    let vector_size = embedding_service.resolve_dimension().await? as u64;
     vector_db.create_collection(vector_size).await?;
 
     // 5. 批量生成 Embedding 并插入
-    let batch_size = 50;
     let mut total_imported = 0;
+    let mut total_points = 0;
+    let mut next_id = 0;
+    let mut skipped = Vec::new();
 
-    for (batch_idx, chunk) in entries.chunks(batch_size).enumerate() {
+    for (batch_idx, batch) in entries.chunks(batch_size).enumerate() {
         log::info!(
             "📝 处理批次 {}/{} ({} 条)...",
             batch_idx + 1,
             (entries.len() + batch_size - 1) / batch_size,
-            chunk.len()
+            batch.len()
         );
 
-        // 生成 embedding
-        let texts: Vec<&str> = chunk.iter().map(|e| e.content.as_str()).collect();
-        let vectors = embedding_service.embed_batch(texts).await?;
-
-        // 准备插入数据
-        let points: Vec<_> = chunk
-            .iter()
-            .zip(vectors)
-            .enumerate()
-            .map(|(i, (entry, vector))| {
-                let id = (batch_idx * batch_size + i) as u64;
-                let payload = json!({
-                    "id": entry.id,
-                    "title": entry.title,
-                    "content": entry.content,
-                    "url": entry.url,
-                    "timestamp": entry.timestamp,
-                    "categories": entry.categories,
-                    "game_id": game_id,
-                });
-                (id, vector, payload)
-            })
-            .collect();
+        // 生成 embedding 并准备插入数据 (失败的分块跳过)
+        let points = embed_entries(
+            &embedding_service,
+            batch,
+            &chunker,
+            &mut next_id,
+            &game_id,
+            &mut skipped,
+        )
+        .await?;
+        let imported = imported_entry_count(&points);
+        total_points += points.len();
 
         // 插入向量
-        vector_db.upsert_points(points).await?;
-        total_imported += chunk.len();
+        if !points.is_empty() {
+            vector_db.upsert_points(points).await?;
+        }
+        total_imported += imported;
 
         log::info!(
             "✅ 批次 {} 完成，累计导入 {} 条",
@@ -232,17 +268,171 @@ async fn import_to_qdrant(
         );
     }
 
-    let summary = format!(
-        "成功导入 {} 条 Wiki 条目到 Qdrant 向量数据库 (集合: {})",
-        total_imported, collection_name
+    let mut summary = format!(
+        "成功导入 {} 条 Wiki 条目 ({} 个分块) 到 Qdrant 向量数据库 (集合: {}, 每批 {} 条)",
+        total_imported, total_points, collection_name, batch_size
     );
+    summary.push_str(&cache_summary(embedding_service.cache_stats()));
+    summary.push_str(&skipped_summary(&skipped));
 
     log::info!("🎉 {}", summary);
     Ok(summary)
 }
 
+/// 条目切分出的一个分块
+struct EntryChunk {
+    /// 所属条目在批次中的下标
+    entry_index: usize,
+    index: usize,
+    count: usize,
+    /// 缓存键 (单块沿用条目哈希, 多块按分块内容计算)
+    hash: String,
+    text: String,
+}
+
+/// 为一批条目生成 embedding 并构建向量点
+///
+/// 每个条目按 `chunker` 切分, 每个分块存为一个向量点, payload 中的 id/title/url
+/// 指向所属条目, 并记录 chunk_index/chunk_count。点 id 从 `next_id` 开始连续分配。
+/// 无法生成 embedding 的分块被跳过, 以 "标题: 原因" 记录到 `skipped`
+pub(crate) async fn embed_entries(
+    embedding_service: &EmbeddingService,
+    entries: &[WikiEntry],
+    chunker: &TextChunker,
+    next_id: &mut u64,
+    game_id: &str,
+    skipped: &mut Vec<String>,
+) -> Result<Vec<(u64, Vec<f32>, serde_json::Value)>> {
+    let mut chunks = Vec::new();
+    for (entry_index, entry) in entries.iter().enumerate() {
+        let texts = chunker.split(&entry.content);
+        let count = texts.len();
+        for (index, text) in texts.into_iter().enumerate() {
+            // 按分块内容计算哈希, 修改分块设置后不会误用旧缓存
+            let hash = if count == 1 || entry.hash.is_empty() {
+                entry.hash.clone()
+            } else {
+                calculate_hash(&text)
+            };
+            chunks.push(EntryChunk {
+                entry_index,
+                index,
+                count,
+                hash,
+                text,
+            });
+        }
+    }
+    if chunks.len() > entries.len() {
+        log::info!("✂️ {} 条条目切分为 {} 个分块", entries.len(), chunks.len());
+    }
+
+    let items: Vec<(&str, &str)> = chunks
+        .iter()
+        .map(|c| (c.hash.as_str(), c.text.as_str()))
+        .collect();
+    let embeddings = embedding_service.embed_batch_cached(&items).await?;
+
+    let stats = embedding_service.cache_stats();
+    if stats.hits + stats.misses > 0 {
+        log::info!(
+            "💾 Embedding 缓存累计命中 {} 条, 未命中 {} 条",
+            stats.hits,
+            stats.misses
+        );
+    }
+
+    if embeddings.truncated > 0 {
+        log::info!(
+            "✂️ {} 个分块内容过长, 已截断后生成 embedding",
+            embeddings.truncated
+        );
+    }
+    for (index, reason) in &embeddings.skipped {
+        let chunk = &chunks[*index];
+        let mut title = entries[chunk.entry_index].title.clone();
+        if chunk.count > 1 {
+            title.push_str(&format!(" (分块 {}/{})", chunk.index + 1, chunk.count));
+        }
+        log::warn!("⚠️ 跳过条目 \"{}\": {}", title, reason);
+        skipped.push(format!("{}: {}", title, reason));
+    }
+
+    Ok(chunks
+        .iter()
+        .zip(embeddings.vectors)
+        .filter_map(|(chunk, vector)| {
+            let entry = &entries[chunk.entry_index];
+            let payload = json!({
+                "id": entry.id,
+                "title": entry.title,
+                "content": chunk.text,
+                "url": entry.url,
+                "timestamp": entry.timestamp,
+                "categories": entry.categories,
+                "game_id": game_id,
+                "chunk_index": chunk.index,
+                "chunk_count": chunk.count,
+            });
+            let vector = vector?;
+            let id = *next_id;
+            *next_id += 1;
+            Some((id, vector, payload))
+        })
+        .collect())
+}
+
+/// 向量点覆盖的条目数 (同一条目的多个分块只计一次)
+fn imported_entry_count(points: &[(u64, Vec<f32>, serde_json::Value)]) -> usize {
+    points
+        .iter()
+        .filter_map(|(_, _, payload)| payload.get("id")?.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Embedding 缓存文件路径 (向量数据库存储目录下)
+fn embedding_cache_path(vdb_config: &crate::settings::VectorDBSettings) -> PathBuf {
+    PathBuf::from(
+        vdb_config
+            .local_storage_path
+            .as_deref()
+            .unwrap_or("./data/vector_db"),
+    )
+    .join(CACHE_FILE_NAME)
+}
+
+/// 导入结果中的缓存命中说明
+fn cache_summary(stats: CacheStats) -> String {
+    if stats.hits == 0 {
+        return String::new();
+    }
+    format!(
+        ", 其中 {} 条使用缓存的 Embedding (共 {} 条)",
+        stats.hits,
+        stats.hits + stats.misses
+    )
+}
+
+/// 导入结果中的跳过说明 (最多列出 10 条)
+fn skipped_summary(skipped: &[String]) -> String {
+    if skipped.is_empty() {
+        return String::new();
+    }
+
+    let mut summary = format!(
+        ", 跳过 {} 条: {}",
+        skipped.len(),
+        skipped[..skipped.len().min(10)].join("; ")
+    );
+    if skipped.len() > 10 {
+        summary.push_str(" ...");
+    }
+    summary
+}
+
 /// 导入到 AI 直接检索模式（将 JSONL 复制到向量数据库目录）
-async fn import_to_ai_direct(
+pub(crate) async fn import_to_ai_direct(
     entries: Vec<WikiEntry>,
     game_id: String,
     vdb_config: &crate::settings::VectorDBSettings,
@@ -284,13 +474,16 @@ async fn import_to_ai_direct(
 }
 
 /// 搜索 Wiki 知识
+///
+/// - `allow_spoilers`: 为 true 时不过滤剧透条目 (默认按剧透过滤设置过滤)
 #[tauri::command]
 pub async fn search_wiki(
     query: String,
     game_id: String,
     top_k: Option<usize>,
+    allow_spoilers: Option<bool>,
 ) -> Result<Vec<WikiSearchResult>, String> {
-    search_wiki_impl(query, game_id, top_k)
+    search_wiki_impl(query, game_id, top_k, allow_spoilers.unwrap_or(false))
         .await
         .map_err(|e| format!("搜索失败: {}", e))
 }
@@ -299,7 +492,21 @@ pub async fn search_wiki_impl(
     query: String,
     game_id: String,
     top_k: Option<usize>,
+    allow_spoilers: bool,
 ) -> Result<Vec<WikiSearchResult>> {
+    search_wiki_with_timings(query, game_id, top_k, allow_spoilers)
+        .await
+        .map(|(results, _)| results)
+}
+
+/// 搜索 Wiki 知识并返回各阶段耗时
+pub async fn search_wiki_with_timings(
+    query: String,
+    game_id: String,
+    top_k: Option<usize>,
+    allow_spoilers: bool,
+) -> Result<(Vec<WikiSearchResult>, SearchTimings)> {
+    let mut timings = SearchTimings::default();
     let top_k = top_k.unwrap_or(5);
 
     log::info!("🔍 搜索 Wiki 知识...");
@@ -313,14 +520,64 @@ pub async fn search_wiki_impl(
 
     log::info!("🔧 搜索模式: {}", vdb_config.mode);
 
+    // 剧透过滤时多取一些候选, 过滤后再截断到 top_k
+    let spoiler_filter = if allow_spoilers {
+        None
+    } else {
+        SpoilerFilter::from_settings(&settings, &game_id)
+    };
+    let requested_k = top_k;
+    let top_k = if spoiler_filter.is_some() {
+        top_k * 2
+    } else {
+        top_k
+    };
+    // 同一条目可能命中多个分块, 多取一些候选用于去重
+    let top_k = top_k * CANDIDATES_PER_RESULT;
+
     // 2. 根据模式选择不同的搜索逻辑
-    match vdb_config.mode.as_str() {
-        "local" => search_with_local_db(query, game_id, top_k, &settings).await,
-        "qdrant" => search_with_qdrant(query, game_id, top_k, &settings).await,
-        "ai_direct" => search_with_ai_direct(query, game_id, top_k, vdb_config).await,
+    let mut results = match vdb_config.mode.as_str() {
+        "local" => search_with_local_db(query, game_id, top_k, &settings, &mut timings).await,
+        "qdrant" => search_with_qdrant(query, game_id, top_k, &settings, &mut timings).await,
+        "ai_direct" => search_with_ai_direct(query, game_id, top_k, vdb_config, &mut timings).await,
+        "hybrid" => search_hybrid(query, game_id, top_k, &settings, &mut timings).await,
         _ => {
             anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode);
         }
+    }?;
+
+    if let Some(filter) = spoiler_filter {
+        let before = results.len();
+        results.retain(|r| !filter.is_spoiler(&r.title, &r.categories));
+        if results.len() < before {
+            log::info!("🙈 过滤 {} 个剧透结果", before - results.len());
+        }
+    }
+    dedupe_by_entry(&mut results);
+    results.truncate(requested_k);
+
+    log::info!(
+        "⏱️ 检索耗时: embedding {} ms, 检索 {} ms",
+        timings.embed_ms,
+        timings.retrieve_ms
+    );
+
+    Ok((results, timings))
+}
+
+/// 每个返回结果对应的检索候选数 (分块去重前)
+const CANDIDATES_PER_RESULT: usize = 3;
+
+/// 按所属条目去重, 每个条目只保留得分最高的分块 (结果需已按得分降序)
+fn dedupe_by_entry(results: &mut Vec<WikiSearchResult>) {
+    let before = results.len();
+    let mut seen = HashSet::new();
+    results.retain(|r| {
+        let key = if r.id.is_empty() { &r.url } else { &r.id };
+        seen.insert(key.clone())
+    });
+    if results.len() < before {
+        log::info!("🧩 合并 {} 个同一条目的分块", before - results.len());
     }
 }
 
@@ -330,18 +587,14 @@ async fn search_with_local_db(
     game_id: String,
     top_k: usize,
     settings: &AppSettings,
+    timings: &mut SearchTimings,
 ) -> Result<Vec<WikiSearchResult>> {
     log::info!("📦 使用本地文件型数据库搜索");
 
     let embedding_config = &settings.ai_models.embedding;
 
     // 1. 初始化 Embedding 服务
-    let embedding_service = EmbeddingService::new(
-        embedding_config.api_base.clone(),
-        embedding_config.api_key.clone(),
-        embedding_config.model_name.clone(),
-    )
-    .await?;
+    let embedding_service = EmbeddingService::from_config(embedding_config).await?;
 
     // 2. 初始化本地数据库
     let storage_path = settings
@@ -356,10 +609,14 @@ async fn search_with_local_db(
     let local_db = LocalVectorDB::new(PathBuf::from(&storage_path), &collection_name)?;
 
     // 3. 生成查询向量
+    let embed_start = Instant::now();
     let query_vector = embedding_service.embed_text(&query).await?;
+    timings.embed_ms = embed_start.elapsed().as_millis() as u64;
 
     // 4. 搜索
+    let retrieve_start = Instant::now();
     let results = local_db.search(query_vector, top_k)?;
+    timings.retrieve_ms = retrieve_start.elapsed().as_millis() as u64;
 
     // 5. 转换结果
     let wiki_results: Vec<WikiSearchResult> = results
@@ -413,18 +670,14 @@ async fn search_with_qdrant(
     game_id: String,
     top_k: usize,
     settings: &AppSettings,
+    timings: &mut SearchTimings,
 ) -> Result<Vec<WikiSearchResult>> {
     log::info!("🚀 使用 Qdrant 服务器搜索");
 
     let embedding_config = &settings.ai_models.embedding;
 
     // 1. 初始化 Embedding 服务
-    let embedding_service = EmbeddingService::new(
-        embedding_config.api_base.clone(),
-        embedding_config.api_key.clone(),
-        embedding_config.model_name.clone(),
-    )
-    .await?;
+    let embedding_service = EmbeddingService::from_config(embedding_config).await?;
 
     // 2. 连接 Qdrant
     let qdrant_url = settings
@@ -435,7 +688,9 @@ async fn search_with_qdrant(
         .cloned()
         .unwrap_or_else(|| "http://localhost:6333".to_string());
     let collection_name = format!("game_wiki_{}", game_id);
-    let vector_db = VectorDB::new(&qdrant_url, &collection_name).await?;
+    let vector_db = VectorDB::new(&qdrant_url, &collection_name)
+        .await?
+        .with_retry(RetryPolicy::from_settings(&settings.ai_models.vector_db));
 
     // 3. 检查集合是否存在
     if !vector_db.collection_exists().await? {
@@ -443,10 +698,14 @@ async fn search_with_qdrant(
     }
 
     // 4. 生成查询向量
+    let embed_start = Instant::now();
     let query_vector = embedding_service.embed_text(&query).await?;
+    timings.embed_ms = embed_start.elapsed().as_millis() as u64;
 
     // 5. 检索
+    let retrieve_start = Instant::now();
     let results = vector_db.search(query_vector, top_k).await?;
+    timings.retrieve_ms = retrieve_start.elapsed().as_millis() as u64;
 
     // 6. 解析结果
     let wiki_results: Vec<WikiSearchResult> = results
@@ -480,6 +739,7 @@ async fn search_with_ai_direct(
     game_id: String,
     top_k: usize,
     vdb_config: &crate::settings::VectorDBSettings,
+    timings: &mut SearchTimings,
 ) -> Result<Vec<WikiSearchResult>> {
     log::info!("🤖 使用 AI 直接检索模式搜索");
 
@@ -491,8 +751,10 @@ async fn search_with_ai_direct(
 
     let ai_search = AIDirectSearch::new(PathBuf::from(storage_path));
 
-    // 执行关键词匹配搜索
+    // 执行关键词匹配搜索 (无 embedding 阶段)
+    let retrieve_start = Instant::now();
     let results = ai_search.search(&query, &game_id, top_k)?;
+    timings.retrieve_ms = retrieve_start.elapsed().as_millis() as u64;
 
     // 转换结果格式 (AI 直接搜索的结果字段较少)
     let wiki_results: Vec<WikiSearchResult> = results
@@ -511,6 +773,180 @@ async fn search_with_ai_direct(
     Ok(wiki_results)
 }
 
+/// 倒数排名融合的平滑常数
+const RRF_K: f32 = 60.0;
+
+/// 混合检索: 向量检索与关键词检索各取 `top_k` 个候选, 用倒数排名融合合并
+async fn search_hybrid(
+    query: String,
+    game_id: String,
+    top_k: usize,
+    settings: &AppSettings,
+    timings: &mut SearchTimings,
+) -> Result<Vec<WikiSearchResult>> {
+    let vdb_config = &settings.ai_models.vector_db;
+    log::info!(
+        "🔀 使用混合检索 (向量: {}, 向量权重 {:.2})",
+        vdb_config.vector_mode(),
+        vdb_config.hybrid_weight
+    );
+
+    let vector_results = match vdb_config.vector_mode() {
+        "local" => {
+            search_with_local_db(query.clone(), game_id.clone(), top_k, settings, timings).await?
+        }
+        "qdrant" => {
+            search_with_qdrant(query.clone(), game_id.clone(), top_k, settings, timings).await?
+        }
+        mode => anyhow::bail!("不支持的混合检索向量模式: {}", mode),
+    };
+    let vector_retrieve_ms = timings.retrieve_ms;
+
+    let keyword_results = search_with_ai_direct(query, game_id, top_k, vdb_config, timings).await?;
+    timings.retrieve_ms += vector_retrieve_ms;
+
+    let results = reciprocal_rank_fusion(vector_results, keyword_results, vdb_config.hybrid_weight);
+    log::info!("✅ 融合后 {} 个结果", results.len());
+    Ok(results)
+}
+
+/// 用倒数排名融合 (RRF) 合并向量与关键词两组结果
+///
+/// 两组结果按 URL 对齐, 每组中排名为 r 的结果得分 `权重 / (RRF_K + r)`,
+/// 总分除以两组都排第一时的得分, 归一化到 0-1。两组都命中时保留向量结果 (含分类信息)
+fn reciprocal_rank_fusion(
+    vector_results: Vec<WikiSearchResult>,
+    keyword_results: Vec<WikiSearchResult>,
+    vector_weight: f32,
+) -> Vec<WikiSearchResult> {
+    let vector_weight = vector_weight.clamp(0.0, 1.0);
+    let mut fused: Vec<WikiSearchResult> = Vec::new();
+    let mut scores: Vec<f32> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (results, weight) in [
+        (vector_results, vector_weight),
+        (keyword_results, 1.0 - vector_weight),
+    ] {
+        // 同一条目的多个分块只按排名最靠前的一个计分
+        let mut seen = HashSet::new();
+        for result in results {
+            let key = if result.url.is_empty() {
+                result.title.clone()
+            } else {
+                result.url.clone()
+            };
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+
+            let score = weight / (RRF_K + seen.len() as f32);
+            match positions.get(&key) {
+                Some(&index) => scores[index] += score,
+                None => {
+                    positions.insert(key, fused.len());
+                    fused.push(result);
+                    scores.push(score);
+                }
+            }
+        }
+    }
+
+    let max_score = 1.0 / (RRF_K + 1.0);
+    for (result, score) in fused.iter_mut().zip(scores) {
+        result.score = score / max_score;
+    }
+    fused.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    fused
+}
+
+/// 查看条目在向量空间中的近邻 (用于评估 Embedding 模型质量)
+///
+/// 读取条目已存储的向量, 检索与其最相似的 k 个其他条目
+#[tauri::command]
+pub async fn entry_neighbors(
+    game_id: String,
+    entry_id: String,
+    k: Option<usize>,
+) -> Result<Vec<EntryNeighbor>, String> {
+    entry_neighbors_impl(game_id, entry_id, k.unwrap_or(10))
+        .await
+        .map_err(|e| format!("获取近邻条目失败: {}", e))
+}
+
+async fn entry_neighbors_impl(
+    game_id: String,
+    entry_id: String,
+    k: usize,
+) -> Result<Vec<EntryNeighbor>> {
+    let settings = AppSettings::load()?;
+    let vdb_config = &settings.ai_models.vector_db;
+    let collection_name = format!("game_wiki_{}", game_id);
+
+    // 多取一些, 结果中会包含条目自身及同一条目的其他分块
+    let candidates = (k + 1) * CANDIDATES_PER_RESULT;
+    let results = match vdb_config.vector_mode() {
+        "local" => {
+            let storage_path = vdb_config
+                .local_storage_path
+                .as_ref()
+                .cloned()
+                .unwrap_or_else(|| "./data/vector_db".to_string());
+            let local_db = LocalVectorDB::new(PathBuf::from(&storage_path), &collection_name)?;
+
+            let Some(vector) = local_db.get_vector_by_entry_id(&entry_id)? else {
+                anyhow::bail!("未找到条目: {}", entry_id);
+            };
+            local_db.search(vector, candidates)?
+        }
+        "qdrant" => {
+            let qdrant_url = vdb_config
+                .qdrant_url
+                .as_ref()
+                .cloned()
+                .unwrap_or_else(|| "http://localhost:6333".to_string());
+            let vector_db = VectorDB::new(&qdrant_url, &collection_name).await?;
+
+            if !vector_db.collection_exists().await? {
+                anyhow::bail!("游戏 {} 的知识库不存在，请先导入 Wiki 数据", game_id);
+            }
+            let Some(vector) = vector_db.get_vector_by_entry_id(&entry_id).await? else {
+                anyhow::bail!("未找到条目: {}", entry_id);
+            };
+            vector_db.search(vector, candidates).await?
+        }
+        "ai_direct" => anyhow::bail!("AI 直接检索模式没有存储向量"),
+        _ => anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode),
+    };
+
+    // 同一条目的多个分块只保留一个
+    let mut seen = HashSet::new();
+    let neighbors: Vec<EntryNeighbor> = results
+        .into_iter()
+        .filter_map(|r| {
+            let id = r.payload.get("id")?.as_str()?.to_string();
+            (id != entry_id && seen.insert(id.clone())).then(|| EntryNeighbor {
+                score: r.score,
+                title: r
+                    .payload
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                id,
+            })
+        })
+        .take(k)
+        .collect();
+
+    log::info!("🧭 条目 {} 的近邻: {} 个", entry_id, neighbors.len());
+    Ok(neighbors)
+}
+
 /// 获取向量数据库统计信息
 #[tauri::command]
 pub async fn get_vector_db_stats(game_id: String) -> Result<VectorDBStats, String> {
@@ -522,46 +958,83 @@ pub async fn get_vector_db_stats(game_id: String) -> Result<VectorDBStats, Strin
 async fn get_vector_db_stats_impl(game_id: String) -> Result<VectorDBStats> {
     let settings = AppSettings::load()?;
     let vdb_config = &settings.ai_models.vector_db;
+    let storage_path = vdb_config
+        .local_storage_path
+        .as_ref()
+        .cloned()
+        .unwrap_or_else(|| "./data/vector_db".to_string());
+    let collection_name = format!("game_wiki_{}", game_id);
 
     // 根据模式获取不同的统计信息
-    match vdb_config.mode.as_str() {
+    match vdb_config.vector_mode() {
         "qdrant" => {
-            // Qdrant 模式 - 获取详细统计
             let qdrant_url = vdb_config
                 .qdrant_url
                 .as_ref()
                 .cloned()
                 .unwrap_or_else(|| "http://localhost:6333".to_string());
-            let collection_name = format!("game_wiki_{}", game_id);
-            let vector_db = VectorDB::new(&qdrant_url, &collection_name).await?;
 
-            if !vector_db.collection_exists().await? {
-                return Ok(VectorDBStats {
-                    exists: false,
-                    vectors_count: 0,
-                    points_count: 0,
-                    game_id,
-                });
+            // 连接失败时无法判断是否已导入
+            let vector_db = match VectorDB::new(&qdrant_url, &collection_name).await {
+                Ok(db) => db,
+                Err(e) => {
+                    log::warn!("⚠️ 无法连接 Qdrant, 统计不可用: {}", e);
+                    return Ok(VectorDBStats::unavailable(game_id, None));
+                }
+            };
+
+            match vector_db.collection_exists().await {
+                Ok(false) => return Ok(VectorDBStats::not_imported(game_id)),
+                Ok(true) => {}
+                Err(e) => {
+                    log::warn!("⚠️ 检查 Qdrant 集合失败: {}", e);
+                    return Ok(VectorDBStats::unavailable(game_id, None));
+                }
             }
 
-            let info = vector_db.get_collection_info().await?;
+            match vector_db.get_collection_info().await {
+                Ok(info) => Ok(VectorDBStats::ready(
+                    game_id,
+                    Some(info.vectors_count),
+                    info.points_count,
+                )),
+                Err(e) => {
+                    log::warn!("⚠️ 获取 Qdrant 集合信息失败: {}", e);
+                    Ok(VectorDBStats::unavailable(game_id, Some(true)))
+                }
+            }
+        }
+        "local" => {
+            let local_db = LocalVectorDB::new(PathBuf::from(&storage_path), &collection_name)?;
+            if !local_db.collection_exists() {
+                return Ok(VectorDBStats::not_imported(game_id));
+            }
 
-            Ok(VectorDBStats {
-                exists: true,
-                vectors_count: info.vectors_count,
-                points_count: info.points_count,
-                game_id,
-            })
+            match local_db.point_count() {
+                Ok(count) => Ok(VectorDBStats::ready(game_id, Some(count), count)),
+                Err(e) => {
+                    log::warn!("⚠️ 读取本地集合失败: {}", e);
+                    Ok(VectorDBStats::unavailable(game_id, Some(true)))
+                }
+            }
         }
-        "local" | "ai_direct" => {
-            // 本地模式和 AI 直接模式 - 简化统计
-            let exists = check_game_vector_db_impl(game_id.clone()).await?;
-            Ok(VectorDBStats {
-                exists,
-                vectors_count: 0, // 本地模式不提供详细统计
-                points_count: 0,
-                game_id,
-            })
+        "ai_direct" => {
+            // AI 直接检索没有向量, 点数量为 JSONL 的条目数
+            let jsonl_path = PathBuf::from(&storage_path).join(format!("{}.jsonl", game_id));
+            if !jsonl_path.exists() {
+                return Ok(VectorDBStats::not_imported(game_id));
+            }
+
+            match fs::read_to_string(&jsonl_path) {
+                Ok(content) => {
+                    let count = content.lines().filter(|l| !l.trim().is_empty()).count();
+                    Ok(VectorDBStats::ready(game_id, None, count as u64))
+                }
+                Err(e) => {
+                    log::warn!("⚠️ 读取 JSONL 失败: {}", e);
+                    Ok(VectorDBStats::unavailable(game_id, Some(true)))
+                }
+            }
         }
         _ => {
             anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode);
@@ -569,6 +1042,121 @@ async fn get_vector_db_stats_impl(game_id: String) -> Result<VectorDBStats> {
     }
 }
 
+/// 知识库占用空间
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeBaseSize {
+    pub game_id: String,
+    /// 向量数据库模式 (local / qdrant / ai_direct)
+    pub mode: String,
+    /// 占用字节数 (未导入时为 0)
+    pub bytes: u64,
+    /// 是否为估算值 (Qdrant 按抽样估算)
+    pub estimated: bool,
+    /// 本地存储文件路径 (Qdrant 模式为 None)
+    pub path: Option<String>,
+}
+
+/// 所有已导入游戏的知识库占用空间
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotalKnowledgeBaseSize {
+    pub mode: String,
+    pub total_bytes: u64,
+    /// 是否包含估算值
+    pub estimated: bool,
+    /// 各游戏占用 (按占用从大到小排序)
+    pub games: Vec<KnowledgeBaseSize>,
+}
+
+/// 获取游戏知识库在当前模式下占用的空间
+#[tauri::command]
+pub async fn knowledge_base_size(game_id: String) -> Result<KnowledgeBaseSize, String> {
+    let settings = AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+
+    knowledge_base_size_impl(&settings, &game_id)
+        .await
+        .map_err(|e| format!("获取知识库占用空间失败: {}", e))
+}
+
+/// 获取所有已导入游戏的知识库占用空间
+#[tauri::command]
+pub async fn total_knowledge_base_size() -> Result<TotalKnowledgeBaseSize, String> {
+    total_knowledge_base_size_impl()
+        .await
+        .map_err(|e| format!("获取知识库占用空间失败: {}", e))
+}
+
+async fn knowledge_base_size_impl(
+    settings: &AppSettings,
+    game_id: &str,
+) -> Result<KnowledgeBaseSize> {
+    let vdb_config = &settings.ai_models.vector_db;
+    let storage_path = PathBuf::from(
+        vdb_config
+            .local_storage_path
+            .as_deref()
+            .unwrap_or("./data/vector_db"),
+    );
+    let collection_name = format!("game_wiki_{}", game_id);
+
+    let (bytes, estimated, path) = match vdb_config.vector_mode() {
+        "local" => {
+            let local_db = LocalVectorDB::new(storage_path.clone(), &collection_name)?;
+            let path = storage_path.join(format!("{}.json", collection_name));
+            (local_db.size_bytes(), false, Some(path))
+        }
+        "qdrant" => {
+            let qdrant_url = vdb_config
+                .qdrant_url
+                .as_deref()
+                .unwrap_or("http://localhost:6333");
+            let vector_db = VectorDB::new(qdrant_url, &collection_name).await?;
+
+            let bytes = if vector_db.collection_exists().await? {
+                vector_db.estimate_size_bytes().await?
+            } else {
+                0
+            };
+            (bytes, true, None)
+        }
+        "ai_direct" => {
+            let path = storage_path.join(format!("{}.jsonl", game_id));
+            let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            (bytes, false, Some(path))
+        }
+        _ => anyhow::bail!("不支持的向量数据库模式: {}", vdb_config.mode),
+    };
+
+    Ok(KnowledgeBaseSize {
+        game_id: game_id.to_string(),
+        mode: vdb_config.mode.clone(),
+        bytes,
+        estimated,
+        path: path.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+async fn total_knowledge_base_size_impl() -> Result<TotalKnowledgeBaseSize> {
+    let settings = AppSettings::load()?;
+
+    let mut games = Vec::new();
+    for game_id in list_imported_games_impl().await? {
+        match knowledge_base_size_impl(&settings, &game_id).await {
+            Ok(size) => games.push(size),
+            Err(e) => log::warn!("⚠️ 获取知识库占用空间失败: {} - {}", game_id, e),
+        }
+    }
+    games.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    Ok(TotalKnowledgeBaseSize {
+        mode: settings.ai_models.vector_db.mode.clone(),
+        total_bytes: games.iter().map(|g| g.bytes).sum(),
+        estimated: games.iter().any(|g| g.estimated),
+        games,
+    })
+}
+
 /// 检查游戏的向量数据库是否已导入
 #[tauri::command]
 pub async fn check_game_vector_db(game_id: String) -> Result<bool, String> {
@@ -582,7 +1170,7 @@ async fn check_game_vector_db_impl(game_id: String) -> Result<bool> {
     let vdb_config = &settings.ai_models.vector_db;
 
     // 根据模式检查不同的后端
-    match vdb_config.mode.as_str() {
+    match vdb_config.vector_mode() {
         "local" => {
             // 检查本地数据库文件是否存在
             let storage_path = vdb_config
@@ -638,7 +1226,7 @@ async fn list_imported_games_impl() -> Result<Vec<String>> {
     let vdb_config = &settings.ai_models.vector_db;
 
     // 根据模式列出不同后端的游戏
-    match vdb_config.mode.as_str() {
+    match vdb_config.vector_mode() {
         "local" => {
             // 列出本地数据库的所有集合
             let storage_path = vdb_config
@@ -758,7 +1346,9 @@ async fn list_imported_games_impl() -> Result<Vec<String>> {
     }
 }
 
-/// 获取游戏最新的 Wiki JSONL 文件路径
+/// 获取游戏活跃版本的 Wiki JSONL 文件路径
+///
+/// 游戏目录下有 `active_version` 标记 (回滚固定的版本) 时使用该版本, 否则使用最新版本
 #[tauri::command]
 pub async fn get_latest_wiki_jsonl(game_id: String) -> Result<String, String> {
     get_latest_wiki_jsonl_impl(game_id).map_err(|e| format!("获取文件路径失败: {}", e))
@@ -776,40 +1366,115 @@ fn get_latest_wiki_jsonl_impl(game_id: String) -> Result<String> {
         anyhow::bail!("游戏目录不存在: {:?}", game_dir);
     }
 
-    // 3. 读取所有时间戳目录,找到最新的
-    let mut timestamp_dirs: Vec<u64> = Vec::new();
+    // 3. 优先使用固定的活跃版本, 否则读取所有时间戳目录找到最新的
+    let timestamp = match read_active_version(&game_dir) {
+        Some(active) => {
+            log::info!("📌 使用固定的技能库版本: {}", active);
+            active
+        }
+        None => match find_latest_crawl_timestamp(&game_dir)? {
+            Some(latest) => latest,
+            None => anyhow::bail!("未找到任何技能库版本目录"),
+        },
+    };
+
+    // 4. 构建 wiki_raw.jsonl 路径
+    let jsonl_path = game_dir.join(timestamp.to_string()).join("wiki_raw.jsonl");
 
-    for entry in fs::read_dir(&game_dir)? {
+    if !jsonl_path.exists() {
+        anyhow::bail!("wiki_raw.jsonl 文件不存在: {:?}", jsonl_path);
+    }
+
+    Ok(jsonl_path.to_string_lossy().to_string())
+}
+
+/// 查找游戏目录下最新的爬取版本 (纯数字时间戳目录, 跳过未完成的爬取)
+pub(crate) fn find_latest_crawl_timestamp(game_dir: &Path) -> Result<Option<u64>> {
+    let mut latest = None;
+
+    for entry in fs::read_dir(game_dir)? {
         let entry = entry?;
         let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy();
 
         // 尝试解析为时间戳(纯数字目录名)
-        if let Ok(timestamp) = file_name_str.parse::<u64>() {
-            if entry.path().is_dir() {
-                timestamp_dirs.push(timestamp);
+        if let Ok(timestamp) = file_name.to_string_lossy().parse::<u64>() {
+            if entry.path().is_dir()
+                && !is_unfinished(&entry.path())
+                && latest.is_none_or(|t| timestamp > t)
+            {
+                latest = Some(timestamp);
             }
         }
     }
 
-    if timestamp_dirs.is_empty() {
-        anyhow::bail!("未找到任何技能库版本目录");
-    }
+    Ok(latest)
+}
 
-    // 4. 获取最新的时间戳
-    timestamp_dirs.sort_unstable();
-    let latest_timestamp = timestamp_dirs.last().unwrap();
+/// 获取所有游戏及其知识库状态 (供游戏选择页面一次性展示)
+#[tauri::command]
+pub async fn get_games_with_status(
+    config: State<'_, Config>,
+) -> Result<Vec<GameWithStatus>, String> {
+    get_games_with_status_impl(config.inner())
+        .await
+        .map_err(|e| format!("获取游戏状态失败: {}", e))
+}
 
-    // 5. 构建 wiki_raw.jsonl 路径
-    let jsonl_path = game_dir
-        .join(latest_timestamp.to_string())
-        .join("wiki_raw.jsonl");
+pub(crate) async fn get_games_with_status_impl(config: &Config) -> Result<Vec<GameWithStatus>> {
+    let settings = AppSettings::load()?;
+    let base_path = PathBuf::from(&settings.skill_library.storage_base_path);
 
-    if !jsonl_path.exists() {
-        anyhow::bail!("wiki_raw.jsonl 文件不存在: {:?}", jsonl_path);
-    }
+    // 一次性获取已导入列表, 避免逐个游戏查询向量数据库
+    let imported_games = match list_imported_games_impl().await {
+        Ok(games) => games,
+        Err(e) => {
+            log::warn!("⚠️ 获取已导入游戏列表失败: {}", e);
+            Vec::new()
+        }
+    };
+
+    let games = config
+        .games
+        .iter()
+        .map(|game| {
+            let mut status = KnowledgeBaseStatus {
+                imported: imported_games.contains(&game.id),
+                ..Default::default()
+            };
+
+            let game_dir = base_path.join(&game.id);
+            if let Ok(Some(timestamp)) = find_latest_crawl_timestamp(&game_dir) {
+                let crawl_dir = game_dir.join(timestamp.to_string());
+                let jsonl_path = crawl_dir.join("wiki_raw.jsonl");
+
+                status.downloaded = jsonl_path.exists();
+                status.latest_crawl_timestamp = Some(timestamp);
+                status.entry_count = read_crawl_entry_count(&crawl_dir, &jsonl_path);
+            }
 
-    Ok(jsonl_path.to_string_lossy().to_string())
+            GameWithStatus {
+                game: game.clone(),
+                status,
+            }
+        })
+        .collect();
+
+    Ok(games)
+}
+
+/// 读取爬取条目数 (优先使用 metadata.json, 否则统计 JSONL 行数)
+fn read_crawl_entry_count(crawl_dir: &Path, jsonl_path: &Path) -> usize {
+    let from_metadata = fs::read_to_string(crawl_dir.join("metadata.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|metadata| metadata["total_entries"].as_u64());
+
+    match from_metadata {
+        Some(count) => count as usize,
+        None => fs::read_to_string(jsonl_path)
+            .map(|content| content.lines().filter(|l| !l.trim().is_empty()).count())
+            .unwrap_or(0),
+    }
 }
 
 /// 自动导入游戏的最新 Wiki 数据
@@ -831,6 +1496,307 @@ async fn auto_import_latest_wiki_impl(game_id: String) -> Result<String> {
     import_wiki_to_vector_db_impl(jsonl_path, game_id).await
 }
 
+/// 校验爬取结果是否可以正常导入 (不写入向量数据库)
+///
+/// 检查 JSONL 编码与格式、空内容条目, 并抽样调用 Embedding 服务确认接口可用且维度一致
+#[tauri::command]
+pub async fn verify_crawl_output(game_id: String) -> Result<CrawlVerifyReport, String> {
+    verify_crawl_output_impl(game_id)
+        .await
+        .map_err(|e| format!("校验失败: {}", e))
+}
+
+async fn verify_crawl_output_impl(game_id: String) -> Result<CrawlVerifyReport> {
+    const SAMPLE_SIZE: usize = 3;
+
+    let jsonl_path = get_latest_wiki_jsonl_impl(game_id.clone())?;
+    log::info!("🔎 校验爬取结果: {}", jsonl_path);
+
+    let mut report = CrawlVerifyReport {
+        jsonl_path: jsonl_path.clone(),
+        ..Default::default()
+    };
+
+    // 1. 逐行校验编码与格式
+    let bytes = fs::read(&jsonl_path)?;
+    let samples = validate_jsonl(&bytes, &mut report, SAMPLE_SIZE);
+
+    // 2. 抽样测试 Embedding 服务 (ai_direct 模式不需要)
+    let settings = AppSettings::load()?;
+    if settings.ai_models.vector_db.mode == "ai_direct" {
+        report.embedding_ok = true;
+    } else if !samples.is_empty() {
+        let embedding_config = &settings.ai_models.embedding;
+        let embedding_service = EmbeddingService::from_config(embedding_config).await?;
+        report.expected_dimension = embedding_service.known_dimension();
+
+        let texts: Vec<&str> = samples.iter().map(|e| e.content.as_str()).collect();
+        match embedding_service.embed_batch(texts).await {
+            Ok(vectors) => {
+                let dims: Vec<usize> = vectors.iter().map(|v| v.len()).collect();
+                report.embedding_dimension = dims.first().copied();
+
+                if vectors.len() != samples.len() {
+                    report.issues.push(format!(
+                        "Embedding 返回 {} 个向量, 期望 {} 个",
+                        vectors.len(),
+                        samples.len()
+                    ));
+                } else if dims
+                    .iter()
+                    .any(|&d| d == 0 || Some(d) != report.embedding_dimension)
+                {
+                    report
+                        .issues
+                        .push(format!("Embedding 维度不一致: {:?}", dims));
+                } else {
+                    report.embedding_ok = true;
+                    if report.expected_dimension.is_some()
+                        && report.embedding_dimension != report.expected_dimension
+                    {
+                        report.issues.push(format!(
+                            "Embedding 实际维度 {:?} 与模型预设维度 {:?} 不符",
+                            report.embedding_dimension, report.expected_dimension
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                report.issues.push(format!("Embedding 服务调用失败: {}", e));
+            }
+        }
+    }
+
+    report.ok = report.parsed_entries > report.empty_contents && report.embedding_ok;
+
+    log::info!(
+        "{} 校验完成: {} 条目, {} 个问题",
+        if report.ok { "✅" } else { "❌" },
+        report.parsed_entries,
+        report.issues.len()
+    );
+
+    Ok(report)
+}
+
+/// 逐行校验 JSONL 的编码与 `WikiEntry` 格式, 把统计和问题写入报告
+///
+/// 返回最多 `sample_size` 个内容非空的条目 (用于抽样测试 Embedding)
+pub(crate) fn validate_jsonl(
+    bytes: &[u8],
+    report: &mut CrawlVerifyReport,
+    sample_size: usize,
+) -> Vec<WikiEntry> {
+    let mut samples: Vec<WikiEntry> = Vec::new();
+
+    for line in bytes.split(|&b| b == b'\n') {
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            continue;
+        }
+        report.total_lines += 1;
+
+        let line = match std::str::from_utf8(line) {
+            Ok(line) => line,
+            Err(_) => {
+                report.invalid_utf8_lines += 1;
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<WikiEntry>(line) {
+            Ok(entry) => {
+                report.parsed_entries += 1;
+                if entry.content.trim().is_empty() {
+                    report.empty_contents += 1;
+                } else if samples.len() < sample_size {
+                    samples.push(entry);
+                }
+            }
+            Err(_) => report.parse_errors += 1,
+        }
+    }
+
+    if report.parsed_entries == 0 {
+        report.issues.push("没有可解析的 Wiki 条目".to_string());
+    }
+    if report.invalid_utf8_lines > 0 {
+        report.issues.push(format!(
+            "{} 行不是有效的 UTF-8 编码",
+            report.invalid_utf8_lines
+        ));
+    }
+    if report.parse_errors > 0 {
+        report
+            .issues
+            .push(format!("{} 行 JSON 解析失败", report.parse_errors));
+    }
+    if report.empty_contents > 0 {
+        report.issues.push(format!(
+            "{} 个条目内容为空 (导入时将无意义)",
+            report.empty_contents
+        ));
+    }
+
+    samples
+}
+
+/// 外部 JSONL 导入结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportResult {
+    /// 新版本时间戳
+    pub timestamp: u64,
+    /// 复制后的 JSONL 路径
+    pub jsonl_path: String,
+    /// 导入的条目数
+    pub total_entries: usize,
+    /// 导入结果信息
+    pub message: String,
+}
+
+/// 从任意路径导入预先爬取的 JSONL (例如其他用户分享的知识库)
+///
+/// 文件必须每一行都是有效的 `WikiEntry`; 校验通过后复制到游戏存储目录作为新版本, 再导入向量数据库
+#[tauri::command]
+pub async fn import_external_jsonl(
+    game_id: String,
+    path: String,
+) -> Result<ExternalImportResult, String> {
+    import_external_jsonl_impl(game_id, path)
+        .await
+        .map_err(|e| format!("导入外部知识库失败: {}", e))
+}
+
+async fn import_external_jsonl_impl(game_id: String, path: String) -> Result<ExternalImportResult> {
+    let config_dir = AppSettings::config_dir()?;
+    let config =
+        Config::from_toml_file(config_dir.join("games.toml")).map_err(anyhow::Error::msg)?;
+    if config.find_game(&game_id).is_none() {
+        anyhow::bail!("未找到游戏配置: {}", game_id);
+    }
+
+    log::info!("📥 导入外部知识库: {} -> {}", path, game_id);
+
+    // 1. 严格校验: 任何一行无效都拒绝导入
+    let bytes = fs::read(&path)?;
+    let mut report = CrawlVerifyReport {
+        jsonl_path: path.clone(),
+        ..Default::default()
+    };
+    validate_jsonl(&bytes, &mut report, 0);
+
+    if report.parsed_entries == 0
+        || report.invalid_utf8_lines > 0
+        || report.parse_errors > 0
+        || report.parsed_entries == report.empty_contents
+    {
+        anyhow::bail!("文件不是有效的 Wiki JSONL: {}", report.issues.join("; "));
+    }
+
+    // 2. 复制到游戏存储目录作为新版本
+    let settings = AppSettings::load()?;
+    let game_dir = PathBuf::from(&settings.skill_library.storage_base_path).join(&game_id);
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let version_dir = game_dir.join(timestamp.to_string());
+    if version_dir.exists() {
+        anyhow::bail!("版本目录已存在, 请稍后重试: {}", version_dir.display());
+    }
+    fs::create_dir_all(&version_dir)?;
+
+    let jsonl_path = version_dir.join("wiki_raw.jsonl");
+    fs::write(&jsonl_path, &bytes)?;
+
+    let metadata = json!({
+        "game_id": game_id,
+        "source_url": path,
+        "source_type": "External",
+        "timestamp": timestamp,
+        "total_entries": report.parsed_entries,
+        "total_bytes": bytes.len(),
+    });
+    fs::write(
+        version_dir.join("metadata.json"),
+        serde_json::to_string_pretty(&metadata)?,
+    )?;
+
+    let snapshot = json!({ "external_path": path });
+    if let Err(e) = record_crawl_version(&game_dir, timestamp, snapshot) {
+        log::warn!("⚠️ 记录版本变化失败: {}", e);
+    }
+
+    // 3. 导入向量数据库
+    let jsonl_path = jsonl_path.to_string_lossy().to_string();
+    let message = import_wiki_to_vector_db_impl(jsonl_path.clone(), game_id).await?;
+
+    Ok(ExternalImportResult {
+        timestamp,
+        jsonl_path,
+        total_entries: report.parsed_entries,
+        message,
+    })
+}
+
+/// 爬取结果校验报告
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawlVerifyReport {
+    /// 是否可以导入 (go/no-go)
+    pub ok: bool,
+    /// 校验的 JSONL 文件
+    pub jsonl_path: String,
+    /// 非空行数
+    pub total_lines: usize,
+    /// 成功解析的条目数
+    pub parsed_entries: usize,
+    /// JSON 解析失败的行数
+    pub parse_errors: usize,
+    /// 非 UTF-8 编码的行数
+    pub invalid_utf8_lines: usize,
+    /// 内容为空的条目数
+    pub empty_contents: usize,
+    /// Embedding 抽样是否成功
+    pub embedding_ok: bool,
+    /// Embedding 实际维度
+    pub embedding_dimension: Option<usize>,
+    /// 模型预设维度
+    pub expected_dimension: Option<usize>,
+    /// 发现的问题
+    pub issues: Vec<String>,
+}
+
+/// 游戏知识库状态
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeBaseStatus {
+    /// 是否已下载 Wiki (最新版本包含 wiki_raw.jsonl)
+    pub downloaded: bool,
+    /// 是否已导入向量数据库
+    pub imported: bool,
+    /// 最新爬取版本的时间戳
+    pub latest_crawl_timestamp: Option<u64>,
+    /// 最新版本的条目数
+    pub entry_count: usize,
+}
+
+/// 带知识库状态的游戏配置
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameWithStatus {
+    #[serde(flatten)]
+    pub game: GameConfig,
+    pub status: KnowledgeBaseStatus,
+}
+
+/// 检索阶段耗时 (毫秒)
+#[derive(Debug, Clone, Default)]
+pub struct SearchTimings {
+    /// 查询向量生成耗时
+    pub embed_ms: u64,
+    /// 向量/关键词检索耗时
+    pub retrieve_ms: u64,
+}
+
 /// Wiki 搜索结果
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -849,16 +1815,162 @@ pub struct WikiSearchResult {
     pub categories: Vec<String>,
 }
 
+/// 近邻条目
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryNeighbor {
+    /// 相似度分数
+    pub score: f32,
+    /// 条目 ID
+    pub id: String,
+    /// 标题
+    pub title: String,
+}
+
+/// 知识库导入状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VectorDBStatus {
+    /// 未导入
+    NotImported,
+    /// 已导入, 统计可用
+    Ready,
+    /// 无法获取 (后端连接失败或数据读取失败)
+    Unavailable,
+}
+
 /// 向量数据库统计信息
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VectorDBStats {
-    /// 集合是否存在
-    pub exists: bool,
-    /// 向量数量
-    pub vectors_count: u64,
-    /// 点数量
-    pub points_count: u64,
+    /// 导入状态
+    pub status: VectorDBStatus,
+    /// 集合是否存在 (无法判断时为 None)
+    pub exists: Option<bool>,
+    /// 向量数量 (None 表示不可用, AI 直接检索模式没有向量)
+    pub vectors_count: Option<u64>,
+    /// 点数量 (None 表示不可用)
+    pub points_count: Option<u64>,
     /// 游戏 ID
     pub game_id: String,
 }
+
+impl VectorDBStats {
+    fn not_imported(game_id: String) -> Self {
+        Self {
+            status: VectorDBStatus::NotImported,
+            exists: Some(false),
+            vectors_count: Some(0),
+            points_count: Some(0),
+            game_id,
+        }
+    }
+
+    fn ready(game_id: String, vectors_count: Option<u64>, points_count: u64) -> Self {
+        Self {
+            status: VectorDBStatus::Ready,
+            exists: Some(true),
+            vectors_count,
+            points_count: Some(points_count),
+            game_id,
+        }
+    }
+
+    fn unavailable(game_id: String, exists: Option<bool>) -> Self {
+        Self {
+            status: VectorDBStatus::Unavailable,
+            exists,
+            vectors_count: None,
+            points_count: None,
+            game_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_jsonl() {
+        let entry = json!({
+            "id": "a",
+            "title": "A",
+            "content": "hello",
+            "url": "https://example.com/a",
+            "timestamp": 0,
+            "hash": "1",
+            "categories": [],
+            "metadata": { "length": 5, "language": "en" },
+        });
+        let mut bytes = format!("{}\n\n{{\"id\": 1}}\n", entry).into_bytes();
+        bytes.extend_from_slice(b"\xff\xfe\n");
+
+        let mut report = CrawlVerifyReport::default();
+        let samples = validate_jsonl(&bytes, &mut report, 3);
+
+        assert_eq!(samples.len(), 1);
+        assert_eq!(report.total_lines, 3);
+        assert_eq!(report.parsed_entries, 1);
+        assert_eq!(report.parse_errors, 1);
+        assert_eq!(report.invalid_utf8_lines, 1);
+        assert_eq!(report.issues.len(), 2);
+    }
+
+    #[test]
+    fn test_dedupe_by_entry() {
+        let result = |id: &str, score: f32| WikiSearchResult {
+            score,
+            id: id.to_string(),
+            title: id.to_uppercase(),
+            content: String::new(),
+            url: format!("https://example.com/{}", id),
+            categories: Vec::new(),
+        };
+        let mut results = vec![
+            result("a", 0.9),
+            result("b", 0.8),
+            result("a", 0.7),
+            result("c", 0.6),
+            result("b", 0.5),
+        ];
+
+        dedupe_by_entry(&mut results);
+
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(results[0].score, 0.9);
+    }
+
+    #[test]
+    fn test_imported_entry_count() {
+        let point = |id: u64, entry: &str| (id, vec![0.0], json!({ "id": entry }));
+        let points = vec![point(0, "a"), point(1, "a"), point(2, "b")];
+        assert_eq!(imported_entry_count(&points), 2);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let result = |url: &str| WikiSearchResult {
+            score: 0.0,
+            id: url.to_string(),
+            title: url.to_uppercase(),
+            content: String::new(),
+            url: url.to_string(),
+            categories: Vec::new(),
+        };
+        let vector = vec![result("a"), result("a"), result("b"), result("c")];
+        let keyword = vec![result("c"), result("d")];
+
+        let fused = reciprocal_rank_fusion(vector.clone(), keyword.clone(), 0.5);
+        let urls: Vec<&str> = fused.iter().map(|r| r.url.as_str()).collect();
+        // c 在两组中都命中, 排在只出现在一组中的 a 之前
+        assert_eq!(urls, vec!["c", "a", "b", "d"]);
+        assert!(fused.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+
+        // 权重为 1 时只按向量排名
+        let fused = reciprocal_rank_fusion(vector, keyword, 1.0);
+        let urls: Vec<&str> = fused.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(&urls[..3], &["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,352 @@
+/// 向量数据库迁移命令
+///
+/// 在 local / qdrant / ai_direct 三种模式之间迁移已导入的知识库, 无需重新爬取:
+/// - local <-> qdrant: 直接复制向量和 payload
+/// - ai_direct -> local / qdrant: 只有原始文本, 需要重新生成 embedding
+/// - local / qdrant -> ai_direct: 从 payload 导出 JSONL
+///
+/// 迁移成功后当前模式切换为目标模式, 进度通过 `vector_db_migration_progress` 事件推送
+use crate::{
+    embeddings::EmbeddingService,
+    settings::{AppSettings, VectorDBSettings},
+    vector_db::{LocalVectorDB, RetryPolicy, VectorDB},
+};
+use anyhow::Result;
+use serde_json::json;
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+/// 每批写入的点数
+const WRITE_BATCH_SIZE: usize = 200;
+/// 每批生成 embedding 的条目数
+const EMBED_BATCH_SIZE: usize = 50;
+/// Qdrant 分页读取大小
+const SCROLL_PAGE_SIZE: usize = 256;
+
+/// 迁移中的数据点 (来自 ai_direct 的点没有向量)
+struct MigrationPoint {
+    id: u64,
+    vector: Option<Vec<f32>>,
+    payload: serde_json::Value,
+}
+
+/// 迁移结果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub game_id: String,
+    pub from_mode: String,
+    pub to_mode: String,
+    /// 迁移的条目数
+    pub migrated: usize,
+    /// 是否重新生成了 embedding
+    pub re_embedded: bool,
+}
+
+/// 迁移游戏知识库到另一种向量数据库模式, 并切换当前模式
+#[tauri::command]
+pub async fn migrate_vector_db(
+    app: AppHandle,
+    game_id: String,
+    from_mode: String,
+    to_mode: String,
+) -> Result<MigrationReport, String> {
+    migrate_vector_db_impl(&app, game_id, from_mode, to_mode)
+        .await
+        .map_err(|e| format!("迁移向量数据库失败: {}", e))
+}
+
+async fn migrate_vector_db_impl(
+    app: &AppHandle,
+    game_id: String,
+    from_mode: String,
+    to_mode: String,
+) -> Result<MigrationReport> {
+    for mode in [&from_mode, &to_mode] {
+        if !matches!(mode.as_str(), "local" | "qdrant" | "ai_direct") {
+            anyhow::bail!("不支持的向量数据库模式: {}", mode);
+        }
+    }
+    if from_mode == to_mode {
+        anyhow::bail!("源模式和目标模式相同: {}", from_mode);
+    }
+
+    log::info!(
+        "🚚 开始迁移知识库: {} ({} -> {})",
+        game_id,
+        from_mode,
+        to_mode
+    );
+
+    let mut settings = AppSettings::load()?;
+    let vdb_config = settings.ai_models.vector_db.clone();
+    let collection_name = format!("game_wiki_{}", game_id);
+
+    // 1. 读取源数据
+    let mut points = match from_mode.as_str() {
+        "local" => read_local(&vdb_config, &collection_name)?,
+        "qdrant" => read_qdrant(&vdb_config, &collection_name).await?,
+        _ => read_ai_direct(&vdb_config, &game_id)?,
+    };
+    if points.is_empty() {
+        anyhow::bail!("源知识库为空, 请先导入 Wiki 数据");
+    }
+    let total = points.len();
+    emit_progress(app, &game_id, "read", total, total);
+    log::info!("✅ 读取 {} 条数据", total);
+
+    // 2. 目标需要向量但源没有时重新生成 embedding
+    let re_embedded = to_mode != "ai_direct" && points.iter().any(|p| p.vector.is_none());
+    if re_embedded {
+        embed_missing(app, &settings, &game_id, &mut points).await?;
+    }
+
+    // 3. 写入目标
+    match to_mode.as_str() {
+        "local" => write_local(app, &vdb_config, &collection_name, &game_id, points)?,
+        "qdrant" => write_qdrant(app, &vdb_config, &collection_name, &game_id, points).await?,
+        _ => write_ai_direct(&vdb_config, &game_id, &points)?,
+    }
+    emit_progress(app, &game_id, "write", total, total);
+
+    // 4. 切换当前模式
+    settings.ai_models.vector_db.mode = to_mode.clone();
+    settings.save()?;
+
+    log::info!("🎉 知识库迁移完成: {} 条, 当前模式: {}", total, to_mode);
+
+    Ok(MigrationReport {
+        game_id,
+        from_mode,
+        to_mode,
+        migrated: total,
+        re_embedded,
+    })
+}
+
+fn local_storage_path(vdb_config: &VectorDBSettings) -> PathBuf {
+    PathBuf::from(
+        vdb_config
+            .local_storage_path
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| "./data/vector_db".to_string()),
+    )
+}
+
+async fn connect_qdrant(vdb_config: &VectorDBSettings, collection_name: &str) -> Result<VectorDB> {
+    let qdrant_url = vdb_config
+        .qdrant_url
+        .as_ref()
+        .cloned()
+        .unwrap_or_else(|| "http://localhost:6333".to_string());
+    Ok(VectorDB::new(&qdrant_url, collection_name)
+        .await?
+        .with_retry(RetryPolicy::from_settings(vdb_config)))
+}
+
+fn read_local(vdb_config: &VectorDBSettings, collection_name: &str) -> Result<Vec<MigrationPoint>> {
+    let local_db = LocalVectorDB::new(local_storage_path(vdb_config), collection_name)?;
+    Ok(local_db
+        .read_all_points()?
+        .into_iter()
+        .map(|(id, vector, payload)| MigrationPoint {
+            id,
+            vector: Some(vector),
+            payload,
+        })
+        .collect())
+}
+
+async fn read_qdrant(
+    vdb_config: &VectorDBSettings,
+    collection_name: &str,
+) -> Result<Vec<MigrationPoint>> {
+    let vector_db = connect_qdrant(vdb_config, collection_name).await?;
+    if !vector_db.collection_exists().await? {
+        anyhow::bail!("Qdrant 集合不存在: {}", collection_name);
+    }
+
+    let mut points = Vec::new();
+    let mut offset = None;
+    loop {
+        let (page, next_offset) = vector_db
+            .scroll_points_with_vectors(offset, SCROLL_PAGE_SIZE)
+            .await?;
+        for (id, vector, payload) in page {
+            points.push(MigrationPoint {
+                id,
+                vector: Some(vector),
+                payload,
+            });
+        }
+
+        match next_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(points)
+}
+
+fn read_ai_direct(vdb_config: &VectorDBSettings, game_id: &str) -> Result<Vec<MigrationPoint>> {
+    let jsonl_path = local_storage_path(vdb_config).join(format!("{}.jsonl", game_id));
+    let content = std::fs::read_to_string(&jsonl_path)?;
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .enumerate()
+        .map(|(i, entry)| {
+            let url = entry["url"].as_str().unwrap_or_default();
+            MigrationPoint {
+                id: i as u64,
+                vector: None,
+                // AI 直接检索只保存了标题/内容/URL, 用 URL 作为条目 ID
+                payload: json!({
+                    "id": url,
+                    "title": entry["title"].as_str().unwrap_or_default(),
+                    "content": entry["content"].as_str().unwrap_or_default(),
+                    "url": url,
+                    "categories": [],
+                    "game_id": game_id,
+                }),
+            }
+        })
+        .collect())
+}
+
+/// 为没有向量的点生成 embedding
+async fn embed_missing(
+    app: &AppHandle,
+    settings: &AppSettings,
+    game_id: &str,
+    points: &mut [MigrationPoint],
+) -> Result<()> {
+    let embedding_config = &settings.ai_models.embedding;
+    let embedding_service = EmbeddingService::from_config(embedding_config).await?;
+
+    let total = points.len();
+    let mut processed = 0;
+    for chunk in points.chunks_mut(EMBED_BATCH_SIZE) {
+        let texts: Vec<&str> = chunk
+            .iter()
+            .map(|p| p.payload["content"].as_str().unwrap_or_default())
+            .collect();
+        let embeddings = embedding_service.embed_batch_partial(&texts).await?;
+        for (index, reason) in &embeddings.skipped {
+            log::warn!("⚠️ 跳过点 {}: {}", chunk[*index].id, reason);
+        }
+
+        // 跳过的点没有向量, 迁移时会被丢弃
+        for (point, vector) in chunk.iter_mut().zip(embeddings.vectors) {
+            point.vector = vector;
+        }
+
+        processed += chunk.len();
+        emit_progress(app, game_id, "embed", processed, total);
+    }
+
+    Ok(())
+}
+
+/// 取出 (id, 向量, payload), 第一个点的向量长度作为集合维度
+fn into_vector_points(
+    points: Vec<MigrationPoint>,
+) -> Result<(usize, Vec<(u64, Vec<f32>, serde_json::Value)>)> {
+    let points: Vec<_> = points
+        .into_iter()
+        .filter_map(|p| Some((p.id, p.vector?, p.payload)))
+        .collect();
+    let dimension = points
+        .first()
+        .map(|(_, vector, _)| vector.len())
+        .ok_or_else(|| anyhow::anyhow!("没有可迁移的向量"))?;
+    Ok((dimension, points))
+}
+
+fn write_local(
+    app: &AppHandle,
+    vdb_config: &VectorDBSettings,
+    collection_name: &str,
+    game_id: &str,
+    points: Vec<MigrationPoint>,
+) -> Result<()> {
+    let (dimension, points) = into_vector_points(points)?;
+    let local_db = LocalVectorDB::new(local_storage_path(vdb_config), collection_name)?;
+    local_db.create_collection(dimension)?;
+
+    let total = points.len();
+    let mut written = 0;
+    for chunk in points.chunks(WRITE_BATCH_SIZE) {
+        local_db.upsert_points(chunk.to_vec())?;
+        written += chunk.len();
+        emit_progress(app, game_id, "write", written, total);
+    }
+
+    Ok(())
+}
+
+async fn write_qdrant(
+    app: &AppHandle,
+    vdb_config: &VectorDBSettings,
+    collection_name: &str,
+    game_id: &str,
+    points: Vec<MigrationPoint>,
+) -> Result<()> {
+    let (dimension, points) = into_vector_points(points)?;
+    let vector_db = connect_qdrant(vdb_config, collection_name).await?;
+
+    if vector_db.collection_exists().await? {
+        log::warn!("⚠️  目标集合已存在，正在删除...");
+        vector_db.delete_collection().await?;
+    }
+    vector_db.create_collection(dimension as u64).await?;
+
+    let total = points.len();
+    let mut written = 0;
+    for chunk in points.chunks(WRITE_BATCH_SIZE) {
+        vector_db.upsert_points(chunk.to_vec()).await?;
+        written += chunk.len();
+        emit_progress(app, game_id, "write", written, total);
+    }
+
+    Ok(())
+}
+
+fn write_ai_direct(
+    vdb_config: &VectorDBSettings,
+    game_id: &str,
+    points: &[MigrationPoint],
+) -> Result<()> {
+    let storage_path = local_storage_path(vdb_config);
+    std::fs::create_dir_all(&storage_path)?;
+
+    // 与 import_to_ai_direct 的格式一致
+    let mut file = std::fs::File::create(storage_path.join(format!("{}.jsonl", game_id)))?;
+    for point in points {
+        let simple_entry = json!({
+            "title": point.payload["title"],
+            "content": point.payload["content"],
+            "url": point.payload["url"],
+        });
+        writeln!(file, "{}", serde_json::to_string(&simple_entry)?)?;
+    }
+
+    Ok(())
+}
+
+fn emit_progress(app: &AppHandle, game_id: &str, stage: &str, processed: usize, total: usize) {
+    let _ = app.emit(
+        "vector_db_migration_progress",
+        json!({
+            "gameId": game_id,
+            "stage": stage,
+            "processed": processed,
+            "total": total,
+        }),
+    );
+}
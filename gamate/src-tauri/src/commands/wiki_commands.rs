@@ -1,7 +1,19 @@
+use crate::commands::vector_commands::find_latest_crawl_timestamp;
+use crate::config::{Config, SkillConfig};
+use crate::crawler::checkpoint::find_unfinished_crawl;
+use crate::crawler::history::{config_snapshot, record_crawl_version};
 use crate::crawler::{
-    CrawlerConfig, CrawlerResult, FandomApiCrawler, GitHubCrawler, WebCrawler, WikiSourceType,
+    default_namespaces, default_output_formats, CrawlerConfig, CrawlerResult, GitHubCrawler,
+    MediaWikiApiCrawler, WebCrawler, WikiEntry, WikiSourceType, DEFAULT_MAX_CONCURRENT_REQUESTS,
+    DEFAULT_MAX_ENTRY_CHARS, DEFAULT_USER_AGENT,
 };
-use std::path::PathBuf;
+use crate::settings::AppSettings;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
 
 /// 下载 Wiki 命令参数
 #[derive(Debug, serde::Deserialize)]
@@ -14,6 +26,27 @@ pub struct DownloadWikiParams {
     pub timestamp: u64,
     pub storage_path: String,
     pub github_token: Option<String>,
+    /// MediaWiki 命名空间 (为空时只爬取主命名空间)
+    #[serde(default)]
+    pub namespaces: Option<Vec<u32>>,
+    /// MediaWiki 分类 (设置后只爬取这些分类下的页面)
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// 自定义 User-Agent
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// 自定义请求头
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
+    /// 预置 Cookie
+    #[serde(default)]
+    pub cookies: Option<HashMap<String, String>>,
+    /// 是否继续上次中断的爬取 (为空时: 游戏目录下有未完成的爬取就继续)
+    #[serde(default)]
+    pub resume: Option<bool>,
+    /// 输出格式 (为空时只输出 JSONL)
+    #[serde(default)]
+    pub output_formats: Option<Vec<String>>,
 }
 
 /// 下载 Wiki
@@ -22,29 +55,57 @@ pub async fn download_wiki(params: DownloadWikiParams) -> Result<CrawlerResult,
     log::info!("开始下载 Wiki: {}", params.game_id);
 
     // 解析源类型
-    let source_type = match params.source_type.as_str() {
-        "FandomWiki" => WikiSourceType::FandomWiki,
-        "GamepediaWiki" => WikiSourceType::GamepediaWiki,
-        "GitHub" => WikiSourceType::GitHub,
-        "CustomWeb" => WikiSourceType::CustomWeb,
-        _ => return Err("不支持的 Wiki 源类型".to_string()),
-    };
+    let source_type: WikiSourceType = params
+        .source_type
+        .parse()
+        .map_err(|e| format!("不支持的 Wiki 源类型: {}", e))?;
+
+    let (storage_path, timestamp, resume) = resolve_crawl_target(
+        PathBuf::from(&params.storage_path),
+        params.timestamp,
+        params.resume,
+    );
+
+    let (max_entry_chars, max_concurrent_requests) = AppSettings::load()
+        .map(|settings| {
+            let crawler = &settings.skill_library.crawler;
+            (crawler.max_entry_chars, crawler.max_concurrent_requests)
+        })
+        .unwrap_or((DEFAULT_MAX_ENTRY_CHARS, DEFAULT_MAX_CONCURRENT_REQUESTS));
 
     // 构建配置
     let config = CrawlerConfig {
         game_id: params.game_id.clone(),
         source_type: source_type.clone(),
         source_url: params.repo.clone(),
-        storage_path: PathBuf::from(&params.storage_path),
-        timestamp: params.timestamp,
+        storage_path: storage_path.clone(),
+        timestamp,
         max_pages: 500, // 限制最大页面数
         max_depth: 5,
         request_delay_ms: 500,
-        user_agent: "GamePartnerSkill/1.0 (Educational Purpose)".to_string(),
+        user_agent: params
+            .user_agent
+            .clone()
+            .filter(|ua| !ua.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+        headers: params.headers.clone().unwrap_or_default(),
+        cookies: params.cookies.clone().unwrap_or_default(),
         include_images: false,
         github_token: params.github_token.clone(),
+        max_entry_chars,
+        namespaces: params.namespaces.clone().unwrap_or_else(default_namespaces),
+        categories: params.categories.clone(),
+        max_concurrent_requests,
+        resume,
+        respect_robots: true,
+        output_formats: params
+            .output_formats
+            .clone()
+            .unwrap_or_else(default_output_formats),
     };
 
+    let snapshot = config_snapshot(&config);
+
     // 根据源类型选择爬虫
     let result = match source_type {
         WikiSourceType::GitHub => {
@@ -53,9 +114,9 @@ pub async fn download_wiki(params: DownloadWikiParams) -> Result<CrawlerResult,
             crawler.crawl().await
         }
         WikiSourceType::FandomWiki | WikiSourceType::GamepediaWiki => {
-            // 使用 Fandom API 而不是 HTML 爬虫
-            log::info!("使用 Fandom MediaWiki API");
-            let mut crawler = FandomApiCrawler::new(config);
+            // 使用 MediaWiki API 而不是 HTML 爬虫 (自动查找 api.php)
+            log::info!("使用 MediaWiki API");
+            let mut crawler = MediaWikiApiCrawler::new(config);
             crawler.crawl().await
         }
         WikiSourceType::CustomWeb => {
@@ -64,7 +125,380 @@ pub async fn download_wiki(params: DownloadWikiParams) -> Result<CrawlerResult,
         }
     };
 
-    result.map_err(|e| format!("爬取失败: {}", e))
+    let result = result.map_err(|e| format!("爬取失败: {}", e))?;
+
+    // 记录与上一版本的差异 (失败不影响爬取结果)
+    if let Some(game_dir) = storage_path.parent() {
+        match record_crawl_version(game_dir, timestamp, snapshot) {
+            Ok(diff) => log::info!(
+                "📝 版本变化: 新增 {}, 更新 {}, 删除 {}",
+                diff.added,
+                diff.updated,
+                diff.removed
+            ),
+            Err(e) => log::warn!("⚠️ 记录版本变化失败: {}", e),
+        }
+    }
+
+    Ok(result)
+}
+
+/// 确定本次爬取的存储目录, 返回 (目录, 时间戳, 是否续爬)
+///
+/// 游戏目录下有未完成的爬取时: 续爬则写回该目录; 不续爬则删除该目录, 在新目录中从头开始
+fn resolve_crawl_target(
+    storage_path: PathBuf,
+    timestamp: u64,
+    resume: Option<bool>,
+) -> (PathBuf, u64, bool) {
+    let Some(game_dir) = storage_path.parent() else {
+        return (storage_path, timestamp, false);
+    };
+    let Some((unfinished_timestamp, unfinished_path)) = find_unfinished_crawl(game_dir) else {
+        return (storage_path, timestamp, false);
+    };
+
+    if resume != Some(false) {
+        log::info!("♻️ 继续未完成的爬取: {:?}", unfinished_path);
+        return (unfinished_path, unfinished_timestamp, true);
+    }
+
+    log::info!("🗑️ 放弃未完成的爬取: {:?}", unfinished_path);
+    if let Err(e) = std::fs::remove_dir_all(&unfinished_path) {
+        log::warn!("⚠️ 删除未完成的爬取目录失败: {}", e);
+    }
+    (storage_path, timestamp, false)
+}
+
+/// 按技能库配置爬取游戏 Wiki, 存储到 `{base_path}/{game_id}/{timestamp}`
+pub(crate) async fn download_skill_library(
+    game_id: &str,
+    skill: &SkillConfig,
+    base_path: &Path,
+    timestamp: u64,
+) -> Result<CrawlerResult, String> {
+    let storage_path = base_path.join(game_id).join(timestamp.to_string());
+
+    download_wiki(DownloadWikiParams {
+        game_id: game_id.to_string(),
+        skill_config_id: skill.id.clone(),
+        repo: skill.repo.clone(),
+        source_type: skill.source_type.clone(),
+        timestamp,
+        storage_path: storage_path.to_string_lossy().to_string(),
+        github_token: None,
+        namespaces: None,
+        categories: None,
+        user_agent: skill.user_agent.clone(),
+        headers: skill.headers.clone(),
+        cookies: skill.cookies.clone(),
+        resume: None,
+        output_formats: None,
+    })
+    .await
+}
+
+/// 批量下载中单个游戏的结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchDownloadItem {
+    pub game_id: String,
+    pub success: bool,
+    pub storage_path: Option<String>,
+    pub total_entries: usize,
+    pub error: Option<String>,
+}
+
+/// 批量下载多个游戏的 Wiki
+///
+/// - 同一站点 (完整主机名相同) 的爬取串行执行, 避免触发限流
+/// - 总并发数不超过 `max_concurrent` (默认使用爬虫设置中的最大并发数)
+/// - 每完成一个游戏发送 `wiki_batch_progress` 事件
+#[tauri::command]
+pub async fn download_wikis(
+    app: AppHandle,
+    config: State<'_, Config>,
+    game_ids: Vec<String>,
+    max_concurrent: Option<usize>,
+) -> Result<Vec<BatchDownloadItem>, String> {
+    let settings = AppSettings::load().map_err(|e| format!("加载配置失败: {}", e))?;
+    let max_concurrent = max_concurrent
+        .unwrap_or(settings.skill_library.crawler.max_concurrent_requests)
+        .max(1);
+    let base_path = PathBuf::from(&settings.skill_library.storage_base_path);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    log::info!(
+        "📦 批量下载 Wiki: {} 个游戏, 最大并发 {}",
+        game_ids.len(),
+        max_concurrent
+    );
+
+    // 解析每个游戏的技能库配置
+    let mut jobs = Vec::new();
+    let mut results = Vec::new();
+    for game_id in &game_ids {
+        match config
+            .find_game(game_id)
+            .and_then(|game| game.skill_configs.first())
+        {
+            Some(skill) => jobs.push((game_id.clone(), skill.clone())),
+            None => results.push(BatchDownloadItem {
+                game_id: game_id.clone(),
+                success: false,
+                storage_path: None,
+                total_entries: 0,
+                error: Some("未找到该游戏的技能库配置".to_string()),
+            }),
+        }
+    }
+
+    // 全局并发限制 + 每个站点一次只允许一个爬取任务
+    let global_limiter = Arc::new(Semaphore::new(max_concurrent));
+    let mut host_limiters: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    for (_, skill) in &jobs {
+        host_limiters
+            .entry(host_key(&skill.repo))
+            .or_insert_with(|| Arc::new(Semaphore::new(1)));
+    }
+
+    let total = game_ids.len();
+    let completed = Arc::new(AtomicUsize::new(results.len()));
+
+    let tasks = jobs.into_iter().map(|(game_id, skill)| {
+        let global_limiter = Arc::clone(&global_limiter);
+        let host_limiter = Arc::clone(&host_limiters[&host_key(&skill.repo)]);
+        let completed = Arc::clone(&completed);
+        let app = app.clone();
+        let base_path = base_path.clone();
+
+        async move {
+            // 先占用站点许可, 再占用全局许可, 避免等待中的任务占满全局并发
+            let _host_permit = host_limiter.acquire().await;
+            let _global_permit = global_limiter.acquire().await;
+
+            log::info!("⬇️ 开始下载: {} ({})", game_id, skill.repo);
+
+            let result = download_skill_library(&game_id, &skill, &base_path, timestamp).await;
+
+            let item = match result {
+                Ok(result) => BatchDownloadItem {
+                    game_id: game_id.clone(),
+                    success: true,
+                    storage_path: Some(result.storage_path),
+                    total_entries: result.total_entries,
+                    error: None,
+                },
+                Err(e) => {
+                    log::error!("❌ 下载失败: {} - {}", game_id, e);
+                    BatchDownloadItem {
+                        game_id: game_id.clone(),
+                        success: false,
+                        storage_path: None,
+                        total_entries: 0,
+                        error: Some(e),
+                    }
+                }
+            };
+
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            let _ = app.emit(
+                "wiki_batch_progress",
+                serde_json::json!({
+                    "gameId": item.game_id,
+                    "success": item.success,
+                    "completed": done,
+                    "total": total,
+                }),
+            );
+
+            item
+        }
+    });
+
+    results.extend(futures::future::join_all(tasks).await);
+
+    let success_count = results.iter().filter(|r| r.success).count();
+    log::info!("✅ 批量下载完成: {}/{} 成功", success_count, total);
+
+    Ok(results)
+}
+
+/// 计算限流用的站点标识 (完整主机名)
+///
+/// 不截取后两段: `*.co.uk`、`*.com.cn` 等站点的后两段是公共后缀, 截取后不相关的站点会被串行执行
+fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// 分类覆盖情况
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryCoverage {
+    pub name: String,
+    /// 来源中该分类的文章数
+    pub source_pages: u64,
+    /// 已爬取的文章数
+    pub crawled_pages: u64,
+}
+
+/// 知识库覆盖率报告
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub game_id: String,
+    pub source_type: String,
+    /// 最新版本已爬取的页面数
+    pub crawled_pages: usize,
+    /// 来源的文章总数 (不支持统计的来源为 None)
+    pub source_total: Option<usize>,
+    /// 覆盖率 (0-100)
+    pub coverage_percent: Option<f32>,
+    /// 缺失的页面数
+    pub missing_count: usize,
+    /// 部分缺失页面标题 (最多 20 个)
+    pub missing_titles_sample: Vec<String>,
+    /// 缺失最多的分类 (最多 10 个)
+    pub missing_categories: Vec<CategoryCoverage>,
+    /// 技能库配置中的最大页面数
+    pub max_pages: Option<usize>,
+    /// 说明
+    pub note: Option<String>,
+}
+
+/// 统计知识库覆盖率 (已爬取页面数 vs 来源总页数)
+///
+/// 目前支持 Fandom / Gamepedia (MediaWiki API), 其他来源只返回已爬取数量
+#[tauri::command]
+pub async fn coverage_report(
+    config: State<'_, Config>,
+    game_id: String,
+) -> Result<CoverageReport, String> {
+    let skill = config
+        .find_game(&game_id)
+        .and_then(|game| game.skill_configs.first())
+        .cloned()
+        .ok_or_else(|| format!("未找到游戏 {} 的技能库配置", game_id))?;
+
+    coverage_report_impl(game_id, skill)
+        .await
+        .map_err(|e| format!("统计覆盖率失败: {}", e))
+}
+
+async fn coverage_report_impl(
+    game_id: String,
+    skill: SkillConfig,
+) -> anyhow::Result<CoverageReport> {
+    let settings = AppSettings::load()?;
+    let game_dir = PathBuf::from(&settings.skill_library.storage_base_path).join(&game_id);
+
+    let Some(timestamp) = find_latest_crawl_timestamp(&game_dir)? else {
+        anyhow::bail!("游戏 {} 尚未下载技能库", game_id);
+    };
+
+    let jsonl_path = game_dir.join(timestamp.to_string()).join("wiki_raw.jsonl");
+    let entries: Vec<WikiEntry> = std::fs::read_to_string(&jsonl_path)?
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let mut report = CoverageReport {
+        game_id: game_id.clone(),
+        source_type: skill.source_type.clone(),
+        crawled_pages: entries.len(),
+        source_total: None,
+        coverage_percent: None,
+        missing_count: 0,
+        missing_titles_sample: Vec::new(),
+        missing_categories: Vec::new(),
+        max_pages: skill.max_pages,
+        note: None,
+    };
+
+    if !skill
+        .source_type
+        .parse::<WikiSourceType>()
+        .is_ok_and(|t| t.is_mediawiki())
+    {
+        report.note = Some("该来源不支持统计总页数".to_string());
+        return Ok(report);
+    }
+
+    let mut crawler = MediaWikiApiCrawler::new(CrawlerConfig {
+        game_id: game_id.clone(),
+        source_url: skill.repo.clone(),
+        ..Default::default()
+    });
+
+    // 页面覆盖率
+    let source_titles = crawler.list_article_titles().await?;
+    let crawled_titles: HashSet<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+    let missing: Vec<&String> = source_titles
+        .iter()
+        .filter(|t| !crawled_titles.contains(t.as_str()))
+        .collect();
+
+    report.source_total = Some(source_titles.len());
+    report.coverage_percent = (!source_titles.is_empty())
+        .then(|| (source_titles.len() - missing.len()) as f32 / source_titles.len() as f32 * 100.0);
+    report.missing_count = missing.len();
+    report.missing_titles_sample = missing.iter().take(20).map(|t| t.to_string()).collect();
+
+    // 分类覆盖率: 按缺失数量排序
+    let mut crawled_per_category: HashMap<&str, u64> = HashMap::new();
+    for entry in &entries {
+        for category in &entry.categories {
+            *crawled_per_category.entry(category.as_str()).or_default() += 1;
+        }
+    }
+
+    match crawler.list_category_sizes().await {
+        Ok(categories) => {
+            let mut gaps: Vec<CategoryCoverage> = categories
+                .into_iter()
+                .map(|(name, source_pages)| {
+                    let crawled_pages = crawled_per_category
+                        .get(name.as_str())
+                        .copied()
+                        .unwrap_or(0);
+                    CategoryCoverage {
+                        name,
+                        source_pages,
+                        crawled_pages,
+                    }
+                })
+                .filter(|c| c.crawled_pages < c.source_pages)
+                .collect();
+
+            gaps.sort_by_key(|c| std::cmp::Reverse(c.source_pages - c.crawled_pages));
+            gaps.truncate(10);
+            report.missing_categories = gaps;
+        }
+        Err(e) => log::warn!("⚠️ 获取分类列表失败: {}", e),
+    }
+
+    if report.missing_count > 0 {
+        report.note = Some(format!(
+            "缺失 {} 个页面, 可提高 max_pages 后重新下载",
+            report.missing_count
+        ));
+    }
+
+    log::info!(
+        "📊 覆盖率: {} - {}/{} 页",
+        game_id,
+        report.crawled_pages,
+        source_titles.len()
+    );
+
+    Ok(report)
 }
 
 /// 更新技能库
@@ -94,6 +528,13 @@ pub async fn update_skill_library(
         timestamp,
         storage_path: new_storage_path,
         github_token,
+        namespaces: None,
+        categories: None,
+        user_agent: None,
+        headers: None,
+        cookies: None,
+        resume: None,
+        output_formats: None,
     })
     .await
 }
@@ -1,4 +1,7 @@
+use crate::crawler::WikiSourceType;
+use crate::steam_auth::OwnedGame;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -16,6 +19,9 @@ pub struct GameConfig {
     pub release_date: Option<String>,
     pub developer: Option<String>,
     pub publisher: Option<String>,
+    /// Steam AppID (用于把 Steam 游戏库中的游戏对应到此配置)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steam_appid: Option<u32>,
     pub skill_configs: Vec<SkillConfig>,
 }
 
@@ -31,6 +37,12 @@ pub struct SkillConfig {
     pub max_pages: Option<usize>,
     pub max_depth: Option<usize>,
     pub request_delay_ms: Option<u64>,
+    /// 自定义 User-Agent (部分 Wiki 的 API 规范要求包含联系方式)
+    pub user_agent: Option<String>,
+    /// 自定义请求头
+    pub headers: Option<HashMap<String, String>>,
+    /// 预置 Cookie (如同意 Cookie 弹窗后得到的 consent Cookie)
+    pub cookies: Option<HashMap<String, String>>,
 }
 
 /// 根配置
@@ -39,6 +51,43 @@ pub struct Config {
     pub games: Vec<GameConfig>,
 }
 
+/// 配置问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigIssueKind {
+    /// ID 为空
+    EmptyId,
+    /// 游戏 ID 重复
+    DuplicateGameId,
+    /// 技能配置 ID 重复 (技能配置 ID 全局唯一)
+    DuplicateSkillConfigId,
+    /// 未知的来源类型
+    UnknownSourceType,
+    /// 技能库地址为空
+    EmptyRepo,
+}
+
+/// 配置校验发现的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    pub kind: ConfigIssueKind,
+    /// 所属游戏 ID
+    pub game_id: String,
+    /// 所属技能配置 ID (游戏级问题为 None)
+    pub skill_config_id: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.skill_config_id {
+            Some(skill_id) => write!(f, "[{} / {}] {}", self.game_id, skill_id, self.message),
+            None => write!(f, "[{}] {}", self.game_id, self.message),
+        }
+    }
+}
+
 impl Config {
     /// 从 TOML 文件加载配置，如果不存在则创建默认配置
     pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
@@ -68,6 +117,13 @@ impl Config {
         toml::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))
     }
 
+    /// 保存配置到 TOML 文件
+    pub fn save_to_toml_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let content = toml::to_string_pretty(self).map_err(|e| format!("序列化配置失败: {}", e))?;
+
+        fs::write(path, content).map_err(|e| format!("写入配置文件失败: {}", e))
+    }
+
     /// 从 JSON 文件加载配置
     pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
         let content = fs::read_to_string(path).map_err(|e| format!("无法读取配置文件: {}", e))?;
@@ -75,11 +131,136 @@ impl Config {
         serde_json::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))
     }
 
+    /// 语义校验: ID 非空且唯一、来源类型可识别、技能库地址非空
+    ///
+    /// 返回发现的所有问题 (为空表示配置有效)
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut game_ids = HashSet::new();
+        let mut skill_ids = HashSet::new();
+
+        for game in &self.games {
+            let issue = |kind, skill_config_id: Option<&str>, message: String| ConfigIssue {
+                kind,
+                game_id: game.id.clone(),
+                skill_config_id: skill_config_id.map(str::to_string),
+                message,
+            };
+
+            if game.id.trim().is_empty() {
+                issues.push(issue(
+                    ConfigIssueKind::EmptyId,
+                    None,
+                    format!("游戏 \"{}\" 的 ID 为空", game.name),
+                ));
+            } else if !game_ids.insert(game.id.as_str()) {
+                issues.push(issue(
+                    ConfigIssueKind::DuplicateGameId,
+                    None,
+                    format!("游戏 ID 重复: {}", game.id),
+                ));
+            }
+
+            for skill in &game.skill_configs {
+                let skill_id = Some(skill.id.as_str());
+
+                if skill.id.trim().is_empty() {
+                    issues.push(issue(
+                        ConfigIssueKind::EmptyId,
+                        skill_id,
+                        format!("技能配置 \"{}\" 的 ID 为空", skill.name),
+                    ));
+                } else if !skill_ids.insert(skill.id.as_str()) {
+                    issues.push(issue(
+                        ConfigIssueKind::DuplicateSkillConfigId,
+                        skill_id,
+                        format!("技能配置 ID 重复: {}", skill.id),
+                    ));
+                }
+
+                if skill.source_type.parse::<WikiSourceType>().is_err() {
+                    issues.push(issue(
+                        ConfigIssueKind::UnknownSourceType,
+                        skill_id,
+                        format!(
+                            "未知的来源类型 \"{}\", 可选值: {}",
+                            skill.source_type,
+                            WikiSourceType::VALID_NAMES.join(", ")
+                        ),
+                    ));
+                }
+
+                if skill.repo.trim().is_empty() {
+                    issues.push(issue(
+                        ConfigIssueKind::EmptyRepo,
+                        skill_id,
+                        "技能库地址 (repo) 为空".to_string(),
+                    ));
+                }
+            }
+        }
+
+        issues
+    }
+
     /// 根据游戏 ID 查找游戏配置
     pub fn find_game(&self, game_id: &str) -> Option<&GameConfig> {
         self.games.iter().find(|g| g.id == game_id)
     }
 
+    /// 查找 Steam 游戏对应的游戏配置
+    ///
+    /// 先按 `steam_{appid}` ID 匹配 (从 Steam 添加的游戏), 再按 `steam_appid` 字段匹配,
+    /// 最后按名称匹配 (忽略大小写和标点), 同时比较中文名和英文名
+    pub fn find_steam_game(&self, appid: u32, name: &str) -> Option<&GameConfig> {
+        let steam_id = format!("steam_{}", appid);
+        if let Some(game) = self.find_game(&steam_id) {
+            return Some(game);
+        }
+        if let Some(game) = self.games.iter().find(|g| g.steam_appid == Some(appid)) {
+            return Some(game);
+        }
+
+        let name = normalize_game_name(name);
+        if name.is_empty() {
+            return None;
+        }
+
+        self.games.iter().find(|game| {
+            normalize_game_name(&game.name) == name
+                || game
+                    .name_en
+                    .as_deref()
+                    .is_some_and(|name_en| normalize_game_name(name_en) == name)
+        })
+    }
+
+    /// 按英文名模糊匹配, 返回相似度最高且不低于 `FUZZY_NAME_THRESHOLD` 的游戏配置
+    ///
+    /// 相似度为 1 - 编辑距离 / 较长名称的长度 (名称先归一化), 过短的名称不做模糊匹配
+    pub fn find_game_by_fuzzy_name(&self, name: &str) -> Option<&GameConfig> {
+        let name: Vec<char> = normalize_game_name(name).chars().collect();
+        if name.len() < FUZZY_NAME_MIN_LEN {
+            return None;
+        }
+
+        self.games
+            .iter()
+            .filter_map(|game| {
+                let name_en: Vec<char> = normalize_game_name(game.name_en.as_deref()?)
+                    .chars()
+                    .collect();
+                if name_en.len() < FUZZY_NAME_MIN_LEN {
+                    return None;
+                }
+                let longest = name.len().max(name_en.len());
+                let similarity = 1.0 - edit_distance(&name, &name_en) as f32 / longest as f32;
+                (similarity >= FUZZY_NAME_THRESHOLD).then_some((similarity, game))
+            })
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, game)| game)
+    }
+
     /// 根据技能配置 ID 查找技能配置
     pub fn find_skill_config(&self, skill_config_id: &str) -> Option<(&GameConfig, &SkillConfig)> {
         for game in &self.games {
@@ -91,6 +272,54 @@ impl Config {
     }
 }
 
+/// 名称模糊匹配的最低相似度
+const FUZZY_NAME_THRESHOLD: f32 = 0.85;
+
+/// 参与模糊匹配的最短名称 (归一化后的字符数)
+const FUZZY_NAME_MIN_LEN: usize = 5;
+
+/// 把 Steam 游戏库中的游戏对应到游戏配置, 返回匹配到的游戏 ID (按游戏库顺序, 不重复)
+///
+/// 先用 `find_steam_game` 按 AppID 和名称精确匹配, 未匹配时按英文名模糊匹配
+pub fn match_owned_games(owned: &[OwnedGame], config: &Config) -> Vec<String> {
+    let mut seen = HashSet::new();
+    owned
+        .iter()
+        .filter_map(|game| {
+            config
+                .find_steam_game(game.appid, &game.name)
+                .or_else(|| config.find_game_by_fuzzy_name(&game.name))
+        })
+        .filter(|game| seen.insert(game.id.as_str()))
+        .map(|game| game.id.clone())
+        .collect()
+}
+
+/// 名称归一化: 小写并去掉空白、标点和商标符号
+fn normalize_game_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 两个字符序列的编辑距离 (Levenshtein)
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -107,6 +336,7 @@ impl Default for Config {
                     release_date: Some("2020-09-18".to_string()),
                     developer: Some("Kinetic Games".to_string()),
                     publisher: Some("Kinetic Games".to_string()),
+                    steam_appid: Some(739630),
                     skill_configs: vec![
                         SkillConfig {
                             id: "phasmophobia-skill-1".to_string(),
@@ -118,6 +348,9 @@ impl Default for Config {
                             max_pages: Some(500),
                             max_depth: Some(5),
                             request_delay_ms: Some(500),
+                            user_agent: None,
+                            headers: None,
+                            cookies: None,
                         },
                     ],
                 },
@@ -133,6 +366,7 @@ impl Default for Config {
                     release_date: Some("2022-02-25".to_string()),
                     developer: Some("FromSoftware".to_string()),
                     publisher: Some("Bandai Namco".to_string()),
+                    steam_appid: Some(1245620),
                     skill_configs: vec![
                         SkillConfig {
                             id: "elden-ring-skill-1".to_string(),
@@ -144,6 +378,9 @@ impl Default for Config {
                             max_pages: Some(1000),
                             max_depth: Some(6),
                             request_delay_ms: Some(500),
+                            user_agent: None,
+                            headers: None,
+                            cookies: None,
                         },
                     ],
                 },
@@ -159,6 +396,7 @@ impl Default for Config {
                     release_date: Some("2023-08-03".to_string()),
                     developer: Some("Larian Studios".to_string()),
                     publisher: Some("Larian Studios".to_string()),
+                    steam_appid: Some(1086940),
                     skill_configs: vec![
                         SkillConfig {
                             id: "bg3-skill-1".to_string(),
@@ -170,6 +408,9 @@ impl Default for Config {
                             max_pages: Some(800),
                             max_depth: Some(5),
                             request_delay_ms: Some(600),
+                            user_agent: None,
+                            headers: None,
+                            cookies: None,
                         },
                     ],
                 },
@@ -205,5 +446,99 @@ tags = ["test"]
         let config: Config = toml::from_str(config_toml).unwrap();
         assert_eq!(config.games.len(), 1);
         assert_eq!(config.games[0].id, "test-game");
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Config::default().validate().is_empty());
+
+        let mut config = Config::default();
+        let mut duplicate = config.games[0].clone();
+        duplicate.skill_configs[0].source_type = "FandomWikki".to_string();
+        duplicate.skill_configs[0].repo = " ".to_string();
+        config.games.push(duplicate);
+
+        let kinds: Vec<ConfigIssueKind> = config.validate().iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ConfigIssueKind::DuplicateGameId,
+                ConfigIssueKind::DuplicateSkillConfigId,
+                ConfigIssueKind::UnknownSourceType,
+                ConfigIssueKind::EmptyRepo,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_steam_game() {
+        let mut config = Config::default();
+        let mut steam_game = config.games[0].clone();
+        steam_game.id = "steam_1245620".to_string();
+        steam_game.name = "ELDEN RING".to_string();
+        steam_game.name_en = None;
+        config.games.push(steam_game);
+
+        // 英文名匹配 (忽略大小写和商标符号)
+        assert_eq!(
+            config
+                .find_steam_game(739630, "PHASMOPHOBIA™")
+                .map(|g| g.id.as_str()),
+            Some("phasmophobia")
+        );
+        // ID 优先于名称
+        assert_eq!(
+            config
+                .find_steam_game(1245620, "Elden Ring Nightreign")
+                .map(|g| g.id.as_str()),
+            Some("steam_1245620")
+        );
+        assert!(config.find_steam_game(570, "Dota 2").is_none());
+        assert!(config.find_steam_game(1, "™").is_none());
+
+        // steam_appid 字段匹配 (名称不同)
+        assert_eq!(
+            config
+                .find_steam_game(1086940, "Baldur's Gate III")
+                .map(|g| g.id.as_str()),
+            Some("baldurs-gate-3")
+        );
+    }
+
+    #[test]
+    fn test_match_owned_games() {
+        let mut config = Config::default();
+        for game in &mut config.games {
+            game.steam_appid = None;
+        }
+
+        let owned = |appid: u32, name: &str| OwnedGame {
+            appid,
+            name: name.to_string(),
+            playtime_forever: 0,
+            playtime_2weeks: None,
+            img_icon_url: String::new(),
+            img_logo_url: String::new(),
+            has_community_visible_stats: None,
+        };
+        let owned_games = vec![
+            owned(1, "Phasmophobia"),
+            // 名称差异过大, 不匹配
+            owned(2, "Baldurs Gate 3™ Deluxe"),
+            // 模糊匹配英文名
+            owned(3, "Eldon Ring"),
+            owned(4, "Phasmophobia"),
+            owned(570, "Dota 2"),
+            owned(5, "Elden Ring Nightreign"),
+        ];
+
+        assert_eq!(
+            match_owned_games(&owned_games, &config),
+            vec!["phasmophobia", "elden-ring"]
+        );
+
+        assert_eq!(edit_distance(&['a', 'b'], &['a', 'c', 'b']), 1);
+        assert_eq!(edit_distance(&[], &['a']), 1);
     }
 }
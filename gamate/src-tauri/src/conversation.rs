@@ -0,0 +1,218 @@
+/// 对话历史记录模块
+///
+/// 以 JSONL 格式持久化用户提问与 AI 回复, 供历史面板和后续查询使用
+///
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+/// 对话引用来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSource {
+    pub title: String,
+    pub url: Option<String>,
+    pub score: f32,
+}
+
+/// 单条对话记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationRecord {
+    /// 时间戳 (秒)
+    pub timestamp: i64,
+    pub game_id: String,
+    pub user_text: String,
+    pub ai_text: String,
+    #[serde(default)]
+    pub sources: Vec<ConversationSource>,
+}
+
+/// 对话历史存储 (JSONL 文件)
+pub struct ConversationStore {
+    path: PathBuf,
+}
+
+impl ConversationStore {
+    /// 使用指定文件路径创建存储
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// 使用默认路径 (可执行文件同级 data/conversations.jsonl)
+    pub fn open_default() -> Result<Self> {
+        let exe_path = std::env::current_exe().context("无法获取可执行文件路径")?;
+        let exe_dir = exe_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("无法获取可执行文件目录"))?;
+
+        let data_dir = exe_dir.join("data");
+        fs::create_dir_all(&data_dir)?;
+
+        Ok(Self::new(data_dir.join("conversations.jsonl")))
+    }
+
+    /// 追加一条记录
+    pub fn append(&self, record: &ConversationRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("无法打开对话历史文件: {}", self.path.display()))?;
+
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// 读取全部记录 (按写入顺序, 跳过损坏的行)
+    pub fn load_all(&self) -> Result<Vec<ConversationRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs::File::open(&self.path)?;
+        let reader = BufReader::new(file);
+
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ConversationRecord>(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("⚠️ 跳过无法解析的对话记录: {}", e),
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// 获取历史记录 (最近的 limit 条, 按时间先后排列)
+    pub fn history(&self, game_id: Option<&str>, limit: usize) -> Result<Vec<ConversationRecord>> {
+        let mut records: Vec<ConversationRecord> = self
+            .load_all()?
+            .into_iter()
+            .filter(|r| game_id.is_none_or(|id| r.game_id == id))
+            .collect();
+
+        if records.len() > limit {
+            records.drain(..records.len() - limit);
+        }
+
+        Ok(records)
+    }
+
+    /// 关键词搜索历史记录 (用户提问与 AI 回复)
+    ///
+    /// 查询按空白拆分为多个关键词 (不区分大小写), 所有关键词都命中才算匹配;
+    /// 结果按命中次数降序, 次数相同时较新的记录在前
+    pub fn search(
+        &self,
+        query: &str,
+        game_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ConversationRecord>> {
+        let keywords: Vec<String> = query.split_whitespace().map(|k| k.to_lowercase()).collect();
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut matches: Vec<(usize, ConversationRecord)> = self
+            .load_all()?
+            .into_iter()
+            .filter(|r| game_id.is_none_or(|id| r.game_id == id))
+            .filter_map(|r| {
+                let text = format!("{}\n{}", r.user_text, r.ai_text).to_lowercase();
+                let mut hits = 0;
+                for keyword in &keywords {
+                    let count = text.matches(keyword.as_str()).count();
+                    if count == 0 {
+                        return None;
+                    }
+                    hits += count;
+                }
+                Some((hits, r))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.timestamp.cmp(&a.1.timestamp)));
+
+        Ok(matches.into_iter().take(limit).map(|(_, r)| r).collect())
+    }
+
+    /// 清空历史记录 (game_id 为 None 时清空全部), 返回删除的条数
+    pub fn clear(&self, game_id: Option<&str>) -> Result<usize> {
+        let records = self.load_all()?;
+
+        let (removed, kept): (Vec<_>, Vec<_>) = records
+            .into_iter()
+            .partition(|r| game_id.is_none_or(|id| r.game_id == id));
+
+        if kept.is_empty() {
+            if self.path.exists() {
+                fs::remove_file(&self.path)?;
+            }
+        } else {
+            let mut content = String::new();
+            for record in &kept {
+                content.push_str(&serde_json::to_string(record)?);
+                content.push('\n');
+            }
+            fs::write(&self.path, content)?;
+        }
+
+        Ok(removed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(game_id: &str, user_text: &str) -> ConversationRecord {
+        ConversationRecord {
+            timestamp: chrono::Utc::now().timestamp(),
+            game_id: game_id.to_string(),
+            user_text: user_text.to_string(),
+            ai_text: "好的".to_string(),
+            sources: vec![],
+        }
+    }
+
+    #[test]
+    fn test_history_search_and_clear() {
+        let path = std::env::temp_dir().join(format!(
+            "gamate_conversations_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let store = ConversationStore::new(path.clone());
+
+        store.append(&record("elden-ring", "问题1")).unwrap();
+        store.append(&record("phasmophobia", "问题2")).unwrap();
+        store.append(&record("elden-ring", "问题3")).unwrap();
+
+        let history = store.history(Some("elden-ring"), 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].user_text, "问题3");
+
+        let latest = store.history(None, 1).unwrap();
+        assert_eq!(latest[0].user_text, "问题3");
+
+        let found = store.search("问题 2", None, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].game_id, "phasmophobia");
+        assert!(store.search("  ", None, 10).unwrap().is_empty());
+
+        assert_eq!(store.clear(Some("elden-ring")).unwrap(), 2);
+        assert_eq!(store.load_all().unwrap().len(), 1);
+
+        assert_eq!(store.clear(None).unwrap(), 1);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::crawler::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 爬取进度文件名
+pub const CRAWL_STATE_FILE: &str = "crawl_state.json";
+
+/// 原始条目文件名
+pub const RAW_ENTRIES_FILE: &str = "wiki_raw.jsonl";
+
+/// 爬取进度 (用于中断后续爬)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CrawlState {
+    /// 已访问的 URL (网页爬虫)
+    #[serde(default)]
+    pub visited_urls: HashSet<String>,
+    /// 待访问的 URL 及深度 (网页爬虫)
+    #[serde(default)]
+    pub queue: Vec<(String, usize)>,
+    /// 已完成的页面标题 (MediaWiki API 爬虫)
+    #[serde(default)]
+    pub completed_titles: HashSet<String>,
+}
+
+/// 增量检查点
+///
+/// 每抓取到一个条目就追加到 `wiki_raw.jsonl`, 并把进度写入 `crawl_state.json`;
+/// 爬取完成后由爬虫重写 `wiki_raw.jsonl` 并调用 [`Checkpoint::finish`] 删除进度文件
+pub struct Checkpoint {
+    state_path: PathBuf,
+    writer: File,
+    pub state: CrawlState,
+}
+
+impl Checkpoint {
+    /// 打开检查点, 返回检查点及已抓取的条目
+    ///
+    /// - `resume = true` 且存在进度文件: 读取进度和已抓取的条目
+    /// - 其他情况: 清空之前的进度, 从头开始
+    pub fn open(storage_path: &Path, resume: bool) -> CrawlerResult2<(Self, Vec<WikiEntry>)> {
+        fs::create_dir_all(storage_path)?;
+
+        let state_path = storage_path.join(CRAWL_STATE_FILE);
+        let entries_path = storage_path.join(RAW_ENTRIES_FILE);
+
+        let (state, entries) = if resume && state_path.exists() {
+            let json = fs::read_to_string(&state_path)?;
+            let state: CrawlState =
+                serde_json::from_str(&json).map_err(|e| CrawlerError::Other(e.to_string()))?;
+            let entries = read_entries(&entries_path)?;
+            // 重写条目文件, 去掉中断时写了一半的行
+            let mut content = String::new();
+            for entry in &entries {
+                let json =
+                    serde_json::to_string(entry).map_err(|e| CrawlerError::Other(e.to_string()))?;
+                content.push_str(&json);
+                content.push('\n');
+            }
+            fs::write(&entries_path, content)?;
+            log::info!(
+                "♻️ 继续上次的爬取: 已有 {} 个条目, {} 个已访问 URL, {} 个已完成标题",
+                entries.len(),
+                state.visited_urls.len(),
+                state.completed_titles.len()
+            );
+            (state, entries)
+        } else {
+            if entries_path.exists() {
+                fs::remove_file(&entries_path)?;
+            }
+            (CrawlState::default(), Vec::new())
+        };
+
+        let writer = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&entries_path)?;
+
+        let checkpoint = Self {
+            state_path,
+            writer,
+            state,
+        };
+        checkpoint.save_state()?;
+
+        Ok((checkpoint, entries))
+    }
+
+    /// 追加一个条目到 `wiki_raw.jsonl`
+    pub fn append(&mut self, entry: &WikiEntry) -> CrawlerResult2<()> {
+        let json = serde_json::to_string(entry).map_err(|e| CrawlerError::Other(e.to_string()))?;
+        writeln!(self.writer, "{}", json)?;
+        Ok(())
+    }
+
+    /// 保存爬取进度
+    pub fn save_state(&self) -> CrawlerResult2<()> {
+        let json =
+            serde_json::to_string(&self.state).map_err(|e| CrawlerError::Other(e.to_string()))?;
+
+        // 先写临时文件再重命名, 避免中断时留下不完整的进度文件
+        let tmp_path = self.state_path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &self.state_path)?;
+        Ok(())
+    }
+
+    /// 爬取完成, 删除进度文件
+    pub fn finish(self) -> CrawlerResult2<()> {
+        if self.state_path.exists() {
+            fs::remove_file(&self.state_path)?;
+        }
+        Ok(())
+    }
+}
+
+/// 目录中的爬取是否未完成 (进度文件还在)
+pub fn is_unfinished(storage_path: &Path) -> bool {
+    storage_path.join(CRAWL_STATE_FILE).exists()
+}
+
+/// 游戏目录下最近一次未完成的爬取 (时间戳目录)
+pub fn find_unfinished_crawl(game_dir: &Path) -> Option<(u64, PathBuf)> {
+    fs::read_dir(game_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let timestamp = entry.file_name().to_string_lossy().parse::<u64>().ok()?;
+            let path = entry.path();
+            (path.is_dir() && is_unfinished(&path)).then_some((timestamp, path))
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+}
+
+/// 读取已追加的条目 (跳过中断时写了一半的行)
+fn read_entries(path: &Path) -> CrawlerResult2<Vec<WikiEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str) -> WikiEntry {
+        WikiEntry {
+            id: format!("test_{}", title),
+            title: title.to_string(),
+            content: format!("{} content", title),
+            url: format!("https://example.com/wiki/{}", title),
+            timestamp: 0,
+            hash: title.to_string(),
+            categories: vec![],
+            metadata: WikiMetadata {
+                length: 0,
+                last_modified: None,
+                author: None,
+                language: "en".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_resume_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("crawl_checkpoint_{}", uuid::Uuid::new_v4()));
+
+        {
+            let (mut checkpoint, entries) = Checkpoint::open(&dir, true).unwrap();
+            assert!(entries.is_empty());
+            checkpoint.append(&entry("A")).unwrap();
+            checkpoint.state.completed_titles.insert("A".to_string());
+            checkpoint.save_state().unwrap();
+        }
+
+        // 模拟中断时写了一半的行
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.join(RAW_ENTRIES_FILE))
+            .unwrap();
+        write!(file, "{{\"id\":\"test_B\"").unwrap();
+        drop(file);
+
+        // 继续爬取
+        let (mut checkpoint, entries) = Checkpoint::open(&dir, true).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(checkpoint.state.completed_titles.contains("A"));
+        checkpoint.append(&entry("C")).unwrap();
+        assert_eq!(read_entries(&dir.join(RAW_ENTRIES_FILE)).unwrap().len(), 2);
+        checkpoint.finish().unwrap();
+        assert!(!dir.join(CRAWL_STATE_FILE).exists());
+
+        // 不续爬时从头开始
+        let (checkpoint, entries) = Checkpoint::open(&dir, false).unwrap();
+        assert!(entries.is_empty());
+        assert!(checkpoint.state.completed_titles.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_unfinished_crawl() {
+        let game_dir =
+            std::env::temp_dir().join(format!("crawl_unfinished_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(game_dir.join("100")).unwrap();
+        assert!(find_unfinished_crawl(&game_dir).is_none());
+
+        // 200 未完成, 300 已完成
+        let (_checkpoint, _) = Checkpoint::open(&game_dir.join("200"), false).unwrap();
+        let (checkpoint, _) = Checkpoint::open(&game_dir.join("300"), false).unwrap();
+        checkpoint.finish().unwrap();
+
+        let (timestamp, path) = find_unfinished_crawl(&game_dir).unwrap();
+        assert_eq!(timestamp, 200);
+        assert!(is_unfinished(&path));
+        assert!(!is_unfinished(&game_dir.join("300")));
+
+        let _ = fs::remove_dir_all(&game_dir);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::crawler::output::write_extra_formats;
 use crate::crawler::types::*;
 use crate::crawler::utils::*;
 use octocrab::models::repos::Content;
@@ -9,6 +10,7 @@ pub struct GitHubCrawler {
     config: CrawlerConfig,
     client: Octocrab,
     entries: Vec<WikiEntry>,
+    deduper: ContentDeduper,
 }
 
 impl GitHubCrawler {
@@ -27,6 +29,7 @@ impl GitHubCrawler {
             config,
             client,
             entries: Vec::new(),
+            deduper: ContentDeduper::default(),
         })
     }
 
@@ -57,6 +60,12 @@ impl GitHubCrawler {
             self.crawl_docs(&owner, &repo).await?;
         }
 
+        // 截断超长条目
+        let truncated = limit_entry_lengths(&mut self.entries, self.config.max_entry_chars);
+        if truncated > 0 {
+            details.push(format!("超长截断条目数: {}", truncated));
+        }
+
         // 保存结果
         let total_bytes = self.save_entries()?;
         let duration_secs = start.elapsed().as_secs();
@@ -71,6 +80,7 @@ impl GitHubCrawler {
         details.push(format!("总条目数: {}", self.entries.len()));
         details.push(format!("总字节数: {}", total_bytes));
         details.push(format!("耗时: {} 秒", duration_secs));
+        details.push(format!("重复内容条目数: {}", self.deduper.duplicates()));
 
         Ok(CrawlerResult {
             total_entries: self.entries.len(),
@@ -159,8 +169,10 @@ impl GitHubCrawler {
                 &readme.html_url.unwrap_or_default(),
                 vec!["README".to_string()],
             );
-            self.entries.push(entry);
-            log::info!("成功爬取 README");
+            if self.deduper.insert(&entry) {
+                self.entries.push(entry);
+                log::info!("成功爬取 README");
+            }
         }
 
         Ok(())
@@ -216,8 +228,10 @@ impl GitHubCrawler {
                     &item.html_url.clone().unwrap_or_default(),
                     vec!["Documentation".to_string()],
                 );
-                self.entries.push(entry);
-                log::info!("成功爬取文件: {}", item.name);
+                if self.deduper.insert(&entry) {
+                    self.entries.push(entry);
+                    log::info!("成功爬取文件: {}", item.name);
+                }
             }
         }
 
@@ -325,6 +339,8 @@ impl GitHubCrawler {
                 .map_err(|e| CrawlerError::Other(e.to_string()))?,
         )?;
 
+        write_extra_formats(&self.config, &self.entries);
+
         Ok(total_bytes)
     }
 }
@@ -0,0 +1,512 @@
+/// 技能库版本历史
+///
+/// 每次爬取完成后与上一个版本比较条目 (按 URL 匹配, 按内容哈希判断是否更新),
+/// 把 `added` / `updated` / `removed`、爬虫版本和配置快照写入该版本的 `metadata.json`,
+/// 供技能库"版本"界面展示知识库的变化
+///
+/// 游戏目录下的 `active_version` 文件记录用户固定 (回滚) 的版本, 不存在时最新版本为活跃版本
+use crate::crawler::checkpoint::is_unfinished;
+use crate::crawler::types::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// 当前爬虫版本 (写入 metadata.json)
+pub const CRAWLER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 活跃版本标记文件名 (内容为版本时间戳)
+pub const ACTIVE_VERSION_FILE: &str = "active_version";
+
+/// 两个版本之间的条目变化
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawlDiff {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// 单个爬取版本
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawlVersion {
+    pub timestamp: u64,
+    pub storage_path: String,
+    pub source_type: Option<String>,
+    pub source_url: Option<String>,
+    pub total_entries: usize,
+    pub total_bytes: u64,
+    /// 相对上一版本的变化 (旧版本的 metadata.json 没有记录时为 None)
+    pub diff: Option<CrawlDiff>,
+    /// 比较的上一版本时间戳
+    pub previous_timestamp: Option<u64>,
+    pub crawler_version: Option<String>,
+    pub config_snapshot: Option<serde_json::Value>,
+    /// 是否为活跃版本 (导入时使用的版本)
+    pub active: bool,
+}
+
+/// 条目匹配键: URL, 没有 URL 的条目使用 ID
+///
+/// 条目 ID 由内容哈希生成, 页面编辑后会变化, 不能用来匹配不同版本中的同一页面
+fn entry_key(entry: &WikiEntry) -> &str {
+    if entry.url.is_empty() {
+        &entry.id
+    } else {
+        &entry.url
+    }
+}
+
+/// 比较两个版本的条目 (按 URL 匹配, 没有 URL 的条目按 ID 匹配)
+pub fn diff_entries(previous: &[WikiEntry], current: &[WikiEntry]) -> CrawlDiff {
+    let previous: HashMap<&str, &str> = previous
+        .iter()
+        .map(|e| (entry_key(e), e.hash.as_str()))
+        .collect();
+
+    let mut diff = CrawlDiff::default();
+    let mut matched = 0;
+    for entry in current {
+        match previous.get(entry_key(entry)) {
+            Some(hash) => {
+                matched += 1;
+                if *hash != entry.hash {
+                    diff.updated += 1;
+                }
+            }
+            None => diff.added += 1,
+        }
+    }
+    diff.removed = previous.len().saturating_sub(matched);
+
+    diff
+}
+
+/// 每个更新条目最多列出的变化行数
+const MAX_CHANGED_LINES: usize = 20;
+
+/// 条目摘要
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntrySummary {
+    pub title: String,
+    pub url: String,
+}
+
+/// 内容有变化的条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedEntry {
+    pub title: String,
+    pub url: String,
+    /// 旧版本标题 (标题有变化时)
+    pub previous_title: Option<String>,
+    /// 新版本中新增的行
+    pub added_lines: Vec<String>,
+    /// 旧版本中被删除的行
+    pub removed_lines: Vec<String>,
+    /// 变化行数超过上限被截断
+    pub truncated: bool,
+}
+
+/// 两个版本之间的内容变化
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionContentDiff {
+    pub from_timestamp: u64,
+    pub to_timestamp: u64,
+    pub added: Vec<EntrySummary>,
+    pub removed: Vec<EntrySummary>,
+    pub updated: Vec<UpdatedEntry>,
+    /// 内容未变化的条目数
+    pub unchanged: usize,
+}
+
+/// 比较两个版本的条目内容 (按 URL 匹配, 没有 URL 的条目按 ID 匹配)
+///
+/// 更新条目给出按行比较的增删 (忽略空行和行的先后顺序)
+pub fn diff_entry_contents(previous: &[WikiEntry], current: &[WikiEntry]) -> VersionContentDiff {
+    let summary = |entry: &WikiEntry| EntrySummary {
+        title: entry.title.clone(),
+        url: entry.url.clone(),
+    };
+
+    let previous_by_key: HashMap<&str, &WikiEntry> =
+        previous.iter().map(|e| (entry_key(e), e)).collect();
+    let current_keys: HashSet<&str> = current.iter().map(entry_key).collect();
+
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    let mut unchanged = 0;
+
+    for entry in current {
+        let Some(old) = previous_by_key.get(entry_key(entry)) else {
+            added.push(summary(entry));
+            continue;
+        };
+
+        if old.content == entry.content && old.title == entry.title {
+            unchanged += 1;
+            continue;
+        }
+
+        let old_lines: HashSet<&str> = content_lines(&old.content).collect();
+        let new_lines: HashSet<&str> = content_lines(&entry.content).collect();
+        let mut added_lines: Vec<String> = content_lines(&entry.content)
+            .filter(|line| !old_lines.contains(line))
+            .map(str::to_string)
+            .collect();
+        let mut removed_lines: Vec<String> = content_lines(&old.content)
+            .filter(|line| !new_lines.contains(line))
+            .map(str::to_string)
+            .collect();
+
+        let truncated =
+            added_lines.len() > MAX_CHANGED_LINES || removed_lines.len() > MAX_CHANGED_LINES;
+        added_lines.truncate(MAX_CHANGED_LINES);
+        removed_lines.truncate(MAX_CHANGED_LINES);
+
+        updated.push(UpdatedEntry {
+            title: entry.title.clone(),
+            url: entry.url.clone(),
+            previous_title: (old.title != entry.title).then(|| old.title.clone()),
+            added_lines,
+            removed_lines,
+            truncated,
+        });
+    }
+
+    let removed = previous
+        .iter()
+        .filter(|e| !current_keys.contains(entry_key(e)))
+        .map(summary)
+        .collect();
+
+    VersionContentDiff {
+        added,
+        removed,
+        updated,
+        unchanged,
+        ..Default::default()
+    }
+}
+
+/// 比较游戏的两个版本 (`from` 为旧版本, `to` 为新版本)
+pub fn diff_versions(game_dir: &Path, from: u64, to: u64) -> CrawlerResult2<VersionContentDiff> {
+    let previous = read_entries(&game_dir.join(from.to_string()))?;
+    let current = read_entries(&game_dir.join(to.to_string()))?;
+
+    Ok(VersionContentDiff {
+        from_timestamp: from,
+        to_timestamp: to,
+        ..diff_entry_contents(&previous, &current)
+    })
+}
+
+fn content_lines(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+/// 爬虫配置快照 (去掉 Token、Cookie 值和请求头值等敏感信息)
+pub fn config_snapshot(config: &CrawlerConfig) -> serde_json::Value {
+    let mut header_names: Vec<&String> = config.headers.keys().collect();
+    header_names.sort();
+    let mut cookie_names: Vec<&String> = config.cookies.keys().collect();
+    cookie_names.sort();
+
+    serde_json::json!({
+        "source_url": config.source_url,
+        "max_pages": config.max_pages,
+        "max_depth": config.max_depth,
+        "request_delay_ms": config.request_delay_ms,
+        "user_agent": config.user_agent,
+        "header_names": header_names,
+        "cookie_names": cookie_names,
+        "include_images": config.include_images,
+        "max_entry_chars": config.max_entry_chars,
+        "namespaces": config.namespaces,
+        "categories": config.categories,
+    })
+}
+
+/// 记录新版本的变化到其 metadata.json
+///
+/// - `game_dir`: 游戏目录 (其下为各时间戳版本目录)
+/// - `timestamp`: 新版本时间戳
+pub fn record_crawl_version(
+    game_dir: &Path,
+    timestamp: u64,
+    config_snapshot: serde_json::Value,
+) -> CrawlerResult2<CrawlDiff> {
+    let version_dir = game_dir.join(timestamp.to_string());
+    let current = read_entries(&version_dir)?;
+
+    let previous_timestamp = list_versions(game_dir)?
+        .into_iter()
+        .filter(|t| *t < timestamp)
+        .max();
+    let previous = match previous_timestamp {
+        Some(t) => read_entries(&game_dir.join(t.to_string())).unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let diff = diff_entries(&previous, &current);
+
+    let metadata_path = version_dir.join("metadata.json");
+    let mut metadata = read_metadata(&version_dir).unwrap_or_else(|| serde_json::json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("added".into(), diff.added.into());
+        object.insert("updated".into(), diff.updated.into());
+        object.insert("removed".into(), diff.removed.into());
+        object.insert("previous_timestamp".into(), previous_timestamp.into());
+        object.insert("crawler_version".into(), CRAWLER_VERSION.into());
+        object.insert("config_snapshot".into(), config_snapshot);
+    }
+
+    fs::write(
+        &metadata_path,
+        serde_json::to_string_pretty(&metadata).map_err(|e| CrawlerError::Other(e.to_string()))?,
+    )?;
+
+    Ok(diff)
+}
+
+/// 读取游戏的版本历史 (按时间倒序)
+pub fn read_crawl_history(game_dir: &Path) -> CrawlerResult2<Vec<CrawlVersion>> {
+    let mut timestamps = list_versions(game_dir)?;
+    timestamps.sort_unstable_by(|a, b| b.cmp(a));
+
+    let active = read_active_version(game_dir).or_else(|| timestamps.first().copied());
+
+    let history = timestamps
+        .into_iter()
+        .map(|timestamp| {
+            let version_dir = game_dir.join(timestamp.to_string());
+            let metadata = read_metadata(&version_dir).unwrap_or_default();
+
+            let count = |key: &str| metadata[key].as_u64().map(|v| v as usize);
+            let diff = match (count("added"), count("updated"), count("removed")) {
+                (Some(added), Some(updated), Some(removed)) => Some(CrawlDiff {
+                    added,
+                    updated,
+                    removed,
+                }),
+                _ => None,
+            };
+
+            let jsonl_path = version_dir.join("wiki_raw.jsonl");
+            let total_entries = count("total_entries").unwrap_or_else(|| {
+                fs::read_to_string(&jsonl_path)
+                    .map(|content| content.lines().filter(|l| !l.trim().is_empty()).count())
+                    .unwrap_or(0)
+            });
+            let total_bytes = metadata["total_bytes"]
+                .as_u64()
+                .or_else(|| fs::metadata(&jsonl_path).ok().map(|m| m.len()))
+                .unwrap_or(0);
+
+            CrawlVersion {
+                timestamp,
+                storage_path: version_dir.to_string_lossy().to_string(),
+                source_type: metadata["source_type"].as_str().map(str::to_string),
+                source_url: metadata["source_url"].as_str().map(str::to_string),
+                total_entries,
+                total_bytes,
+                diff,
+                previous_timestamp: metadata["previous_timestamp"].as_u64(),
+                crawler_version: metadata["crawler_version"].as_str().map(str::to_string),
+                config_snapshot: metadata
+                    .get("config_snapshot")
+                    .filter(|v| !v.is_null())
+                    .cloned(),
+                active: active == Some(timestamp),
+            }
+        })
+        .collect();
+
+    Ok(history)
+}
+
+/// 游戏目录下的所有版本时间戳 (纯数字目录名, 跳过未完成的爬取)
+pub fn list_versions(game_dir: &Path) -> CrawlerResult2<Vec<u64>> {
+    let mut versions = Vec::new();
+
+    for entry in fs::read_dir(game_dir)? {
+        let entry = entry?;
+        if let Ok(timestamp) = entry.file_name().to_string_lossy().parse::<u64>() {
+            if entry.path().is_dir() && !is_unfinished(&entry.path()) {
+                versions.push(timestamp);
+            }
+        }
+    }
+
+    Ok(versions)
+}
+
+/// 读取固定的活跃版本 (标记不存在、无效或指向已删除的版本时为 None)
+pub fn read_active_version(game_dir: &Path) -> Option<u64> {
+    let timestamp = fs::read_to_string(game_dir.join(ACTIVE_VERSION_FILE))
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    game_dir
+        .join(timestamp.to_string())
+        .is_dir()
+        .then_some(timestamp)
+}
+
+/// 固定活跃版本; None 表示取消固定 (始终使用最新版本)
+pub fn write_active_version(game_dir: &Path, timestamp: Option<u64>) -> CrawlerResult2<()> {
+    let marker = game_dir.join(ACTIVE_VERSION_FILE);
+
+    match timestamp {
+        Some(timestamp) => fs::write(marker, timestamp.to_string())?,
+        None if marker.exists() => fs::remove_file(marker)?,
+        None => {}
+    }
+
+    Ok(())
+}
+
+fn read_entries(version_dir: &Path) -> CrawlerResult2<Vec<WikiEntry>> {
+    let content = fs::read_to_string(version_dir.join("wiki_raw.jsonl"))?;
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn read_metadata(version_dir: &Path) -> Option<serde_json::Value> {
+    fs::read_to_string(version_dir.join("metadata.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, hash: &str) -> WikiEntry {
+        WikiEntry {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            url: String::new(),
+            timestamp: 0,
+            hash: hash.to_string(),
+            categories: Vec::new(),
+            metadata: WikiMetadata {
+                length: 0,
+                last_modified: None,
+                author: None,
+                language: "en".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let previous = vec![entry("a", "1"), entry("b", "1"), entry("c", "1")];
+        let current = vec![entry("a", "1"), entry("b", "2"), entry("d", "1")];
+
+        assert_eq!(
+            diff_entries(&previous, &current),
+            CrawlDiff {
+                added: 1,
+                updated: 1,
+                removed: 1,
+            }
+        );
+
+        // 首个版本: 全部为新增
+        assert_eq!(diff_entries(&[], &current).added, 3);
+    }
+
+    #[test]
+    fn test_diff_entries_matches_by_url() {
+        let with_url = |id: &str, url: &str, hash: &str| WikiEntry {
+            url: url.to_string(),
+            ..entry(id, hash)
+        };
+        let previous = vec![
+            with_url("game_1", "https://wiki/a", "1"),
+            with_url("game_2", "https://wiki/b", "2"),
+        ];
+        // 页面编辑后哈希和 ID 都变化, URL 不变
+        let current = vec![
+            with_url("game_3", "https://wiki/a", "3"),
+            with_url("game_2", "https://wiki/b", "2"),
+        ];
+
+        assert_eq!(
+            diff_entries(&previous, &current),
+            CrawlDiff {
+                added: 0,
+                updated: 1,
+                removed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_entry_contents() {
+        let with = |id: &str, url: &str, content: &str| WikiEntry {
+            url: url.to_string(),
+            content: content.to_string(),
+            ..entry(id, "")
+        };
+        let previous = vec![
+            with("1", "https://wiki/a", "line 1\nline 2"),
+            with("2", "https://wiki/b", "same"),
+            with("3", "https://wiki/c", "gone"),
+        ];
+        // ID 变化但 URL 相同时视为同一条目
+        let current = vec![
+            with("10", "https://wiki/a", "line 1\n\nline 3"),
+            with("2", "https://wiki/b", "same"),
+            with("4", "https://wiki/d", "new"),
+        ];
+
+        let diff = diff_entry_contents(&previous, &current);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].url, "https://wiki/d");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].url, "https://wiki/c");
+        assert_eq!(diff.unchanged, 1);
+
+        assert_eq!(diff.updated.len(), 1);
+        assert_eq!(diff.updated[0].added_lines, vec!["line 3"]);
+        assert_eq!(diff.updated[0].removed_lines, vec!["line 2"]);
+        assert!(!diff.updated[0].truncated);
+    }
+
+    #[test]
+    fn test_active_version_marker() {
+        let game_dir =
+            std::env::temp_dir().join(format!("gamate_history_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(game_dir.join("100")).unwrap();
+        fs::create_dir_all(game_dir.join("200")).unwrap();
+
+        assert_eq!(read_active_version(&game_dir), None);
+
+        write_active_version(&game_dir, Some(100)).unwrap();
+        assert_eq!(read_active_version(&game_dir), Some(100));
+
+        // 指向不存在的版本时忽略
+        write_active_version(&game_dir, Some(300)).unwrap();
+        assert_eq!(read_active_version(&game_dir), None);
+
+        write_active_version(&game_dir, None).unwrap();
+        assert!(!game_dir.join(ACTIVE_VERSION_FILE).exists());
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
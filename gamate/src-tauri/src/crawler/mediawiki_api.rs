@@ -0,0 +1,1003 @@
+use crate::crawler::checkpoint::Checkpoint;
+use crate::crawler::output::write_extra_formats;
+use crate::crawler::types::*;
+use crate::crawler::utils::*;
+use futures::stream::{FuturesOrdered, StreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+
+/// 每个内容请求包含的页面标题数 (MediaWiki API 的 titles 上限为 50)
+const CONTENT_BATCH_SIZE: usize = 50;
+
+/// MediaWiki API 响应
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    query: Option<QueryResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResponse {
+    pages: std::collections::HashMap<String, PageData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageData {
+    pageid: u64,
+    title: String,
+    revisions: Option<Vec<RevisionData>>,
+    categories: Option<Vec<CategoryData>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RevisionData {
+    #[serde(rename = "*")]
+    content: Option<String>,
+    slots: Option<Slots>,
+    /// 修订时间 (ISO 8601)
+    timestamp: Option<String>,
+    /// 修订作者
+    user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Slots {
+    main: Option<MainSlot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MainSlot {
+    #[serde(rename = "*")]
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CategoryData {
+    title: String,
+}
+
+/// MediaWiki 站点信息 (来自 `meta=siteinfo`)
+#[derive(Debug, Clone)]
+pub struct SiteInfo {
+    /// api.php 地址
+    pub api_url: String,
+    /// 站点地址 (如 `https://phasmophobia.fandom.com`)
+    pub server: String,
+    /// 文章路径模板 (如 `/wiki/$1`)
+    pub article_path: String,
+    /// 站点语言
+    pub lang: String,
+}
+
+impl SiteInfo {
+    /// 根据标题生成文章地址
+    pub fn page_url(&self, title: &str) -> String {
+        let server = if self.server.starts_with("//") {
+            format!("https:{}", self.server)
+        } else {
+            self.server.clone()
+        };
+        format!(
+            "{}{}",
+            server,
+            self.article_path.replace("$1", &title.replace(' ', "_"))
+        )
+    }
+}
+
+/// 根据 Wiki 地址推测可能的 api.php 地址
+///
+/// - 已经是 api.php: 直接使用
+/// - `/wiki/` 路径 (Fandom 等): `/api.php`, `/w/api.php`
+/// - 其他: 站点根目录下的 `/api.php`, `/w/api.php`
+pub fn api_url_candidates(source_url: &str) -> Vec<String> {
+    let trimmed = source_url.split(['?', '#']).next().unwrap_or(source_url);
+    if trimmed.ends_with("api.php") {
+        return vec![trimmed.to_string()];
+    }
+
+    let base = match trimmed.find("/wiki/") {
+        Some(idx) => trimmed[..idx].to_string(),
+        None => match url::Url::parse(trimmed) {
+            Ok(url) => url.origin().ascii_serialization(),
+            Err(_) => trimmed.trim_end_matches('/').to_string(),
+        },
+    };
+
+    vec![format!("{}/api.php", base), format!("{}/w/api.php", base)]
+}
+
+/// 查找 MediaWiki API 并读取站点信息
+///
+/// 先尝试常见的 api.php 位置, 都失败时从页面的 `EditURI` 链接中发现;
+/// 每个请求前都经过 `rate_limiter` 限速
+pub async fn discover_site(
+    client: &Client,
+    rate_limiter: &DomainRateLimiter,
+    source_url: &str,
+) -> CrawlerResult2<SiteInfo> {
+    for api_url in api_url_candidates(source_url) {
+        if let Some(site) = fetch_site_info(client, rate_limiter, &api_url).await {
+            return Ok(site);
+        }
+    }
+
+    // MediaWiki 页面头部包含 <link rel="EditURI" href=".../api.php?action=rsd">
+    rate_limiter.wait(source_url).await;
+    let html = client.get(source_url).send().await?.text().await?;
+    let re = regex::Regex::new(r#"<link[^>]+rel="EditURI"[^>]+href="([^"]+)""#).unwrap();
+    if let Some(href) = re.captures(&html).and_then(|c| c.get(1)) {
+        let href = href.as_str().split('?').next().unwrap_or_default();
+        if let Ok(api_url) = url::Url::parse(source_url).and_then(|base| base.join(href)) {
+            if let Some(site) = fetch_site_info(client, rate_limiter, api_url.as_str()).await {
+                return Ok(site);
+            }
+        }
+    }
+
+    Err(CrawlerError::InvalidUrl(format!(
+        "无法找到 MediaWiki API: {}",
+        source_url
+    )))
+}
+
+/// 请求 siteinfo, 返回 None 表示该地址不是可用的 MediaWiki API
+async fn fetch_site_info(
+    client: &Client,
+    rate_limiter: &DomainRateLimiter,
+    api_url: &str,
+) -> Option<SiteInfo> {
+    rate_limiter.wait(api_url).await;
+    let json: serde_json::Value = client
+        .get(api_url)
+        .query(&[
+            ("action", "query"),
+            ("meta", "siteinfo"),
+            ("siprop", "general"),
+            ("format", "json"),
+        ])
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+
+    let general = &json["query"]["general"];
+    Some(SiteInfo {
+        api_url: api_url.to_string(),
+        server: general["server"].as_str()?.to_string(),
+        article_path: general["articlepath"].as_str()?.to_string(),
+        lang: general["lang"].as_str().unwrap_or("en").to_string(),
+    })
+}
+
+/// 分类名补全为分类页标题 (如 `Bosses` -> `Category:Bosses`)
+fn category_title(category: &str) -> String {
+    let category = category.trim();
+    if category.contains(':') {
+        category.to_string()
+    } else {
+        format!("Category:{}", category)
+    }
+}
+
+/// MediaWiki API 爬虫 (Fandom, Gamepedia 及其他 MediaWiki 站点)
+pub struct MediaWikiApiCrawler {
+    config: CrawlerConfig,
+    client: Client,
+    entries: Vec<WikiEntry>,
+    site: Option<SiteInfo>,
+    rate_limiter: DomainRateLimiter,
+}
+
+impl MediaWikiApiCrawler {
+    pub fn new(config: CrawlerConfig) -> Self {
+        let user_agent = if config.user_agent.is_empty() {
+            DEFAULT_USER_AGENT
+        } else {
+            config.user_agent.as_str()
+        };
+
+        let client = Client::builder()
+            .user_agent(user_agent)
+            .default_headers(build_headers(Default::default(), &config.headers))
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        Self {
+            client,
+            entries: Vec::new(),
+            site: None,
+            rate_limiter: DomainRateLimiter::global(Duration::from_millis(config.request_delay_ms)),
+            config,
+        }
+    }
+
+    /// 使用指定的域名限速器 (默认与其他爬虫共享全局限速记录)
+    pub fn with_rate_limiter(mut self, rate_limiter: DomainRateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 获取站点信息 (首次调用时自动发现 API 地址)
+    async fn site(&mut self) -> CrawlerResult2<SiteInfo> {
+        if let Some(site) = &self.site {
+            return Ok(site.clone());
+        }
+
+        let site = discover_site(&self.client, &self.rate_limiter, &self.config.source_url).await?;
+        self.site = Some(site.clone());
+        Ok(site)
+    }
+
+    /// 开始爬取
+    pub async fn crawl(&mut self) -> CrawlerResult2<CrawlerResult> {
+        let start = std::time::Instant::now();
+        let mut details = Vec::new();
+
+        log::info!("🚀 开始使用 MediaWiki API 爬取: {}", self.config.source_url);
+
+        // 查找 api.php 地址
+        // 例如: https://phasmophobia.fandom.com/wiki/ -> https://phasmophobia.fandom.com/api.php
+        let api_url = self.site().await?.api_url;
+
+        log::info!("📡 API URL: {}", api_url);
+        log::info!("⚙️  最大页面数: {}", self.config.max_pages);
+
+        // 1. 获取页面列表 (指定分类时只获取分类成员)
+        log::info!("📋 正在获取页面列表...");
+        let page_titles = match self.config.categories.clone() {
+            Some(categories) if !categories.is_empty() => {
+                details.push(format!("分类: {}", categories.join(", ")));
+                self.fetch_category_pages(&api_url, &categories).await?
+            }
+            _ => self.fetch_all_pages(&api_url, false).await?,
+        };
+        log::info!("✅ 找到 {} 个页面", page_titles.len());
+
+        if page_titles.is_empty() {
+            log::error!("❌ 未找到任何页面！");
+            log::error!("   请检查:");
+            log::error!("   1. source_url: {}", self.config.source_url);
+            log::error!("   2. api_url: {}", api_url);
+            log::error!("   3. 网络连接是否正常");
+            return Ok(CrawlerResult {
+                total_entries: 0,
+                total_bytes: 0,
+                duration_secs: start.elapsed().as_secs(),
+                error_count: 0,
+                storage_path: self.config.storage_path.to_string_lossy().to_string(),
+                details: vec!["错误: 未找到任何页面".to_string()],
+            });
+        }
+
+        details.push(format!("总页面数: {}", page_titles.len()));
+
+        // 2. 批量获取页面内容
+        let max_pages = self.config.max_pages.min(page_titles.len());
+        log::info!(
+            "📄 正在获取 {} 个页面的内容（共{}个）...",
+            max_pages,
+            page_titles.len()
+        );
+
+        // 续爬时跳过已完成的页面
+        let (mut checkpoint, resumed) =
+            Checkpoint::open(&self.config.storage_path, self.config.resume)?;
+        if !resumed.is_empty() {
+            details.push(format!("继续上次爬取: 已有 {} 个条目", resumed.len()));
+        }
+        self.entries = resumed;
+
+        let pending: Vec<String> = page_titles[..max_pages]
+            .iter()
+            .filter(|title| !checkpoint.state.completed_titles.contains(*title))
+            .cloned()
+            .collect();
+        let mut deduper = ContentDeduper::from_entries(&self.entries);
+        let entries = self
+            .fetch_all_contents(&api_url, &pending, &mut deduper, Some(&mut checkpoint))
+            .await?;
+        self.entries.extend(entries);
+        details.push(format!("重复内容条目数: {}", deduper.duplicates()));
+
+        // 截断超长条目
+        let truncated = limit_entry_lengths(&mut self.entries, self.config.max_entry_chars);
+        if truncated > 0 {
+            details.push(format!("超长截断条目数: {}", truncated));
+        }
+
+        // 保存结果
+        log::info!("💾 正在保存 {} 个条目到文件...", self.entries.len());
+        let total_bytes = self.save_entries()?;
+        checkpoint.finish()?;
+        let duration_secs = start.elapsed().as_secs();
+
+        log::info!(
+            "🎉 爬取完成: {} 条目, {} 字节, {} 秒",
+            self.entries.len(),
+            total_bytes,
+            duration_secs
+        );
+
+        details.push(format!("成功条目数: {}", self.entries.len()));
+        details.push(format!("总字节数: {}", total_bytes));
+        details.push(format!("耗时: {} 秒", duration_secs));
+
+        Ok(CrawlerResult {
+            total_entries: self.entries.len(),
+            total_bytes,
+            duration_secs,
+            error_count: 0,
+            storage_path: self.config.storage_path.to_string_lossy().to_string(),
+            details,
+        })
+    }
+
+    /// 获取 Wiki 所有文章标题 (不含重定向页, 用于覆盖率统计)
+    pub async fn list_article_titles(&mut self) -> CrawlerResult2<Vec<String>> {
+        let api_url = self.site().await?.api_url;
+        self.fetch_all_pages(&api_url, true).await
+    }
+
+    /// 获取 Wiki 所有分类及其文章数
+    pub async fn list_category_sizes(&mut self) -> CrawlerResult2<Vec<(String, u64)>> {
+        let api_url = self.site().await?.api_url;
+        let mut categories = Vec::new();
+        let mut continue_token: Option<String> = None;
+
+        loop {
+            let mut params = vec![
+                ("action", "query"),
+                ("format", "json"),
+                ("list", "allcategories"),
+                ("acprop", "size"),
+                ("aclimit", "500"),
+                ("acmin", "1"),
+            ];
+
+            if let Some(ref token) = continue_token {
+                params.push(("accontinue", token));
+            }
+
+            self.rate_limiter.wait(&api_url).await;
+            let json: serde_json::Value = self
+                .client
+                .get(&api_url)
+                .query(&params)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            if let Some(items) = json["query"]["allcategories"].as_array() {
+                for item in items {
+                    if let (Some(name), Some(pages)) = (item["*"].as_str(), item["pages"].as_u64())
+                    {
+                        categories.push((name.to_string(), pages));
+                    }
+                }
+            }
+
+            match json["continue"]["accontinue"].as_str() {
+                Some(token) => continue_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+
+        Ok(categories)
+    }
+
+    /// 获取所有页面标题 (依次遍历配置的命名空间)
+    async fn fetch_all_pages(
+        &self,
+        api_url: &str,
+        skip_redirects: bool,
+    ) -> CrawlerResult2<Vec<String>> {
+        let mut namespaces = self.config.namespaces.clone();
+        if namespaces.is_empty() {
+            namespaces = default_namespaces();
+        }
+        namespaces.sort_unstable();
+        namespaces.dedup();
+
+        let mut all_titles = Vec::new();
+        for namespace in namespaces {
+            let titles = self
+                .fetch_namespace_pages(api_url, namespace, skip_redirects)
+                .await?;
+            log::info!("命名空间 {}: {} 个页面", namespace, titles.len());
+            all_titles.extend(titles);
+        }
+
+        Ok(all_titles)
+    }
+
+    /// 获取指定分类下的页面标题 (多个分类去重, 保持顺序)
+    async fn fetch_category_pages(
+        &self,
+        api_url: &str,
+        categories: &[String],
+    ) -> CrawlerResult2<Vec<String>> {
+        let namespaces = if self.config.namespaces.is_empty() {
+            default_namespaces()
+        } else {
+            self.config.namespaces.clone()
+        };
+        let namespaces = namespaces
+            .iter()
+            .map(|ns| ns.to_string())
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let mut seen = HashSet::new();
+        let mut all_titles = Vec::new();
+
+        for category in categories {
+            let category_title = category_title(category);
+            let mut continue_token: Option<String> = None;
+            let mut count = 0;
+
+            loop {
+                let mut params = vec![
+                    ("action", "query"),
+                    ("format", "json"),
+                    ("list", "categorymembers"),
+                    ("cmtitle", category_title.as_str()),
+                    ("cmtype", "page"),
+                    ("cmnamespace", namespaces.as_str()),
+                    ("cmlimit", "500"),
+                ];
+
+                if let Some(ref token) = continue_token {
+                    params.push(("cmcontinue", token));
+                }
+
+                self.rate_limiter.wait(api_url).await;
+                let json: serde_json::Value = self
+                    .client
+                    .get(api_url)
+                    .query(&params)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                if let Some(members) = json["query"]["categorymembers"].as_array() {
+                    for member in members {
+                        if let Some(title) = member["title"].as_str() {
+                            count += 1;
+                            if seen.insert(title.to_string()) {
+                                all_titles.push(title.to_string());
+                            }
+                        }
+                    }
+                } else {
+                    log::warn!("未找到 query.categorymembers 字段: {}", category_title);
+                }
+
+                match json["continue"]["cmcontinue"].as_str() {
+                    Some(token) => continue_token = Some(token.to_string()),
+                    None => break,
+                }
+            }
+
+            log::info!("分类 {}: {} 个页面", category_title, count);
+        }
+
+        log::info!("分类共 {} 个页面标题 (已去重)", all_titles.len());
+        Ok(all_titles)
+    }
+
+    /// 获取单个命名空间的页面标题
+    async fn fetch_namespace_pages(
+        &self,
+        api_url: &str,
+        namespace: u32,
+        skip_redirects: bool,
+    ) -> CrawlerResult2<Vec<String>> {
+        let mut all_titles = Vec::new();
+        let mut continue_token: Option<String> = None;
+        let namespace = namespace.to_string();
+
+        log::info!("正在从 {} 获取页面列表...", api_url);
+
+        loop {
+            let mut params = vec![
+                ("action", "query"),
+                ("format", "json"),
+                ("list", "allpages"),
+                ("aplimit", "500"), // 每次获取500个
+                ("apnamespace", namespace.as_str()),
+            ];
+
+            if skip_redirects {
+                params.push(("apfilterredir", "nonredirects"));
+            }
+
+            if let Some(ref token) = continue_token {
+                params.push(("apcontinue", token));
+            }
+
+            self.rate_limiter.wait(api_url).await;
+            let response = self.client.get(api_url).query(&params).send().await?;
+
+            let status = response.status();
+            log::info!("API 响应状态: {}", status);
+
+            if !status.is_success() {
+                log::error!("API 返回错误状态: {}", status);
+                return Err(CrawlerError::HttpError(reqwest::Error::from(
+                    response.error_for_status().unwrap_err(),
+                )));
+            }
+
+            let json: serde_json::Value = response.json().await?;
+
+            // 调试：打印响应结构
+            log::debug!(
+                "API 响应: {}",
+                serde_json::to_string_pretty(&json).unwrap_or_default()
+            );
+
+            // 提取页面标题
+            if let Some(pages) = json["query"]["allpages"].as_array() {
+                log::info!("本次获取 {} 个页面", pages.len());
+                for page in pages {
+                    if let Some(title) = page["title"].as_str() {
+                        all_titles.push(title.to_string());
+                    }
+                }
+            } else {
+                log::warn!("未找到 query.allpages 字段");
+                log::debug!("响应结构: {:?}", json);
+            }
+
+            // 检查是否有更多页面
+            if let Some(continue_obj) = json["continue"].as_object() {
+                if let Some(token) = continue_obj["apcontinue"].as_str() {
+                    continue_token = Some(token.to_string());
+                    log::info!("已获取 {} 个页面标题，继续...", all_titles.len());
+                } else {
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+
+        log::info!("总共获取 {} 个页面标题", all_titles.len());
+        Ok(all_titles)
+    }
+
+    /// 并发获取页面内容
+    ///
+    /// 每批 50 个标题一个请求, 最多同时进行 `max_concurrent_requests` 个请求;
+    /// 同一域名的请求间隔不小于 `request_delay_ms` 毫秒, 避免触发限流。
+    /// 结果按批次顺序合并, 并按内容哈希去重 (重定向可能让不同标题指向同一页面);
+    /// 传入检查点时每完成一个批次就记录进度
+    async fn fetch_all_contents(
+        &self,
+        api_url: &str,
+        titles: &[String],
+        deduper: &mut ContentDeduper,
+        mut checkpoint: Option<&mut Checkpoint>,
+    ) -> CrawlerResult2<Vec<WikiEntry>> {
+        let max_concurrent = self.config.max_concurrent_requests.max(1);
+        let limiter = Semaphore::new(max_concurrent);
+        let chunks: Vec<&[String]> = titles.chunks(CONTENT_BATCH_SIZE).collect();
+        let total_chunks = chunks.len();
+
+        log::info!("⚙️  {} 个批次, 最大并发 {}", total_chunks, max_concurrent);
+
+        let tasks = chunks.into_iter().enumerate().map(|(i, chunk)| {
+            let limiter = &limiter;
+            async move {
+                let _permit = limiter
+                    .acquire()
+                    .await
+                    .map_err(|e| CrawlerError::Other(e.to_string()))?;
+
+                // 延迟避免限流
+                self.rate_limiter.wait(api_url).await;
+
+                log::info!(
+                    "   批次 {}/{}: 获取 {} 个页面...",
+                    i + 1,
+                    total_chunks,
+                    chunk.len()
+                );
+                let entries = self.fetch_pages_content(api_url, chunk).await?;
+                Ok((chunk, entries))
+            }
+        });
+
+        let mut entries = Vec::new();
+        let mut results: FuturesOrdered<_> = tasks.collect();
+        while let Some(result) = results.next().await {
+            let (chunk, chunk_entries) = result?;
+            for entry in chunk_entries {
+                if deduper.insert(&entry) {
+                    if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                        checkpoint.append(&entry)?;
+                    }
+                    entries.push(entry);
+                }
+            }
+
+            if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                checkpoint
+                    .state
+                    .completed_titles
+                    .extend(chunk.iter().cloned());
+                checkpoint.save_state()?;
+            }
+        }
+
+        log::info!("当前已添加 {} 个条目", entries.len());
+        Ok(entries)
+    }
+
+    /// 批量获取页面内容
+    async fn fetch_pages_content(
+        &self,
+        api_url: &str,
+        titles: &[String],
+    ) -> CrawlerResult2<Vec<WikiEntry>> {
+        let titles_str = titles.join("|");
+
+        log::info!("获取 {} 个页面的内容...", titles.len());
+
+        // 修改：使用 revisions 而不是 extracts
+        let params = vec![
+            ("action", "query"),
+            ("format", "json"),
+            ("prop", "revisions|categories"),
+            ("titles", &titles_str),
+            ("rvprop", "content|timestamp|user"), // 获取修订内容、时间和作者
+            ("rvslots", "main"),                  // 获取主槽位
+            ("cllimit", "50"),                    // 最多50个分类
+            ("redirects", "1"),                   // 自动跟随重定向
+        ];
+
+        let response = self.client.get(api_url).query(&params).send().await?;
+
+        let status = response.status();
+        log::info!("内容 API 响应状态: {}", status);
+
+        if !status.is_success() {
+            log::error!("内容 API 返回错误: {}", status);
+            return Err(CrawlerError::HttpError(reqwest::Error::from(
+                response.error_for_status().unwrap_err(),
+            )));
+        }
+
+        // 先获取原始 JSON 来调试
+        let json: serde_json::Value = response.json().await?;
+        log::debug!(
+            "📝 原始 API 响应: {}",
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        );
+
+        // 尝试解析
+        let api_response: ApiResponse = serde_json::from_value(json.clone()).map_err(|e| {
+            log::error!("❌ 解析 API 响应失败: {}", e);
+            log::error!("响应内容: {:?}", json);
+            CrawlerError::Other(format!("解析失败: {}", e))
+        })?;
+
+        let mut entries = Vec::new();
+        if let Some(query) = api_response.query {
+            log::info!("收到 {} 个页面的数据", query.pages.len());
+
+            let mut success_count = 0;
+            let mut no_content_count = 0;
+
+            for (page_id, page_data) in query.pages {
+                log::debug!("处理页面: {} (ID: {})", page_data.title, page_id);
+
+                match self.page_to_entry(page_data) {
+                    Some(entry) => {
+                        log::debug!("✅ 成功添加条目: {}", entry.title);
+                        entries.push(entry);
+                        success_count += 1;
+                    }
+                    None => no_content_count += 1,
+                }
+            }
+
+            log::info!(
+                "📊 本批次: 成功 {} 个，无内容 {} 个",
+                success_count,
+                no_content_count
+            );
+        } else {
+            log::warn!("API 响应中没有 query 字段");
+        }
+
+        Ok(entries)
+    }
+
+    /// 将 API 返回的页面转换为条目 (没有内容时返回 None)
+    fn page_to_entry(&self, page_data: PageData) -> Option<WikiEntry> {
+        let revision = page_data
+            .revisions
+            .and_then(|revisions| revisions.into_iter().next());
+
+        // 修订时间 (ISO 8601) 和作者, 用于增量更新
+        let last_modified = revision.as_ref().and_then(|r| r.timestamp.clone());
+        let author = revision.as_ref().and_then(|r| r.user.clone());
+
+        // 从 revisions 中提取内容
+        let content_opt = revision.and_then(|revision| {
+            // 优先使用 slots.main.content
+            if let Some(slots) = revision.slots {
+                if let Some(main) = slots.main {
+                    return Some(main.content);
+                }
+            }
+            // 降级：使用旧格式的 content
+            revision.content
+        });
+
+        let Some(raw_content) = content_opt else {
+            log::warn!(
+                "⚠️  页面 {} 没有内容（可能是重定向或特殊页面）",
+                page_data.title
+            );
+            return None;
+        };
+
+        if raw_content.trim().is_empty() {
+            log::warn!("页面 {} 的内容为空", page_data.title);
+            return None;
+        }
+
+        let categories = page_data
+            .categories
+            .unwrap_or_default()
+            .iter()
+            .map(|c| c.title.replace("Category:", ""))
+            .collect();
+
+        // 清理 Wiki 标记语法
+        let content = clean_wiki_markup(&raw_content);
+        let hash = calculate_hash(&content);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Some(WikiEntry {
+            id: format!("{}_{}", self.config.game_id, hash),
+            title: page_data.title.clone(),
+            content,
+            url: match &self.site {
+                Some(site) => site.page_url(&page_data.title),
+                None => format!(
+                    "{}{}",
+                    self.config.source_url,
+                    page_data.title.replace(" ", "_")
+                ),
+            },
+            timestamp,
+            hash,
+            categories,
+            metadata: WikiMetadata {
+                length: raw_content.len(),
+                last_modified,
+                author,
+                language: self
+                    .site
+                    .as_ref()
+                    .map(|site| site.lang.clone())
+                    .unwrap_or_else(|| "en".to_string()),
+            },
+        })
+    }
+
+    /// 保存条目到文件
+    fn save_entries(&self) -> CrawlerResult2<usize> {
+        std::fs::create_dir_all(&self.config.storage_path)?;
+
+        let file_path = self.config.storage_path.join("wiki_raw.jsonl");
+        let mut total_bytes = 0;
+
+        let mut file_content = String::new();
+        for entry in &self.entries {
+            let json =
+                serde_json::to_string(entry).map_err(|e| CrawlerError::Other(e.to_string()))?;
+            file_content.push_str(&json);
+            file_content.push('\n');
+            total_bytes += json.len() + 1;
+        }
+
+        std::fs::write(&file_path, file_content)?;
+
+        // 保存元数据
+        let metadata = serde_json::json!({
+            "game_id": self.config.game_id,
+            "source_url": self.config.source_url,
+            "source_type": "MediaWikiAPI",
+            "timestamp": self.config.timestamp,
+            "total_entries": self.entries.len(),
+            "total_bytes": total_bytes,
+        });
+
+        let metadata_path = self.config.storage_path.join("metadata.json");
+        std::fs::write(
+            &metadata_path,
+            serde_json::to_string_pretty(&metadata)
+                .map_err(|e| CrawlerError::Other(e.to_string()))?,
+        )?;
+
+        write_extra_formats(&self.config, &self.entries);
+
+        Ok(total_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_url_candidates() {
+        assert_eq!(
+            api_url_candidates("https://phasmophobia.fandom.com/wiki/"),
+            vec![
+                "https://phasmophobia.fandom.com/api.php",
+                "https://phasmophobia.fandom.com/w/api.php"
+            ]
+        );
+        assert_eq!(
+            api_url_candidates("https://minecraft.wiki/w/api.php?action=query"),
+            vec!["https://minecraft.wiki/w/api.php"]
+        );
+        assert_eq!(
+            api_url_candidates("https://wiki.example.org/index.php?title=Main_Page"),
+            vec![
+                "https://wiki.example.org/api.php",
+                "https://wiki.example.org/w/api.php"
+            ]
+        );
+
+        let site = SiteInfo {
+            api_url: "https://minecraft.wiki/api.php".to_string(),
+            server: "//minecraft.wiki".to_string(),
+            article_path: "/w/$1".to_string(),
+            lang: "en".to_string(),
+        };
+        assert_eq!(
+            site.page_url("Crafting Table"),
+            "https://minecraft.wiki/w/Crafting_Table"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_is_bounded() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    let index = requests.fetch_add(1, Ordering::SeqCst);
+                    tokio::spawn(async move {
+                        let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(current, Ordering::SeqCst);
+
+                        // GET 请求没有请求体, 读到请求头结束即可
+                        let mut buf = Vec::new();
+                        let mut chunk = [0u8; 4096];
+                        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                            let n = stream.read(&mut chunk).await.unwrap_or(0);
+                            if n == 0 {
+                                break;
+                            }
+                            buf.extend_from_slice(&chunk[..n]);
+                        }
+
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                        let body = format!(
+                            r#"{{"query":{{"pages":{{"{0}":{{"pageid":{0},"title":"Page {0}","revisions":[{{"slots":{{"main":{{"*":"content {0}"}}}}}}]}}}}}}}}"#,
+                            index
+                        );
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        let _ = stream.write_all(response.as_bytes()).await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            });
+        }
+
+        let crawler = MediaWikiApiCrawler::new(CrawlerConfig {
+            game_id: "test".to_string(),
+            request_delay_ms: 0,
+            max_concurrent_requests: 2,
+            ..Default::default()
+        });
+        let titles: Vec<String> = (0..CONTENT_BATCH_SIZE * 6)
+            .map(|i| format!("Page {}", i))
+            .collect();
+
+        let entries = crawler
+            .fetch_all_contents(
+                &format!("http://{}/api.php", addr),
+                &titles,
+                &mut ContentDeduper::default(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 6);
+        assert_eq!(entries.len(), 6);
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_revision_metadata() {
+        let json = r#"{
+            "query": {
+                "pages": {
+                    "42": {
+                        "pageid": 42,
+                        "title": "Spirit",
+                        "revisions": [{
+                            "user": "GhostHunter",
+                            "timestamp": "2024-03-15T08:30:00Z",
+                            "slots": {"main": {"*": "A [[Spirit]] is a ghost."}}
+                        }],
+                        "categories": [{"title": "Category:Ghosts"}]
+                    }
+                }
+            }
+        }"#;
+
+        let response: ApiResponse = serde_json::from_str(json).unwrap();
+        let page = response.query.unwrap().pages.remove("42").unwrap();
+        let crawler = MediaWikiApiCrawler::new(CrawlerConfig::default());
+        let entry = crawler.page_to_entry(page).unwrap();
+
+        assert_eq!(
+            entry.metadata.last_modified.as_deref(),
+            Some("2024-03-15T08:30:00Z")
+        );
+        assert_eq!(entry.metadata.author.as_deref(), Some("GhostHunter"));
+        assert_eq!(entry.categories, vec!["Ghosts".to_string()]);
+    }
+
+    #[test]
+    fn test_category_title() {
+        assert_eq!(category_title("Bosses"), "Category:Bosses");
+        assert_eq!(category_title(" Weapons "), "Category:Weapons");
+        assert_eq!(category_title("Category:Bosses"), "Category:Bosses");
+    }
+}
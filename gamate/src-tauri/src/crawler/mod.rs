@@ -1,10 +1,17 @@
-pub mod fandom_api;
+pub mod checkpoint;
 pub mod github_crawler;
+pub mod history;
+pub mod mediawiki_api;
+pub mod output;
+pub mod robots;
+pub mod spoiler_filter;
 pub mod types;
+pub mod update_check;
 pub mod utils;
 pub mod web_crawler;
 
-pub use fandom_api::FandomApiCrawler;
 pub use github_crawler::GitHubCrawler;
+pub use mediawiki_api::MediaWikiApiCrawler;
+pub use spoiler_filter::{EntryProcessor, SpoilerFilter};
 pub use types::*;
 pub use web_crawler::WebCrawler;
@@ -0,0 +1,159 @@
+use crate::crawler::types::*;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Markdown 页面目录名
+pub const PAGES_DIR: &str = "pages";
+
+/// Windows 保留的设备名 (不区分大小写, 带扩展名也不能用作文件名)
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 按配置写入 JSONL 以外的输出格式
+///
+/// `wiki_raw.jsonl` 由各爬虫的 `save_entries` 始终写入 (导入流程依赖它),
+/// 这里只处理额外的格式。额外格式写入失败只记录警告, 不影响爬取结果
+pub fn write_extra_formats(config: &CrawlerConfig, entries: &[WikiEntry]) {
+    for format in &config.output_formats {
+        match format.trim().to_lowercase().as_str() {
+            OUTPUT_FORMAT_JSONL => {}
+            OUTPUT_FORMAT_MARKDOWN | "md" => {
+                match write_markdown_pages(&config.storage_path, entries) {
+                    Ok(count) => log::info!("📝 已导出 {} 个 Markdown 页面", count),
+                    Err(e) => log::warn!("⚠️ 导出 Markdown 页面失败: {}", e),
+                }
+            }
+            other => log::warn!("⚠️ 不支持的输出格式: {}", other),
+        }
+    }
+}
+
+/// 每个条目写入 `pages/` 下的一个 `.md` 文件, 返回写入的文件数
+pub fn write_markdown_pages(storage_path: &Path, entries: &[WikiEntry]) -> CrawlerResult2<usize> {
+    let pages_dir = storage_path.join(PAGES_DIR);
+    if pages_dir.exists() {
+        fs::remove_dir_all(&pages_dir)?;
+    }
+    fs::create_dir_all(&pages_dir)?;
+
+    let mut used_names = HashSet::new();
+    for entry in entries {
+        let file_name = markdown_file_name(entry, &mut used_names);
+        fs::write(pages_dir.join(file_name), render_markdown(entry))?;
+    }
+
+    Ok(entries.len())
+}
+
+/// 渲染为带 front-matter 的 Markdown
+fn render_markdown(entry: &WikiEntry) -> String {
+    // JSON 字符串/数组同时也是合法的 YAML, 可以直接用于转义
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string());
+    let categories = serde_json::to_string(&entry.categories).unwrap_or_else(|_| "[]".into());
+
+    format!(
+        "---\ntitle: {}\nurl: {}\ncategories: {}\ntimestamp: {}\n---\n\n# {}\n\n{}\n",
+        quote(&entry.title),
+        quote(&entry.url),
+        categories,
+        entry.timestamp,
+        entry.title,
+        entry.content.trim()
+    )
+}
+
+/// 根据标题生成文件名 (替换非法字符, 避开 Windows 保留名, 重名时追加内容哈希)
+fn markdown_file_name(entry: &WikiEntry, used_names: &mut HashSet<String>) -> String {
+    let mut stem: String = entry
+        .title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(100)
+        .collect();
+    stem = stem.trim_matches(['.', ' ']).to_string();
+    if stem.is_empty() {
+        stem = entry.hash.clone();
+    }
+    if is_windows_reserved(&stem) {
+        stem.insert(0, '_');
+    }
+
+    if !used_names.insert(stem.to_lowercase()) {
+        stem = format!("{}_{}", stem, entry.hash);
+        used_names.insert(stem.to_lowercase());
+    }
+
+    format!("{}.md", stem)
+}
+
+/// 文件名 (扩展名之前的部分) 是否为 Windows 保留的设备名
+fn is_windows_reserved(stem: &str) -> bool {
+    let base = stem.split('.').next().unwrap_or(stem).trim_end();
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, hash: &str) -> WikiEntry {
+        WikiEntry {
+            id: format!("test_{}", hash),
+            title: title.to_string(),
+            content: "Ghosts can be identified by evidence.\n".to_string(),
+            url: "https://phasmophobia.fandom.com/wiki/Ghost".to_string(),
+            timestamp: 1700000000,
+            hash: hash.to_string(),
+            categories: vec!["Ghosts".to_string()],
+            metadata: WikiMetadata {
+                length: 0,
+                last_modified: None,
+                author: None,
+                language: "en".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = render_markdown(&entry("Ghost: \"Types\"", "abc"));
+        assert!(markdown.starts_with("---\ntitle: \"Ghost: \\\"Types\\\"\"\n"));
+        assert!(markdown.contains("categories: [\"Ghosts\"]\ntimestamp: 1700000000\n---\n"));
+        assert!(markdown.ends_with("Ghosts can be identified by evidence.\n"));
+    }
+
+    #[test]
+    fn test_markdown_file_name() {
+        let mut used = HashSet::new();
+        assert_eq!(
+            markdown_file_name(&entry("A/B: C?", "1"), &mut used),
+            "A_B_ C_.md"
+        );
+        assert_eq!(
+            markdown_file_name(&entry("a/b: c?", "2"), &mut used),
+            "a_b_ c__2.md"
+        );
+        assert_eq!(markdown_file_name(&entry("..", "3"), &mut used), "3.md");
+
+        // Windows 保留名
+        assert_eq!(markdown_file_name(&entry("CON", "4"), &mut used), "_CON.md");
+        assert_eq!(
+            markdown_file_name(&entry("nul.txt", "5"), &mut used),
+            "_nul.txt.md"
+        );
+        assert_eq!(
+            markdown_file_name(&entry("Console", "6"), &mut used),
+            "Console.md"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use reqwest::Client;
+
+/// robots.txt 规则 (只保留适用于当前 User-Agent 的分组)
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    /// (是否允许, 路径模式)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// 解析 robots.txt
+    ///
+    /// 优先使用名称包含在 User-Agent 中的分组, 没有时使用 `*` 分组
+    pub fn parse(content: &str, user_agent: &str) -> Self {
+        let user_agent = user_agent.to_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+
+        // 当前分组的 User-agent 列表; 遇到规则后再出现 User-agent 表示新分组开始
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // 空的 Disallow 表示全部允许
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if agents
+                        .iter()
+                        .any(|agent| agent != "*" && user_agent.contains(agent.as_str()))
+                    {
+                        specific.push(rule.clone());
+                    }
+                    if agents.iter().any(|agent| agent == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if specific.is_empty() {
+                wildcard
+            } else {
+                specific
+            },
+        }
+    }
+
+    /// 判断路径 (含查询参数) 是否允许访问: 最长匹配的规则生效, 长度相同时 Allow 优先
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+
+        for (allow, pattern) in &self.rules {
+            if !pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            match best {
+                Some((best_len, best_allow))
+                    if best_len > len || (best_len == len && best_allow) => {}
+                _ => best = Some((len, *allow)),
+            }
+        }
+
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+/// 匹配 robots.txt 路径模式 (支持 `*` 通配符和结尾的 `$`)
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    if !path.starts_with(first) {
+        return false;
+    }
+
+    let mut pos = first.len();
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        // 结尾锚定时最后一段必须匹配路径末尾
+        if anchored && i == parts.len() - 1 {
+            return path.len() >= pos + part.len() && path.ends_with(part);
+        }
+        match path[pos..].find(part) {
+            Some(idx) => pos += idx + part.len(),
+            None => return false,
+        }
+    }
+
+    !anchored || pos == path.len()
+}
+
+/// 获取站点的 robots.txt 规则 (获取失败或不存在时视为全部允许)
+pub async fn fetch_robots(client: &Client, source_url: &str, user_agent: &str) -> RobotsRules {
+    let Ok(url) = url::Url::parse(source_url).and_then(|url| url.join("/robots.txt")) else {
+        return RobotsRules::default();
+    };
+
+    let response = match client.get(url.as_str()).send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            log::info!("robots.txt 不可用 ({}), 不限制爬取路径", response.status());
+            return RobotsRules::default();
+        }
+        Err(e) => {
+            log::warn!("⚠️ 获取 robots.txt 失败: {}", e);
+            return RobotsRules::default();
+        }
+    };
+
+    match response.text().await {
+        Ok(content) => {
+            let rules = RobotsRules::parse(&content, user_agent);
+            log::info!("🤖 已加载 robots.txt: {} 条规则", rules.rules.len());
+            rules
+        }
+        Err(e) => {
+            log::warn!("⚠️ 读取 robots.txt 失败: {}", e);
+            RobotsRules::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+User-agent: *
+Disallow: /wiki/Special:
+Disallow: /*?action=
+Allow: /wiki/Special:Random
+Disallow: /*.php$
+
+User-agent: BadBot
+Disallow: /
+";
+
+    #[test]
+    fn test_robots_rules() {
+        let rules = RobotsRules::parse(ROBOTS, "Mozilla/5.0 (Windows NT 10.0)");
+        assert!(rules.is_allowed("/wiki/Main_Page"));
+        assert!(!rules.is_allowed("/wiki/Special:AllPages"));
+        assert!(rules.is_allowed("/wiki/Special:Random"));
+        assert!(!rules.is_allowed("/wiki/Ghost?action=edit"));
+        assert!(!rules.is_allowed("/index.php"));
+        assert!(rules.is_allowed("/index.php?title=Ghost"));
+
+        let rules = RobotsRules::parse(ROBOTS, "BadBot/1.0");
+        assert!(!rules.is_allowed("/wiki/Main_Page"));
+
+        assert!(RobotsRules::parse("", "BadBot/1.0").is_allowed("/"));
+    }
+}
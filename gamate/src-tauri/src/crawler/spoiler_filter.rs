@@ -0,0 +1,83 @@
+use crate::crawler::types::WikiEntry;
+use crate::settings::AppSettings;
+
+/// Wiki 条目后处理器 (导入向量数据库前对条目进行过滤/改写)
+pub trait EntryProcessor {
+    fn process(&self, entries: Vec<WikiEntry>) -> Vec<WikiEntry>;
+}
+
+/// 剧透过滤器
+///
+/// 根据标题和分类匹配剧透关键词 (不区分大小写的子串匹配)
+#[derive(Debug, Clone)]
+pub struct SpoilerFilter {
+    patterns: Vec<String>,
+}
+
+impl SpoilerFilter {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    /// 根据设置创建指定游戏的过滤器 (未启用时返回 None)
+    pub fn from_settings(settings: &AppSettings, game_id: &str) -> Option<Self> {
+        let config = &settings.skill_library.spoiler_filter;
+        if !config.enabled {
+            return None;
+        }
+
+        let mut patterns = config.patterns.clone();
+        if let Some(game_patterns) = config.game_patterns.get(game_id) {
+            patterns.extend(game_patterns.iter().cloned());
+        }
+
+        Some(Self::new(patterns))
+    }
+
+    /// 标题或分类是否命中剧透关键词
+    pub fn is_spoiler(&self, title: &str, categories: &[String]) -> bool {
+        let title = title.to_lowercase();
+        self.patterns.iter().any(|pattern| {
+            title.contains(pattern.as_str())
+                || categories
+                    .iter()
+                    .any(|c| c.to_lowercase().contains(pattern.as_str()))
+        })
+    }
+}
+
+impl EntryProcessor for SpoilerFilter {
+    fn process(&self, entries: Vec<WikiEntry>) -> Vec<WikiEntry> {
+        let before = entries.len();
+        let entries: Vec<WikiEntry> = entries
+            .into_iter()
+            .filter(|e| !self.is_spoiler(&e.title, &e.categories))
+            .collect();
+
+        if entries.len() < before {
+            log::info!("🙈 剧透过滤: 移除 {} 个条目", before - entries.len());
+        }
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_spoiler() {
+        let filter = SpoilerFilter::new(vec!["Ending".to_string(), "隐藏Boss".to_string()]);
+
+        assert!(filter.is_spoiler("True Ending", &[]));
+        assert!(filter.is_spoiler("Malenia", &["隐藏Boss".to_string()]));
+        assert!(!filter.is_spoiler("Weapons", &["Items".to_string()]));
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 use thiserror::Error;
 
 /// Wiki 源类型
@@ -16,6 +18,67 @@ pub enum WikiSourceType {
     CustomWeb,
 }
 
+impl WikiSourceType {
+    /// 所有来源类型的规范名称 (games.toml 中的 source_type)
+    pub const VALID_NAMES: &'static [&'static str] =
+        &["FandomWiki", "GamepediaWiki", "GitHub", "CustomWeb"];
+
+    /// 规范名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WikiSourceType::FandomWiki => "FandomWiki",
+            WikiSourceType::GamepediaWiki => "GamepediaWiki",
+            WikiSourceType::GitHub => "GitHub",
+            WikiSourceType::CustomWeb => "CustomWeb",
+        }
+    }
+
+    /// 是否为 MediaWiki 站点 (可使用 MediaWiki API)
+    pub fn is_mediawiki(&self) -> bool {
+        matches!(
+            self,
+            WikiSourceType::FandomWiki | WikiSourceType::GamepediaWiki
+        )
+    }
+}
+
+impl FromStr for WikiSourceType {
+    type Err = CrawlerError;
+
+    /// 解析来源类型 (不区分大小写, 忽略 `_` / `-` / 空格), 支持常见别名
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "fandomwiki" | "fandom" | "fandomapi" | "mediawiki" | "mediawikiapi" => {
+                Ok(WikiSourceType::FandomWiki)
+            }
+            "gamepediawiki" | "gamepedia" => Ok(WikiSourceType::GamepediaWiki),
+            "github" | "githubrepo" => Ok(WikiSourceType::GitHub),
+            "customweb" | "custom" | "web" => Ok(WikiSourceType::CustomWeb),
+            _ => Err(CrawlerError::UnknownSourceType(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<&str> for WikiSourceType {
+    type Error = CrawlerError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl std::fmt::Display for WikiSourceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Wiki 内容项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -72,12 +135,74 @@ pub struct CrawlerConfig {
     pub max_depth: usize,
     /// 请求延迟（毫秒）
     pub request_delay_ms: u64,
-    /// User-Agent
+    /// User-Agent (为空时使用 DEFAULT_USER_AGENT)
     pub user_agent: String,
+    /// 自定义请求头 (覆盖爬虫的默认请求头)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 预置 Cookie (名称 -> 值, 作用于起始 URL 的站点); 爬取过程中服务器设置的 Cookie 会自动保留
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
     /// 是否包含图片
     pub include_images: bool,
     /// GitHub Token (可选)
     pub github_token: Option<String>,
+    /// 单个条目的最大字符数 (超出时在段落/句子边界截断, 0 表示不限制)
+    #[serde(default = "default_max_entry_chars")]
+    pub max_entry_chars: usize,
+    /// MediaWiki 命名空间 (默认只爬取主命名空间 0)
+    #[serde(default = "default_namespaces")]
+    pub namespaces: Vec<u32>,
+    /// MediaWiki 分类 (设置后只爬取这些分类下的页面)
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+    /// 同时进行的最大请求数 (MediaWiki 批量获取页面内容时使用)
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// 是否从 `crawl_state.json` 继续上次中断的爬取 (false 时从头开始)
+    #[serde(default)]
+    pub resume: bool,
+    /// 是否遵守站点的 robots.txt (网页爬虫)
+    #[serde(default = "default_respect_robots")]
+    pub respect_robots: bool,
+    /// 输出格式: `jsonl` (默认, 始终写入) / `markdown` (每个条目一个 .md 文件)
+    #[serde(default = "default_output_formats")]
+    pub output_formats: Vec<String>,
+}
+
+/// 默认 User-Agent
+pub const DEFAULT_USER_AGENT: &str = "GamePartnerSkill/1.0 (https://github.com/syf20020816/Gamate)";
+
+/// 默认单条目最大字符数
+pub const DEFAULT_MAX_ENTRY_CHARS: usize = 20_000;
+
+/// 默认最大并发请求数
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 5;
+
+fn default_max_entry_chars() -> usize {
+    DEFAULT_MAX_ENTRY_CHARS
+}
+
+fn default_max_concurrent_requests() -> usize {
+    DEFAULT_MAX_CONCURRENT_REQUESTS
+}
+
+fn default_respect_robots() -> bool {
+    true
+}
+
+/// JSONL 输出格式 (wiki_raw.jsonl)
+pub const OUTPUT_FORMAT_JSONL: &str = "jsonl";
+
+/// Markdown 输出格式 (pages/*.md)
+pub const OUTPUT_FORMAT_MARKDOWN: &str = "markdown";
+
+pub fn default_output_formats() -> Vec<String> {
+    vec![OUTPUT_FORMAT_JSONL.to_string()]
+}
+
+pub fn default_namespaces() -> Vec<u32> {
+    vec![0]
 }
 
 impl Default for CrawlerConfig {
@@ -91,9 +216,18 @@ impl Default for CrawlerConfig {
             max_pages: 1000,
             max_depth: 5,
             request_delay_ms: 500,
-            user_agent: "GamePartnerSkill/1.0".to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: HashMap::new(),
+            cookies: HashMap::new(),
             include_images: false,
             github_token: None,
+            max_entry_chars: DEFAULT_MAX_ENTRY_CHARS,
+            namespaces: default_namespaces(),
+            categories: None,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            resume: false,
+            respect_robots: true,
+            output_formats: default_output_formats(),
         }
     }
 }
@@ -140,8 +274,43 @@ pub enum CrawlerError {
     #[error("Unsupported source type")]
     UnsupportedSourceType,
 
+    #[error(
+        "Unknown source type \"{0}\" (valid values: FandomWiki, GamepediaWiki, GitHub, CustomWeb)"
+    )]
+    UnknownSourceType(String),
+
     #[error("Other error: {0}")]
     Other(String),
 }
 
 pub type CrawlerResult2<T> = Result<T, CrawlerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source_type() {
+        for name in WikiSourceType::VALID_NAMES {
+            let source_type: WikiSourceType = name.parse().unwrap();
+            assert_eq!(source_type.as_str(), *name);
+        }
+
+        assert!(matches!(
+            "FandomAPI".parse(),
+            Ok(WikiSourceType::FandomWiki)
+        ));
+        assert!(matches!(
+            WikiSourceType::try_from("github"),
+            Ok(WikiSourceType::GitHub)
+        ));
+        assert!(matches!(
+            "custom_web".parse(),
+            Ok(WikiSourceType::CustomWeb)
+        ));
+
+        let err = "FandomWikki".parse::<WikiSourceType>().unwrap_err();
+        assert!(err.to_string().contains("FandomWikki"));
+        assert!(err.to_string().contains("CustomWeb"));
+    }
+}
@@ -0,0 +1,118 @@
+/// 技能库来源变化检测
+///
+/// 用尽量便宜的请求判断 Wiki 来源自上次爬取以来是否有更新:
+/// - Fandom / Gamepedia 等 MediaWiki 站点: 查询 `recentchanges`, 只取 1 条
+/// - 其他来源: 对源地址发送 HEAD 请求比较 `Last-Modified`, 没有该响应头时视为已变化
+use crate::crawler::mediawiki_api::discover_site;
+use crate::crawler::types::*;
+use crate::crawler::utils::DomainRateLimiter;
+use reqwest::Client;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct RecentChangesResponse {
+    query: Option<RecentChangesQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentChangesQuery {
+    recentchanges: Vec<serde_json::Value>,
+}
+
+/// 检查来源自 `since_secs` (Unix 秒) 以来是否有变化
+pub async fn source_changed_since(
+    source_type: &str,
+    source_url: &str,
+    since_secs: u64,
+) -> CrawlerResult2<bool> {
+    let client = Client::builder()
+        .user_agent(DEFAULT_USER_AGENT)
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+
+    if source_type.parse::<WikiSourceType>()?.is_mediawiki() {
+        mediawiki_changed_since(&client, source_url, since_secs).await
+    } else {
+        last_modified_changed_since(&client, source_url, since_secs).await
+    }
+}
+
+/// MediaWiki: 查询 since 之后是否有条目修改 (recentchanges 通常只保留约 30 天)
+async fn mediawiki_changed_since(
+    client: &Client,
+    source_url: &str,
+    since_secs: u64,
+) -> CrawlerResult2<bool> {
+    // 与爬虫共享同一站点的限速记录
+    let rate_limiter = DomainRateLimiter::global(std::time::Duration::from_millis(
+        CrawlerConfig::default().request_delay_ms,
+    ));
+    let api_url = discover_site(client, &rate_limiter, source_url)
+        .await?
+        .api_url;
+    let rcend = to_mediawiki_timestamp(since_secs)?;
+
+    rate_limiter.wait(&api_url).await;
+
+    let response: RecentChangesResponse = client
+        .get(&api_url)
+        .query(&[
+            ("action", "query"),
+            ("list", "recentchanges"),
+            ("rcnamespace", "0"),
+            ("rctype", "edit|new"),
+            ("rclimit", "1"),
+            ("rcend", rcend.as_str()),
+            ("format", "json"),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response
+        .query
+        .map(|q| !q.recentchanges.is_empty())
+        .unwrap_or(true))
+}
+
+/// 其他来源: 比较 Last-Modified 响应头
+async fn last_modified_changed_since(
+    client: &Client,
+    source_url: &str,
+    since_secs: u64,
+) -> CrawlerResult2<bool> {
+    let response = client.head(source_url).send().await?.error_for_status()?;
+
+    let last_modified = response
+        .headers()
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok());
+
+    Ok(match last_modified {
+        Some(time) => time.timestamp() > since_secs as i64,
+        None => true,
+    })
+}
+
+/// Unix 秒转换为 MediaWiki 时间戳 (ISO 8601)
+fn to_mediawiki_timestamp(secs: u64) -> CrawlerResult2<String> {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .ok_or_else(|| CrawlerError::Other(format!("无效的时间戳: {}", secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mediawiki_timestamp() {
+        assert_eq!(
+            to_mediawiki_timestamp(1_700_000_000).unwrap(),
+            "2023-11-14T22:13:20Z"
+        );
+    }
+}
@@ -1,6 +1,11 @@
+use crate::crawler::types::WikiEntry;
 use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 /// 清洗 HTML 文本
 pub fn clean_html_text(text: &str) -> String {
@@ -85,6 +90,79 @@ pub fn split_into_chunks(text: &str, max_chunk_size: usize, overlap: usize) -> V
     chunks
 }
 
+/// 在段落/句子边界截断文本, 使其不超过 max_chars 个字符
+///
+/// 只在截断窗口的后 30% 内寻找边界, 找不到时按字符硬截断
+pub fn truncate_at_boundary(text: &str, max_chars: usize) -> String {
+    let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+        return text.to_string();
+    };
+
+    let window = &text[..cut];
+    let min_cut = window
+        .char_indices()
+        .nth(max_chars * 7 / 10)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+
+    // 依次尝试: 段落 > 句子 > 空白
+    let boundary = window
+        .rfind("\n\n")
+        .filter(|&i| i >= min_cut)
+        .or_else(|| {
+            ["。", "！", "？", ". ", "! ", "? "]
+                .iter()
+                .filter_map(|p| window.rfind(p).map(|i| i + p.len()))
+                .filter(|&i| i >= min_cut)
+                .max()
+        })
+        .or_else(|| window.rfind(char::is_whitespace).filter(|&i| i >= min_cut))
+        .unwrap_or(cut);
+
+    window[..boundary].trim_end().to_string()
+}
+
+/// 截断超长条目, 返回被截断的条目数
+pub fn limit_entry_lengths(entries: &mut [WikiEntry], max_chars: usize) -> usize {
+    if max_chars == 0 {
+        return 0;
+    }
+
+    let mut truncated = 0;
+    for entry in entries.iter_mut() {
+        let length = entry.content.chars().count();
+        if length > max_chars {
+            entry.content = truncate_at_boundary(&entry.content, max_chars);
+            log::warn!(
+                "✂️ 条目过长已截断: {} ({} -> {} 字符)",
+                entry.title,
+                length,
+                entry.content.chars().count()
+            );
+            truncated += 1;
+        }
+    }
+
+    truncated
+}
+
+/// 构建请求头: 在默认请求头之上应用自定义请求头 (无效的名称或值会被忽略)
+pub fn build_headers(defaults: HeaderMap, custom: &HashMap<String, String>) -> HeaderMap {
+    let mut headers = defaults;
+    for (name, value) in custom {
+        match (
+            HeaderName::from_bytes(name.trim().as_bytes()),
+            HeaderValue::from_str(value.trim()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => log::warn!("⚠️ 忽略无效的请求头: {}: {}", name, value),
+        }
+    }
+    headers
+}
+
 /// 清理 Wiki 标记语法，转换为纯文本
 pub fn clean_wiki_markup(text: &str) -> String {
     let mut result = text.to_string();
@@ -135,6 +213,97 @@ pub fn clean_wiki_markup(text: &str) -> String {
     result.trim().to_string()
 }
 
+/// 按内容哈希去重 (重定向、别名等不同 URL 可能得到相同内容)
+#[derive(Debug, Default)]
+pub struct ContentDeduper {
+    seen: HashSet<String>,
+    duplicates: usize,
+}
+
+impl ContentDeduper {
+    /// 以已有条目初始化 (续爬时使用)
+    pub fn from_entries(entries: &[WikiEntry]) -> Self {
+        Self {
+            seen: entries.iter().map(|e| e.hash.clone()).collect(),
+            duplicates: 0,
+        }
+    }
+
+    /// 记录条目, 内容首次出现时返回 true; 重复时记录日志并返回 false
+    pub fn insert(&mut self, entry: &WikiEntry) -> bool {
+        if self.seen.insert(entry.hash.clone()) {
+            return true;
+        }
+        self.duplicates += 1;
+        log::info!("♻️ 跳过重复内容: {} ({})", entry.title, entry.url);
+        false
+    }
+
+    /// 被跳过的重复条目数
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+}
+
+/// 各域名上次请求的时间 (所有爬虫共享)
+static DOMAIN_LAST_REQUEST: OnceLock<Arc<Mutex<HashMap<String, Instant>>>> = OnceLock::new();
+
+/// 按域名限速: 同一域名的两次请求间隔不小于 `interval`
+///
+/// 克隆后共享同一份记录, 可在多个并发任务之间使用
+#[derive(Debug, Clone)]
+pub struct DomainRateLimiter {
+    interval: Duration,
+    last_request: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl DomainRateLimiter {
+    /// 创建独立的限速器
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_request: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 创建与进程内所有爬虫共享记录的限速器 (同时爬取多个游戏时同一站点也不会超速)
+    pub fn global(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_request: DOMAIN_LAST_REQUEST.get_or_init(Default::default).clone(),
+        }
+    }
+
+    /// 等待直到可以请求该 URL 所在的域名
+    pub async fn wait(&self, url: &str) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_else(|| url.to_string());
+
+        // 在锁内预留下一个请求时间, 并发调用时依次排队
+        let scheduled = {
+            let mut last_request = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let scheduled = match last_request.get(&host) {
+                Some(last) => (*last + self.interval).max(now),
+                None => now,
+            };
+            last_request.insert(host, scheduled);
+            scheduled
+        };
+
+        let wait = scheduled.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,6 +322,35 @@ mod tests {
         assert!(!hash.is_empty());
     }
 
+    #[test]
+    fn test_truncate_at_boundary() {
+        let text = "第一句话。第二句话。第三句话很长很长很长";
+        assert_eq!(truncate_at_boundary(text, 100), text);
+
+        // 在句号处截断
+        assert_eq!(truncate_at_boundary(text, 12), "第一句话。第二句话。");
+
+        // 窗口内没有边界时硬截断
+        assert_eq!(truncate_at_boundary("abcdefghij", 4), "abcd");
+    }
+
+    #[test]
+    fn test_build_headers() {
+        let mut defaults = HeaderMap::new();
+        defaults.insert("Accept", HeaderValue::from_static("text/html"));
+
+        let custom = HashMap::from([
+            ("Accept".to_string(), "application/json".to_string()),
+            ("X-Contact".to_string(), "me@example.com".to_string()),
+            ("Bad Header".to_string(), "x".to_string()),
+        ]);
+
+        let headers = build_headers(defaults, &custom);
+        assert_eq!(headers["accept"], "application/json");
+        assert_eq!(headers["x-contact"], "me@example.com");
+        assert_eq!(headers.len(), 2);
+    }
+
     #[test]
     fn test_split_into_chunks() {
         let text = "one two three four five six seven eight nine ten";
@@ -176,4 +374,50 @@ mod tests {
         assert!(!clean_text.contains("<!--"));
         assert!(!clean_text.contains("-->"));
     }
+
+    #[test]
+    fn test_content_deduper() {
+        let entry = |title: &str, content: &str| WikiEntry {
+            id: title.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            url: format!("https://example.com/wiki/{}", title),
+            timestamp: 0,
+            hash: calculate_hash(content),
+            categories: vec![],
+            metadata: crate::crawler::types::WikiMetadata {
+                length: content.len(),
+                last_modified: None,
+                author: None,
+                language: "en".to_string(),
+            },
+        };
+
+        let mut deduper = ContentDeduper::from_entries(&[entry("Ghost", "A ghost.")]);
+        assert!(!deduper.insert(&entry("Ghosts", "A ghost.")));
+        assert!(deduper.insert(&entry("Spirit", "A spirit.")));
+        assert!(!deduper.insert(&entry("Spirits", "A spirit.")));
+        assert_eq!(deduper.duplicates(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_domain_rate_limiter() {
+        let delay = Duration::from_millis(100);
+        let limiter = DomainRateLimiter::new(delay);
+
+        let start = Instant::now();
+        limiter
+            .wait("https://phasmophobia.fandom.com/api.php")
+            .await;
+        let first = start.elapsed();
+        limiter
+            .wait("https://phasmophobia.fandom.com/wiki/Ghost")
+            .await;
+        assert!(start.elapsed() - first >= delay);
+
+        // 不同域名互不影响
+        let other = Instant::now();
+        limiter.wait("https://minecraft.wiki/w/api.php").await;
+        assert!(other.elapsed() < delay);
+    }
 }
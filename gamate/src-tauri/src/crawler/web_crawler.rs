@@ -1,27 +1,44 @@
+use crate::crawler::checkpoint::Checkpoint;
+use crate::crawler::output::write_extra_formats;
+use crate::crawler::robots::{fetch_robots, RobotsRules};
 use crate::crawler::types::*;
 use crate::crawler::utils::*;
+use reqwest::cookie::Jar;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use std::collections::{HashSet, VecDeque};
 use std::fs;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use tokio::time::sleep;
 use url::Url;
 
+/// 每爬取多少个页面保存一次进度 (条目本身逐个追加, 中断后最多重新访问这么多页面)
+const CHECKPOINT_INTERVAL_PAGES: usize = 20;
+
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36";
+
 pub struct WebCrawler {
     config: CrawlerConfig,
     client: Client,
     visited_urls: HashSet<String>,
     entries: Vec<WikiEntry>,
+    user_agent: String,
+    /// 已解析的 robots.txt 规则 (首次爬取时获取)
+    robots: Option<RobotsRules>,
+    /// 因 robots.txt 跳过的 URL
+    robots_skipped: Mutex<HashSet<String>>,
+    rate_limiter: DomainRateLimiter,
+    deduper: ContentDeduper,
 }
 
 impl WebCrawler {
     pub fn new(config: CrawlerConfig) -> Self {
-        // 使用更真实的 User-Agent 来避免反爬虫
-        let user_agent = if config.user_agent.contains("Mozilla") {
-            config.user_agent.clone()
+        // 未自定义 User-Agent 时使用更真实的浏览器 UA 来避免反爬虫
+        let is_default_ua = config.user_agent.is_empty() || config.user_agent == DEFAULT_USER_AGENT;
+        let user_agent = if is_default_ua {
+            BROWSER_USER_AGENT.to_string()
         } else {
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36".to_string()
+            config.user_agent.clone()
         };
 
         // 构建更完整的 Headers 模拟真实浏览器
@@ -51,22 +68,42 @@ impl WebCrawler {
         headers.insert("Sec-Fetch-Site", "none".parse().unwrap());
         headers.insert("Sec-Fetch-User", "?1".parse().unwrap());
 
+        // Cookie 在整个爬取过程中保留 (部分 Wiki 需要先通过同意弹窗/验证页)
+        let jar = Arc::new(Jar::default());
+        if let Ok(source_url) = Url::parse(&config.source_url) {
+            for (name, value) in &config.cookies {
+                jar.add_cookie_str(&format!("{}={}", name, value), &source_url);
+            }
+        }
+
         let client = Client::builder()
-            .user_agent(user_agent)
-            .default_headers(headers)
+            .user_agent(user_agent.clone())
+            .default_headers(build_headers(headers, &config.headers))
+            .cookie_provider(jar)
             .timeout(Duration::from_secs(30))
             .redirect(reqwest::redirect::Policy::limited(10))
             .build()
             .unwrap();
 
         Self {
-            config,
             client,
             visited_urls: HashSet::new(),
             entries: Vec::new(),
+            user_agent,
+            robots: None,
+            robots_skipped: Mutex::new(HashSet::new()),
+            rate_limiter: DomainRateLimiter::global(Duration::from_millis(config.request_delay_ms)),
+            deduper: ContentDeduper::default(),
+            config,
         }
     }
 
+    /// 使用指定的域名限速器 (默认与其他爬虫共享全局限速记录)
+    pub fn with_rate_limiter(mut self, rate_limiter: DomainRateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     /// 开始爬取
     pub async fn crawl(&mut self) -> CrawlerResult2<CrawlerResult> {
         let start = Instant::now();
@@ -76,10 +113,27 @@ impl WebCrawler {
         log::info!("开始爬取 Wiki: {}", self.config.source_url);
         details.push(format!("起始 URL: {}", self.config.source_url));
 
-        // BFS 爬取
+        // 获取 robots.txt (同一个爬虫只获取一次)
+        if self.config.respect_robots && self.robots.is_none() {
+            self.robots =
+                Some(fetch_robots(&self.client, &self.config.source_url, &self.user_agent).await);
+        }
+
+        // BFS 爬取 (续爬时恢复上次的队列和已访问 URL)
+        let (mut checkpoint, resumed) =
+            Checkpoint::open(&self.config.storage_path, self.config.resume)?;
         let mut queue = VecDeque::new();
-        queue.push_back((self.config.source_url.clone(), 0)); // (url, depth)
+        if checkpoint.state.visited_urls.is_empty() {
+            queue.push_back((self.config.source_url.clone(), 0)); // (url, depth)
+        } else {
+            details.push(format!("继续上次爬取: 已有 {} 个条目", resumed.len()));
+            self.visited_urls = checkpoint.state.visited_urls.clone();
+            queue.extend(checkpoint.state.queue.drain(..));
+            self.deduper = ContentDeduper::from_entries(&resumed);
+            self.entries = resumed;
+        }
 
+        let mut pages_since_checkpoint = 0;
         while let Some((url, depth)) = queue.pop_front() {
             // 检查是否超过限制
             if self.entries.len() >= self.config.max_pages {
@@ -100,11 +154,17 @@ impl WebCrawler {
 
             self.visited_urls.insert(normalized_url.clone());
 
+            // 按域名限速，避免过快请求
+            self.rate_limiter.wait(&url).await;
+
             // 爬取页面
             match self.crawl_page(&url).await {
                 Ok((entry, links)) => {
                     log::info!("成功爬取: {} (深度: {})", entry.title, depth);
-                    self.entries.push(entry);
+                    if self.deduper.insert(&entry) {
+                        checkpoint.append(&entry)?;
+                        self.entries.push(entry);
+                    }
 
                     // 将新链接加入队列
                     for link in links {
@@ -119,12 +179,25 @@ impl WebCrawler {
                 }
             }
 
-            // 延迟，避免过快请求
-            sleep(Duration::from_millis(self.config.request_delay_ms)).await;
+            // 定期记录进度 (每页都复制已访问 URL 和队列的开销随页面数平方增长)
+            pages_since_checkpoint += 1;
+            if pages_since_checkpoint >= CHECKPOINT_INTERVAL_PAGES {
+                pages_since_checkpoint = 0;
+                checkpoint.state.visited_urls = self.visited_urls.clone();
+                checkpoint.state.queue = queue.iter().cloned().collect();
+                checkpoint.save_state()?;
+            }
+        }
+
+        // 截断超长条目
+        let truncated = limit_entry_lengths(&mut self.entries, self.config.max_entry_chars);
+        if truncated > 0 {
+            details.push(format!("超长截断条目数: {}", truncated));
         }
 
         // 保存结果
         let total_bytes = self.save_entries()?;
+        checkpoint.finish()?;
         let duration_secs = start.elapsed().as_secs();
 
         log::info!(
@@ -138,6 +211,11 @@ impl WebCrawler {
         details.push(format!("总字节数: {}", total_bytes));
         details.push(format!("耗时: {} 秒", duration_secs));
         details.push(format!("错误数: {}", error_count));
+        details.push(format!("重复内容条目数: {}", self.deduper.duplicates()));
+        if self.config.respect_robots {
+            let skipped = self.robots_skipped.lock().map(|s| s.len()).unwrap_or(0);
+            details.push(format!("robots.txt 跳过的 URL 数: {}", skipped));
+        }
 
         Ok(CrawlerResult {
             total_entries: self.entries.len(),
@@ -343,7 +421,28 @@ impl WebCrawler {
         }
 
         // 必须是内部链接
-        is_internal_link(&self.config.source_url, url)
+        if !is_internal_link(&self.config.source_url, url) {
+            return false;
+        }
+
+        // 遵守 robots.txt
+        if let Some(robots) = &self.robots {
+            if let Ok(parsed) = Url::parse(url) {
+                let path = match parsed.query() {
+                    Some(query) => format!("{}?{}", parsed.path(), query),
+                    None => parsed.path().to_string(),
+                };
+                if !robots.is_allowed(&path) {
+                    log::debug!("robots.txt 不允许访问, 跳过: {}", url);
+                    if let Ok(mut skipped) = self.robots_skipped.lock() {
+                        skipped.insert(normalize_url(url));
+                    }
+                    return false;
+                }
+            }
+        }
+
+        true
     }
 
     /// 保存条目到文件
@@ -383,6 +482,8 @@ impl WebCrawler {
                 .map_err(|e| CrawlerError::Other(e.to_string()))?,
         )?;
 
+        write_extra_formats(&self.config, &self.entries);
+
         Ok(total_bytes)
     }
 }
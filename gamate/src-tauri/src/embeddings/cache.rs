@@ -0,0 +1,156 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+/// 缓存文件名 (放在向量数据库存储目录下)
+pub const CACHE_FILE_NAME: &str = "embeddings_cache.jsonl";
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// 缓存文件中的一行
+#[derive(Serialize, Deserialize)]
+struct CacheLine {
+    model: String,
+    hash: String,
+    vector: Vec<f32>,
+}
+
+/// Embedding 缓存
+///
+/// 以 (模型名, 内容哈希) 为键, 内存中保存全部向量, 新向量追加写入 JSONL 文件。
+/// 重新导入时内容未变化的条目直接使用缓存, 不再请求 API
+pub struct EmbeddingCache {
+    path: PathBuf,
+    vectors: HashMap<(String, String), Vec<f32>>,
+    stats: CacheStats,
+}
+
+impl EmbeddingCache {
+    /// 从文件加载缓存 (文件不存在时为空缓存, 损坏的行跳过)
+    pub fn load(path: PathBuf) -> Self {
+        let mut vectors = HashMap::new();
+
+        if let Ok(file) = std::fs::File::open(&path) {
+            let mut corrupted = 0;
+            for line in BufReader::new(file).lines() {
+                let Ok(line) = line else {
+                    corrupted += 1;
+                    continue;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<CacheLine>(&line) {
+                    Ok(entry) => {
+                        vectors.insert((entry.model, entry.hash), entry.vector);
+                    }
+                    Err(_) => corrupted += 1,
+                }
+            }
+
+            log::info!(
+                "💾 加载 Embedding 缓存: {} 条 ({})",
+                vectors.len(),
+                path.display()
+            );
+            if corrupted > 0 {
+                log::warn!("⚠️ Embedding 缓存中 {} 行无法解析, 已跳过", corrupted);
+            }
+        }
+
+        Self {
+            path,
+            vectors,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// 查找缓存的向量并记录命中/未命中
+    pub fn get(&mut self, model: &str, hash: &str) -> Option<Vec<f32>> {
+        let vector = self
+            .vectors
+            .get(&(model.to_string(), hash.to_string()))
+            .cloned();
+        match vector {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        vector
+    }
+
+    /// 记录一次无法使用缓存的查询 (内容没有哈希)
+    pub fn record_miss(&mut self) {
+        self.stats.misses += 1;
+    }
+
+    /// 保存新生成的向量 (哈希, 向量), 追加写入缓存文件
+    pub fn insert_all(&mut self, model: &str, items: Vec<(String, Vec<f32>)>) -> Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        for (hash, vector) in items {
+            let line = CacheLine {
+                model: model.to_string(),
+                hash,
+                vector,
+            };
+            writeln!(file, "{}", serde_json::to_string(&line)?)?;
+            self.vectors.insert((line.model, line.hash), line.vector);
+        }
+
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_cache_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("gamate_embed_cache_{}", uuid::Uuid::new_v4()));
+        let path = dir.join(CACHE_FILE_NAME);
+
+        let mut cache = EmbeddingCache::load(path.clone());
+        assert_eq!(cache.get("model-a", "h1"), None);
+        cache
+            .insert_all(
+                "model-a",
+                vec![
+                    ("h1".to_string(), vec![0.1, 0.2]),
+                    ("h2".to_string(), vec![0.3, 0.4]),
+                ],
+            )
+            .unwrap();
+        assert_eq!(cache.get("model-a", "h1"), Some(vec![0.1, 0.2]));
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // 重新加载后仍可命中, 不同模型不共用缓存
+        let mut reloaded = EmbeddingCache::load(path);
+        assert_eq!(reloaded.get("model-a", "h2"), Some(vec![0.3, 0.4]));
+        assert_eq!(reloaded.get("model-b", "h2"), None);
+        assert_eq!(reloaded.stats(), CacheStats { hits: 1, misses: 1 });
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::settings::VectorDBSettings;
+
+/// 分块时优先断开的位置 (句末标点和空白)
+const BREAK_CHARS: &[char] = &['\n', '。', '！', '？', '；', '.', '!', '?', ';', ' '];
+
+/// 文本分块器
+///
+/// 按字符数把长文本切成相互重叠的窗口, 每个窗口单独生成 embedding,
+/// 避免长页面超出模型输入上限后被截断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextChunker {
+    chunk_size: usize,
+    chunk_overlap: usize,
+}
+
+impl TextChunker {
+    /// 创建分块器 (`chunk_size` 为 0 时不分块, 重叠长度至多为块大小的一半)
+    pub fn new(chunk_size: usize, chunk_overlap: usize) -> Self {
+        Self {
+            chunk_size,
+            chunk_overlap: chunk_overlap.min(chunk_size / 2),
+        }
+    }
+
+    /// 按向量数据库设置创建
+    pub fn from_settings(vdb_config: &VectorDBSettings) -> Self {
+        Self::new(vdb_config.chunk_size, vdb_config.chunk_overlap)
+    }
+
+    /// 切分文本
+    ///
+    /// 窗口末尾优先落在后半段的最后一个句末标点或空白处;
+    /// 不超过块大小的文本原样返回一个块
+    pub fn split(&self, text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        if self.chunk_size == 0 || chars.len() <= self.chunk_size {
+            return vec![text.to_string()];
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        loop {
+            let mut end = (start + self.chunk_size).min(chars.len());
+            if end < chars.len() {
+                let min_end = start + self.chunk_size / 2;
+                if let Some(pos) = chars[min_end..end]
+                    .iter()
+                    .rposition(|c| BREAK_CHARS.contains(c))
+                {
+                    end = min_end + pos + 1;
+                }
+            }
+
+            let chunk: String = chars[start..end].iter().collect();
+            let chunk = chunk.trim();
+            if !chunk.is_empty() {
+                chunks.push(chunk.to_string());
+            }
+
+            if end >= chars.len() {
+                break;
+            }
+            start = (end - self.chunk_overlap).max(start + 1);
+        }
+
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_short_text() {
+        let chunker = TextChunker::new(100, 20);
+        assert_eq!(chunker.split("幽灵猎人"), vec!["幽灵猎人".to_string()]);
+        assert_eq!(chunker.split(""), vec![String::new()]);
+
+        // 块大小为 0 时不分块
+        let long = "鬼".repeat(500);
+        assert_eq!(TextChunker::new(0, 20).split(&long), vec![long.clone()]);
+    }
+
+    #[test]
+    fn test_split_overlapping_windows() {
+        let text: String = ('a'..='z').cycle().take(250).collect();
+        let chunks = TextChunker::new(100, 20).split(&text);
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 100));
+        // 相邻块之间重叠 20 个字符
+        assert_eq!(chunks[0][80..], chunks[1][..20]);
+        assert!(text.ends_with(chunks.last().unwrap().as_str()));
+    }
+
+    #[test]
+    fn test_split_prefers_sentence_breaks() {
+        let sentence = format!("{}。", "鬼".repeat(39));
+        let text = sentence.repeat(5);
+        let chunks = TextChunker::new(100, 0).split(&text);
+
+        assert!(chunks.iter().all(|c| c.ends_with('。')));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn test_overlap_clamped() {
+        let chunker = TextChunker::new(10, 50);
+        assert_eq!(chunker.chunk_overlap, 5);
+
+        // 重叠过大时仍然保证前进
+        let chunks = chunker.split(&"鬼".repeat(40));
+        assert_eq!(chunks.len(), 7);
+    }
+}
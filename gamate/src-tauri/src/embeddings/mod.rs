@@ -1,11 +1,68 @@
+mod cache;
+mod chunker;
+
+use crate::settings::ModelConfig;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+pub use cache::{CacheStats, EmbeddingCache, CACHE_FILE_NAME};
+pub use chunker::TextChunker;
+
+/// 单个文本的最大字符数 (超出部分截断, 避免超过模型上下文导致整批失败)
+pub const MAX_EMBED_CHARS: usize = 6000;
+
+/// 探测向量维度时使用的文本
+const DIMENSION_PROBE: &str = "dimension probe";
+
+/// 未知模型的默认维度
+const DEFAULT_DIMENSION: usize = 768;
+
+/// 容错批量 embedding 的结果
+#[derive(Debug, Default)]
+pub struct PartialEmbeddings {
+    /// 与输入一一对应, 跳过的文本为 None
+    pub vectors: Vec<Option<Vec<f32>>>,
+    /// 被跳过的文本 (输入下标, 原因)
+    pub skipped: Vec<(usize, String)>,
+    /// 被截断的文本数
+    pub truncated: usize,
+}
+
+/// Embedding 接口格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmbeddingApi {
+    /// OpenAI 兼容接口: POST {base}/embeddings, 一次请求多条 input
+    OpenAI,
+    /// Ollama 原生接口: POST {base}/api/embeddings, 每次请求一条 prompt
+    OllamaNative,
+}
+
+impl EmbeddingApi {
+    /// 根据提供商和 API 地址判断接口格式
+    ///
+    /// - `ollama`: 总是使用原生接口
+    /// - `local`: 地址以 `/v1` 结尾时使用 Ollama 的 OpenAI 兼容接口, 否则使用原生接口
+    fn detect(provider: &str, api_base: &str) -> Self {
+        match provider {
+            "ollama" => Self::OllamaNative,
+            "local" if !api_base.trim_end_matches('/').ends_with("/v1") => Self::OllamaNative,
+            _ => Self::OpenAI,
+        }
+    }
+}
 
-/// Embedding 服务 - 使用 OpenAI API
+/// Embedding 服务 - 使用 OpenAI 兼容 API 或 Ollama 原生 API
 pub struct EmbeddingService {
     api_key: String,
     api_base: String,
     model: String,
+    api: EmbeddingApi,
+    /// 探测到的向量维度 (只探测一次)
+    detected_dimension: OnceLock<usize>,
+    /// 按内容哈希缓存的向量 (未启用时为 None)
+    cache: Option<Mutex<EmbeddingCache>>,
 }
 
 #[derive(Serialize)]
@@ -24,6 +81,19 @@ struct EmbeddingData {
     embedding: Vec<f32>,
 }
 
+/// Ollama 原生 embedding 请求
+#[derive(Serialize)]
+struct OllamaEmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+/// Ollama 原生 embedding 响应
+#[derive(Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
 impl EmbeddingService {
     /// 创建新的 Embedding 服务
     ///
@@ -52,9 +122,49 @@ impl EmbeddingService {
             api_key,
             api_base,
             model,
+            api: EmbeddingApi::OpenAI,
+            detected_dimension: OnceLock::new(),
+            cache: None,
         })
     }
 
+    /// 按模型配置创建 Embedding 服务 (根据提供商选择 OpenAI 兼容接口或 Ollama 原生接口)
+    pub async fn from_config(config: &ModelConfig) -> Result<Self> {
+        let mut service = Self::new(
+            config.api_base.clone(),
+            config.api_key.clone(),
+            config.model_name.clone(),
+        )
+        .await?;
+
+        service.api = EmbeddingApi::detect(&config.provider, &config.api_base);
+        if service.api == EmbeddingApi::OllamaNative {
+            // 原生接口不使用 /v1 前缀
+            let base = service.api_base.trim_end_matches('/');
+            service.api_base = base.strip_suffix("/v1").unwrap_or(base).to_string();
+            log::info!(
+                "🦙 使用 Ollama 原生 embedding 接口: {}/api/embeddings",
+                service.api_base
+            );
+        }
+
+        Ok(service)
+    }
+
+    /// 启用 Embedding 缓存 (缓存文件不存在时自动创建)
+    pub fn with_cache(mut self, path: PathBuf) -> Self {
+        self.cache = Some(Mutex::new(EmbeddingCache::load(path)));
+        self
+    }
+
+    /// 缓存命中统计 (未启用缓存时为 0)
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache
+            .as_ref()
+            .map(|cache| cache.lock().unwrap().stats())
+            .unwrap_or_default()
+    }
+
     /// 生成单个文本的嵌入向量
     pub async fn embed_text(&self, text: &str) -> Result<Vec<f32>> {
         let vectors = self.embed_batch(vec![text]).await?;
@@ -69,6 +179,9 @@ impl EmbeddingService {
         if texts.is_empty() {
             return Ok(vec![]);
         }
+        if self.api == EmbeddingApi::OllamaNative {
+            return self.embed_batch_ollama(&texts).await;
+        }
 
         log::info!("📝 批量生成 {} 个文本的 embedding...", texts.len());
         log::info!("📡 请求 URL: {}/embeddings", self.api_base);
@@ -116,21 +229,324 @@ impl EmbeddingService {
         Ok(embeddings)
     }
 
-    /// 获取向量维度
+    /// 使用 Ollama 原生接口逐条生成嵌入向量 (原生接口不支持批量)
+    async fn embed_batch_ollama(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        log::info!("📝 逐条生成 {} 个文本的 embedding (Ollama)...", texts.len());
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/embeddings", self.api_base);
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for text in texts {
+            let request = OllamaEmbeddingRequest {
+                model: &self.model,
+                prompt: text,
+            };
+            let response = client.post(&url).json(&request).send().await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await?;
+                anyhow::bail!("Ollama Embedding 请求失败 ({}): {}", status, error_text);
+            }
+
+            let response: OllamaEmbeddingResponse = response.json().await?;
+            if response.embedding.is_empty() {
+                anyhow::bail!("Ollama 返回了空向量 (模型: {})", self.model);
+            }
+            embeddings.push(response.embedding);
+        }
+
+        log::info!("✅ 批量 embedding 完成");
+
+        Ok(embeddings)
+    }
+
+    /// 容错批量生成嵌入向量
+    ///
+    /// - 空文本直接跳过, 超长文本截断到 `MAX_EMBED_CHARS`
+    /// - 整批请求失败时逐条重试, 单条失败只跳过该条
+    /// - 所有文本都失败时返回错误 (通常是服务不可用)
+    pub async fn embed_batch_partial(&self, texts: &[&str]) -> Result<PartialEmbeddings> {
+        let mut result = PartialEmbeddings {
+            vectors: vec![None; texts.len()],
+            ..Default::default()
+        };
+
+        let mut pending: Vec<(usize, &str)> = Vec::new();
+        for (index, text) in texts.iter().enumerate() {
+            match prepare_text(text) {
+                Some((prepared, truncated)) => {
+                    if truncated {
+                        result.truncated += 1;
+                    }
+                    pending.push((index, prepared));
+                }
+                None => result.skipped.push((index, "内容为空".to_string())),
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(result);
+        }
+
+        let batch: Vec<&str> = pending.iter().map(|(_, text)| *text).collect();
+        match self.embed_batch(batch).await {
+            Ok(vectors) if vectors.len() == pending.len() => {
+                for ((index, _), vector) in pending.into_iter().zip(vectors) {
+                    result.vectors[index] = Some(vector);
+                }
+            }
+            outcome => {
+                match outcome {
+                    Ok(vectors) => log::warn!(
+                        "⚠️ Embedding 返回数量不匹配 (期望 {}, 实际 {}), 改为逐条生成",
+                        pending.len(),
+                        vectors.len()
+                    ),
+                    Err(e) => log::warn!("⚠️ 批量 embedding 失败, 改为逐条生成: {}", e),
+                }
+
+                let mut last_error = None;
+                let mut succeeded = 0;
+                for (index, text) in pending {
+                    match self.embed_text(text).await {
+                        Ok(vector) => {
+                            result.vectors[index] = Some(vector);
+                            succeeded += 1;
+                        }
+                        Err(e) => {
+                            result.skipped.push((index, e.to_string()));
+                            last_error = Some(e);
+                        }
+                    }
+                }
+
+                if succeeded == 0 {
+                    if let Some(e) = last_error {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        result.skipped.sort_by_key(|(index, _)| *index);
+        Ok(result)
+    }
+
+    /// 带缓存的容错批量生成 (`items` 为 (内容哈希, 文本))
+    ///
+    /// 先按 (模型, 哈希) 查缓存, 只有未命中的文本调用 API, 新向量写回缓存。
+    /// 哈希为空的文本不使用缓存; 未启用缓存时等同于 `embed_batch_partial`
+    pub async fn embed_batch_cached(&self, items: &[(&str, &str)]) -> Result<PartialEmbeddings> {
+        let Some(cache) = &self.cache else {
+            let texts: Vec<&str> = items.iter().map(|(_, text)| *text).collect();
+            return self.embed_batch_partial(&texts).await;
+        };
+
+        let mut result = PartialEmbeddings {
+            vectors: vec![None; items.len()],
+            ..Default::default()
+        };
+        let mut missing: Vec<usize> = Vec::new();
+        {
+            let mut cache = cache.lock().unwrap();
+            for (index, (hash, _)) in items.iter().enumerate() {
+                let cached = if hash.is_empty() {
+                    cache.record_miss();
+                    None
+                } else {
+                    cache.get(&self.model, hash)
+                };
+                match cached {
+                    Some(vector) => result.vectors[index] = Some(vector),
+                    None => missing.push(index),
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(result);
+        }
+
+        let texts: Vec<&str> = missing.iter().map(|&i| items[i].1).collect();
+        let generated = self.embed_batch_partial(&texts).await?;
+        result.truncated = generated.truncated;
+        result.skipped = generated
+            .skipped
+            .into_iter()
+            .map(|(i, reason)| (missing[i], reason))
+            .collect();
+
+        let mut new_vectors = Vec::new();
+        for (i, vector) in generated.vectors.into_iter().enumerate() {
+            let index = missing[i];
+            if let Some(vector) = vector {
+                let hash = items[index].0;
+                if !hash.is_empty() {
+                    new_vectors.push((hash.to_string(), vector.clone()));
+                }
+                result.vectors[index] = Some(vector);
+            }
+        }
+
+        // 写缓存失败不影响导入
+        if let Err(e) = cache.lock().unwrap().insert_all(&self.model, new_vectors) {
+            log::warn!("⚠️ 写入 Embedding 缓存失败: {}", e);
+        }
+
+        Ok(result)
+    }
+
+    /// 获取向量维度 (已探测时使用探测结果, 否则按模型预设, 未知模型为 768)
     pub fn dimension(&self) -> usize {
+        if let Some(&dimension) = self.detected_dimension.get() {
+            return dimension;
+        }
+
+        self.known_dimension().unwrap_or_else(|| {
+            log::warn!(
+                "⚠️  未知模型 '{}', 使用默认维度 {}",
+                self.model,
+                DEFAULT_DIMENSION
+            );
+            DEFAULT_DIMENSION
+        })
+    }
+
+    /// 模型的预设维度 (不在预设表中时为 None)
+    pub fn known_dimension(&self) -> Option<usize> {
         // 根据模型返回对应的维度
         match self.model.as_str() {
-            "text-embedding-3-small" => 1536,
-            "text-embedding-3-large" => 3072,
-            "text-embedding-ada-002" => 1536,
-            "nomic-embed-text" => 768, // Ollama nomic-embed-text 实际维度
-            "mxbai-embed-large" => 1024, // Ollama mxbai 模型
-            "qwen3-embedding:4b" => 2560, // Qwen3 embedding 模型
-            "all-minilm" => 384,
-            _ => {
-                log::warn!("⚠️  未知模型 '{}', 使用默认维度 768", self.model);
-                768
-            }
+            "text-embedding-3-small" => Some(1536),
+            "text-embedding-3-large" => Some(3072),
+            "text-embedding-ada-002" => Some(1536),
+            "nomic-embed-text" => Some(768), // Ollama nomic-embed-text 实际维度
+            "mxbai-embed-large" => Some(1024), // Ollama mxbai 模型
+            "qwen3-embedding:4b" => Some(2560), // Qwen3 embedding 模型
+            "all-minilm" => Some(384),
+            _ => None,
+        }
+    }
+
+    /// 调用一次 API 探测实际向量维度 (结果缓存在服务中)
+    pub async fn detect_dimension(&self) -> Result<usize> {
+        if let Some(&dimension) = self.detected_dimension.get() {
+            return Ok(dimension);
+        }
+
+        let dimension = self.embed_text(DIMENSION_PROBE).await?.len();
+        if dimension == 0 {
+            anyhow::bail!("Embedding 模型 '{}' 返回了空向量", self.model);
+        }
+
+        match self.known_dimension() {
+            Some(known) if known != dimension => log::warn!(
+                "⚠️  模型 '{}' 实际维度 {} 与预设维度 {} 不同, 使用实际维度",
+                self.model,
+                dimension,
+                known
+            ),
+            Some(_) => {}
+            None => log::info!(
+                "📐 模型 '{}' 不在预设表中, 探测到维度 {} (替代默认的 {})",
+                self.model,
+                dimension,
+                DEFAULT_DIMENSION
+            ),
+        }
+
+        Ok(*self.detected_dimension.get_or_init(|| dimension))
+    }
+
+    /// 创建集合时使用的维度: 预设表中的模型直接使用预设值, 其他模型调用 API 探测
+    pub async fn resolve_dimension(&self) -> Result<usize> {
+        match self.known_dimension() {
+            Some(dimension) => Ok(dimension),
+            None => self.detect_dimension().await,
         }
     }
 }
+
+/// 预处理待 embedding 的文本: 空文本返回 None, 超长文本截断
+///
+/// 返回 (文本, 是否被截断)
+fn prepare_text(text: &str) -> Option<(&str, bool)> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    match text.char_indices().nth(MAX_EMBED_CHARS) {
+        Some((end, _)) => Some((&text[..end], true)),
+        None => Some((text, false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_text() {
+        assert_eq!(prepare_text("  \n "), None);
+        assert_eq!(prepare_text(" 幽灵 "), Some(("幽灵", false)));
+
+        let long = "鬼".repeat(MAX_EMBED_CHARS + 10);
+        let (prepared, truncated) = prepare_text(&long).unwrap();
+        assert!(truncated);
+        assert_eq!(prepared.chars().count(), MAX_EMBED_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_api_detect() {
+        assert_eq!(
+            EmbeddingApi::detect("local", "http://localhost:11434/v1"),
+            EmbeddingApi::OpenAI
+        );
+        assert_eq!(
+            EmbeddingApi::detect("local", "http://localhost:11434/"),
+            EmbeddingApi::OllamaNative
+        );
+        assert_eq!(
+            EmbeddingApi::detect("openai", "https://api.openai.com/v1"),
+            EmbeddingApi::OpenAI
+        );
+
+        // ollama 提供商总是使用原生接口, 并去掉 /v1
+        let config = ModelConfig {
+            provider: "ollama".to_string(),
+            api_base: "http://localhost:11434/v1/".to_string(),
+            api_key: None,
+            model_name: "nomic-embed-text".to_string(),
+            enabled: true,
+            temperature: 0.0,
+            max_tokens: 512,
+        };
+        let service = EmbeddingService::from_config(&config).await.unwrap();
+        assert_eq!(service.api, EmbeddingApi::OllamaNative);
+        assert_eq!(service.api_base, "http://localhost:11434");
+    }
+
+    #[tokio::test]
+    async fn test_dimension_fast_path() {
+        let service = |model: &str| {
+            EmbeddingService::new("http://localhost:1".to_string(), None, model.to_string())
+        };
+
+        // 预设表中的模型不请求 API
+        let known = service("all-minilm").await.unwrap();
+        assert_eq!(known.known_dimension(), Some(384));
+        assert_eq!(known.resolve_dimension().await.unwrap(), 384);
+
+        // 未知模型需要探测, 探测失败时返回错误而不是猜测 768
+        let unknown = service("my-custom-embedder").await.unwrap();
+        assert_eq!(unknown.known_dimension(), None);
+        assert!(unknown.resolve_dimension().await.is_err());
+
+        // 探测结果缓存后优先使用
+        unknown.detected_dimension.set(1024).unwrap();
+        assert_eq!(unknown.dimension(), 1024);
+        assert_eq!(unknown.resolve_dimension().await.unwrap(), 1024);
+    }
+}
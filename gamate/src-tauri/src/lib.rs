@@ -1,11 +1,16 @@
 mod aliyun_voice_service;
+mod api_server; // 本地 HTTP API
 mod audio;
 mod commands;
 mod config;
+mod conversation; // 对话历史记录
 mod crawler;
 mod embeddings;
+mod library_updater; // 技能库自动更新
 mod livestream; // 新增直播间功能
 mod llm;
+mod log_buffer; // 内存日志缓冲
+mod ollama_keep_alive; // Ollama 模型常驻
 mod personality;
 mod rag;
 mod screenshot;
@@ -28,8 +33,8 @@ use crate::steam_wiki_commands::get_steam_game_wiki_configs;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // 初始化日志
-    env_logger::init();
+    // 初始化日志 (同时写入内存缓冲, 供界面日志面板使用)
+    log_buffer::init();
 
     // 获取配置目录路径（可执行文件同级的 config 目录）
     let config_dir = settings::AppSettings::config_dir().expect("无法获取配置目录");
@@ -42,6 +47,11 @@ pub fn run() {
 
     log::info!("成功加载 {} 个游戏配置", game_config.games.len());
 
+    // 校验游戏配置 (仅警告, 不阻止启动)
+    for issue in game_config.validate() {
+        log::warn!("⚠️ games.toml 配置问题: {}", issue);
+    }
+
     // 加载应用配置文件 (config/config.toml)
     let app_settings = settings::AppSettings::load().expect("无法加载应用配置");
 
@@ -61,6 +71,10 @@ pub fn run() {
     // 初始化模拟状态
     let simulation_state = simulation_engine_commands::SimulationState::new();
     let smart_capture_state = smart_capture_commands::SmartCaptureState::new();
+    let overlay_state = overlay_commands::OverlayState::new();
+    let focus_mode_state = focus_mode_commands::FocusModeState::new();
+    let api_server_state = api_server_commands::ApiServerState::new();
+    let ai_generation_state = ai_commands::AiGenerationState::default();
 
     // 初始化 Steam 认证状态
     let steam_auth_state = SteamAuthState::default();
@@ -74,9 +88,50 @@ pub fn run() {
         .manage(simulation_state) // 注入模拟状态
         .manage(smart_capture_state) // 注入智能截图状态
         .manage(steam_auth_state) // 注入 Steam 认证状态
+        .manage(overlay_state) // 注入叠加层服务状态
+        .manage(focus_mode_state) // 注入专注模式状态
+        .manage(api_server_state) // 注入 API 服务状态
+        .manage(ai_generation_state) // 注入 AI 回复生成状态
         .setup(|app| {
             // 创建系统托盘
             tray::create_tray(app.handle())?;
+
+            // 推送新日志到前端
+            log_buffer::spawn_event_pump(app.handle().clone());
+
+            // 技能库自动更新 (按设置中的 auto_update 开关执行)
+            library_updater::spawn(app.handle().clone());
+
+            // 本地 Ollama 模型常驻 (按设置中的 ollama_keep_alive 开关执行)
+            ollama_keep_alive::spawn();
+
+            // 按设置自动启动 OBS 叠加层服务
+            if let Ok(settings) = settings::AppSettings::load() {
+                let overlay = settings.simulation.overlay;
+                if overlay.enabled {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        use tauri::Manager;
+                        let state = handle.state::<overlay_commands::OverlayState>();
+                        if let Err(e) = state.start(handle.clone(), overlay.port).await {
+                            log::warn!("⚠️ 叠加层服务启动失败: {}", e);
+                        }
+                    });
+                }
+
+                // 按设置自动启动本地 HTTP API 服务
+                let api_server = settings.api_server;
+                if api_server.enabled {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        use tauri::Manager;
+                        let state = handle.state::<api_server_commands::ApiServerState>();
+                        if let Err(e) = state.start(handle.clone(), api_server.port).await {
+                            log::warn!("⚠️ API 服务启动失败: {}", e);
+                        }
+                    });
+                }
+            }
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -90,17 +145,25 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             download_wiki,
+            download_wikis,
+            coverage_report,
             update_skill_library,
             open_folder,
             delete_skill_library,
             validate_skill_library,
             get_folder_size,
             get_games_config,
+            validate_games_config,
+            cache_game_image,
             // 截图命令
             list_displays,
             capture_fullscreen,
             capture_area,
             refresh_displays,
+            set_capture_display,
+            set_subtitle_region,
+            capture_and_ocr_region,
+            capture_and_analyze,
             // 窗口捕获命令
             list_windows_command,
             capture_window_command,
@@ -108,19 +171,30 @@ pub fn run() {
             // 向量数据库命令
             import_wiki_to_vector_db,
             search_wiki,
+            entry_neighbors,
             get_vector_db_stats,
+            knowledge_base_size,
+            total_knowledge_base_size,
             check_game_vector_db,
             list_imported_games,
+            get_games_with_status,
             get_latest_wiki_jsonl,
             auto_import_latest_wiki,
+            verify_crawl_output,
+            import_external_jsonl,
+            migrate_vector_db,
             // 设置命令
             get_app_settings,
             save_app_settings,
             reset_app_settings,
             // 向量数据库测试命令
             test_vector_db_connection,
+            run_self_test,
             // AI 命令
             generate_ai_response,
+            generate_ai_response_stream,
+            cancel_ai_response,
+            preload_model,
             // AI 助手命令
             start_ai_assistant,
             stop_ai_assistant,
@@ -128,23 +202,42 @@ pub fn run() {
             // TTS 命令
             speak_text,
             stop_speaking,
+            begin_tts_playback,
+            end_tts_playback,
             set_tts_rate,
             set_tts_volume,
             get_tts_voices,
             set_tts_voice,
             apply_personality_voice,
+            // 角色命令
+            list_personalities,
+            save_personality,
+            delete_personality,
+            test_tts_output,
             // 音频命令
             start_continuous_listening,
             stop_continuous_listening,
+            pause_listening,
+            resume_listening,
             get_listener_state,
             test_microphone,
             start_microphone_test,
             stop_microphone_test,
+            get_audio_device_info,
+            list_audio_input_devices,
+            list_output_devices,
+            // 对话历史命令
+            get_conversation_history,
+            search_conversations,
+            clear_conversation_history,
             // 阿里云语音服务命令
             aliyun_voice_service::aliyun_get_token,
             aliyun_voice_service::aliyun_get_cached_token,
             aliyun_voice_service::aliyun_test_connection,
             aliyun_voice_service::aliyun_one_sentence_recognize,
+            aliyun_voice_service::aliyun_realtime_transcribe_start,
+            aliyun_voice_service::aliyun_realtime_transcribe_push,
+            aliyun_voice_service::aliyun_realtime_transcribe_stop,
             aliyun_voice_service::aliyun_tts_synthesize,
             // HUD 浮窗命令
             open_hud_window,
@@ -159,6 +252,9 @@ pub fn run() {
             // 技能库管理命令
             scan_downloaded_libraries,
             sync_libraries_to_config,
+            get_crawl_history,
+            diff_crawl_versions,
+            rollback_skill_library,
             // 模拟场景命令
             save_simulation_config,
             load_simulation_config,
@@ -166,11 +262,28 @@ pub fn run() {
             start_livestream_simulation,
             stop_livestream_simulation,
             is_simulation_running,
+            reload_simulation_config,
+            employee_send,
             streamer_speak,
             // 智能截图命令
             start_smart_capture,
             stop_smart_capture,
+            reset_smart_capture,
             get_smart_capture_status,
+            // 专注模式命令
+            pause_all_activity,
+            resume_all_activity,
+            get_focus_mode_status,
+            // 日志命令
+            get_recent_logs,
+            // OBS 叠加层命令
+            start_overlay_server,
+            stop_overlay_server,
+            get_overlay_status,
+            // 本地 HTTP API 命令
+            start_api_server,
+            stop_api_server,
+            get_api_server_status,
             // AI 分析命令
             trigger_ai_analysis,
             // Steam 登录命令
@@ -181,6 +294,9 @@ pub fn run() {
             load_steam_user_from_config,
             fetch_steam_library,
             fetch_recently_played_games,
+            suggest_games_for_setup,
+            match_steam_library_games,
+            add_selected_games,
             get_cached_steam_library,
             get_steam_library_paginated,
             steam_logout,
@@ -189,6 +305,8 @@ pub fn run() {
             get_steam_game_wiki_configs,
             // Steam 游戏配置管理
             save_steam_games_to_config,
+            search_steam_games,
+            refresh_steam_app_list,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,138 @@
+/// 技能库自动更新
+///
+/// 开启 `skill_library.auto_update` 后, 每隔 `update_check_interval` 小时检查一次已选择游戏的 Wiki 来源;
+/// 来源有变化时重新爬取, 若该游戏已导入向量数据库则同时重新导入, 完成后发送 `library_updated` 事件
+use crate::commands::vector_commands::find_latest_crawl_timestamp;
+use crate::commands::wiki_commands::download_skill_library;
+use crate::commands::{auto_import_latest_wiki, check_game_vector_db};
+use crate::config::Config;
+use crate::crawler::update_check::source_changed_since;
+use crate::settings::AppSettings;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 启动后首次检查前的等待时间
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+/// 轮询设置的间隔 (开关和检查间隔可随时修改)
+const POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 暂停自动更新 (专注模式)
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// 暂停或恢复自动更新 (不修改设置中的开关)
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// 启动后台自动更新任务
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+
+        let mut last_check: Option<Instant> = None;
+        loop {
+            if let Ok(settings) = AppSettings::load() {
+                let library = &settings.skill_library;
+                let interval =
+                    Duration::from_secs(library.update_check_interval.max(1) as u64 * 3600);
+
+                if library.auto_update
+                    && !PAUSED.load(Ordering::Relaxed)
+                    && last_check.is_none_or(|t| t.elapsed() >= interval)
+                {
+                    last_check = Some(Instant::now());
+                    check_for_updates(&app, &settings, interval).await;
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// 检查所有已选择游戏的技能库
+async fn check_for_updates(app: &AppHandle, settings: &AppSettings, max_age: Duration) {
+    log::info!("🔄 检查技能库更新...");
+
+    let config = app.state::<Config>();
+    let base_path = PathBuf::from(&settings.skill_library.storage_base_path);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    for game_id in &settings.user.selected_games {
+        let Some(skill) = config
+            .find_game(game_id)
+            .and_then(|game| game.skill_configs.first())
+        else {
+            continue;
+        };
+
+        // 只更新已经下载过的技能库
+        let game_dir = base_path.join(game_id);
+        let Ok(Some(latest)) = find_latest_crawl_timestamp(&game_dir) else {
+            continue;
+        };
+
+        if now.saturating_sub(latest) < max_age.as_secs() {
+            continue;
+        }
+
+        match source_changed_since(&skill.source_type, &skill.repo, latest).await {
+            Ok(true) => {}
+            Ok(false) => {
+                log::info!("✅ 技能库无变化: {}", game_id);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("⚠️ 检查技能库更新失败: {} - {}", game_id, e);
+                continue;
+            }
+        }
+
+        log::info!("⬇️ 技能库来源有更新, 重新爬取: {}", game_id);
+
+        let result = download_skill_library(game_id, skill, &base_path, now).await;
+
+        let crawl = match result {
+            Ok(crawl) if crawl.total_entries > 0 => crawl,
+            Ok(_) => {
+                log::warn!("⚠️ 重新爬取未获得任何条目: {}", game_id);
+                continue;
+            }
+            Err(e) => {
+                log::error!("❌ 重新爬取失败: {} - {}", game_id, e);
+                continue;
+            }
+        };
+
+        // 已导入的游戏同步更新向量数据库
+        let mut imported = false;
+        if check_game_vector_db(game_id.clone()).await.unwrap_or(false) {
+            match auto_import_latest_wiki(game_id.clone()).await {
+                Ok(_) => imported = true,
+                Err(e) => log::error!("❌ 重新导入失败: {} - {}", game_id, e),
+            }
+        }
+
+        log::info!(
+            "🎉 技能库已更新: {} ({} 条目, 已导入: {})",
+            game_id,
+            crawl.total_entries,
+            imported
+        );
+
+        let _ = app.emit(
+            "library_updated",
+            serde_json::json!({
+                "gameId": game_id,
+                "storagePath": crawl.storage_path,
+                "totalEntries": crawl.total_entries,
+                "imported": imported,
+            }),
+        );
+    }
+}
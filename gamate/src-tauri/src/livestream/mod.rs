@@ -1,4 +1,6 @@
 /// 直播间功能模块
+pub mod overlay_server;
 pub mod smart_capture;
 
+pub use overlay_server::OverlayServer;
 pub use smart_capture::{SmartCaptureConfig, SmartCaptureEvent, SmartCaptureManager, VadConfigDto};
@@ -0,0 +1,183 @@
+/// OBS 叠加层服务
+///
+/// 在本地启动 HTTP + WebSocket 服务, 将 `simulation_event` 以 JSON 推送给 OBS 浏览器源:
+/// - `http://127.0.0.1:{port}/` 返回内置的弹幕叠加层页面
+/// - `ws://127.0.0.1:{port}/` 订阅事件流 (每条消息为一个 SimulationEvent)
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use tauri::{AppHandle, EventId, Listener};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+
+/// 叠加层服务
+pub struct OverlayServer {
+    app: AppHandle,
+    port: u16,
+    listener_id: EventId,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl OverlayServer {
+    /// 启动服务
+    pub async fn start(app: AppHandle, port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| format!("无法监听端口 {}", port))?;
+
+        let (event_tx, _) = broadcast::channel::<String>(256);
+
+        // 订阅后端发出的模拟事件
+        let tx = event_tx.clone();
+        let listener_id = app.listen_any("simulation_event", move |event| {
+            let _ = tx.send(event.payload().to_string());
+        });
+
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, addr)) => {
+                            log::debug!("📺 叠加层连接: {}", addr);
+                            let rx = event_tx.subscribe();
+                            tauri::async_runtime::spawn(async move {
+                                if let Err(e) = handle_connection(stream, rx).await {
+                                    log::debug!("叠加层连接结束: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => log::warn!("⚠️ 叠加层接受连接失败: {}", e),
+                    },
+                }
+            }
+            log::info!("⏹️ 叠加层服务已停止");
+        });
+
+        log::info!("📺 叠加层服务已启动: http://127.0.0.1:{}/", port);
+
+        Ok(Self {
+            app,
+            port,
+            listener_id,
+            shutdown_tx: Some(shutdown_tx),
+        })
+    }
+
+    /// 停止服务
+    pub fn stop(mut self) {
+        self.app.unlisten(self.listener_id);
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+/// 处理单个连接: WebSocket 升级请求推送事件, 普通 HTTP 请求返回叠加层页面
+async fn handle_connection(
+    mut stream: TcpStream,
+    mut rx: broadcast::Receiver<String>,
+) -> Result<()> {
+    let mut peek_buf = [0u8; 2048];
+    let n = stream.peek(&mut peek_buf).await?;
+    let request = String::from_utf8_lossy(&peek_buf[..n]).to_lowercase();
+
+    if !request.contains("upgrade: websocket") {
+        // 读掉请求头后返回页面
+        let mut buf = [0u8; 2048];
+        let _ = stream.read(&mut buf).await?;
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            OVERLAY_HTML.len(),
+            OVERLAY_HTML
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        return Ok(());
+    }
+
+    let ws = tokio_tungstenite::accept_async(stream).await?;
+    let (mut sink, mut source) = ws.split();
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Ok(payload) => sink.send(Message::Text(payload)).await?,
+                // 客户端处理过慢时丢弃积压事件
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Err(e)) => return Err(e.into()),
+                _ => {}
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// 内置叠加层页面 (透明背景, 适合 OBS 浏览器源)
+const OVERLAY_HTML: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+  body { margin: 0; background: transparent; font-family: "Microsoft YaHei", sans-serif; overflow: hidden; }
+  #chat { position: absolute; bottom: 0; left: 0; right: 0; padding: 12px; }
+  .msg { display: flex; align-items: center; margin: 6px 0; padding: 6px 10px; border-radius: 8px;
+         background: rgba(0, 0, 0, 0.45); color: #fff; font-size: 18px; animation: fade 0.3s; }
+  .msg img { width: 28px; height: 28px; border-radius: 50%; margin-right: 8px; }
+  .name { font-weight: bold; margin-right: 6px; }
+  .gift { color: #FFD93D; }
+  @keyframes fade { from { opacity: 0; transform: translateY(10px); } to { opacity: 1; } }
+</style>
+</head>
+<body>
+<div id="chat"></div>
+<script>
+  const chat = document.getElementById("chat");
+  function render(event) {
+    const e = event.event_type;
+    const row = document.createElement("div");
+    row.className = "msg";
+    if (e.avatar) {
+      const img = document.createElement("img");
+      img.src = e.avatar;
+      row.appendChild(img);
+    }
+    const name = document.createElement("span");
+    name.className = "name";
+    name.style.color = e.color || "#4D96FF";
+    name.textContent = e.nickname + ":";
+    row.appendChild(name);
+    const text = document.createElement("span");
+    if (e.type === "gift") {
+      text.className = "gift";
+      text.textContent = "送出 " + e.gift_name + " x" + e.count;
+    } else {
+      text.textContent = e.message;
+    }
+    row.appendChild(text);
+    chat.appendChild(row);
+    while (chat.children.length > 12) chat.removeChild(chat.firstChild);
+  }
+  function connect() {
+    const ws = new WebSocket("ws://" + location.host + "/");
+    ws.onmessage = (msg) => render(JSON.parse(msg.data));
+    ws.onclose = () => setTimeout(connect, 2000);
+  }
+  connect();
+</script>
+</body>
+</html>
+"##;
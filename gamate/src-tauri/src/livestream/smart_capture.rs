@@ -13,16 +13,21 @@
 /// - 最长语音：60秒（支持连续讲解）
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use crate::audio::{
-    continuous_listener::ContinuousListener, recorder::RecorderConfig, vad::VadConfig,
+    continuous_listener::{ContinuousListener, SttBackend},
+    echo_gate,
+    recorder::RecorderConfig,
+    vad::{VadConfig, DEFAULT_PRE_ROLL_MS, DEFAULT_THRESHOLD_MULTIPLIER},
 };
 use crate::commands::screen_commands::ScreenshotState;
 use crate::screenshot::Screenshot;
+use crate::settings::AppSettings;
 
 /// 智能截图事件
 #[derive(Debug, Clone, Serialize)]
@@ -89,6 +94,23 @@ pub struct VadConfigDto {
     pub silence_duration_secs: f32,
     pub min_speech_duration_secs: f32,
     pub max_speech_duration_secs: f32,
+    /// 是否根据背景噪声 (如游戏声音) 自动调整阈值
+    #[serde(default)]
+    pub adaptive_threshold: bool,
+    /// 自适应阈值倍数
+    #[serde(default = "default_threshold_multiplier")]
+    pub threshold_multiplier: f32,
+    /// 语音前导缓冲时长(毫秒)
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+}
+
+fn default_threshold_multiplier() -> f32 {
+    DEFAULT_THRESHOLD_MULTIPLIER
+}
+
+fn default_pre_roll_ms() -> u32 {
+    DEFAULT_PRE_ROLL_MS
 }
 
 impl VadConfigDto {
@@ -99,6 +121,9 @@ impl VadConfigDto {
             silence_duration_secs: 2.5,     // 允许主播思考暂停
             min_speech_duration_secs: 0.5,  // 过滤短促噪音
             max_speech_duration_secs: 60.0, // 支持连续讲解
+            adaptive_threshold: false,
+            threshold_multiplier: DEFAULT_THRESHOLD_MULTIPLIER,
+            pre_roll_ms: DEFAULT_PRE_ROLL_MS,
         }
     }
 }
@@ -111,10 +136,30 @@ impl From<VadConfigDto> for VadConfig {
             min_speech_duration_secs: dto.min_speech_duration_secs,
             max_speech_duration_secs: dto.max_speech_duration_secs,
             rms_window_size: 1024, // 固定值
+            adaptive_threshold: dto.adaptive_threshold,
+            threshold_multiplier: dto.threshold_multiplier,
+            pre_roll_ms: dto.pre_roll_ms,
         }
     }
 }
 
+/// 强制停止句柄
+///
+/// 在管理器被正常停止流程占用 (例如卡在停止监听器) 时, 仍可用于中止监听并重置运行状态
+pub struct SmartCaptureAbortHandle {
+    is_running: Arc<Mutex<bool>>,
+    listener_cancel: Option<Arc<AtomicBool>>,
+}
+
+impl SmartCaptureAbortHandle {
+    pub fn abort(&self) {
+        if let Some(cancel) = &self.listener_cancel {
+            cancel.store(true, Ordering::SeqCst);
+        }
+        *self.is_running.lock().unwrap_or_else(|e| e.into_inner()) = false;
+    }
+}
+
 /// 智能截图管理器
 pub struct SmartCaptureManager {
     app: AppHandle,
@@ -166,10 +211,19 @@ impl SmartCaptureManager {
             self.config.vad_config.max_speech_duration_secs
         );
 
-        // 创建语音监听器
+        // 创建语音监听器 (使用设置中选择的语音识别后端)
         let vad_config: VadConfig = self.config.vad_config.clone().into();
-        let recorder_config = RecorderConfig::default();
-        let mut listener = ContinuousListener::new(vad_config, recorder_config);
+        let recorder_config = RecorderConfig::from_settings();
+        let mut listener = ContinuousListener::new(vad_config, recorder_config)
+            .with_stt_backend(SttBackend::from_settings());
+
+        // 模拟直播间的 TTS 播报期间忽略麦克风输入, 避免识别到 AI 自己的声音
+        let echo_suppression = AppSettings::load()
+            .map(|settings| settings.tts.echo_suppression)
+            .unwrap_or(true);
+        if echo_suppression {
+            listener = listener.with_echo_gate(echo_gate::tts_playing_flag());
+        }
 
         // 设置事件回调
         let app = self.app.clone();
@@ -222,6 +276,34 @@ impl SmartCaptureManager {
         Ok(())
     }
 
+    /// 强制停止: 中止监听任务并释放录音设备, 不等待最后一段语音识别
+    pub fn force_stop(&mut self) {
+        log::warn!("⏹️ 强制停止直播间智能截图系统");
+
+        *self.is_running.lock().unwrap_or_else(|e| e.into_inner()) = false;
+
+        if let Some(mut listener) = self.listener.take() {
+            listener.abort();
+        }
+
+        if let Some(task) = self.listen_task.take() {
+            task.abort();
+        }
+
+        *self
+            .current_screenshot_start
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// 获取强制停止句柄
+    pub fn abort_handle(&self) -> SmartCaptureAbortHandle {
+        SmartCaptureAbortHandle {
+            is_running: Arc::clone(&self.is_running),
+            listener_cancel: self.listener.as_ref().map(|l| l.cancel_flag()),
+        }
+    }
+
     /// 处理监听器事件
     async fn handle_listener_event(
         app: &AppHandle,
@@ -259,6 +341,8 @@ impl SmartCaptureManager {
                     }
                     Err(e) => {
                         log::error!("❌ 开始截图失败: {}", e);
+                        // 清掉上一句话的截图, 避免分析时用到过期画面 (无截图时走纯文本分析)
+                        screenshot_start_ref.lock().unwrap().take();
                     }
                 }
             }
@@ -318,8 +402,8 @@ impl SmartCaptureManager {
             ListenerEvent::VoiceTranscribed { text } => {
                 log::info!("📝 语音识别完成: {}", text);
 
-                // 这里可以发送包含双截图和识别结果的事件
-                // TODO: 实现完整的事件发送逻辑
+                // 本地识别 (Windows / Whisper) 的结果, 前端带上双截图触发 AI 分析
+                let _ = app.emit("livestream_transcribed", text);
             }
 
             ListenerEvent::Error { message } => {
@@ -343,6 +427,10 @@ impl SmartCaptureManager {
     ) -> Result<Screenshot> {
         // 从 Tauri State 获取 ScreenshotState
         let screenshot_state: tauri::State<ScreenshotState> = app.state();
+        // 全屏截图使用设置中选择的显示器
+        let display_id = AppSettings::load()
+            .ok()
+            .and_then(|s| s.screenshot.capture_display_id);
 
         match config.capture_mode.as_str() {
             "window" => {
@@ -353,14 +441,18 @@ impl SmartCaptureManager {
                 } else {
                     log::warn!("⚠️ 窗口模式但未设置窗口 ID，回退到全屏截图");
                     let capturer = screenshot_state.get_or_init().context("初始化截图器失败")?;
-                    capturer.capture_fullscreen(None).context("全屏截图失败")
+                    capturer
+                        .capture_fullscreen(display_id)
+                        .context("全屏截图失败")
                 }
             }
             "fullscreen" | _ => {
                 // 全屏截图
                 log::debug!("🖥️ 全屏截图");
                 let capturer = screenshot_state.get_or_init().context("初始化截图器失败")?;
-                capturer.capture_fullscreen(None).context("全屏截图失败")
+                capturer
+                    .capture_fullscreen(display_id)
+                    .context("全屏截图失败")
             }
         }
     }
@@ -1,5 +1,9 @@
 pub mod ollama;
 pub mod openai;
+pub mod postprocess;
+pub mod tokens;
 
 pub use ollama::OllamaClient;
-pub use openai::OpenAIClient;
+pub use openai::{is_transient_error, OpenAIClient};
+pub use postprocess::{process_response, FinishReason, ProcessedResponse};
+pub use tokens::{count_tokens, remaining_budget, truncate_to_tokens};
@@ -10,6 +10,9 @@ struct OllamaChatRequest {
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<OllamaOptions>,
+    /// 请求结束后模型在内存中保留的时长 (未设置时使用 Ollama 默认的 5 分钟)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<String>,
 }
 
 /// Ollama 消息
@@ -52,6 +55,7 @@ pub struct OllamaClient {
     base_url: String,
     settings: ModelConfig,
     client: reqwest::Client,
+    keep_alive: Option<String>,
 }
 
 impl OllamaClient {
@@ -72,11 +76,66 @@ impl OllamaClient {
             base_url,
             settings,
             client: reqwest::Client::new(),
+            keep_alive: None,
         })
     }
 
+    /// 设置对话请求的 keep_alive
+    pub fn with_keep_alive(mut self, keep_alive: Option<String>) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// 预加载模型到内存
+    ///
+    /// 发送不带消息的 `/api/chat` 请求, Ollama 只加载模型并按 `keep_alive` 保留, 不做推理
+    pub async fn preload(&self, keep_alive: &str) -> Result<()> {
+        let request = OllamaChatRequest {
+            model: self.settings.model_name.clone(),
+            messages: Vec::new(),
+            stream: false,
+            options: None,
+            keep_alive: Some(keep_alive.to_string()),
+        };
+
+        let url = format!("{}/api/chat", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Ollama 预加载请求失败: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Ollama 预加载返回错误 {}: {}", status, error_text));
+        }
+
+        log::debug!(
+            "🦙 模型 {} 已加载 (keep_alive: {})",
+            self.settings.model_name,
+            keep_alive
+        );
+        Ok(())
+    }
+
     /// 调用 Ollama 模型 (纯文本)
     pub async fn chat(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.chat_stream(system_prompt, user_prompt, |_| {}).await
+    }
+
+    /// 调用 Ollama 模型 (纯文本, 流式)
+    ///
+    /// 每收到一段增量内容就调用 `on_token`, 结束后返回完整回复
+    pub async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        mut on_token: impl FnMut(&str),
+    ) -> Result<String> {
         log::info!("🦙 调用 Ollama API: {}", self.settings.model_name);
 
         let messages = vec![
@@ -95,18 +154,19 @@ impl OllamaClient {
         let request = OllamaChatRequest {
             model: self.settings.model_name.clone(),
             messages,
-            stream: false,
+            stream: true,
             options: Some(OllamaOptions {
                 temperature: self.settings.temperature,
                 num_predict: self.settings.max_tokens as i32,
                 stop: None,
             }),
+            keep_alive: self.keep_alive.clone(),
         };
 
         let url = format!("{}/api/chat", self.base_url);
         log::debug!("📤 请求 URL: {}", url);
 
-        let response = self
+        let mut response = self
             .client
             .post(&url)
             .json(&request)
@@ -120,19 +180,52 @@ impl OllamaClient {
             return Err(anyhow!("Ollama API 返回错误 {}: {}", status, error_text));
         }
 
-        let ollama_response: OllamaChatResponse = response
-            .json()
-            .await
-            .map_err(|e| anyhow!("解析 Ollama 响应失败: {}", e))?;
+        // 流式响应是按行分隔的 JSON, 一个网络分块里可能有半行或多行
+        let mut content = String::new();
+        let mut thinking_len = 0;
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut done = false;
+
+        while !done {
+            let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| anyhow!("读取 Ollama 响应失败: {}", e))?
+            else {
+                break;
+            };
+            buffer.extend_from_slice(&chunk);
+
+            while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                if let Some(part) = parse_stream_line(&line)? {
+                    thinking_len += part.message.thinking.as_ref().map_or(0, |t| t.len());
+                    if !part.message.content.is_empty() {
+                        on_token(&part.message.content);
+                        content.push_str(&part.message.content);
+                    }
+                    if part.done {
+                        done = true;
+                        break;
+                    }
+                }
+            }
+        }
 
-        log::info!("✅ Ollama 响应成功");
+        // 最后一行可能没有换行符
+        if !done {
+            if let Some(part) = parse_stream_line(&buffer)? {
+                if !part.message.content.is_empty() {
+                    on_token(&part.message.content);
+                    content.push_str(&part.message.content);
+                }
+            }
+        }
 
-        // 提取内容: content 是真正的答案
-        let content = ollama_response.message.content;
+        log::info!("✅ Ollama 响应成功");
 
-        // 记录 thinking 信息（如果存在）
-        if let Some(ref thinking) = ollama_response.message.thinking {
-            log::debug!("🧠 模型返回了 thinking 字段: {} bytes", thinking.len());
+        if thinking_len > 0 {
+            log::debug!("🧠 模型返回了 thinking 字段: {} bytes", thinking_len);
         }
 
         if content.is_empty() {
@@ -189,6 +282,7 @@ impl OllamaClient {
                 num_predict: self.settings.max_tokens as i32,
                 stop: None,
             }),
+            keep_alive: self.keep_alive.clone(),
         };
 
         let url = format!("{}/api/chat", self.base_url);
@@ -276,6 +370,28 @@ impl OllamaClient {
     }
 }
 
+/// 解析流式响应中的一行 JSON (空行返回 None)
+fn parse_stream_line(line: &[u8]) -> Result<Option<OllamaChatResponse>> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    match serde_json::from_str::<OllamaChatResponse>(line) {
+        Ok(part) => Ok(Some(part)),
+        Err(e) => {
+            // 生成中途出错时 Ollama 会返回 {"error": "..."}
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
+                if let Some(error) = value.get("error").and_then(|v| v.as_str()) {
+                    return Err(anyhow!("Ollama API 返回错误: {}", error));
+                }
+            }
+            Err(anyhow!("解析 Ollama 响应失败: {}", e))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +399,31 @@ mod tests {
     use std::fs;
     use std::path::Path; // 导入 base64 Engine trait
 
+    #[test]
+    fn test_parse_stream_line() {
+        let part = parse_stream_line(
+            r#"{"model":"qwen3","message":{"role":"assistant","content":"你好"},"done":false}"#
+                .as_bytes(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(part.message.content, "你好");
+        assert!(!part.done);
+
+        let last = parse_stream_line(
+            b"{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(last.done);
+
+        assert!(parse_stream_line(b"  \n").unwrap().is_none());
+        assert!(parse_stream_line(br#"{"error":"model not found"}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("model not found"));
+    }
+
     #[tokio::test]
     #[ignore] // 需要本地 Ollama 服务运行
     async fn test_ollama_chat() {
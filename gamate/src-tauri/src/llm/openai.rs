@@ -1,7 +1,8 @@
 use crate::settings::ModelConfig;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_openai::{
     config::OpenAIConfig,
+    error::OpenAIError,
     types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
         ChatCompletionRequestUserMessageArgs, ChatCompletionRequestUserMessageContent,
@@ -9,6 +10,47 @@ use async_openai::{
     },
     Client,
 };
+use futures::StreamExt;
+
+/// 模型返回了空内容 (通常是服务端临时问题, 可以重试)
+#[derive(Debug, thiserror::Error)]
+#[error("{0} 返回空内容")]
+pub struct EmptyResponseError(&'static str);
+
+/// 包装 API 错误, 保留原始错误以便判断是否为临时错误
+fn api_error(api: &str, error: OpenAIError) -> anyhow::Error {
+    let message = format!("{} 调用失败: {}", api, error);
+    anyhow::Error::new(error).context(message)
+}
+
+/// 是否为值得重试的临时错误: 超时、连接失败、429、5xx 或模型返回空内容
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if cause.is::<EmptyResponseError>() {
+            return true;
+        }
+        match cause.downcast_ref::<OpenAIError>() {
+            Some(OpenAIError::Reqwest(e)) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            // 服务端返回的错误体 (async-openai 按 type / code 区分)
+            Some(OpenAIError::ApiError(e)) => [e.r#type.as_deref(), e.code.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|kind| {
+                    matches!(
+                        kind,
+                        "server_error" | "rate_limit_exceeded" | "overloaded_error"
+                    )
+                }),
+            _ => false,
+        }
+    })
+}
 
 /// OpenAI 客户端
 pub struct OpenAIClient {
@@ -37,6 +79,18 @@ impl OpenAIClient {
 
     /// 调用 GPT 模型 (纯文本)
     pub async fn chat(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.chat_stream(system_prompt, user_prompt, |_| {}).await
+    }
+
+    /// 调用 GPT 模型 (纯文本, 流式)
+    ///
+    /// 每收到一段增量内容就调用 `on_token`, 结束后返回完整回复
+    pub async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        mut on_token: impl FnMut(&str),
+    ) -> Result<String> {
         log::info!("🤖 调用 OpenAI API: {}", self.settings.model_name);
 
         let messages = vec![
@@ -57,25 +111,34 @@ impl OpenAIClient {
             .messages(messages)
             .temperature(self.settings.temperature)
             .max_tokens(self.settings.max_tokens)
+            .stream(true)
             .build()?;
 
-        let response = self
+        let mut stream = self
             .client
             .chat()
-            .create(request)
+            .create_stream(request)
             .await
-            .map_err(|e| anyhow!("OpenAI API 调用失败: {}", e))?;
+            .map_err(|e| api_error("OpenAI API", e))?;
+
+        let mut content = String::new();
+        while let Some(result) = stream.next().await {
+            let response = result.map_err(|e| api_error("OpenAI API", e))?;
+            for choice in response.choices {
+                if let Some(delta) = choice.delta.content {
+                    if !delta.is_empty() {
+                        on_token(&delta);
+                        content.push_str(&delta);
+                    }
+                }
+            }
+        }
 
-        let content = response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow!("OpenAI 返回空内容"))?;
+        if content.is_empty() {
+            return Err(EmptyResponseError("OpenAI").into());
+        }
 
-        log::info!(
-            "✅ OpenAI 响应成功 ({} tokens)",
-            response.usage.map(|u| u.total_tokens).unwrap_or(0)
-        );
+        log::info!("✅ OpenAI 响应成功 ({} bytes)", content.len());
 
         Ok(content)
     }
@@ -133,13 +196,13 @@ impl OpenAIClient {
             .chat()
             .create(request)
             .await
-            .map_err(|e| anyhow!("OpenAI Vision API 调用失败: {}", e))?;
+            .map_err(|e| api_error("OpenAI Vision API", e))?;
 
         let content = response
             .choices
             .first()
             .and_then(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow!("OpenAI Vision 返回空内容"))?;
+            .ok_or(EmptyResponseError("OpenAI Vision"))?;
 
         log::info!(
             "✅ OpenAI Vision 响应成功 ({} tokens)",
@@ -214,13 +277,13 @@ impl OpenAIClient {
             .chat()
             .create(request)
             .await
-            .map_err(|e| anyhow!("OpenAI Multi-Vision API 调用失败: {}", e))?;
+            .map_err(|e| api_error("OpenAI Multi-Vision API", e))?;
 
         let content = response
             .choices
             .first()
             .and_then(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow!("OpenAI Multi-Vision 返回空内容"))?;
+            .ok_or(EmptyResponseError("OpenAI Multi-Vision"))?;
 
         log::info!(
             "✅ OpenAI Multi-Vision 响应成功 ({} tokens)",
@@ -234,6 +297,7 @@ impl OpenAIClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anyhow::anyhow;
 
     #[tokio::test]
     #[ignore] // 需要真实 API Key 才能运行
@@ -256,4 +320,31 @@ mod tests {
         assert!(response.is_ok());
         println!("响应: {}", response.unwrap());
     }
+
+    #[test]
+    fn test_transient_errors() {
+        let api_error = |kind: &str| {
+            let error: async_openai::error::ApiError = serde_json::from_value(serde_json::json!({
+                "message": "error",
+                "type": kind,
+                "param": null,
+                "code": null,
+            }))
+            .unwrap();
+            api_error("OpenAI API", OpenAIError::ApiError(error))
+        };
+
+        assert!(is_transient_error(&api_error("server_error")));
+        assert!(is_transient_error(&api_error("rate_limit_exceeded")));
+        assert!(!is_transient_error(&api_error("invalid_request_error")));
+        assert!(is_transient_error(&EmptyResponseError("OpenAI").into()));
+        assert!(is_transient_error(
+            &anyhow::Error::from(EmptyResponseError("OpenAI")).context("AI API 调用失败")
+        ));
+
+        // 错误信息中包含状态码等字样不影响判断
+        assert!(!is_transient_error(&anyhow!(
+            "invalid_api_key: 500 credits left, timeout"
+        )));
+    }
 }
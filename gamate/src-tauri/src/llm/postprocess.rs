@@ -0,0 +1,227 @@
+/// LLM 回复后处理
+///
+/// 不同的本地/远程模型输出格式差异很大: 有的把整段回复包在 Markdown 代码块里,
+/// 有的返回 `{"answer": "..."}` 这样的 JSON, 有的带上 `Assistant:` 前缀、`<think>` 推理过程
+/// 或聊天模板的结束标记。这里统一清理, 并把模型拒答识别为单独的结果
+use serde::{Deserialize, Serialize};
+
+/// 回复结束原因
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// 正常回复
+    #[default]
+    Stop,
+    /// 模型拒绝回答
+    Refusal,
+    /// 清理后没有内容
+    Empty,
+    /// 模型不可用, 使用了回退回复
+    Fallback,
+}
+
+impl FinishReason {
+    /// 回复是否来自模型 (空回复和回退回复是程序生成的提示文字)
+    pub fn is_model_reply(self) -> bool {
+        matches!(self, FinishReason::Stop | FinishReason::Refusal)
+    }
+}
+
+/// 清理后的回复
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedResponse {
+    pub text: String,
+    pub finish_reason: FinishReason,
+}
+
+/// 聊天模板的特殊标记 (部分本地模型会原样输出)
+const TEMPLATE_TOKENS: [&str; 7] = [
+    "<|im_start|>assistant",
+    "<|im_start|>",
+    "<|im_end|>",
+    "<|assistant|>",
+    "<|eot_id|>",
+    "<|end|>",
+    "</s>",
+];
+
+/// 角色扮演前缀
+const ROLE_PREFIXES: [&str; 6] = ["Assistant:", "assistant:", "AI:", "助手:", "助手：", "AI："];
+
+/// JSON 回复中可能存放正文的字段
+const JSON_TEXT_FIELDS: [&str; 5] = ["content", "answer", "response", "text", "message"];
+
+/// 拒答特征 (只在较短的回复中判断, 避免误伤正常回答中的引用)
+const REFUSAL_PATTERNS: [&str; 12] = [
+    "i can't help with",
+    "i cannot help with",
+    "i can't assist with",
+    "i cannot assist with",
+    "i'm sorry, but i can",
+    "i am unable to",
+    "i'm unable to",
+    "i won't be able to",
+    "抱歉，我无法",
+    "抱歉,我无法",
+    "我无法协助",
+    "我不能提供",
+];
+
+/// 超过该长度的回复不做拒答判断
+const REFUSAL_MAX_CHARS: usize = 300;
+
+/// 清理 LLM 回复
+pub fn process_response(raw: &str) -> ProcessedResponse {
+    let text = strip_think_blocks(raw);
+    let text = strip_template_tokens(&text);
+    let text = strip_role_prefix(&text);
+    let text = unwrap_fence(text);
+    let text = extract_json_text(text).unwrap_or_else(|| text.to_string());
+    let text = text.trim().to_string();
+
+    let finish_reason = if text.is_empty() {
+        FinishReason::Empty
+    } else if is_refusal(&text) {
+        FinishReason::Refusal
+    } else {
+        FinishReason::Stop
+    };
+
+    ProcessedResponse {
+        text,
+        finish_reason,
+    }
+}
+
+/// 去掉推理模型输出的 `<think>...</think>` (未闭合时丢弃之后的全部内容)
+fn strip_think_blocks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("<think>") {
+        result.push_str(&rest[..start]);
+        match rest[start..].find("</think>") {
+            Some(end) => rest = &rest[start + end + "</think>".len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+fn strip_template_tokens(text: &str) -> String {
+    TEMPLATE_TOKENS
+        .iter()
+        .fold(text.to_string(), |text, token| text.replace(token, ""))
+}
+
+fn strip_role_prefix(text: &str) -> &str {
+    let text = text.trim();
+    ROLE_PREFIXES
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text)
+        .trim()
+}
+
+/// 整段回复被包在一个代码块中时去掉代码块 (回复中间的代码块保持不变)
+fn unwrap_fence(text: &str) -> &str {
+    let Some(inner) = text.strip_prefix("```").and_then(|t| t.strip_suffix("```")) else {
+        return text;
+    };
+    // 中间还有代码块标记说明不是单个代码块
+    if inner.contains("```") {
+        return text;
+    }
+
+    // 去掉语言标记 (```markdown / ```json)
+    match inner.split_once('\n') {
+        Some((lang, body)) if !lang.trim().contains(' ') => body.trim(),
+        _ => inner.trim(),
+    }
+}
+
+/// 回复是 JSON 对象时取出正文字段
+fn extract_json_text(text: &str) -> Option<String> {
+    if !text.starts_with('{') {
+        return None;
+    }
+
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    JSON_TEXT_FIELDS
+        .iter()
+        .find_map(|field| value.get(field)?.as_str())
+        .map(str::to_string)
+}
+
+fn is_refusal(text: &str) -> bool {
+    if text.chars().count() > REFUSAL_MAX_CHARS {
+        return false;
+    }
+
+    let lower = text.to_lowercase().replace('’', "'");
+    REFUSAL_PATTERNS
+        .iter()
+        .any(|pattern| lower.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_response_cleans_artifacts() {
+        let processed = process_response(
+            "<think>用户在问 Boss</think>\nAssistant: ```markdown\n## 打法\n先躲技能\n```<|im_end|>",
+        );
+        assert_eq!(processed.text, "## 打法\n先躲技能");
+        assert_eq!(processed.finish_reason, FinishReason::Stop);
+
+        // 回复中间的代码块保持不变
+        let text = "```rust\nfn a() {}\n```\n说明\n```rust\nfn b() {}\n```";
+        assert_eq!(process_response(text).text, text);
+
+        // JSON 回复
+        assert_eq!(
+            process_response("```json\n{\"answer\": \"用盐\"}\n```").text,
+            "用盐"
+        );
+        // 不是 JSON 时原样保留
+        assert_eq!(process_response("{不是 JSON}").text, "{不是 JSON}");
+    }
+
+    #[test]
+    fn test_process_response_finish_reason() {
+        assert_eq!(
+            process_response("I’m sorry, but I can’t help with that.").finish_reason,
+            FinishReason::Refusal
+        );
+        assert_eq!(
+            process_response("抱歉，我无法回答这个问题。").finish_reason,
+            FinishReason::Refusal
+        );
+        assert_eq!(
+            process_response("<think>...").finish_reason,
+            FinishReason::Empty
+        );
+
+        // 较长的正常回答中出现类似句子不算拒答
+        let long = format!(
+            "{}I am unable to find it on the map.",
+            "攻略内容。".repeat(80)
+        );
+        assert_eq!(process_response(&long).finish_reason, FinishReason::Stop);
+    }
+
+    #[test]
+    fn test_is_model_reply() {
+        assert!(FinishReason::Stop.is_model_reply());
+        assert!(FinishReason::Refusal.is_model_reply());
+        assert!(!FinishReason::Empty.is_model_reply());
+        assert!(!FinishReason::Fallback.is_model_reply());
+    }
+}
@@ -0,0 +1,78 @@
+/// Token 估算与截断
+///
+/// 不依赖具体模型的分词器, 只用于在请求前粗略判断 Prompt 是否会超出模型的上下文窗口:
+/// 中日韩字符按 1 Token/字, 其他字符按 4 字符/Token 估算 (与 tiktoken 对中英文的统计接近)
+
+/// 估算文本的 Token 数
+pub fn count_tokens(text: &str) -> usize {
+    let mut cjk = 0;
+    let mut other = 0;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + (other + 3) / 4
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF     // CJK 统一汉字
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x3040..=0x30FF   // 平假名/片假名
+        | 0xAC00..=0xD7AF   // 韩文
+        | 0xFF00..=0xFFEF   // 全角符号
+        | 0x3000..=0x303F   // CJK 标点
+    )
+}
+
+/// 将文本截断到约 max_tokens 个 Token (按字符边界)
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    let mut tokens = 0.0f32;
+    let mut end = 0;
+    for (idx, c) in text.char_indices() {
+        tokens += if is_cjk(c) { 1.0 } else { 0.25 };
+        if tokens > max_tokens as f32 {
+            break;
+        }
+        end = idx + c.len_utf8();
+    }
+    text[..end].to_string()
+}
+
+/// 上下文窗口中扣除回复预留和固定内容后, 还能放入多少 Token 的可裁剪内容
+///
+/// - `context_tokens`: 模型的上下文窗口
+/// - `reply_tokens`: 为模型回复预留的 Token (即请求的 max_tokens)
+/// - `fixed`: 不能裁剪的 Prompt 部分 (系统提示词、用户问题等)
+pub fn remaining_budget(context_tokens: usize, reply_tokens: usize, fixed: &[&str]) -> usize {
+    let used: usize = fixed.iter().map(|text| count_tokens(text)).sum();
+    context_tokens.saturating_sub(reply_tokens + used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("火焰武器"), 4);
+        assert_eq!(count_tokens("boss"), 1);
+    }
+
+    #[test]
+    fn test_truncate_to_tokens() {
+        assert_eq!(truncate_to_tokens("火焰武器", 2), "火焰");
+        assert_eq!(truncate_to_tokens("boss fight", 1), "boss");
+        assert_eq!(truncate_to_tokens("火焰", 10), "火焰");
+    }
+
+    #[test]
+    fn test_remaining_budget() {
+        assert_eq!(remaining_budget(100, 20, &["火焰武器", "boss"]), 75);
+        assert_eq!(remaining_budget(10, 20, &["火焰武器"]), 0);
+    }
+}
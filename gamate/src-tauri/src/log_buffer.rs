@@ -0,0 +1,197 @@
+/// 内存日志缓冲
+///
+/// 在 env_logger 之外保留最近的日志行 (Info 及以上), 供界面的实时日志面板使用:
+/// - `recent` 读取最近的日志
+/// - `spawn_event_pump` 定期把新日志通过 `logs_appended` 事件推送给前端
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 缓冲区保留的最大行数
+const CAPACITY: usize = 2000;
+/// 缓冲区记录的最低级别 (与 RUST_LOG 无关)
+const CAPTURE_LEVEL: Level = Level::Info;
+/// 推送新日志的间隔
+const PUMP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 单行日志
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    /// 递增序号
+    pub seq: u64,
+    /// 时间戳 (毫秒)
+    pub timestamp: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// 环形日志缓冲区
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            next_seq: 1,
+        }
+    }
+
+    /// 追加一行, 超出容量时丢弃最旧的
+    pub fn push(&mut self, level: Level, target: &str, message: String) {
+        if self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(LogLine {
+            seq: self.next_seq,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: level.to_string(),
+            target: target.to_string(),
+            message,
+        });
+        self.next_seq += 1;
+    }
+
+    /// 最近的 limit 行 (不低于 min_level), 按时间先后排列
+    pub fn recent(&self, min_level: Level, limit: usize) -> Vec<LogLine> {
+        let mut lines: Vec<LogLine> = self
+            .lines
+            .iter()
+            .rev()
+            .filter(|line| {
+                line.level
+                    .parse::<Level>()
+                    .ok()
+                    .is_none_or(|level| level <= min_level)
+            })
+            .take(limit)
+            .cloned()
+            .collect();
+        lines.reverse();
+        lines
+    }
+
+    /// 序号大于 seq 的所有行
+    pub fn since(&self, seq: u64) -> Vec<LogLine> {
+        self.lines
+            .iter()
+            .filter(|line| line.seq > seq)
+            .cloned()
+            .collect()
+    }
+}
+
+static BUFFER: Lazy<Mutex<LogBuffer>> = Lazy::new(|| Mutex::new(LogBuffer::new(CAPACITY)));
+
+/// 包装 env_logger: 控制台输出保持原样, 同时写入内存缓冲
+struct BufferedLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for BufferedLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= CAPTURE_LEVEL || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+
+        if record.level() <= CAPTURE_LEVEL {
+            if let Ok(mut buffer) = BUFFER.lock() {
+                buffer.push(record.level(), record.target(), record.args().to_string());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// 初始化日志 (替代 `env_logger::init`)
+pub fn init() {
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(CAPTURE_LEVEL.to_level_filter());
+
+    if log::set_boxed_logger(Box::new(BufferedLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// 读取最近的日志
+pub fn recent(min_level: Level, limit: usize) -> Vec<LogLine> {
+    BUFFER
+        .lock()
+        .map(|buffer| buffer.recent(min_level, limit))
+        .unwrap_or_default()
+}
+
+/// 定期推送新日志 (`logs_appended` 事件, 负载为 `LogLine` 数组)
+pub fn spawn_event_pump(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_seq = 0;
+        loop {
+            tokio::time::sleep(PUMP_INTERVAL).await;
+
+            let lines = BUFFER
+                .lock()
+                .map(|buffer| buffer.since(last_seq))
+                .unwrap_or_default();
+
+            if let Some(last) = lines.last() {
+                last_seq = last.seq;
+                let _ = app.emit("logs_appended", lines);
+            }
+        }
+    });
+}
+
+/// 解析日志级别 (不区分大小写, 无效时为 Info)
+pub fn parse_level(level: Option<&str>) -> Level {
+    level
+        .and_then(|l| l.parse::<LevelFilter>().ok())
+        .and_then(|filter| filter.to_level())
+        .unwrap_or(Level::Info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut buffer = LogBuffer::new(3);
+        buffer.push(Level::Info, "gamate", "a".to_string());
+        buffer.push(Level::Warn, "gamate", "b".to_string());
+        buffer.push(Level::Error, "gamate", "c".to_string());
+        buffer.push(Level::Info, "gamate", "d".to_string());
+
+        // 超出容量丢弃最旧的
+        let all = buffer.recent(Level::Info, 10);
+        let messages: Vec<&str> = all.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, vec!["b", "c", "d"]);
+
+        let warnings = buffer.recent(Level::Warn, 10);
+        assert_eq!(warnings.len(), 2);
+
+        let newer = buffer.since(all[1].seq);
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].message, "d");
+
+        assert_eq!(parse_level(Some("WARN")), Level::Warn);
+        assert_eq!(parse_level(None), Level::Info);
+    }
+}
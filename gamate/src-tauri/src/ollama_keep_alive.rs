@@ -0,0 +1,153 @@
+/// Ollama 模型常驻
+///
+/// 开启 `ai_models.ollama_keep_alive.enabled` 且对话模型为本地 Ollama 时,
+/// 每隔 `ping_interval_minutes` 分钟预加载一次模型, 避免空闲后模型被卸载导致下一次提问很慢
+use crate::llm::OllamaClient;
+use crate::settings::AppSettings;
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+
+/// 启动后首次预热前的等待时间
+const STARTUP_DELAY: Duration = Duration::from_secs(15);
+/// 轮询设置的间隔 (开关和预热间隔可随时修改)
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// 预热间隔下限
+const MIN_PING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动后台预热任务
+pub fn spawn() {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+
+        let mut last_ping: Option<Instant> = None;
+        let mut last_interval = None;
+        loop {
+            if let Ok(settings) = AppSettings::load() {
+                let keep_alive = &settings.ai_models.ollama_keep_alive;
+                let multimodal = &settings.ai_models.multimodal;
+                let interval =
+                    ping_interval(keep_alive.ping_interval_minutes, &keep_alive.keep_alive);
+                if last_interval != Some(interval) {
+                    if interval.as_secs() != keep_alive.ping_interval_minutes.max(1) as u64 * 60 {
+                        log::warn!(
+                            "⚠️ 预热间隔 {} 分钟不短于 keep_alive ({}), 改为 {} 秒",
+                            keep_alive.ping_interval_minutes,
+                            keep_alive.keep_alive,
+                            interval.as_secs()
+                        );
+                    }
+                    last_interval = Some(interval);
+                }
+
+                if keep_alive.enabled
+                    && multimodal.enabled
+                    && multimodal.provider == "local"
+                    && should_ping(last_ping, interval)
+                {
+                    last_ping = Some(Instant::now());
+                    if let Err(e) = preload(None).await {
+                        log::warn!("⚠️ Ollama 模型预热失败: {}", e);
+                    }
+                }
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// 距上次预热已超过间隔 (从未预热时立即预热)
+fn should_ping(last_ping: Option<Instant>, interval: Duration) -> bool {
+    last_ping.is_none_or(|t| t.elapsed() >= interval)
+}
+
+/// 实际使用的预热间隔
+///
+/// 预热间隔不短于 keep_alive 时模型会在两次预热之间被卸载,
+/// 此时改为 keep_alive 的一半 (不低于 1 分钟)
+fn ping_interval(ping_interval_minutes: u32, keep_alive: &str) -> Duration {
+    let interval = Duration::from_secs(ping_interval_minutes.max(1) as u64 * 60);
+
+    match parse_keep_alive(keep_alive) {
+        Some(keep_alive) if interval >= keep_alive => (keep_alive / 2).max(MIN_PING_INTERVAL),
+        _ => interval,
+    }
+}
+
+/// 解析 Ollama keep_alive 时长 ("30s" "10m" "1h" 或秒数); 负数 (永久保留) 或无法解析时返回 None
+fn parse_keep_alive(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(pos) => value.split_at(pos),
+        None => (value, "s"),
+    };
+
+    let number: f64 = number.trim().parse().ok()?;
+    if number < 0.0 {
+        return None;
+    }
+
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(seconds))
+}
+
+/// 预加载模型 (为空时使用设置中的多模态模型), 返回加载的模型名称
+pub async fn preload(model: Option<String>) -> Result<String> {
+    let settings = AppSettings::load()?;
+    let mut config = settings.ai_models.multimodal;
+    if config.provider != "local" {
+        return Err(anyhow!("当前对话模型不是本地 Ollama 模型"));
+    }
+    if let Some(model) = model.filter(|m| !m.trim().is_empty()) {
+        config.model_name = model.trim().to_string();
+    }
+
+    let keep_alive = settings.ai_models.ollama_keep_alive.keep_alive;
+    let keep_alive = match keep_alive.trim() {
+        "" => "10m",
+        value => value,
+    };
+
+    let model_name = config.model_name.clone();
+    OllamaClient::new(config)?.preload(keep_alive).await?;
+    log::info!(
+        "🦙 已预加载模型 {} (keep_alive: {})",
+        model_name,
+        keep_alive
+    );
+
+    Ok(model_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_ping() {
+        let interval = Duration::from_secs(240);
+
+        assert!(should_ping(None, interval));
+        assert!(!should_ping(Some(Instant::now()), interval));
+        assert!(should_ping(Some(Instant::now() - interval), interval));
+    }
+
+    #[test]
+    fn test_ping_interval_shorter_than_keep_alive() {
+        assert_eq!(ping_interval(4, "10m"), Duration::from_secs(240));
+        // 预热间隔不短于 keep_alive 时取一半
+        assert_eq!(ping_interval(10, "10m"), Duration::from_secs(300));
+        assert_eq!(ping_interval(5, "90s"), MIN_PING_INTERVAL);
+        // 永久保留或无法解析时使用设置值
+        assert_eq!(ping_interval(30, "-1"), Duration::from_secs(1800));
+        assert_eq!(ping_interval(30, "abc"), Duration::from_secs(1800));
+
+        assert_eq!(parse_keep_alive("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_keep_alive("300"), Some(Duration::from_secs(300)));
+    }
+}
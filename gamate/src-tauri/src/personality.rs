@@ -1,11 +1,14 @@
 /// AI 陪玩角色配置加载模块
 ///
-/// 负责加载不同角色的提示词配置文件 (prompts_*.toml)
+/// 负责加载不同角色的提示词配置文件 (prompts_*.toml),
+/// 以及直播间模拟使用的角色定义 (personalities.toml)
 ///
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 /// 角色配置结构 (对应 prompts_*.toml 文件)
 #[derive(Debug, Clone, Deserialize)]
@@ -240,6 +243,299 @@ fn get_config_path(filename: &str) -> Result<PathBuf> {
     )
 }
 
+/// 内置的角色定义 (配置目录中没有 personalities.toml 或解析失败时使用)
+const BUILTIN_PERSONALITIES: &str = include_str!("../../config/personalities.toml");
+
+/// 角色定义文件名
+const PERSONALITIES_FILE: &str = "personalities.toml";
+
+/// 用户自定义角色文件名 (保存在配置目录)
+const CUSTOM_PERSONALITIES_FILE: &str = "custom_personalities.toml";
+
+/// 直播间模拟的角色定义 (对应 personalities.toml 中的一项)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalityDefinition {
+    pub id: String,
+    /// 性格描述 (提示词和界面中显示)
+    pub description: String,
+    /// 默认昵称 (员工未填写昵称时使用)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_nickname: Option<String>,
+    /// 打招呼模板 ({nickname} 替换为员工昵称)
+    #[serde(default)]
+    pub greetings: Vec<String>,
+    /// 弹幕模板
+    #[serde(default)]
+    pub danmaku: Vec<String>,
+    /// 性格设定提示词 (追加到 AI 分析提示词; 没有 prompts_*.toml 的自定义角色在生成弹幕时也用它作为系统提示词)
+    #[serde(default)]
+    pub prompt: String,
+}
+
+impl PersonalityDefinition {
+    /// 随机选一条打招呼消息
+    pub fn greeting(&self, nickname: &str) -> Option<String> {
+        pick(&self.greetings).map(|template| template.replace("{nickname}", nickname))
+    }
+
+    /// 随机选一条弹幕模板
+    pub fn random_danmaku(&self) -> Option<&str> {
+        pick(&self.danmaku)
+    }
+}
+
+fn pick(items: &[String]) -> Option<&str> {
+    if items.is_empty() {
+        return None;
+    }
+    Some(items[rand::random::<usize>() % items.len()].as_str())
+}
+
+/// personalities.toml / custom_personalities.toml 文件结构
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersonalitiesFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fallback: Option<PersonalityDefinition>,
+    #[serde(default, rename = "personality")]
+    personalities: Vec<PersonalityDefinition>,
+}
+
+/// 角色定义注册表
+///
+/// 包含内置角色 (personalities.toml) 和用户自定义角色 (custom_personalities.toml)。
+/// 未知的角色 ID 返回 fallback 定义; 模板为空的角色使用 fallback 的模板
+#[derive(Debug, Clone)]
+pub struct PersonalityRegistry {
+    personalities: Vec<PersonalityDefinition>,
+    custom: Vec<PersonalityDefinition>,
+    fallback: PersonalityDefinition,
+}
+
+/// 当前使用的注册表 (首次使用时加载, `reload` 后替换)
+static REGISTRY: Lazy<RwLock<Arc<PersonalityRegistry>>> =
+    Lazy::new(|| RwLock::new(Arc::new(PersonalityRegistry::load())));
+
+impl PersonalityRegistry {
+    /// 从 TOML 内容解析
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: PersonalitiesFile = toml::from_str(content).context("解析角色定义失败")?;
+        let fallback = match file.fallback {
+            Some(fallback) => fallback,
+            None => Self::builtin().fallback,
+        };
+
+        Ok(Self {
+            personalities: file.personalities,
+            custom: Vec::new(),
+            fallback,
+        })
+    }
+
+    /// 内置的默认角色定义
+    pub fn builtin() -> Self {
+        let file: PersonalitiesFile =
+            toml::from_str(BUILTIN_PERSONALITIES).expect("内置角色定义格式错误");
+        Self {
+            personalities: file.personalities,
+            custom: Vec::new(),
+            fallback: file.fallback.expect("内置角色定义缺少 fallback"),
+        }
+    }
+
+    /// 加载内置角色和用户自定义角色
+    pub fn load() -> Self {
+        let mut registry = Self::load_builtin();
+
+        match custom_personalities_path().and_then(|path| read_custom_personalities(&path)) {
+            Ok(custom) => {
+                for definition in custom {
+                    if let Err(e) = registry.upsert_custom(definition) {
+                        log::warn!("⚠️ 忽略无效的自定义角色: {}", e);
+                    }
+                }
+            }
+            Err(e) => log::warn!("⚠️ 加载自定义角色失败: {:#}", e),
+        }
+
+        registry
+    }
+
+    /// 从配置目录加载 personalities.toml, 找不到或解析失败时使用内置定义
+    fn load_builtin() -> Self {
+        let loaded = get_config_path(PERSONALITIES_FILE).and_then(|path| {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("无法读取配置文件: {}", path.display()))?;
+            Self::from_toml(&content)
+                .with_context(|| format!("解析配置文件失败: {}", path.display()))
+        });
+
+        match loaded {
+            Ok(registry) => {
+                log::info!("✅ 已加载 {} 个角色定义", registry.personalities.len());
+                registry
+            }
+            Err(e) => {
+                log::warn!("⚠️ 加载角色定义失败: {:#}, 使用内置定义", e);
+                Self::builtin()
+            }
+        }
+    }
+
+    /// 当前使用的注册表
+    pub fn current() -> Arc<PersonalityRegistry> {
+        Arc::clone(&REGISTRY.read().unwrap())
+    }
+
+    /// 重新加载 personalities.toml
+    pub fn reload() {
+        *REGISTRY.write().unwrap() = Arc::new(Self::load());
+    }
+
+    /// 查找角色定义 (未知 ID 返回 fallback)
+    pub fn get(&self, id: &str) -> &PersonalityDefinition {
+        self.personalities
+            .iter()
+            .chain(&self.custom)
+            .find(|p| p.id == id)
+            .unwrap_or(&self.fallback)
+    }
+
+    /// 是否为内置角色
+    pub fn is_builtin(&self, id: &str) -> bool {
+        self.personalities.iter().any(|p| p.id == id)
+    }
+
+    /// 所有角色 (内置在前), 附带是否为内置角色
+    pub fn entries(&self) -> impl Iterator<Item = (&PersonalityDefinition, bool)> {
+        self.personalities
+            .iter()
+            .map(|p| (p, true))
+            .chain(self.custom.iter().map(|p| (p, false)))
+    }
+
+    /// 添加或更新自定义角色
+    ///
+    /// ID 不能为空、只能包含字母数字和 `_` `-`, 且不能与内置角色重复; 与已有自定义角色相同时覆盖
+    pub fn upsert_custom(&mut self, mut definition: PersonalityDefinition) -> Result<()> {
+        definition.id = definition.id.trim().to_string();
+        let id = &definition.id;
+
+        if id.is_empty() {
+            anyhow::bail!("角色 ID 不能为空");
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!("角色 ID 只能包含字母、数字、下划线和连字符: {}", id);
+        }
+        if self.is_builtin(id) {
+            anyhow::bail!("角色 ID 与内置角色重复: {}", id);
+        }
+        if definition.description.trim().is_empty() {
+            definition.description = id.clone();
+        }
+
+        match self.custom.iter_mut().find(|p| p.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.custom.push(definition),
+        }
+        Ok(())
+    }
+
+    /// 删除自定义角色 (内置角色不能删除)
+    pub fn remove_custom(&mut self, id: &str) -> Result<()> {
+        if self.is_builtin(id) {
+            anyhow::bail!("内置角色不能删除: {}", id);
+        }
+
+        let before = self.custom.len();
+        self.custom.retain(|p| p.id != id);
+        if self.custom.len() == before {
+            anyhow::bail!("角色不存在: {}", id);
+        }
+        Ok(())
+    }
+
+    /// 打招呼消息
+    pub fn greeting(&self, id: &str, nickname: &str) -> String {
+        self.get(id)
+            .greeting(nickname)
+            .or_else(|| self.fallback.greeting(nickname))
+            .unwrap_or_else(|| format!("{}来了~", nickname))
+    }
+
+    /// 随机弹幕模板
+    pub fn random_danmaku(&self, id: &str) -> String {
+        self.get(id)
+            .random_danmaku()
+            .or_else(|| self.fallback.random_danmaku())
+            .unwrap_or("666")
+            .to_string()
+    }
+
+    /// 所有角色 ID
+    pub fn ids(&self) -> Vec<&str> {
+        self.entries().map(|(p, _)| p.id.as_str()).collect()
+    }
+}
+
+/// 自定义角色文件路径
+fn custom_personalities_path() -> Result<PathBuf> {
+    Ok(crate::settings::AppSettings::config_dir()?.join(CUSTOM_PERSONALITIES_FILE))
+}
+
+/// 读取自定义角色 (文件不存在时为空)
+fn read_custom_personalities(path: &Path) -> Result<Vec<PersonalityDefinition>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("无法读取配置文件: {}", path.display()))?;
+    let file: PersonalitiesFile = toml::from_str(&content)
+        .with_context(|| format!("解析配置文件失败: {}", path.display()))?;
+    Ok(file.personalities)
+}
+
+/// 写入自定义角色
+fn write_custom_personalities(path: &Path, custom: &[PersonalityDefinition]) -> Result<()> {
+    let file = PersonalitiesFile {
+        fallback: None,
+        personalities: custom.to_vec(),
+    };
+    let content = toml::to_string_pretty(&file).context("序列化自定义角色失败")?;
+    fs::write(path, content).with_context(|| format!("无法写入配置文件: {}", path.display()))
+}
+
+/// 修改自定义角色并保存到文件, 成功后替换当前注册表
+fn update_custom_personalities(
+    update: impl FnOnce(&mut PersonalityRegistry) -> Result<()>,
+) -> Result<()> {
+    let mut current = REGISTRY.write().unwrap();
+    let mut registry = PersonalityRegistry::clone(&current);
+    update(&mut registry)?;
+
+    write_custom_personalities(&custom_personalities_path()?, &registry.custom)?;
+    *current = Arc::new(registry);
+    Ok(())
+}
+
+/// 保存自定义角色 (ID 已存在时覆盖)
+pub fn save_custom_personality(definition: PersonalityDefinition) -> Result<()> {
+    let id = definition.id.clone();
+    update_custom_personalities(|registry| registry.upsert_custom(definition))?;
+    log::info!("💾 已保存自定义角色: {}", id.trim());
+    Ok(())
+}
+
+/// 删除自定义角色
+pub fn delete_custom_personality(id: &str) -> Result<()> {
+    update_custom_personalities(|registry| registry.remove_custom(id))?;
+    log::info!("🗑️ 已删除自定义角色: {}", id);
+    Ok(())
+}
+
 /// 获取所有可用的角色类型
 pub fn get_available_personalities() -> Vec<&'static str> {
     vec![
@@ -267,6 +563,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builtin_personalities() {
+        let registry = PersonalityRegistry::builtin();
+        assert_eq!(
+            registry.ids(),
+            vec![
+                "sunnyou_male",
+                "funny_female",
+                "kobe",
+                "sweet_girl",
+                "trump"
+            ]
+        );
+
+        let kobe = registry.get("kobe");
+        assert!(kobe.description.contains("科比"));
+        assert!(!kobe.danmaku.is_empty());
+        assert_eq!(
+            registry.greeting("sunnyou_male", "小明"),
+            "小明来啦!兄弟们冲鸭!"
+        );
+    }
+
+    #[test]
+    fn test_unknown_personality_falls_back() {
+        let registry = PersonalityRegistry::from_toml(
+            r#"
+[[personality]]
+id = "quiet"
+description = "安静"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(registry.get("nope").description, "默认性格");
+        assert_eq!(registry.greeting("nope", "小明"), "小明来了~");
+        // 没有模板的角色使用 fallback 的模板
+        assert_eq!(registry.get("quiet").description, "安静");
+        assert_eq!(registry.greeting("quiet", "小红"), "小红来了~");
+        assert!(!registry.random_danmaku("quiet").is_empty());
+    }
+
+    #[test]
+    fn test_custom_personalities() {
+        let mut registry = PersonalityRegistry::builtin();
+        let custom = |id: &str| PersonalityDefinition {
+            id: id.to_string(),
+            description: "话痨".to_string(),
+            default_nickname: Some("小话痨".to_string()),
+            greetings: vec!["{nickname}驾到".to_string()],
+            danmaku: vec!["说两句".to_string()],
+            prompt: "你是个话痨".to_string(),
+        };
+
+        registry.upsert_custom(custom(" chatty ")).unwrap();
+        assert_eq!(registry.get("chatty").prompt, "你是个话痨");
+        assert_eq!(registry.greeting("chatty", "小明"), "小明驾到");
+        assert!(!registry.is_builtin("chatty"));
+
+        // 相同 ID 覆盖而不是重复添加
+        let mut updated = custom("chatty");
+        updated.description = "超级话痨".to_string();
+        registry.upsert_custom(updated).unwrap();
+        assert_eq!(
+            registry.ids().iter().filter(|id| **id == "chatty").count(),
+            1
+        );
+        assert_eq!(registry.get("chatty").description, "超级话痨");
+
+        // 校验 ID
+        assert!(registry.upsert_custom(custom("  ")).is_err());
+        assert!(registry.upsert_custom(custom("bad id")).is_err());
+        assert!(registry.upsert_custom(custom("kobe")).is_err());
+
+        // 内置角色不能删除
+        assert!(registry.remove_custom("kobe").is_err());
+        assert!(registry.remove_custom("missing").is_err());
+        registry.remove_custom("chatty").unwrap();
+        assert_eq!(registry.get("chatty").description, "默认性格");
+    }
+
+    #[test]
+    fn test_custom_personalities_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("gamate_personality_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CUSTOM_PERSONALITIES_FILE);
+
+        assert!(read_custom_personalities(&path).unwrap().is_empty());
+
+        let definition = PersonalityDefinition {
+            id: "chatty".to_string(),
+            description: "话痨".to_string(),
+            default_nickname: None,
+            greetings: vec![],
+            danmaku: vec!["说两句".to_string()],
+            prompt: "你是个话痨".to_string(),
+        };
+        write_custom_personalities(&path, &[definition]).unwrap();
+
+        let loaded = read_custom_personalities(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, "chatty");
+        assert_eq!(loaded[0].danmaku, vec!["说两句"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_build_system_prompt() {
         let config = PersonalityConfig {
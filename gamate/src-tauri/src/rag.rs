@@ -1,6 +1,17 @@
-use crate::commands::vector_commands::search_wiki_impl;
-use anyhow::Result;
+use crate::commands::vector_commands::search_wiki_with_timings;
+use crate::llm::{
+    count_tokens, remaining_budget, truncate_to_tokens, FinishReason, OllamaClient, OpenAIClient,
+};
+use crate::settings::AIModelSettings;
+use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 重排时每个候选条目带入提示词的最大 Token 数
+const RERANK_ENTRY_TOKENS: usize = 300;
+
+/// 重排请求超时
+const RERANK_TIMEOUT: Duration = Duration::from_secs(20);
 
 /// RAG 上下文结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +19,26 @@ pub struct RAGContext {
     pub screenshot: Option<String>,
     pub game_state: serde_json::Value,
     pub wiki_entries: Vec<WikiReference>,
+    /// 各阶段耗时
+    #[serde(default)]
+    pub timings: RagTimings,
+}
+
+/// RAG 查询各阶段耗时 (毫秒)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RagTimings {
+    pub embed_ms: u64,
+    pub retrieve_ms: u64,
+    pub rerank_ms: u64,
+    pub llm_ms: u64,
+    pub total_ms: u64,
+}
+
+impl RAGContext {
+    /// 是否有可作为依据的 Wiki 资料 (检索为空或相关度全部过低时为 false)
+    pub fn is_grounded(&self) -> bool {
+        !self.wiki_entries.is_empty()
+    }
 }
 
 /// Wiki 引用
@@ -23,7 +54,13 @@ pub struct WikiReference {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIResponse {
     pub content: String,
+    /// 回复结束原因 (正常/拒答/空回复/回退)
+    #[serde(default)]
+    pub finish_reason: FinishReason,
     pub wiki_references: Option<Vec<WikiReference>>,
+    /// 各阶段耗时 (仅在请求时返回)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RagTimings>,
 }
 
 /// 构建 RAG 上下文
@@ -41,16 +78,26 @@ pub async fn build_rag_context(
     log::info!("   提取关键词: {}", extracted_query);
 
     // 2. 向量检索 Wiki
-    let search_results = search_wiki_impl(extracted_query.clone(), game_id.to_string(), Some(3))
-        .await
-        .unwrap_or_else(|e| {
-            log::warn!("向量检索失败: {}", e);
-            vec![]
-        });
+    let settings = crate::settings::AppSettings::load().unwrap_or_default();
+    let rag_settings = &settings.ai_models.rag;
+    let top_k = rag_settings.top_k;
+    let (search_results, search_timings) = search_wiki_with_timings(
+        extracted_query.clone(),
+        game_id.to_string(),
+        Some(top_k),
+        false,
+    )
+    .await
+    .unwrap_or_else(|e| {
+        log::warn!("向量检索失败: {}", e);
+        (vec![], Default::default())
+    });
 
-    // 3. 转换为 WikiReference
+    // 3. 转换为 WikiReference (过滤相关度过低的结果)
+    let total_results = search_results.len();
     let wiki_entries: Vec<WikiReference> = search_results
         .into_iter()
+        .filter(|result| result.score >= rag_settings.min_score)
         .map(|result| WikiReference {
             title: result.title,
             content: result.content,
@@ -59,18 +106,184 @@ pub async fn build_rag_context(
         })
         .collect();
 
-    log::info!("✅ 检索到 {} 条 Wiki 条目", wiki_entries.len());
+    log::info!(
+        "✅ 检索到 {} 条 Wiki 条目 (相关度不足被过滤 {} 条)",
+        wiki_entries.len(),
+        total_results - wiki_entries.len()
+    );
+
+    // 4. 重排 (可选, 失败时保持向量检索顺序)
+    let rerank_start = Instant::now();
+    let wiki_entries = if rag_settings.rerank && !wiki_entries.is_empty() {
+        rerank_entries(
+            query,
+            wiki_entries,
+            rag_settings.rerank_top_m,
+            &settings.ai_models,
+        )
+        .await
+    } else {
+        wiki_entries
+    };
+    let rerank_ms = if rag_settings.rerank {
+        rerank_start.elapsed().as_millis() as u64
+    } else {
+        0
+    };
 
-    // 4. 构建上下文
+    // 5. 构建上下文
     let context = RAGContext {
         screenshot,
         game_state: serde_json::json!({}), // TODO: 后续可以从截图中提取游戏状态
         wiki_entries,
+        timings: RagTimings {
+            embed_ms: search_timings.embed_ms,
+            retrieve_ms: search_timings.retrieve_ms,
+            rerank_ms,
+            ..Default::default()
+        },
     };
 
     Ok(context)
 }
 
+/// 使用对话模型对检索结果重新打分排序, 保留最相关的 `top_m` 条
+///
+/// 模型未配置、调用失败或返回无法解析时按原向量检索顺序截断
+pub async fn rerank_entries(
+    query: &str,
+    mut entries: Vec<WikiReference>,
+    top_m: usize,
+    models: &AIModelSettings,
+) -> Vec<WikiReference> {
+    if entries.len() <= 1 {
+        entries.truncate(top_m);
+        return entries;
+    }
+
+    match llm_relevance_scores(query, &entries, models).await {
+        Ok(scores) => apply_rerank_scores(entries, &scores, top_m),
+        Err(e) => {
+            log::warn!("⚠️ 重排失败, 使用向量检索顺序: {}", e);
+            entries.truncate(top_m);
+            entries
+        }
+    }
+}
+
+/// 请求对话模型为每个候选条目打分 (0-10)
+async fn llm_relevance_scores(
+    query: &str,
+    entries: &[WikiReference],
+    models: &AIModelSettings,
+) -> Result<Vec<f32>> {
+    let config = &models.multimodal;
+    if !config.enabled {
+        bail!("对话模型未启用");
+    }
+
+    let (system_prompt, user_prompt) = build_rerank_prompt(query, entries);
+    let call = async {
+        if config.provider == "local" {
+            OllamaClient::new(config.clone())?
+                .with_keep_alive(models.ollama_keep_alive.request_keep_alive())
+                .chat(&system_prompt, &user_prompt)
+                .await
+        } else {
+            if config.api_key.is_none() {
+                bail!("未配置 API Key");
+            }
+            OpenAIClient::new(config.clone())?
+                .chat(&system_prompt, &user_prompt)
+                .await
+        }
+    };
+
+    let raw = tokio::time::timeout(RERANK_TIMEOUT, call)
+        .await
+        .map_err(|_| anyhow!("重排超时 ({} 秒)", RERANK_TIMEOUT.as_secs()))??;
+
+    parse_rerank_scores(&raw, entries.len())
+}
+
+/// 构建重排提示词
+fn build_rerank_prompt(query: &str, entries: &[WikiReference]) -> (String, String) {
+    let system_prompt = "你是检索结果相关性评估器。根据用户问题, 为每条资料打 0-10 分 (10 = 能直接回答问题, 0 = 完全无关)。\n只输出一个 JSON 数组, 按资料编号顺序给出分数, 例如 [8, 2, 5], 不要输出其他内容。".to_string();
+
+    let mut user_prompt = format!("用户问题: {}\n\n资料:\n", query);
+    for (i, entry) in entries.iter().enumerate() {
+        user_prompt.push_str(&format!(
+            "[{}] {}\n{}\n\n",
+            i + 1,
+            entry.title,
+            truncate_to_tokens(&entry.content, RERANK_ENTRY_TOKENS)
+        ));
+    }
+    user_prompt.push_str(&format!("请输出包含 {} 个分数的 JSON 数组:", entries.len()));
+
+    (system_prompt, user_prompt)
+}
+
+/// 解析模型返回的分数数组 (取最后一个 `[...]`, 跳过推理过程等多余内容)
+fn parse_rerank_scores(raw: &str, expected: usize) -> Result<Vec<f32>> {
+    let end = raw
+        .rfind(']')
+        .ok_or_else(|| anyhow!("重排结果中没有分数数组"))?;
+    let start = raw[..end]
+        .rfind('[')
+        .ok_or_else(|| anyhow!("重排结果中没有分数数组"))?;
+
+    let scores: Vec<f32> = serde_json::from_str(&raw[start..=end])
+        .map_err(|e| anyhow!("解析重排分数失败: {} | 原始: {}", e, &raw[start..=end]))?;
+    if scores.len() != expected {
+        bail!(
+            "重排分数数量不匹配: 期望 {}, 实际 {}",
+            expected,
+            scores.len()
+        );
+    }
+
+    Ok(scores.into_iter().map(|s| s.clamp(0.0, 10.0)).collect())
+}
+
+/// 按重排分数排序 (分数相同时保持原顺序) 并截断到 `top_m` 条
+///
+/// 条目的 `score` 替换为重排分数 (归一化到 0-1), 后续按相关度组装上下文时使用
+fn apply_rerank_scores(
+    entries: Vec<WikiReference>,
+    scores: &[f32],
+    top_m: usize,
+) -> Vec<WikiReference> {
+    let total = entries.len();
+    let mut reranked: Vec<WikiReference> = entries
+        .into_iter()
+        .zip(scores)
+        .map(|(mut entry, score)| {
+            entry.score = score / 10.0;
+            entry
+        })
+        .collect();
+    reranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    reranked.truncate(top_m);
+
+    log::info!(
+        "🔀 重排完成: 保留 {}/{} 条 ({})",
+        reranked.len(),
+        total,
+        reranked
+            .iter()
+            .map(|e| format!("{} {:.1}", e.title, e.score * 10.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    reranked
+}
+
 /// 提取查询关键词
 /// 将用户消息转换为适合向量检索的查询
 pub fn extract_query_keywords(user_message: &str) -> String {
@@ -166,19 +379,37 @@ pub fn build_prompt(game_name: &str, user_message: &str, context: &RAGContext) -
         }
     };
 
+    // 没有检索到资料时, 要求 AI 明确说明而不是凭记忆编造
+    let system_prompt = if context.is_grounded() {
+        system_prompt
+    } else {
+        log::info!("⚠️ 未检索到相关 Wiki 资料, 切换为无依据模式");
+        format!("{}\n\n{}", system_prompt, ungrounded_notice(game_name))
+    };
+
+    // 全局前言放在最前面, 对所有角色生效
+    let system_prompt = settings.ai_models.with_preamble(&system_prompt);
+
     // 用户 Prompt
     let mut user_prompt = String::new();
 
-    // 添加 Wiki 上下文
-    if !context.wiki_entries.is_empty() {
+    // 添加 Wiki 上下文 (按 Token 预算裁剪, 同时不能超出模型的上下文窗口)
+    let wiki_budget = context_budget(
+        settings.ai_models.rag.context_token_budget,
+        settings.ai_models.context_tokens,
+        settings.ai_models.multimodal.max_tokens as usize,
+        &[&system_prompt, user_message],
+    );
+    let wiki_entries = assemble_context(&context.wiki_entries, wiki_budget);
+    if !wiki_entries.is_empty() {
         user_prompt.push_str("**参考知识库:**\n\n");
-        for (i, entry) in context.wiki_entries.iter().enumerate() {
+        for (i, entry) in wiki_entries.iter().enumerate() {
             user_prompt.push_str(&format!(
                 "{}. **{}** (相关度: {:.1}%)\n{}\n\n",
                 i + 1,
                 entry.title,
                 entry.score * 100.0,
-                &entry.content[..entry.content.len().min(300)]
+                entry.content
             ));
         }
     } else {
@@ -197,6 +428,97 @@ pub fn build_prompt(game_name: &str, user_message: &str, context: &RAGContext) -
     (system_prompt, user_prompt)
 }
 
+/// 无 Wiki 依据时追加到系统提示词的说明
+fn ungrounded_notice(game_name: &str) -> String {
+    format!(
+        r#"## 重要: 本次没有检索到相关的 Wiki 资料
+- 先明确告诉用户: 知识库中没有关于这个问题的《{}》Wiki 资料
+- 不要编造具体的数值、位置、掉落、配方等细节
+- 如果给出建议, 说明这只是通用经验, 可能不准确
+- 可以提示用户更新或导入该游戏的知识库"#,
+        game_name
+    )
+}
+
+/// 用户 Prompt 中除 Wiki 条目外的固定格式开销 (标题、截图说明、结尾指令)
+const PROMPT_OVERHEAD_TOKENS: usize = 64;
+
+/// 计算知识库上下文可用的 Token 数
+///
+/// 取配置的知识库预算与上下文窗口剩余空间 (扣除回复预留和系统提示词、用户问题) 中较小的一个
+fn context_budget(
+    rag_budget: usize,
+    context_tokens: usize,
+    reply_tokens: usize,
+    fixed: &[&str],
+) -> usize {
+    let available = remaining_budget(context_tokens, reply_tokens, fixed)
+        .saturating_sub(PROMPT_OVERHEAD_TOKENS);
+    if available < rag_budget {
+        log::warn!(
+            "✂️ 上下文窗口 {} Tokens 不足, 知识库预算从 {} 缩减到 {} Tokens",
+            context_tokens,
+            rag_budget,
+            available
+        );
+        available
+    } else {
+        rag_budget
+    }
+}
+
+/// 在 Token 预算内组装知识库上下文
+///
+/// 按相关度从高到低加入条目, 超出预算后丢弃剩余相关度较低的条目;
+/// 若相关度最高的条目本身就超出预算, 则截断其内容而不是全部丢弃
+pub fn assemble_context(entries: &[WikiReference], token_budget: usize) -> Vec<WikiReference> {
+    // 每个条目的标题、序号等格式开销
+    const ENTRY_OVERHEAD: usize = 16;
+
+    let mut sorted: Vec<&WikiReference> = entries.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut selected = Vec::new();
+    let mut used = 0;
+
+    for entry in sorted {
+        let cost = count_tokens(&entry.title) + count_tokens(&entry.content) + ENTRY_OVERHEAD;
+
+        if used + cost <= token_budget {
+            used += cost;
+            selected.push(entry.clone());
+            continue;
+        }
+
+        if selected.is_empty() {
+            let remaining =
+                token_budget.saturating_sub(count_tokens(&entry.title) + ENTRY_OVERHEAD);
+            if remaining > 0 {
+                let mut truncated = entry.clone();
+                truncated.content = truncate_to_tokens(&entry.content, remaining);
+                selected.push(truncated);
+            }
+        }
+
+        break;
+    }
+
+    if selected.len() < entries.len() {
+        log::info!(
+            "✂️ 上下文预算 {} Tokens, 保留 {}/{} 条 Wiki 条目",
+            token_budget,
+            selected.len(),
+            entries.len()
+        );
+    }
+
+    selected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,6 +534,83 @@ mod tests {
         assert!(result2.contains("火焰") || result2.contains("武器"));
     }
 
+    #[test]
+    fn test_parse_rerank_scores() {
+        assert_eq!(
+            parse_rerank_scores("[8, 2, 5]", 3).unwrap(),
+            vec![8.0, 2.0, 5.0]
+        );
+        // 跳过推理过程和代码块, 超出范围的分数截断到 0-10
+        assert_eq!(
+            parse_rerank_scores("<think>看看 [1] 和 [2]</think>\n```json\n[12, -1]\n```", 2)
+                .unwrap(),
+            vec![10.0, 0.0]
+        );
+        assert!(parse_rerank_scores("[8, 2]", 3).is_err());
+        assert!(parse_rerank_scores("都很相关", 3).is_err());
+    }
+
+    #[test]
+    fn test_apply_rerank_scores() {
+        let entry = |title: &str, score: f32| WikiReference {
+            title: title.to_string(),
+            content: String::new(),
+            score,
+            url: None,
+        };
+        let entries = vec![entry("a", 0.9), entry("b", 0.8), entry("c", 0.7)];
+
+        let reranked = apply_rerank_scores(entries, &[3.0, 9.0, 3.0], 2);
+        assert_eq!(reranked.len(), 2);
+        assert_eq!(reranked[0].title, "b");
+        assert!((reranked[0].score - 0.9).abs() < 1e-6);
+        // 分数相同时保持向量检索顺序
+        assert_eq!(reranked[1].title, "a");
+    }
+
+    #[test]
+    fn test_context_budget() {
+        // 上下文窗口充足: 使用配置的知识库预算
+        assert_eq!(context_budget(1500, 8192, 1000, &["系统提示词"]), 1500);
+
+        // 上下文窗口不足: 缩减到剩余空间
+        let system = "提示".repeat(500);
+        assert_eq!(
+            context_budget(1500, 3000, 1000, &[&system]),
+            3000 - 1000 - 1000 - PROMPT_OVERHEAD_TOKENS
+        );
+
+        // 固定内容已经超出窗口: 不放入知识库
+        assert_eq!(context_budget(1500, 1024, 1000, &[&system]), 0);
+    }
+
+    #[test]
+    fn test_assemble_context_budget() {
+        let entry = |title: &str, score: f32| WikiReference {
+            title: title.to_string(),
+            content: "内容".repeat(50),
+            score,
+            url: None,
+        };
+        let entries = vec![entry("低", 0.2), entry("高", 0.9), entry("中", 0.5)];
+
+        // 预算足够: 全部保留, 按相关度排序
+        let all = assemble_context(&entries, 10_000);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].title, "高");
+
+        // 预算只够两条: 丢弃相关度最低的
+        let two = assemble_context(&entries, 250);
+        assert_eq!(two.len(), 2);
+        assert!(two.iter().all(|e| e.title != "低"));
+
+        // 预算不足一条: 截断最高相关度条目
+        let one = assemble_context(&entries, 50);
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].title, "高");
+        assert!(one[0].content.chars().count() < 100);
+    }
+
     #[test]
     fn test_build_prompt() {
         let context = RAGContext {
@@ -223,6 +622,7 @@ mod tests {
                 score: 0.95,
                 url: None,
             }],
+            timings: RagTimings::default(),
         };
 
         let (system_prompt, user_prompt) = build_prompt("测试游戏", "测试问题", &context);
@@ -230,5 +630,35 @@ mod tests {
         assert!(system_prompt.contains("测试游戏"));
         assert!(user_prompt.contains("测试问题"));
         assert!(user_prompt.contains("测试条目"));
+        assert!(!system_prompt.contains("没有检索到相关的 Wiki 资料"));
+    }
+
+    #[test]
+    fn test_build_prompt_without_sources() {
+        let context = RAGContext {
+            screenshot: None,
+            game_state: serde_json::json!({}),
+            wiki_entries: vec![],
+            timings: RagTimings::default(),
+        };
+        assert!(!context.is_grounded());
+
+        let (system_prompt, user_prompt) = build_prompt("测试游戏", "测试问题", &context);
+
+        assert!(system_prompt.contains("没有检索到相关的 Wiki 资料"));
+        assert!(system_prompt.contains("《测试游戏》"));
+        assert!(user_prompt.contains("暂无相关信息"));
+    }
+
+    #[test]
+    fn test_prepend_preamble() {
+        use crate::settings::prepend_preamble;
+
+        assert_eq!(
+            prepend_preamble(Some(" 不要剧透 "), "系统提示词"),
+            "不要剧透\n\n系统提示词"
+        );
+        assert_eq!(prepend_preamble(Some("  "), "系统提示词"), "系统提示词");
+        assert_eq!(prepend_preamble(None, "系统提示词"), "系统提示词");
     }
 }
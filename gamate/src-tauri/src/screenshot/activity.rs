@@ -0,0 +1,65 @@
+// 用户输入活动检测
+// Windows 使用 GetLastInputInfo, macOS 读取 IOHIDSystem 的 HIDIdleTime, 其他平台暂不支持
+
+use std::time::Duration;
+
+/// 距离最后一次键盘/鼠标输入的时间 (不支持的平台返回 None)
+#[cfg(windows)]
+pub fn idle_duration() -> Option<Duration> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+
+    unsafe {
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return None;
+        }
+        let now = GetTickCount();
+        Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+}
+
+/// 距离最后一次键盘/鼠标输入的时间 (不支持的平台返回 None)
+#[cfg(target_os = "macos")]
+pub fn idle_duration() -> Option<Duration> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+
+    // 形如: "HIDIdleTime" = 1234567890 (纳秒)
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_nanos)
+}
+
+/// 距离最后一次键盘/鼠标输入的时间 (不支持的平台返回 None)
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn idle_duration() -> Option<Duration> {
+    None
+}
+
+/// 根据空闲时间判断用户是否处于闲置状态 (无法检测时视为活跃)
+pub fn is_idle(idle: Option<Duration>, threshold: Duration) -> bool {
+    idle.is_some_and(|idle| idle >= threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_idle() {
+        let threshold = Duration::from_secs(60);
+        assert!(!is_idle(None, threshold));
+        assert!(!is_idle(Some(Duration::from_secs(5)), threshold));
+        assert!(is_idle(Some(Duration::from_secs(60)), threshold));
+    }
+}
@@ -3,8 +3,12 @@ use base64::{engine::general_purpose, Engine};
 use image::{DynamicImage, ImageFormat};
 use screenshots::Screen;
 use std::io::Cursor;
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// 截图锁: 周期截图和智能截图可能同时调用, 系统截图接口需要串行访问
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
 /// 屏幕截图器
 pub struct ScreenCapturer {
     screens: Vec<Screen>,
@@ -35,9 +39,32 @@ impl ScreenCapturer {
             .collect()
     }
 
-    /// 全屏截图
+    /// 解析要截取的显示器
+    ///
+    /// 未指定时使用主显示器; 指定的显示器不存在 (例如已拔出) 时回退到主显示器
+    pub fn resolve_display(&self, display_id: Option<usize>) -> usize {
+        match display_id {
+            Some(id) if id < self.screens.len() => id,
+            Some(id) => {
+                log::warn!("⚠️ 显示器 {} 不存在, 回退到主显示器", id);
+                0
+            }
+            None => 0,
+        }
+    }
+
+    /// 全屏截图 (`display_id` 为显示器序号, 见 `list_displays`)
     pub fn capture_fullscreen(&self, display_id: Option<usize>) -> Result<Screenshot> {
-        let display_id = display_id.unwrap_or(0);
+        self.capture_fullscreen_with_image(display_id)
+            .map(|(screenshot, _)| screenshot)
+    }
+
+    /// 全屏截图 (同时返回原始图片, 供 OCR / 场景哈希等本地处理使用)
+    pub fn capture_fullscreen_with_image(
+        &self,
+        display_id: Option<usize>,
+    ) -> Result<(Screenshot, image::RgbaImage)> {
+        let display_id = self.resolve_display(display_id);
 
         let screen = self
             .screens
@@ -46,9 +73,7 @@ impl ScreenCapturer {
 
         log::info!("开始全屏截图，显示器 {}", display_id);
 
-        let image = screen
-            .capture()
-            .map_err(|e| ScreenshotError::CaptureFailed(e.to_string()))?;
+        let image = capture_screen(screen)?;
 
         let width = image.width();
         let height = image.height();
@@ -63,45 +88,23 @@ impl ScreenCapturer {
 
         log::info!("✅ 截图完成: {}x{}, {} bytes", width, height, data.len());
 
-        Ok(Screenshot {
+        let screenshot = Screenshot {
             data,
             width,
             height,
             timestamp,
             display_id: Some(display_id),
             mode: CaptureMode::Fullscreen,
-        })
+        };
+
+        Ok((screenshot, image))
     }
 
     /// 区域截图
     pub fn capture_area(&self, area: CaptureArea, display_id: Option<usize>) -> Result<Screenshot> {
-        let display_id = display_id.unwrap_or(0);
+        let display_id = self.resolve_display(display_id);
 
-        let screen = self
-            .screens
-            .get(display_id)
-            .ok_or(ScreenshotError::DisplayNotFound(display_id))?;
-
-        // 验证区域有效性
-        if area.width == 0 || area.height == 0 {
-            return Err(ScreenshotError::InvalidArea);
-        }
-
-        log::info!(
-            "开始区域截图: {}x{} at ({}, {})",
-            area.width,
-            area.height,
-            area.x,
-            area.y
-        );
-
-        // 先截取全屏
-        let full_image = screen
-            .capture()
-            .map_err(|e| ScreenshotError::CaptureFailed(e.to_string()))?;
-
-        // 裁剪指定区域
-        let cropped = self.crop_image(&full_image, &area)?;
+        let cropped = self.capture_area_image(&area, display_id)?;
 
         let width = cropped.width();
         let height = cropped.height();
@@ -126,12 +129,51 @@ impl ScreenCapturer {
         })
     }
 
+    /// 区域截图 (返回原始图片, 供 OCR 等本地处理使用)
+    pub fn capture_area_image(
+        &self,
+        area: &CaptureArea,
+        display_id: usize,
+    ) -> Result<image::RgbaImage> {
+        let screen = self
+            .screens
+            .get(display_id)
+            .ok_or(ScreenshotError::DisplayNotFound(display_id))?;
+
+        // 验证区域有效性
+        if area.width == 0 || area.height == 0 {
+            return Err(ScreenshotError::InvalidArea);
+        }
+
+        log::info!(
+            "开始区域截图: {}x{} at ({}, {})",
+            area.width,
+            area.height,
+            area.x,
+            area.y
+        );
+
+        // 先截取全屏
+        let full_image = capture_screen(screen)?;
+
+        // 裁剪指定区域
+        self.crop_image(&full_image, area)
+    }
+
     /// 裁剪图片
-    fn crop_image(&self, image: &image::RgbaImage, area: &CaptureArea) -> Result<image::RgbaImage> {
+    pub fn crop_image(
+        &self,
+        image: &image::RgbaImage,
+        area: &CaptureArea,
+    ) -> Result<image::RgbaImage> {
         let x = area.x.max(0) as u32;
         let y = area.y.max(0) as u32;
-        let width = area.width.min(image.width() - x);
-        let height = area.height.min(image.height() - y);
+        // 区域起点超出图片时裁剪结果为空
+        let width = area.width.min(image.width().saturating_sub(x));
+        let height = area.height.min(image.height().saturating_sub(y));
+        if width == 0 || height == 0 {
+            return Err(ScreenshotError::InvalidArea);
+        }
 
         Ok(image::imageops::crop_imm(image, x, y, width, height).to_image())
     }
@@ -210,8 +252,55 @@ impl ScreenCapturer {
     }
 }
 
+/// 串行调用系统截图接口
+fn capture_screen(screen: &Screen) -> Result<image::RgbaImage> {
+    let _guard = CAPTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    screen
+        .capture()
+        .map_err(|e| ScreenshotError::CaptureFailed(e.to_string()))
+}
+
 impl Default for ScreenCapturer {
     fn default() -> Self {
         Self::new().expect("无法初始化屏幕截图器")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_display_fallback() {
+        let capturer = ScreenCapturer { screens: Vec::new() };
+
+        // 未指定或显示器不存在时都回退到主显示器
+        assert_eq!(capturer.resolve_display(None), 0);
+        assert_eq!(capturer.resolve_display(Some(3)), 0);
+    }
+
+    #[test]
+    fn test_crop_image_out_of_bounds() {
+        let capturer = ScreenCapturer {
+            screens: Vec::new(),
+        };
+        let image = image::RgbaImage::new(100, 50);
+        let area = |x, y, width, height| CaptureArea {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        // 超出右下角的部分被截掉
+        let cropped = capturer.crop_image(&image, &area(80, 40, 50, 50)).unwrap();
+        assert_eq!(cropped.dimensions(), (20, 10));
+
+        // 起点在图片外时返回错误而不是溢出
+        assert!(matches!(
+            capturer.crop_image(&image, &area(150, 0, 10, 10)),
+            Err(ScreenshotError::InvalidArea)
+        ));
+        assert!(capturer.crop_image(&image, &area(0, 0, 0, 10)).is_err());
+    }
+}
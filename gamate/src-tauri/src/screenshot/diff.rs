@@ -0,0 +1,139 @@
+/// 截图差异检测
+///
+/// 将两张截图缩放为同样大小的灰度缩略图, 按块计算平均绝对差;
+/// 任一块的差异超过阈值即认为画面发生了变化 (局部变化也能被发现)
+use super::types::Screenshot;
+use base64::{engine::general_purpose, Engine as _};
+use image::{imageops::FilterType, GrayImage};
+
+/// 缩略图尺寸
+const THUMB_WIDTH: u32 = 64;
+const THUMB_HEIGHT: u32 = 36;
+/// 分块边长 (缩略图像素)
+const BLOCK_SIZE: u32 = 8;
+
+/// 两张截图的画面是否有明显变化
+///
+/// `threshold` 为单个块的平均亮度差 (0-1); 解码失败时视为有变化, 避免误跳过分析
+pub fn screenshots_differ(a: &Screenshot, b: &Screenshot, threshold: f32) -> bool {
+    frames_differ(&a.data, &b.data, threshold)
+}
+
+/// 按 Base64 图片数据 (可带 data URL 前缀) 判断画面是否有明显变化
+pub fn frames_differ(a: &str, b: &str, threshold: f32) -> bool {
+    match (thumbnail(a), thumbnail(b)) {
+        (Some(a), Some(b)) => max_block_difference(&a, &b) > threshold,
+        _ => true,
+    }
+}
+
+/// 前后两张截图都存在且画面几乎没有变化 (可以只发送语音给 AI)
+///
+/// `threshold` 不大于 0 时关闭检测
+pub fn frames_unchanged(before: &str, after: &str, threshold: f32) -> bool {
+    threshold > 0.0
+        && !before.is_empty()
+        && !after.is_empty()
+        && !frames_differ(before, after, threshold)
+}
+
+/// 解码并缩放为灰度缩略图
+fn thumbnail(data: &str) -> Option<GrayImage> {
+    let encoded = data.split_once(',').map_or(data, |(_, encoded)| encoded);
+    let bytes = general_purpose::STANDARD.decode(encoded.trim()).ok()?;
+    let image = image::load_from_memory(&bytes).ok()?;
+
+    Some(
+        image
+            .resize_exact(THUMB_WIDTH, THUMB_HEIGHT, FilterType::Triangle)
+            .to_luma8(),
+    )
+}
+
+/// 各块平均绝对差的最大值 (0-1)
+fn max_block_difference(a: &GrayImage, b: &GrayImage) -> f32 {
+    let mut max_diff = 0.0f32;
+
+    for block_y in (0..THUMB_HEIGHT).step_by(BLOCK_SIZE as usize) {
+        for block_x in (0..THUMB_WIDTH).step_by(BLOCK_SIZE as usize) {
+            let mut sum = 0u32;
+            let mut count = 0u32;
+            for y in block_y..(block_y + BLOCK_SIZE).min(THUMB_HEIGHT) {
+                for x in block_x..(block_x + BLOCK_SIZE).min(THUMB_WIDTH) {
+                    sum += a.get_pixel(x, y)[0].abs_diff(b.get_pixel(x, y)[0]) as u32;
+                    count += 1;
+                }
+            }
+            max_diff = max_diff.max(sum as f32 / count as f32 / 255.0);
+        }
+    }
+
+    max_diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screenshot::CaptureMode;
+    use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+    use std::io::Cursor;
+
+    fn screenshot(image: RgbaImage) -> Screenshot {
+        let (width, height) = image.dimensions();
+        let mut buffer = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .unwrap();
+
+        Screenshot {
+            data: format!(
+                "data:image/png;base64,{}",
+                general_purpose::STANDARD.encode(buffer.into_inner())
+            ),
+            width,
+            height,
+            timestamp: 0,
+            display_id: None,
+            mode: CaptureMode::Fullscreen,
+        }
+    }
+
+    fn solid(value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(320, 180, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_identical_screenshots() {
+        let a = screenshot(solid(40));
+        let b = screenshot(solid(40));
+
+        assert!(!screenshots_differ(&a, &b, 0.03));
+        assert!(frames_unchanged(&a.data, &b.data, 0.03));
+    }
+
+    #[test]
+    fn test_different_screenshots() {
+        let black = screenshot(solid(0));
+        let white = screenshot(solid(255));
+        assert!(screenshots_differ(&black, &white, 0.03));
+
+        // 只有一角发生变化 (例如弹出对话框) 也视为不同
+        let mut corner = solid(0);
+        for y in 0..40 {
+            for x in 0..60 {
+                corner.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            }
+        }
+        assert!(screenshots_differ(&black, &screenshot(corner), 0.03));
+    }
+
+    #[test]
+    fn test_unreadable_frames() {
+        let a = screenshot(solid(0));
+
+        // 解码失败视为有变化, 空截图或关闭检测时不跳过
+        assert!(frames_differ(&a.data, "data:image/png;base64,???", 0.03));
+        assert!(!frames_unchanged(&a.data, "", 0.03));
+        assert!(!frames_unchanged(&a.data, &a.data, 0.0));
+    }
+}
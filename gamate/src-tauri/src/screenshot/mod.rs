@@ -1,4 +1,8 @@
+pub mod activity;
 pub mod capture;
+pub mod diff;
+pub mod ocr;
+pub mod scene_hash;
 pub mod types;
 pub mod window;
 
@@ -0,0 +1,72 @@
+// OCR 文字识别
+// Windows 使用 Windows.Media.Ocr API, 其他平台暂不支持
+
+use super::types::*;
+
+/// 识别图片中的文字
+///
+/// 多行文字以换行符连接
+#[cfg(windows)]
+pub fn recognize_text(image: &image::RgbaImage) -> Result<String> {
+    use windows::{
+        core::HSTRING,
+        Globalization::Language,
+        Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap},
+        Media::Ocr::OcrEngine,
+        Storage::Streams::DataWriter,
+    };
+
+    let map_err = |e: windows::core::Error| ScreenshotError::OcrFailed(e.to_string());
+
+    // Windows OCR 需要 BGRA8 像素格式
+    let mut bgra = image.as_raw().clone();
+    for pixel in bgra.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+
+    let writer = DataWriter::new().map_err(map_err)?;
+    writer.WriteBytes(&bgra).map_err(map_err)?;
+    let buffer = writer.DetachBuffer().map_err(map_err)?;
+
+    let bitmap = SoftwareBitmap::CreateCopyFromBuffer(
+        &buffer,
+        BitmapPixelFormat::Bgra8,
+        image.width() as i32,
+        image.height() as i32,
+    )
+    .map_err(map_err)?;
+
+    // 优先使用简体中文, 不可用时回退到用户系统语言
+    let engine = Language::CreateLanguage(&HSTRING::from("zh-Hans-CN"))
+        .and_then(|lang| OcrEngine::TryCreateFromLanguage(&lang))
+        .or_else(|_| OcrEngine::TryCreateFromUserProfileLanguages())
+        .map_err(|e| ScreenshotError::OcrFailed(format!("无法创建 OCR 引擎: {}", e)))?;
+
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .map_err(map_err)?
+        .get()
+        .map_err(map_err)?;
+
+    let mut lines = Vec::new();
+    for line in result.Lines().map_err(map_err)? {
+        lines.push(line.Text().map_err(map_err)?.to_string());
+    }
+
+    let text = lines.join("\n");
+    log::info!(
+        "🔤 OCR 识别完成: {} 行, {} 字符",
+        lines.len(),
+        text.chars().count()
+    );
+
+    Ok(text)
+}
+
+/// 识别图片中的文字 (当前平台不支持)
+#[cfg(not(windows))]
+pub fn recognize_text(_image: &image::RgbaImage) -> Result<String> {
+    Err(ScreenshotError::OcrFailed(
+        "当前平台不支持 OCR (仅支持 Windows)".to_string(),
+    ))
+}
@@ -0,0 +1,62 @@
+/// 场景感知哈希 (dHash)
+///
+/// 将画面缩放为 9x8 灰度图, 比较相邻像素亮度得到 64 位指纹;
+/// 画面相近时哈希的汉明距离很小, 可用于判断场景是否变化
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+
+/// 计算图片的感知哈希
+pub fn scene_hash(image: &RgbaImage) -> u64 {
+    let gray = DynamicImage::ImageRgba8(image.clone())
+        .resize_exact(9, 8, FilterType::Triangle)
+        .to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = gray.get_pixel(x, y)[0];
+            let right = gray.get_pixel(x + 1, y)[0];
+            hash <<= 1;
+            if left > right {
+                hash |= 1;
+            }
+        }
+    }
+
+    hash
+}
+
+/// 计算图片的感知哈希 (16 位十六进制字符串)
+pub fn scene_hash_hex(image: &RgbaImage) -> String {
+    format!("{:016x}", scene_hash(image))
+}
+
+/// 两个哈希之间的汉明距离 (0 表示几乎相同, 最大 64)
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn gradient(width: u32, height: u32, reversed: bool) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, _| {
+            let v = (x * 255 / (width - 1)) as u8;
+            let v = if reversed { 255 - v } else { v };
+            Rgba([v, v, v, 255])
+        })
+    }
+
+    #[test]
+    fn test_scene_hash_similarity() {
+        let a = scene_hash(&gradient(320, 180, false));
+        let b = scene_hash(&gradient(640, 360, false));
+        let c = scene_hash(&gradient(320, 180, true));
+
+        // 同一画面不同分辨率哈希接近, 反向渐变差异很大
+        assert!(hamming_distance(a, b) <= 4);
+        assert!(hamming_distance(a, c) > 32);
+        assert_eq!(scene_hash_hex(&gradient(320, 180, false)).len(), 16);
+    }
+}
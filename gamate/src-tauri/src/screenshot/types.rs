@@ -63,6 +63,9 @@ pub enum ScreenshotError {
     #[error("无效的截图区域")]
     InvalidArea,
 
+    #[error("OCR 识别失败: {0}")]
+    OcrFailed(String),
+
     #[error("IO 错误: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::path::PathBuf;
 
@@ -25,6 +26,9 @@ pub struct AppSettings {
     /// 模拟场景设置
     #[serde(default)]
     pub simulation: SimulationSettings,
+    /// 本地 HTTP API 设置
+    #[serde(default)]
+    pub api_server: ApiServerSettings,
 }
 
 /// 用户设置
@@ -82,6 +86,49 @@ pub struct GeneralSettings {
     /// 直播间 HUD 窗口位置
     #[serde(default)]
     pub livestream_hud_position: Option<HudPosition>,
+    /// 语音输入设备名称 (None 使用系统默认麦克风)
+    #[serde(default)]
+    pub input_device: Option<String>,
+    /// 语音识别后端 (windows, whisper, aliyun)
+    #[serde(default = "default_stt_backend")]
+    pub stt_backend: String,
+    /// Whisper 模型文件路径 (ggml 格式, stt_backend 为 whisper 时使用)
+    #[serde(default)]
+    pub whisper_model_path: Option<String>,
+    /// 是否保存识别前的语音为 WAV 文件 (调试用, 默认关闭)
+    #[serde(default)]
+    pub save_debug_audio: bool,
+    /// 调试音频保存目录 (None 时使用配置目录下的 debug_audio)
+    #[serde(default)]
+    pub debug_audio_dir: Option<String>,
+}
+
+impl GeneralSettings {
+    /// 调试音频的输出目录, 未开启 save_debug_audio 或目录无法创建时返回 None
+    pub fn debug_audio_output_dir(&self) -> Option<PathBuf> {
+        if !self.save_debug_audio {
+            return None;
+        }
+
+        let dir = match self
+            .debug_audio_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+        {
+            Some(dir) => PathBuf::from(dir),
+            None => AppSettings::config_dir()
+                .map(|dir| dir.join("debug_audio"))
+                .unwrap_or_else(|_| env::temp_dir().join("gamate_debug_audio")),
+        };
+
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            log::warn!("⚠️ 无法创建调试音频目录 {:?}: {}", dir, e);
+            return None;
+        }
+
+        Some(dir)
+    }
 }
 
 /// HUD 窗口位置
@@ -95,6 +142,10 @@ fn default_hud_mode() -> bool {
     true
 }
 
+fn default_stt_backend() -> String {
+    "aliyun".to_string()
+}
+
 /// 技能库设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -109,6 +160,52 @@ pub struct SkillLibrarySettings {
     pub update_check_interval: u32,
     /// 爬虫设置
     pub crawler: CrawlerSettings,
+    /// 剧透过滤设置
+    #[serde(default)]
+    pub spoiler_filter: SpoilerFilterSettings,
+}
+
+/// 剧透过滤设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SpoilerFilterSettings {
+    /// 是否启用 (启用后检索默认排除剧透条目)
+    #[serde(default)]
+    pub enabled: bool,
+    /// 导入时直接丢弃剧透条目 (否则仅在检索时过滤)
+    #[serde(default)]
+    pub drop_on_import: bool,
+    /// 通用剧透关键词 (匹配标题和分类)
+    #[serde(default = "default_spoiler_patterns")]
+    pub patterns: Vec<String>,
+    /// 各游戏额外的剧透关键词 (game_id -> 关键词)
+    #[serde(default)]
+    pub game_patterns: HashMap<String, Vec<String>>,
+}
+
+fn default_spoiler_patterns() -> Vec<String> {
+    [
+        "Ending",
+        "Spoiler",
+        "Secret Boss",
+        "结局",
+        "剧透",
+        "隐藏Boss",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+impl Default for SpoilerFilterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drop_on_import: false,
+            patterns: default_spoiler_patterns(),
+            game_patterns: HashMap::new(),
+        }
+    }
 }
 
 /// 爬虫设置
@@ -121,6 +218,13 @@ pub struct CrawlerSettings {
     pub max_concurrent_requests: usize,
     /// 超时时间 (秒)
     pub timeout_seconds: u64,
+    /// 单个条目的最大字符数 (超出时截断, 0 表示不限制)
+    #[serde(default = "default_max_entry_chars")]
+    pub max_entry_chars: usize,
+}
+
+fn default_max_entry_chars() -> usize {
+    crate::crawler::DEFAULT_MAX_ENTRY_CHARS
 }
 
 /// 截图设置
@@ -137,12 +241,40 @@ pub struct ScreenshotSettings {
     /// 目标窗口名称 (用于显示)
     #[serde(default)]
     pub target_window_name: Option<String>,
+    /// 全屏/区域截图使用的显示器序号 (为空时使用主显示器)
+    #[serde(default)]
+    pub capture_display_id: Option<usize>,
     /// 活跃模式截图间隔 (秒)
     pub active_interval_seconds: u64,
     /// 闲置模式截图间隔 (秒)
     pub idle_interval_seconds: u64,
+    /// 无键盘/鼠标输入超过该时间 (秒) 视为闲置
+    #[serde(default = "default_idle_threshold_seconds")]
+    pub idle_threshold_seconds: u64,
+    /// 定时截图: 超过该时间 (秒) 未检测到语音时切换为闲置间隔
+    #[serde(default = "default_speech_idle_seconds")]
+    pub speech_idle_seconds: u64,
     /// 是否自动发送给 AI 分析
     pub auto_send_to_ai: bool,
+    /// 前后截图差异低于该值 (0-1, 块平均亮度差) 时跳过视觉分析, 只发送语音; 0 表示关闭
+    #[serde(default = "default_min_screen_diff")]
+    pub min_screen_diff: f32,
+    /// 各游戏的字幕/对话框区域 (game_id -> 区域), 用于 OCR
+    #[serde(default)]
+    pub subtitle_regions: HashMap<String, SubtitleRegion>,
+}
+
+/// 字幕/对话框区域
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SubtitleRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 显示器 ID (默认主显示器)
+    #[serde(default)]
+    pub display_id: Option<usize>,
 }
 
 impl Default for ScreenshotSettings {
@@ -152,9 +284,14 @@ impl Default for ScreenshotSettings {
             capture_mode: "fullscreen".to_string(),
             target_window_id: None,
             target_window_name: None,
+            capture_display_id: None,
             active_interval_seconds: 5,
             idle_interval_seconds: 15,
+            idle_threshold_seconds: default_idle_threshold_seconds(),
+            speech_idle_seconds: default_speech_idle_seconds(),
             auto_send_to_ai: true,
+            min_screen_diff: default_min_screen_diff(),
+            subtitle_regions: HashMap::new(),
         }
     }
 }
@@ -186,12 +323,22 @@ pub struct TtsSettings {
     pub volume: f32,
     /// AI 回复时自动播报
     pub auto_speak: bool,
+    /// 播报输出设备名称 (None 使用系统默认设备)
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// 播报期间忽略麦克风输入 (避免 AI 识别到自己的声音)
+    #[serde(default = "default_echo_suppression")]
+    pub echo_suppression: bool,
 }
 
 fn default_tts_provider() -> String {
     "windows".to_string()
 }
 
+fn default_echo_suppression() -> bool {
+    true
+}
+
 impl Default for TtsSettings {
     fn default() -> Self {
         Self {
@@ -204,6 +351,8 @@ impl Default for TtsSettings {
             rate: 1.0,
             volume: 0.8,
             auto_speak: true,
+            output_device: None,
+            echo_suppression: default_echo_suppression(),
         }
     }
 }
@@ -221,6 +370,47 @@ pub struct AIModelSettings {
     /// 向量数据库配置
     #[serde(default)]
     pub vector_db: VectorDBSettings,
+    /// RAG 检索配置
+    #[serde(default)]
+    pub rag: RagSettings,
+    /// 全局系统提示词前言 (放在所有 AI 对话的系统提示词最前面, 如"回答简洁""不要剧透""用英文回答")
+    #[serde(default)]
+    pub global_system_preamble: Option<String>,
+    /// 直播间模拟分析是否也使用全局前言 (分析结果必须是 JSON, 默认不使用)
+    #[serde(default)]
+    pub preamble_in_simulation: bool,
+    /// 本地 Ollama 模型常驻设置
+    #[serde(default)]
+    pub ollama_keep_alive: OllamaKeepAliveSettings,
+    /// 对话模型的上下文窗口 (Token), Prompt 超出时裁剪知识库条目和对话历史
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: usize,
+}
+
+impl AIModelSettings {
+    /// 在系统提示词前加上全局前言 (未配置时原样返回)
+    pub fn with_preamble(&self, system_prompt: &str) -> String {
+        prepend_preamble(self.global_system_preamble.as_deref(), system_prompt)
+    }
+
+    /// 直播间模拟分析使用的前言
+    pub fn simulation_preamble(&self) -> Option<String> {
+        self.global_system_preamble
+            .clone()
+            .filter(|_| self.preamble_in_simulation)
+    }
+}
+
+/// 把前言加在系统提示词之前 (空白前言忽略)
+pub fn prepend_preamble(preamble: Option<&str>, system_prompt: &str) -> String {
+    match preamble.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(preamble) => format!("{}\n\n{}", preamble, system_prompt),
+        None => system_prompt.to_string(),
+    }
+}
+
+fn default_context_tokens() -> usize {
+    8192
 }
 
 fn default_ai_personality() -> String {
@@ -231,7 +421,7 @@ fn default_ai_personality() -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct VectorDBSettings {
-    /// 数据库模式 (local, qdrant, ai_direct)
+    /// 数据库模式 (local, qdrant, ai_direct, hybrid)
     pub mode: String,
     /// Qdrant URL (仅在 mode=qdrant 时使用)
     #[serde(default)]
@@ -239,6 +429,72 @@ pub struct VectorDBSettings {
     /// 本地存储路径 (仅在 mode=local 时使用)
     #[serde(default)]
     pub local_storage_path: Option<String>,
+    /// Qdrant 请求失败 (5xx/连接错误) 时的最多重试次数
+    #[serde(default = "default_retry_max")]
+    pub retry_max: u32,
+    /// Qdrant 重试的基础等待时间 (毫秒), 每次重试翻倍
+    #[serde(default = "default_retry_base_ms")]
+    pub retry_base_ms: u64,
+    /// 导入时每批生成 Embedding 的条目数 (默认 50)
+    #[serde(default)]
+    pub import_batch_size: Option<usize>,
+    /// 导入时每个分块的最大字符数 (0 表示不分块)
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// 相邻分块之间重叠的字符数
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: usize,
+    /// 混合检索中向量部分使用的数据库 (local, qdrant; 仅在 mode=hybrid 时使用)
+    #[serde(default = "default_hybrid_vector_mode")]
+    pub hybrid_vector_mode: String,
+    /// 混合检索中向量结果的融合权重 (0-1), 关键词结果的权重为 1 - hybrid_weight
+    #[serde(default = "default_hybrid_weight")]
+    pub hybrid_weight: f32,
+}
+
+/// 默认导入批大小
+pub const DEFAULT_IMPORT_BATCH_SIZE: usize = 50;
+
+impl VectorDBSettings {
+    /// 导入批大小 (未设置时使用默认值, 至少为 1)
+    pub fn batch_size(&self) -> usize {
+        self.import_batch_size
+            .unwrap_or(DEFAULT_IMPORT_BATCH_SIZE)
+            .max(1)
+    }
+
+    /// 存储向量使用的数据库模式 (hybrid 模式下为其向量部分的模式)
+    pub fn vector_mode(&self) -> &str {
+        if self.mode == "hybrid" {
+            &self.hybrid_vector_mode
+        } else {
+            &self.mode
+        }
+    }
+}
+
+fn default_retry_max() -> u32 {
+    3
+}
+
+fn default_retry_base_ms() -> u64 {
+    500
+}
+
+fn default_chunk_size() -> usize {
+    1000
+}
+
+fn default_chunk_overlap() -> usize {
+    150
+}
+
+fn default_hybrid_vector_mode() -> String {
+    "local".to_string()
+}
+
+fn default_hybrid_weight() -> f32 {
+    0.5
 }
 
 impl Default for VectorDBSettings {
@@ -247,10 +503,110 @@ impl Default for VectorDBSettings {
             mode: "local".to_string(),
             qdrant_url: Some("http://localhost:6333".to_string()),
             local_storage_path: Some("./data/vector_db".to_string()),
+            retry_max: default_retry_max(),
+            retry_base_ms: default_retry_base_ms(),
+            import_batch_size: None,
+            chunk_size: default_chunk_size(),
+            chunk_overlap: default_chunk_overlap(),
+            hybrid_vector_mode: default_hybrid_vector_mode(),
+            hybrid_weight: default_hybrid_weight(),
+        }
+    }
+}
+
+/// RAG 检索设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct RagSettings {
+    /// 检索返回的 Wiki 条目数
+    #[serde(default = "default_rag_top_k")]
+    pub top_k: usize,
+    /// 注入 Prompt 的知识库上下文 Token 预算 (超出时丢弃相关度最低的条目)
+    #[serde(default = "default_context_token_budget")]
+    pub context_token_budget: usize,
+    /// 最低相关度 (0-1), 低于该分数的检索结果不作为参考资料
+    #[serde(default = "default_rag_min_score")]
+    pub min_score: f32,
+    /// 是否用对话模型对检索结果重新打分排序 (开启后建议调大 top_k 作为候选)
+    #[serde(default)]
+    pub rerank: bool,
+    /// 重排后保留的条目数
+    #[serde(default = "default_rerank_top_m")]
+    pub rerank_top_m: usize,
+}
+
+fn default_rag_top_k() -> usize {
+    3
+}
+
+fn default_context_token_budget() -> usize {
+    1500
+}
+
+fn default_rag_min_score() -> f32 {
+    0.2
+}
+
+fn default_rerank_top_m() -> usize {
+    3
+}
+
+impl Default for RagSettings {
+    fn default() -> Self {
+        Self {
+            top_k: default_rag_top_k(),
+            context_token_budget: default_context_token_budget(),
+            min_score: default_rag_min_score(),
+            rerank: false,
+            rerank_top_m: default_rerank_top_m(),
+        }
+    }
+}
+
+/// Ollama 模型常驻设置
+///
+/// Ollama 默认在空闲 5 分钟后卸载模型, 之后的第一次提问需要重新加载 (数秒)。
+/// 开启后定期预热对话模型使其保持在内存中, 会一直占用显存, 默认关闭
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OllamaKeepAliveSettings {
+    /// 是否开启
+    #[serde(default)]
+    pub enabled: bool,
+    /// 每次请求后模型在内存中保留的时长 (Ollama keep_alive 格式, 如 "10m" "1h" "-1")
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive: String,
+    /// 后台预热间隔 (分钟)
+    #[serde(default = "default_keep_alive_interval")]
+    pub ping_interval_minutes: u32,
+}
+
+fn default_keep_alive() -> String {
+    "10m".to_string()
+}
+
+fn default_keep_alive_interval() -> u32 {
+    4
+}
+
+impl Default for OllamaKeepAliveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_alive: default_keep_alive(),
+            ping_interval_minutes: default_keep_alive_interval(),
         }
     }
 }
 
+impl OllamaKeepAliveSettings {
+    /// 对话请求使用的 keep_alive (未开启时使用 Ollama 默认值)
+    pub fn request_keep_alive(&self) -> Option<String> {
+        let keep_alive = self.keep_alive.trim();
+        (self.enabled && !keep_alive.is_empty()).then(|| keep_alive.to_string())
+    }
+}
+
 /// 模型配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -291,6 +647,11 @@ impl Default for AppSettings {
                 hud_mode: default_hud_mode(),
                 hud_position: None,
                 livestream_hud_position: None,
+                input_device: None,
+                stt_backend: default_stt_backend(),
+                whisper_model_path: None,
+                save_debug_audio: false,
+                debug_audio_dir: None,
             },
             user: UserSettings::default(),
             skill_library: SkillLibrarySettings {
@@ -302,7 +663,9 @@ impl Default for AppSettings {
                     request_delay_ms: 1000,
                     max_concurrent_requests: 5,
                     timeout_seconds: 30,
+                    max_entry_chars: default_max_entry_chars(),
                 },
+                spoiler_filter: SpoilerFilterSettings::default(),
             },
             ai_models: AIModelSettings {
                 embedding: ModelConfig {
@@ -328,11 +691,24 @@ impl Default for AppSettings {
                     mode: "local".to_string(),
                     qdrant_url: None,
                     local_storage_path: Some("./data/vector_db".to_string()),
+                    retry_max: default_retry_max(),
+                    retry_base_ms: default_retry_base_ms(),
+                    import_batch_size: None,
+                    chunk_size: default_chunk_size(),
+                    chunk_overlap: default_chunk_overlap(),
+                    hybrid_vector_mode: default_hybrid_vector_mode(),
+                    hybrid_weight: default_hybrid_weight(),
                 },
+                rag: RagSettings::default(),
+                global_system_preamble: None,
+                preamble_in_simulation: false,
+                ollama_keep_alive: OllamaKeepAliveSettings::default(),
+                context_tokens: default_context_tokens(),
             },
             screenshot: ScreenshotSettings::default(),
             tts: TtsSettings::default(),
             simulation: SimulationSettings::default(),
+            api_server: ApiServerSettings::default(),
         }
     }
 }
@@ -494,6 +870,28 @@ pub struct SimulationSettings {
     /// AI 员工列表
     #[serde(default)]
     pub employees: Vec<AIEmployeeConfig>,
+    /// OBS 叠加层服务设置
+    #[serde(default)]
+    pub overlay: OverlaySettings,
+    /// 是否保存员工的对话记忆 (停止时保存, 下次启动时恢复)
+    #[serde(default)]
+    pub persist_memory: bool,
+    /// 每个员工保留的最大消息条数 (超出时淘汰最早的消息)
+    #[serde(default = "default_max_history")]
+    pub max_history: usize,
+    /// 事件日志路径 (JSONL, 为空时不记录)
+    #[serde(default)]
+    pub event_log_path: Option<String>,
+    /// 礼物列表 (按权重随机选择)
+    #[serde(default = "default_gifts")]
+    pub gifts: Vec<GiftConfig>,
+    /// AI 分析的备用模型 (主模型重试后仍失败时使用, 共用多模态模型的 API 地址和 Key)
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+}
+
+fn default_max_history() -> usize {
+    crate::simulation::memory::DEFAULT_MAX_HISTORY
 }
 
 impl Default for SimulationSettings {
@@ -501,6 +899,112 @@ impl Default for SimulationSettings {
         Self {
             livestream: LivestreamConfig::default(),
             employees: Vec::new(),
+            overlay: OverlaySettings::default(),
+            persist_memory: false,
+            max_history: default_max_history(),
+            event_log_path: None,
+            gifts: default_gifts(),
+            fallback_model: None,
+        }
+    }
+}
+
+/// 礼物配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct GiftConfig {
+    /// 礼物名称
+    pub name: String,
+    /// 礼物图标 (可为空)
+    #[serde(default)]
+    pub emoji: String,
+    /// 随机权重 (0 表示不随机送出)
+    #[serde(default = "default_gift_weight")]
+    pub weight: u32,
+    /// 礼物价值
+    #[serde(default)]
+    pub value: u32,
+}
+
+impl GiftConfig {
+    fn new(name: &str, emoji: &str, weight: u32, value: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+            weight,
+            value,
+        }
+    }
+
+    /// 弹幕/事件中显示的名称 (图标 + 名称, 如 "🚀火箭")
+    pub fn label(&self) -> String {
+        format!("{}{}", self.emoji, self.name)
+    }
+}
+
+fn default_gift_weight() -> u32 {
+    1
+}
+
+/// 默认礼物 (与早期版本的固定礼物列表一致)
+pub fn default_gifts() -> Vec<GiftConfig> {
+    vec![
+        GiftConfig::new("火箭", "🚀", 1, 500),
+        GiftConfig::new("鲜花", "🌹", 1, 1),
+        GiftConfig::new("666", "", 1, 6),
+    ]
+}
+
+/// OBS 叠加层服务设置 (本地 HTTP + WebSocket, 推送模拟事件)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct OverlaySettings {
+    /// 是否在启动时开启
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口 (仅监听 127.0.0.1)
+    #[serde(default = "default_overlay_port")]
+    pub port: u16,
+}
+
+fn default_overlay_port() -> u16 {
+    17890
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_overlay_port(),
+        }
+    }
+}
+
+/// 本地 HTTP API 设置 (供 Stream Deck、脚本等外部工具调用)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ApiServerSettings {
+    /// 是否在启动时开启
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口 (仅监听 127.0.0.1)
+    #[serde(default = "default_api_server_port")]
+    pub port: u16,
+    /// 访问令牌 (请求头 `Authorization: Bearer <token>`, 为空时首次启动自动生成)
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_api_server_port() -> u16 {
+    17891
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_server_port(),
+            token: String::new(),
         }
     }
 }
@@ -521,6 +1025,32 @@ pub struct LivestreamConfig {
     pub gift_frequency: String,
     /// 是否可上麦
     pub allow_mic: bool,
+    /// 两次 AI 分析之间的最小间隔 (毫秒)
+    #[serde(default = "default_ai_analysis_cooldown_ms")]
+    pub ai_analysis_cooldown_ms: u64,
+    /// 分析进行中时新请求的处理方式 (drop: 丢弃, queue: 排队只保留最新一个)
+    #[serde(default = "default_ai_analysis_busy_policy")]
+    pub ai_analysis_busy_policy: String,
+}
+
+fn default_min_screen_diff() -> f32 {
+    0.03
+}
+
+fn default_idle_threshold_seconds() -> u64 {
+    60
+}
+
+fn default_speech_idle_seconds() -> u64 {
+    30
+}
+
+fn default_ai_analysis_cooldown_ms() -> u64 {
+    3000
+}
+
+fn default_ai_analysis_busy_policy() -> String {
+    "drop".to_string()
 }
 
 impl Default for LivestreamConfig {
@@ -532,6 +1062,8 @@ impl Default for LivestreamConfig {
             danmaku_frequency: "medium".to_string(),
             gift_frequency: "medium".to_string(),
             allow_mic: true,
+            ai_analysis_cooldown_ms: default_ai_analysis_cooldown_ms(),
+            ai_analysis_busy_policy: default_ai_analysis_busy_policy(),
         }
     }
 }
@@ -548,4 +1080,10 @@ pub struct AIEmployeeConfig {
     pub interaction_frequency: String,
     /// AI 昵称
     pub nickname: String,
+    /// 头像 URL (可选)
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// 用户名颜色 (如 "#FF6B6B", 未设置时自动生成)
+    #[serde(default)]
+    pub color: Option<String>,
 }
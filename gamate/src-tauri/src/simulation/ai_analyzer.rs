@@ -1,5 +1,7 @@
-use crate::llm::OpenAIClient;
-use crate::settings::ModelConfig;
+use super::gifts::GiftCatalog;
+use crate::llm::{count_tokens, is_transient_error, remaining_budget, OpenAIClient};
+use crate::personality::PersonalityRegistry;
+use crate::settings::{prepend_preamble, ModelConfig};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
 /// AI 分析服务
@@ -7,6 +9,23 @@ use base64::{engine::general_purpose, Engine as _};
 /// 接收主播语音 + 双截图 + 员工对话历史，返回智能化的弹幕回复
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// 临时错误重试前的等待时间
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// 单次调用模型失败的原因
+struct CallError {
+    message: String,
+    /// 是否为值得重试的临时错误 (超时、限流、5xx 等)
+    transient: bool,
+}
+
+/// 每个员工最多带入提示词的对话历史条数
+const MAX_HISTORY_IN_PROMPT: usize = 5;
+
+/// 默认的模型上下文窗口 (Token)
+const DEFAULT_CONTEXT_TOKENS: usize = 8192;
 
 /// AI 分析请求
 #[derive(Debug, Clone, Serialize)]
@@ -78,11 +97,27 @@ pub struct EmployeeAction {
     pub gift_count: Option<u32>,
 }
 
+/// 带截图时的系统提示词
+const VISION_SYSTEM_PROMPT: &str = "你是一个直播间互动分析专家。根据主播的语音和游戏画面变化，为每个AI员工生成自然、有趣、符合其性格的弹幕回复。\n\n你必须严格按照以下JSON格式返回，不要包含任何其他文字：\n{\n  \"actions\": [\n    {\n      \"employee\": \"员工ID\",\n      \"content\": \"弹幕内容\",\n      \"gift\": false\n    }\n  ]\n}";
+
+/// 没有截图时的系统提示词（纯文本分析）
+const TEXT_ONLY_SYSTEM_PROMPT: &str = "你是一个直播间互动分析专家。当前无法获取游戏画面，请仅根据主播的语音内容，为每个AI员工生成自然、有趣、符合其性格的弹幕回复。\n\n你必须严格按照以下JSON格式返回，不要包含任何其他文字：\n{\n  \"actions\": [\n    {\n      \"employee\": \"员工ID\",\n      \"content\": \"弹幕内容\",\n      \"gift\": false\n    }\n  ]\n}";
+
 /// AI 分析器
 #[derive(Clone)]
 pub struct AIAnalyzer {
     client: Arc<OpenAIClient>,
     model: String,
+    /// 主模型配置 (创建备用模型客户端时复用 API 地址和 Key)
+    config: ModelConfig,
+    /// 备用模型 (主模型失败后使用)
+    fallback: Option<(String, Arc<OpenAIClient>)>,
+    /// 全局系统提示词前言 (设置中开启 `preamble_in_simulation` 时使用)
+    preamble: Option<String>,
+    /// 礼物目录 (提示词中的可选礼物, 并校验返回的礼物名称)
+    gift_catalog: GiftCatalog,
+    /// 模型上下文窗口 (Token), 提示词超出时裁剪对话历史
+    context_tokens: usize,
 }
 
 impl AIAnalyzer {
@@ -98,17 +133,159 @@ impl AIAnalyzer {
             max_tokens: 2000,
         };
 
-        let client = OpenAIClient::new(config).expect("创建 OpenAI 客户端失败");
+        let client = OpenAIClient::new(config.clone()).expect("创建 OpenAI 客户端失败");
 
         Self {
             client: Arc::new(client),
             model,
+            config,
+            fallback: None,
+            preamble: None,
+            gift_catalog: GiftCatalog::default(),
+            context_tokens: DEFAULT_CONTEXT_TOKENS,
         }
     }
 
+    /// 设置全局系统提示词前言
+    pub fn with_preamble(mut self, preamble: Option<String>) -> Self {
+        self.preamble = preamble;
+        self
+    }
+
+    /// 设置备用模型 (使用相同的 API 地址和 Key, 为空或与主模型相同时不启用)
+    pub fn with_fallback_model(mut self, fallback_model: Option<String>) -> Self {
+        self.fallback = fallback_model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty() && *model != self.model)
+            .and_then(|model| {
+                let config = ModelConfig {
+                    model_name: model.clone(),
+                    ..self.config.clone()
+                };
+                match OpenAIClient::new(config) {
+                    Ok(client) => Some((model, Arc::new(client))),
+                    Err(e) => {
+                        log::warn!("⚠️ 创建备用模型客户端失败: {}", e);
+                        None
+                    }
+                }
+            });
+        self
+    }
+
+    /// 设置礼物目录
+    pub fn with_gift_catalog(mut self, gift_catalog: GiftCatalog) -> Self {
+        self.gift_catalog = gift_catalog;
+        self
+    }
+
+    /// 设置模型上下文窗口 (Token)
+    pub fn with_context_tokens(mut self, context_tokens: usize) -> Self {
+        self.context_tokens = context_tokens;
+        self
+    }
+
     /// 分析主播语音和游戏状态，生成员工互动决策
+    ///
+    /// 截图全部缺失（如截图权限被拒绝）时退化为纯文本分析，仅根据语音内容生成互动
     pub async fn analyze(&self, request: AIAnalysisRequest) -> Result<AIAnalysisResponse, String> {
-        // 清理和验证 base64 图片，过滤掉空截图
+        let images = Self::collect_images(&request);
+
+        let system_prompt = prepend_preamble(
+            self.preamble.as_deref(),
+            if images.is_empty() {
+                TEXT_ONLY_SYSTEM_PROMPT
+            } else {
+                VISION_SYSTEM_PROMPT
+            },
+        );
+
+        // 构建提示词 (超出上下文窗口时裁剪对话历史)
+        let user_prompt = self.build_prompt_within_budget(&request, images.len(), &system_prompt);
+
+        if images.is_empty() {
+            log::info!("📝 没有可用截图，使用纯文本分析");
+        }
+
+        let error = match self
+            .call_with_retry(&self.client, &system_prompt, &user_prompt, &images)
+            .await
+        {
+            Ok(response) => return Ok(response),
+            Err(e) => e,
+        };
+
+        let Some((fallback_model, fallback_client)) = &self.fallback else {
+            return Err(error);
+        };
+
+        log::warn!("⚠️ {}，改用备用模型 {}", error, fallback_model);
+        self.call_with_retry(fallback_client, &system_prompt, &user_prompt, &images)
+            .await
+            .map_err(|e| format!("{}; 备用模型 {} 也失败: {}", error, fallback_model, e))
+    }
+
+    /// 调用模型并解析结果, 临时错误 (超时、限流、5xx 等) 重试一次
+    async fn call_with_retry(
+        &self,
+        client: &OpenAIClient,
+        system_prompt: &str,
+        user_prompt: &str,
+        images: &[String],
+    ) -> Result<AIAnalysisResponse, String> {
+        match self.call_once(client, system_prompt, user_prompt, images).await {
+            Err(e) if e.transient => {
+                log::warn!("⚠️ {}，{} 秒后重试", e.message, RETRY_DELAY.as_secs());
+                tokio::time::sleep(RETRY_DELAY).await;
+                self.call_once(client, system_prompt, user_prompt, images)
+                    .await
+                    .map_err(|e| e.message)
+            }
+            result => result.map_err(|e| e.message),
+        }
+    }
+
+    /// 调用一次模型 (有截图时使用 Multi-Vision API)
+    async fn call_once(
+        &self,
+        client: &OpenAIClient,
+        system_prompt: &str,
+        user_prompt: &str,
+        images: &[String],
+    ) -> Result<AIAnalysisResponse, CallError> {
+        let ai_response = if images.is_empty() {
+            client.chat(system_prompt, user_prompt).await
+        } else {
+            client
+                .chat_with_multi_vision(system_prompt, user_prompt, images)
+                .await
+        }
+        .map_err(|e| CallError {
+            message: format!("AI API 调用失败: {}", e),
+            transient: is_transient_error(&e),
+        })?;
+
+        let mut response = Self::parse_response(&ai_response).map_err(|message| CallError {
+            message,
+            transient: false,
+        })?;
+        self.validate_gifts(&mut response);
+        Ok(response)
+    }
+
+    /// 礼物名称必须来自礼物目录, 未知名称替换为权重最高的礼物
+    fn validate_gifts(&self, response: &mut AIAnalysisResponse) {
+        for action in response.actions.iter_mut().filter(|a| a.gift) {
+            let gift_name = self.gift_catalog.resolve(action.gift_name.as_deref());
+            if action.gift_name.as_deref() != Some(gift_name.as_str()) {
+                log::debug!("🎁 礼物名称 {:?} 不在礼物目录中, 使用 {}", action.gift_name, gift_name);
+            }
+            action.gift_name = Some(gift_name);
+        }
+    }
+
+    /// 清理和验证 base64 图片，过滤掉空截图或无效截图
+    fn collect_images(request: &AIAnalysisRequest) -> Vec<String> {
         let mut images = Vec::new();
 
         // 处理第一张截图
@@ -125,22 +302,44 @@ impl AIAnalyzer {
             }
         }
 
-        // 构建提示词
-        let user_prompt = self.build_prompt(&request, images.len());
-        let system_prompt = "你是一个直播间互动分析专家。根据主播的语音和游戏画面变化，为每个AI员工生成自然、有趣、符合其性格的弹幕回复。\n\n你必须严格按照以下JSON格式返回，不要包含任何其他文字：\n{\n  \"actions\": [\n    {\n      \"employee\": \"员工ID\",\n      \"content\": \"弹幕内容\",\n      \"gift\": false\n    }\n  ]\n}";
+        images
+    }
 
-        // 调用 OpenAI Multi-Vision API
-        let ai_response = self
-            .client
-            .chat_with_multi_vision(system_prompt, &user_prompt, &images)
-            .await
-            .map_err(|e| format!("AI API 调用失败: {}", e))?;
+    /// 解析 AI 返回的 JSON（兼容 ```json 代码块包裹及代码块前后的说明文字）
+    fn parse_response(ai_response: &str) -> Result<AIAnalysisResponse, String> {
+        let json = Self::strip_code_fences(ai_response);
+
+        serde_json::from_str(json)
+            .or_else(|e| {
+                // 仍有多余文字时, 取第一个 `{` 到最后一个 `}` 之间的内容
+                match (json.find('{'), json.rfind('}')) {
+                    (Some(start), Some(end)) if start < end => {
+                        serde_json::from_str(&json[start..=end])
+                    }
+                    _ => Err(e),
+                }
+            })
+            .map_err(|e| format!("解析 AI 响应 JSON 失败: {}\n原始响应: {}", e, ai_response))
+    }
 
-        // 解析 JSON 响应
-        let response: AIAnalysisResponse = serde_json::from_str(&ai_response)
-            .map_err(|e| format!("解析 AI 响应 JSON 失败: {}\n原始响应: {}", e, ai_response))?;
+    /// 去掉 Markdown 代码块标记, 返回代码块内的内容 (没有代码块时返回原文)
+    fn strip_code_fences(text: &str) -> &str {
+        let trimmed = text.trim();
+        let Some(start) = trimmed.find("```") else {
+            return trimmed;
+        };
 
-        Ok(response)
+        // 跳过 ``` 后的语言标记 (如 json)
+        let after_fence = &trimmed[start + 3..];
+        let body = match after_fence.find('\n') {
+            Some(newline) => &after_fence[newline + 1..],
+            None => after_fence.trim_start_matches(|c: char| c.is_ascii_alphabetic()),
+        };
+
+        match body.find("```") {
+            Some(end) => body[..end].trim(),
+            None => body.trim(),
+        }
     }
 
     /// 净化 base64 图片字符串
@@ -184,8 +383,62 @@ impl AIAnalyzer {
         }
     }
 
-    /// 构建提示词
-    fn build_prompt(&self, request: &AIAnalysisRequest, screenshot_count: usize) -> String {
+    /// 构建不超出上下文窗口的提示词
+    ///
+    /// 超出时逐步减少每个员工带入的对话历史 (先去掉最早的消息), 直到放得下或历史全部去掉
+    fn build_prompt_within_budget(
+        &self,
+        request: &AIAnalysisRequest,
+        screenshot_count: usize,
+        system_prompt: &str,
+    ) -> String {
+        let budget = remaining_budget(
+            self.context_tokens,
+            self.config.max_tokens as usize,
+            &[system_prompt],
+        );
+
+        let mut history_limit = MAX_HISTORY_IN_PROMPT;
+        loop {
+            let prompt = self.build_prompt_with_history(request, screenshot_count, history_limit);
+            let tokens = count_tokens(&prompt);
+            if tokens <= budget || history_limit == 0 {
+                if history_limit < MAX_HISTORY_IN_PROMPT {
+                    let dropped: usize = request
+                        .employees
+                        .iter()
+                        .map(|e| {
+                            let len = e.conversation_history.len();
+                            len.min(MAX_HISTORY_IN_PROMPT) - len.min(history_limit)
+                        })
+                        .sum();
+                    log::info!(
+                        "✂️ 提示词超出上下文窗口 ({} Tokens), 裁剪了 {} 条最早的对话历史 (每个员工保留 {} 条)",
+                        self.context_tokens,
+                        dropped,
+                        history_limit
+                    );
+                }
+                if tokens > budget {
+                    log::warn!(
+                        "⚠️ 提示词约 {} Tokens, 仍超出可用预算 {} Tokens",
+                        tokens,
+                        budget
+                    );
+                }
+                return prompt;
+            }
+            history_limit -= 1;
+        }
+    }
+
+    /// 构建提示词, 每个员工最多带入 `history_limit` 条最近的对话历史
+    fn build_prompt_with_history(
+        &self,
+        request: &AIAnalysisRequest,
+        screenshot_count: usize,
+        history_limit: usize,
+    ) -> String {
         let screenshot_info = match screenshot_count {
             0 => "（没有游戏截图，仅根据语音内容分析）",
             1 => "- 图片：主播说话时的游戏状态\n请分析游戏画面中的内容",
@@ -212,18 +465,25 @@ impl AIAnalyzer {
         );
 
         // 添加每个员工的信息
+        let personalities = PersonalityRegistry::current();
         for (i, employee) in request.employees.iter().enumerate() {
+            let personality = personalities.get(&employee.personality);
             prompt.push_str(&format!(
                 "### 员工 {} - {} (性格: {})\n",
                 i + 1,
                 employee.nickname,
-                self.get_personality_description(&employee.personality)
+                personality.description
             ));
+            if !personality.prompt.is_empty() {
+                prompt.push_str(&format!("{}\n", personality.prompt));
+            }
 
             // 添加对话历史
-            if !employee.conversation_history.is_empty() {
+            let history = &employee.conversation_history;
+            let history = &history[history.len().saturating_sub(history_limit)..];
+            if !history.is_empty() {
                 prompt.push_str("**最近对话历史:**\n");
-                for msg in employee.conversation_history.iter().rev().take(5).rev() {
+                for msg in history {
                     let role_label = if msg.role == "user" {
                         "主播"
                     } else {
@@ -260,27 +520,20 @@ impl AIAnalyzer {
             ```\n\n\
             **注意事项:**\n\
             - 如果主播说的话不需要回复（如自言自语、咕哝），可以返回空数组 `[]`\n\
-            - 弹幕要简短、有趣、符合直播间氛围\n\
-            - 礼物名称可选: 🚀火箭, 🌹鲜花, 666, 💎钻石\n\
-            - 不要所有员工都回复，选择1-3个最相关的员工即可\n\
+            - 弹幕要简短、有趣、符合直播间氛围\n",
+        );
+        prompt.push_str(&format!(
+            "- 礼物名称可选: {}\n",
+            self.gift_catalog.labels().join(", ")
+        ));
+        prompt.push_str(
+            "- 不要所有员工都回复，选择1-3个最相关的员工即可\n\
             - 参考员工的对话历史，避免重复相似的内容\n\n\
             请直接返回 JSON，不要包含任何其他说明文字。",
         );
 
         prompt
     }
-
-    /// 获取性格描述
-    fn get_personality_description(&self, personality: &str) -> &'static str {
-        match personality {
-            "sunnyou_male" => "损友男（幽默调侃、兄弟义气）",
-            "funny_female" => "搞笑女（活泼开朗、爱开玩笑）",
-            "kobe" => "科比风格（励志、专注、冠军心态）",
-            "sweet_girl" => "甜妹（温柔可爱、鼓励支持）",
-            "trump" => "特朗普风格（夸张、自信、口号式）",
-            _ => "默认性格",
-        }
-    }
 }
 
 #[cfg(test)]
@@ -317,9 +570,119 @@ mod tests {
             game_id: Some("phasmophobia".to_string()),
         };
 
-        let prompt = analyzer.build_prompt(&request, 0);
+        let prompt = analyzer.build_prompt_with_history(&request, 0, MAX_HISTORY_IN_PROMPT);
         assert!(prompt.contains("直播间互动分析任务"));
         assert!(prompt.contains("小明"));
         assert!(prompt.contains("损友男"));
     }
+
+    #[test]
+    fn test_text_only_analysis() {
+        let analyzer = AIAnalyzer::new(
+            "https://api.example.com/v1/chat/completions".to_string(),
+            "test-key".to_string(),
+            "gpt-4o".to_string(),
+        );
+
+        // 截图全部失败: 空字符串和无效 base64 都会被过滤
+        let request = AIAnalysisRequest {
+            streamer_speech: "截图好像坏了，大家还在吗".to_string(),
+            screenshot_before: String::new(),
+            screenshot_after: "data:image/png;base64,".to_string(),
+            employees: vec![EmployeeContext {
+                id: "emp1".to_string(),
+                nickname: "小明".to_string(),
+                personality: "sweet_girl".to_string(),
+                conversation_history: vec![],
+            }],
+            game_id: None,
+        };
+
+        let images = AIAnalyzer::collect_images(&request);
+        assert!(images.is_empty());
+
+        let prompt =
+            analyzer.build_prompt_with_history(&request, images.len(), MAX_HISTORY_IN_PROMPT);
+        assert!(prompt.contains("仅根据语音内容分析"));
+        assert!(!prompt.contains("图片1"));
+
+        let raw = "```json\n{\"actions\": [{\"employee\": \"emp1\", \"content\": \"在呢在呢\", \"gift\": false}]}\n```";
+        let response = AIAnalyzer::parse_response(raw).unwrap();
+        assert_eq!(response.actions.len(), 1);
+        assert_eq!(response.actions[0].employee, "emp1");
+        assert!(!response.actions[0].gift);
+    }
+
+    #[test]
+    fn test_fenced_json_parses() {
+        let raw = "好的，以下是结果：\n```json\n{\"actions\": [{\"employee\": \"emp1\", \"content\": \"666\", \"gift\": true, \"gift_count\": 3}]}\n```\n希望对你有帮助";
+        let response = AIAnalyzer::parse_response(raw).unwrap();
+        assert_eq!(response.actions.len(), 1);
+        assert_eq!(response.actions[0].gift_count, Some(3));
+
+        let raw = "```\n{\"actions\": []}\n```";
+        assert!(AIAnalyzer::parse_response(raw).unwrap().actions.is_empty());
+
+        let raw = "```json{\"actions\": []}```";
+        assert!(AIAnalyzer::parse_response(raw).unwrap().actions.is_empty());
+
+        assert!(AIAnalyzer::parse_response("抱歉，我无法回答").is_err());
+    }
+
+    #[test]
+    fn test_prompt_trims_oldest_history() {
+        let history = (1..=5)
+            .map(|i| ConversationMessage {
+                role: "user".to_string(),
+                content: format!("第{}条{}", i, "很长的对话内容".repeat(30)),
+            })
+            .collect();
+        let request = AIAnalysisRequest {
+            streamer_speech: "来了来了".to_string(),
+            screenshot_before: String::new(),
+            screenshot_after: String::new(),
+            employees: vec![EmployeeContext {
+                id: "emp1".to_string(),
+                nickname: "小明".to_string(),
+                personality: "kobe".to_string(),
+                conversation_history: history,
+            }],
+            game_id: None,
+        };
+
+        let analyzer = AIAnalyzer::new(
+            "https://api.example.com/v1/chat/completions".to_string(),
+            "test-key".to_string(),
+            "gpt-4o".to_string(),
+        );
+
+        // 上下文窗口充足: 保留全部历史
+        let prompt = analyzer.build_prompt_within_budget(&request, 0, TEXT_ONLY_SYSTEM_PROMPT);
+        assert!(prompt.contains("第1条") && prompt.contains("第5条"));
+
+        // 只够放下一条历史: 保留最新的, 丢弃最早的
+        let base = count_tokens(&analyzer.build_prompt_with_history(&request, 0, 0));
+        let context_tokens = 2000 + count_tokens(TEXT_ONLY_SYSTEM_PROMPT) + base + 300;
+        let analyzer = analyzer.with_context_tokens(context_tokens);
+        let prompt = analyzer.build_prompt_within_budget(&request, 0, TEXT_ONLY_SYSTEM_PROMPT);
+        assert!(prompt.contains("第5条"));
+        assert!(!prompt.contains("第1条") && !prompt.contains("第4条"));
+    }
+
+    #[test]
+    fn test_gift_names_validated() {
+        let analyzer = AIAnalyzer::new(
+            "https://api.example.com/v1/chat/completions".to_string(),
+            "test-key".to_string(),
+            "gpt-4o".to_string(),
+        );
+
+        let raw = "{\"actions\": [\
+            {\"employee\": \"emp1\", \"content\": \"送你\", \"gift\": true, \"gift_name\": \"💎钻石\"},\
+            {\"employee\": \"emp2\", \"content\": \"花花\", \"gift\": true, \"gift_name\": \"🌹鲜花\"}]}";
+        let mut response = AIAnalyzer::parse_response(raw).unwrap();
+        analyzer.validate_gifts(&mut response);
+        assert_eq!(response.actions[0].gift_name.as_deref(), Some("🚀火箭"));
+        assert_eq!(response.actions[1].gift_name.as_deref(), Some("🌹鲜花"));
+    }
 }
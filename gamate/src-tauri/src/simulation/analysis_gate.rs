@@ -0,0 +1,217 @@
+/// AI 分析节流器
+///
+/// 智能模式下主播连续说话会触发多次视觉大模型调用, 既浪费费用又会导致回复乱序。
+/// 节流器保证同一时刻最多只有一个分析在进行, 并限制两次分析之间的最小间隔:
+/// - `drop`: 分析进行中时直接丢弃新的请求
+/// - `queue`: 保留最新的一个请求, 等当前分析结束 (且冷却完成) 后执行, 更早排队的请求被替换
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// 分析进行中时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusyPolicy {
+    Drop,
+    Queue,
+}
+
+impl BusyPolicy {
+    /// 从配置字符串解析 (未知值按 drop 处理)
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "queue" => BusyPolicy::Queue,
+            _ => BusyPolicy::Drop,
+        }
+    }
+}
+
+/// 跳过原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// 冷却中, 附带剩余毫秒数
+    Cooldown { remaining_ms: u64 },
+    /// 已有分析进行中
+    Busy,
+    /// 排队期间被更新的请求替换
+    Superseded,
+}
+
+impl SkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Cooldown { .. } => "cooldown",
+            SkipReason::Busy => "busy",
+            SkipReason::Superseded => "superseded",
+        }
+    }
+}
+
+#[derive(Default)]
+struct GateState {
+    in_flight: bool,
+    last_started: Option<Instant>,
+    /// 当前排队中的请求编号 (0 表示无)
+    pending_ticket: u64,
+    next_ticket: u64,
+}
+
+struct GateInner {
+    cooldown: Duration,
+    policy: BusyPolicy,
+    state: Mutex<GateState>,
+    notify: Notify,
+}
+
+/// AI 分析节流器 (可克隆, 克隆共享同一状态)
+#[derive(Clone)]
+pub struct AnalysisGate {
+    inner: Arc<GateInner>,
+}
+
+/// 分析许可, 释放时允许下一个分析开始
+pub struct AnalysisPermit {
+    inner: Arc<GateInner>,
+}
+
+impl Drop for AnalysisPermit {
+    fn drop(&mut self) {
+        self.inner.state.lock().unwrap().in_flight = false;
+        self.inner.notify.notify_waiters();
+    }
+}
+
+impl AnalysisGate {
+    pub fn new(cooldown: Duration, policy: BusyPolicy) -> Self {
+        Self {
+            inner: Arc::new(GateInner {
+                cooldown,
+                policy,
+                state: Mutex::new(GateState::default()),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// 申请执行一次分析
+    ///
+    /// 成功时返回许可 (持有期间视为分析进行中); 被节流时返回跳过原因
+    pub async fn acquire(&self) -> Result<AnalysisPermit, SkipReason> {
+        let ticket = {
+            let mut state = self.inner.state.lock().unwrap();
+
+            if !state.in_flight {
+                let remaining = self.remaining_cooldown(&state);
+                if remaining.is_zero() {
+                    return Ok(self.start(&mut state));
+                }
+                return Err(SkipReason::Cooldown {
+                    remaining_ms: remaining.as_millis() as u64,
+                });
+            }
+
+            if self.inner.policy == BusyPolicy::Drop {
+                return Err(SkipReason::Busy);
+            }
+
+            // 排队, 替换之前排队的请求
+            state.next_ticket += 1;
+            state.pending_ticket = state.next_ticket;
+            state.next_ticket
+        };
+
+        // 替换掉的请求需要被唤醒, 以便返回 Superseded
+        self.inner.notify.notify_waiters();
+
+        loop {
+            let notified = self.inner.notify.notified();
+
+            let wait_cooldown = {
+                let mut state = self.inner.state.lock().unwrap();
+                if state.pending_ticket != ticket {
+                    return Err(SkipReason::Superseded);
+                }
+                if state.in_flight {
+                    None
+                } else {
+                    let remaining = self.remaining_cooldown(&state);
+                    if remaining.is_zero() {
+                        state.pending_ticket = 0;
+                        return Ok(self.start(&mut state));
+                    }
+                    Some(remaining)
+                }
+            };
+
+            match wait_cooldown {
+                Some(remaining) => tokio::time::sleep(remaining).await,
+                None => notified.await,
+            }
+        }
+    }
+
+    fn remaining_cooldown(&self, state: &GateState) -> Duration {
+        state
+            .last_started
+            .map(|t| self.inner.cooldown.saturating_sub(t.elapsed()))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    fn start(&self, state: &mut GateState) -> AnalysisPermit {
+        state.in_flight = true;
+        state.last_started = Some(Instant::now());
+        AnalysisPermit {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for AnalysisGate {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(3000), BusyPolicy::Drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drop_and_cooldown() {
+        let gate = AnalysisGate::new(Duration::from_millis(50), BusyPolicy::Drop);
+
+        let permit = gate.acquire().await.unwrap();
+        assert_eq!(gate.acquire().await.err(), Some(SkipReason::Busy));
+        drop(permit);
+
+        assert!(matches!(
+            gate.acquire().await.err(),
+            Some(SkipReason::Cooldown { .. })
+        ));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(gate.acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queue_keeps_latest() {
+        let gate = AnalysisGate::new(Duration::ZERO, BusyPolicy::Queue);
+        let permit = gate.acquire().await.unwrap();
+
+        let first = tokio::spawn({
+            let gate = gate.clone();
+            async move { gate.acquire().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let second = tokio::spawn({
+            let gate = gate.clone();
+            async move { gate.acquire().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        drop(permit);
+
+        assert_eq!(first.await.unwrap(), Err(SkipReason::Superseded));
+        assert_eq!(second.await.unwrap(), Ok(()));
+    }
+}
@@ -0,0 +1,157 @@
+/// 弹幕生成
+///
+/// 使用多模态模型配置, 以员工性格为系统提示词、最近的记忆为上下文生成弹幕
+use anyhow::{anyhow, bail, Result};
+use std::time::Duration;
+
+use super::memory::Message;
+use crate::llm::{process_response, FinishReason, OllamaClient, OpenAIClient};
+use crate::settings::ModelConfig;
+
+/// LLM 生成弹幕的超时时间 (超时后使用模板)
+const DANMAKU_LLM_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// 弹幕最大 Token 数
+const DANMAKU_MAX_TOKENS: u32 = 200;
+
+/// 弹幕最大字符数 (超出时截断)
+const DANMAKU_MAX_CHARS: usize = 50;
+
+/// 作为上下文的最近记忆条数
+const CONTEXT_MESSAGES: usize = 10;
+
+/// 使用 LLM 生成一条弹幕
+///
+/// `model` 为模拟引擎加载配置时读取的多模态模型配置。
+/// 未配置模型、调用失败、超时或回复为空时返回错误, 由调用方回退到模板
+pub async fn generate_with_llm(
+    model: &ModelConfig,
+    personality: &str,
+    nickname: &str,
+    history: &[Message],
+) -> Result<String> {
+    let mut config = model.clone();
+
+    if !config.enabled {
+        bail!("多模态模型未启用");
+    }
+    let is_local = config.provider == "local";
+    if !is_local && config.api_key.is_none() {
+        bail!("未配置 API Key (提供商: {})", config.provider);
+    }
+    config.max_tokens = config.max_tokens.min(DANMAKU_MAX_TOKENS);
+
+    let system_prompt = build_system_prompt(personality, nickname);
+    let user_prompt = build_user_prompt(history);
+
+    let request = async {
+        if is_local {
+            OllamaClient::new(config)?
+                .chat(&system_prompt, &user_prompt)
+                .await
+        } else {
+            OpenAIClient::new(config)?
+                .chat(&system_prompt, &user_prompt)
+                .await
+        }
+    };
+
+    let raw = tokio::time::timeout(DANMAKU_LLM_TIMEOUT, request)
+        .await
+        .map_err(|_| anyhow!("生成弹幕超时 ({} 秒)", DANMAKU_LLM_TIMEOUT.as_secs()))??;
+
+    let processed = process_response(&raw);
+    if processed.finish_reason != FinishReason::Stop {
+        bail!("模型没有返回可用的弹幕 ({:?})", processed.finish_reason);
+    }
+
+    clean_danmaku(&processed.text).ok_or_else(|| anyhow!("模型返回了空弹幕"))
+}
+
+/// 系统提示词: 员工性格 (优先使用角色配置文件) + 弹幕要求
+fn build_system_prompt(personality: &str, nickname: &str) -> String {
+    let character = match crate::personality::load_personality(personality) {
+        Ok(config) => format!(
+            "{}\n{}",
+            config.system.role.trim(),
+            config.system.personality.trim()
+        ),
+        Err(_) => crate::personality::PersonalityRegistry::current()
+            .get(personality)
+            .prompt
+            .clone(),
+    };
+
+    format!(
+        "你是直播间里的一名观众, 昵称「{}」。\n\n## 你的性格\n{}\n\n## 要求\n- 根据之前的互动发一条符合你性格的弹幕\n- 不超过 30 个字, 口语化\n- 只输出弹幕内容本身, 不要加引号、昵称或任何解释",
+        nickname, character
+    )
+}
+
+/// 用户提示词: 最近的互动记录
+fn build_user_prompt(history: &[Message]) -> String {
+    let recent = &history[history.len().saturating_sub(CONTEXT_MESSAGES)..];
+    if recent.is_empty() {
+        return "直播刚开始, 请发一条弹幕:".to_string();
+    }
+
+    let mut prompt = String::from("最近的互动:\n");
+    for msg in recent {
+        let speaker = if msg.role == "user" { "主播" } else { "我" };
+        prompt.push_str(&format!("{}: {}\n", speaker, msg.content));
+    }
+    prompt.push_str("\n请发一条新的弹幕 (不要重复之前说过的话):");
+    prompt
+}
+
+/// 只保留第一行, 去掉包裹的引号并限制长度
+fn clean_danmaku(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line
+        .trim_matches(|c| matches!(c, '"' | '\'' | '“' | '”' | '「' | '」'))
+        .trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    Some(line.chars().take(DANMAKU_MAX_CHARS).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_clean_danmaku() {
+        assert_eq!(
+            clean_danmaku("\n“这波操作可以啊!”\n解释: ...").as_deref(),
+            Some("这波操作可以啊!")
+        );
+        assert_eq!(clean_danmaku("\"\"").as_deref(), None);
+        assert_eq!(
+            clean_danmaku(&"哈".repeat(80)).map(|s| s.chars().count()),
+            Some(DANMAKU_MAX_CHARS)
+        );
+    }
+
+    #[test]
+    fn test_user_prompt_uses_recent_history() {
+        assert!(build_user_prompt(&[]).contains("直播刚开始"));
+
+        let history: Vec<Message> = (0..15)
+            .map(|i| message("user", &format!("第{}句", i)))
+            .collect();
+        let prompt = build_user_prompt(&history);
+        assert!(!prompt.contains("第4句"));
+        assert!(prompt.contains("第5句"));
+        assert!(prompt.contains("主播: 第14句"));
+    }
+}
@@ -2,15 +2,25 @@ use rand::Rng;
 /// 直播间模拟引擎
 ///
 /// 核心调度器,负责触发各种事件
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use tokio::time::{interval, sleep};
 
 use super::ai_analyzer::{AIAnalysisRequest, AIAnalyzer, ConversationMessage, EmployeeContext};
-use super::events::{frequency_to_interval, gift_frequency_to_params, EventType, SimulationEvent};
-use super::memory::MemoryManager;
-use crate::settings::AppSettings;
+use super::analysis_gate::{AnalysisGate, BusyPolicy, SkipReason};
+use super::danmaku;
+use super::event_log;
+use super::events::{
+    default_color_for, frequency_to_interval, gift_frequency_to_params, EventType, GiftRequest,
+    SimulationEvent,
+};
+use super::gifts::GiftCatalog;
+use super::memory::{default_memory_path, MemoryManager};
+use crate::personality::PersonalityRegistry;
+use crate::screenshot::diff::frames_unchanged;
+use crate::settings::{AIEmployeeConfig, AppSettings, ModelConfig};
 use crate::tts::TtsEngine;
 
 /// AI 员工配置
@@ -20,6 +30,76 @@ pub struct EmployeeConfig {
     pub personality: String,
     pub interaction_frequency: String,
     pub nickname: String,
+    pub avatar: Option<String>,
+    /// 用户名颜色 (未配置时根据 ID 生成)
+    pub color: String,
+}
+
+impl From<&AIEmployeeConfig> for EmployeeConfig {
+    fn from(emp: &AIEmployeeConfig) -> Self {
+        Self {
+            id: emp.id.clone(),
+            personality: emp.personality.clone(),
+            interaction_frequency: emp.interaction_frequency.clone(),
+            nickname: Self::nickname_or_default(emp),
+            avatar: emp.avatar.clone(),
+            color: emp
+                .color
+                .clone()
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| default_color_for(&emp.id)),
+        }
+    }
+}
+
+impl EmployeeConfig {
+    /// 员工昵称, 未填写时使用角色定义的默认昵称
+    fn nickname_or_default(emp: &AIEmployeeConfig) -> String {
+        if !emp.nickname.trim().is_empty() {
+            return emp.nickname.clone();
+        }
+
+        PersonalityRegistry::current()
+            .get(&emp.personality)
+            .default_nickname
+            .clone()
+            .unwrap_or_else(|| emp.id.clone())
+    }
+
+    /// 构建弹幕事件
+    pub fn danmaku_event(&self, message: String) -> EventType {
+        EventType::Danmaku {
+            employee_id: self.id.clone(),
+            nickname: self.nickname.clone(),
+            message,
+            personality: self.personality.clone(),
+            avatar: self.avatar.clone(),
+            color: self.color.clone(),
+        }
+    }
+
+    /// 构建礼物事件
+    pub fn gift_event(&self, gift_name: String, count: u32) -> EventType {
+        EventType::Gift {
+            employee_id: self.id.clone(),
+            nickname: self.nickname.clone(),
+            gift_name,
+            count,
+            avatar: self.avatar.clone(),
+            color: self.color.clone(),
+        }
+    }
+
+    /// 构建打招呼事件
+    pub fn greeting_event(&self, message: String) -> EventType {
+        EventType::Greeting {
+            employee_id: self.id.clone(),
+            nickname: self.nickname.clone(),
+            message,
+            avatar: self.avatar.clone(),
+            color: self.color.clone(),
+        }
+    }
 }
 
 /// 模拟引擎
@@ -29,13 +109,33 @@ pub struct SimulationEngine {
     is_running: Arc<Mutex<bool>>,
     pub employees: Vec<EmployeeConfig>,
     gift_frequency: String,
+    /// 礼物目录
+    gift_catalog: GiftCatalog,
     pub ai_analyzer: Option<AIAnalyzer>,
+    /// 生成弹幕使用的模型配置 (加载/热重载配置时读取, 未加载时使用模板)
+    danmaku_model: Option<ModelConfig>,
     /// 智能模式开关：true = 等待语音触发, false = 自动循环发送
     pub enable_smart_mode: bool,
     /// TTS 引擎（用于语音播报）
     pub tts_engine: Option<Arc<TtsEngine>>,
     /// 当前游戏 ID
     pub game_id: Option<String>,
+    /// 员工配置版本号 (热重载时递增, 旧的员工循环检测到变化后自行退出)
+    config_generation: Arc<AtomicU64>,
+    /// AI 分析节流器 (冷却 + 单飞)
+    pub analysis_gate: AnalysisGate,
+    /// 是否在停止时保存记忆, 启动时恢复
+    persist_memory: bool,
+}
+
+/// 配置热重载结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadSummary {
+    pub employee_count: usize,
+    pub gift_frequency: String,
+    pub ai_analyzer_enabled: bool,
+    pub is_running: bool,
 }
 
 impl SimulationEngine {
@@ -46,10 +146,15 @@ impl SimulationEngine {
             is_running: Arc::new(Mutex::new(false)),
             employees: Vec::new(),
             gift_frequency: "medium".to_string(),
+            gift_catalog: GiftCatalog::default(),
             ai_analyzer: None,
+            danmaku_model: None,
             enable_smart_mode: true, //  默认启用智能模式
             tts_engine: None,        //  TTS 引擎延迟初始化
             game_id: None,           //  游戏 ID
+            config_generation: Arc::new(AtomicU64::new(0)),
+            analysis_gate: AnalysisGate::default(),
+            persist_memory: false,
         }
     }
     
@@ -68,15 +173,17 @@ impl SimulationEngine {
             .simulation
             .employees
             .iter()
-            .map(|emp| EmployeeConfig {
-                id: emp.id.clone(),
-                personality: emp.personality.clone(),
-                interaction_frequency: emp.interaction_frequency.clone(),
-                nickname: emp.nickname.clone(),
-            })
+            .map(EmployeeConfig::from)
             .collect();
 
         self.gift_frequency = settings.simulation.livestream.gift_frequency.clone();
+        self.gift_catalog = GiftCatalog::new(settings.simulation.gifts.clone());
+        self.analysis_gate = Self::build_analysis_gate(&settings);
+        self.persist_memory = settings.simulation.persist_memory;
+        self.memory.set_max_history(settings.simulation.max_history);
+        self.danmaku_model = Some(settings.ai_models.multimodal.clone());
+        event_log::configure(settings.simulation.event_log_path.as_deref());
+        PersonalityRegistry::reload();
 
         // 初始化 AI 分析器（使用多模态模型配置）
         let multimodal_config = &settings.ai_models.multimodal;
@@ -85,7 +192,13 @@ impl SimulationEngine {
         let model = multimodal_config.model_name.clone();
 
         if !api_endpoint.is_empty() && !api_key.is_empty() {
-            self.ai_analyzer = Some(AIAnalyzer::new(api_endpoint, api_key, model));
+            self.ai_analyzer = Some(
+                AIAnalyzer::new(api_endpoint, api_key, model)
+                    .with_preamble(settings.ai_models.simulation_preamble())
+                    .with_gift_catalog(self.gift_catalog.clone())
+                    .with_fallback_model(settings.simulation.fallback_model.clone())
+                    .with_context_tokens(settings.ai_models.context_tokens),
+            );
         } else {
             println!("⚠️ 多模态 AI 未配置，将使用传统模板模式");
         }
@@ -108,6 +221,125 @@ impl SimulationEngine {
         Ok(())
     }
 
+    /// 根据直播间配置创建 AI 分析节流器
+    fn build_analysis_gate(settings: &AppSettings) -> AnalysisGate {
+        let livestream = &settings.simulation.livestream;
+        AnalysisGate::new(
+            Duration::from_millis(livestream.ai_analysis_cooldown_ms),
+            BusyPolicy::from_config(&livestream.ai_analysis_busy_policy),
+        )
+    }
+
+    /// 发送模拟事件给前端, 并写入事件日志 (如已配置)
+    pub fn emit_event(app: &AppHandle, event: SimulationEvent) {
+        event_log::record(&event);
+        let _ = app.emit("simulation_event", event);
+    }
+
+    /// 准备送给 AI 的前后截图
+    ///
+    /// 画面几乎没有变化 (差异低于 `min_screen_diff`) 时丢弃截图, 只根据语音分析,
+    /// 省去一次多模态调用
+    pub fn screenshots_for_analysis(
+        screenshot_before: &str,
+        screenshot_after: &str,
+    ) -> (String, String) {
+        let min_screen_diff = AppSettings::load()
+            .map(|s| s.screenshot.min_screen_diff)
+            .unwrap_or_default();
+
+        if frames_unchanged(screenshot_before, screenshot_after, min_screen_diff) {
+            log::info!("🖼️ 画面几乎没有变化, 跳过视觉分析, 只发送语音");
+            return (String::new(), String::new());
+        }
+
+        (screenshot_before.to_string(), screenshot_after.to_string())
+    }
+
+    /// 通知前端: AI 分析因节流被跳过
+    pub fn emit_analysis_skipped(app: &AppHandle, reason: &SkipReason, speech_text: &str) {
+        let remaining_ms = match reason {
+            SkipReason::Cooldown { remaining_ms } => Some(*remaining_ms),
+            _ => None,
+        };
+
+        log::info!("⏳ 跳过 AI 分析 ({}): {}", reason.as_str(), speech_text);
+
+        let _ = app.emit(
+            "ai_analysis_skipped",
+            serde_json::json!({
+                "reason": reason.as_str(),
+                "remainingMs": remaining_ms,
+                "speechText": speech_text,
+            }),
+        );
+    }
+
+    /// 重新加载员工配置 (运行中可调用)
+    ///
+    /// 替换员工列表、礼物频率和 AI 分析器; TTS 引擎保持不变。
+    /// 非智能模式下会以新配置重启员工循环, 旧循环在当前事件完成后退出, 不会中断进行中的任务
+    pub fn reload_config(&mut self) -> Result<ReloadSummary, String> {
+        let settings = AppSettings::load().map_err(|e| e.to_string())?;
+
+        self.employees = settings
+            .simulation
+            .employees
+            .iter()
+            .map(EmployeeConfig::from)
+            .collect();
+
+        self.gift_frequency = settings.simulation.livestream.gift_frequency.clone();
+        self.gift_catalog = GiftCatalog::new(settings.simulation.gifts.clone());
+        self.analysis_gate = Self::build_analysis_gate(&settings);
+        self.persist_memory = settings.simulation.persist_memory;
+        self.memory.set_max_history(settings.simulation.max_history);
+        self.danmaku_model = Some(settings.ai_models.multimodal.clone());
+        event_log::configure(settings.simulation.event_log_path.as_deref());
+        PersonalityRegistry::reload();
+
+        let multimodal_config = &settings.ai_models.multimodal;
+        let api_key = multimodal_config.api_key.clone().unwrap_or_default();
+        self.ai_analyzer = if !multimodal_config.api_base.is_empty() && !api_key.is_empty() {
+            Some(
+                AIAnalyzer::new(
+                    multimodal_config.api_base.clone(),
+                    api_key,
+                    multimodal_config.model_name.clone(),
+                )
+                .with_preamble(settings.ai_models.simulation_preamble())
+                .with_gift_catalog(self.gift_catalog.clone())
+                .with_fallback_model(settings.simulation.fallback_model.clone())
+                .with_context_tokens(settings.ai_models.context_tokens),
+            )
+        } else {
+            None
+        };
+
+        // 使旧的员工循环失效
+        self.config_generation.fetch_add(1, Ordering::SeqCst);
+
+        let is_running = *self.is_running.lock().unwrap();
+        if is_running && !self.enable_smart_mode {
+            for employee in &self.employees {
+                self.spawn_employee_loop(employee.clone());
+            }
+        }
+
+        log::info!(
+            "🔄 模拟配置已重新加载: {} 个员工, 礼物频率 {}",
+            self.employees.len(),
+            self.gift_frequency
+        );
+
+        Ok(ReloadSummary {
+            employee_count: self.employees.len(),
+            gift_frequency: self.gift_frequency.clone(),
+            ai_analyzer_enabled: self.ai_analyzer.is_some(),
+            is_running,
+        })
+    }
+
     /// 启动模拟
     pub async fn start(&self) -> Result<(), String> {
         {
@@ -118,8 +350,15 @@ impl SimulationEngine {
             *running = true;
         }
 
-        // 清空所有记忆
+        // 清空所有记忆 (开启记忆保存时恢复上次的记忆)
         self.memory.clear_all();
+        if self.persist_memory {
+            match default_memory_path().and_then(|path| self.memory.load_from(&path)) {
+                Ok(()) => log::info!("🧠 已恢复上次的员工记忆"),
+                Err(e) => log::warn!("⚠️ 恢复员工记忆失败: {}", e),
+            }
+        }
+
         // 触发开播事件
         self.trigger_stream_start().await;
 
@@ -133,10 +372,45 @@ impl SimulationEngine {
         Ok(())
     }
 
-    /// 停止模拟
+    /// 停止模拟 (开启记忆保存时写入磁盘)
     pub fn stop(&self) {
+        {
+            let mut running = self.is_running.lock().unwrap();
+            *running = false;
+        }
+
+        if self.persist_memory {
+            match default_memory_path().and_then(|path| self.memory.save_to(&path)) {
+                Ok(()) => log::info!("🧠 员工记忆已保存"),
+                Err(e) => log::warn!("⚠️ 保存员工记忆失败: {}", e),
+            }
+        }
+    }
+
+    /// 暂停模拟 (员工循环退出, 记忆保留), 返回暂停前是否在运行
+    pub fn pause(&self) -> bool {
         let mut running = self.is_running.lock().unwrap();
-        *running = false;
+        std::mem::replace(&mut *running, false)
+    }
+
+    /// 恢复暂停的模拟 (不清空记忆, 不重新触发开播事件)
+    pub fn resume(&self) {
+        {
+            let mut running = self.is_running.lock().unwrap();
+            if *running {
+                return;
+            }
+            *running = true;
+        }
+
+        // 使暂停前仍在等待中的员工循环失效
+        self.config_generation.fetch_add(1, Ordering::SeqCst);
+
+        if !self.enable_smart_mode {
+            for employee in &self.employees {
+                self.spawn_employee_loop(employee.clone());
+            }
+        }
     }
 
     /// 触发开播事件
@@ -165,13 +439,9 @@ impl SimulationEngine {
                     // 保存到记忆
                     memory.add_message(&employee.id, "assistant", &greeting);
 
-                    let event = SimulationEvent::new(EventType::Greeting {
-                        employee_id: employee.id.clone(),
-                        nickname: employee.nickname.clone(),
-                        message: greeting,
-                    });
+                    let event = SimulationEvent::new(employee.greeting_event(greeting));
 
-                    let _ = app.emit("simulation_event", event);
+                    Self::emit_event(&app, event);
                 }
             }
         });
@@ -179,14 +449,7 @@ impl SimulationEngine {
 
     /// 生成打招呼消息
     fn generate_greeting(personality: &str, nickname: &str) -> String {
-        match personality {
-            "sunnyou_male" => format!("{}来啦!兄弟们冲鸭!", nickname),
-            "funny_female" => format!("{}报到~今天也要开心鸭!", nickname),
-            "kobe" => format!("Mamba is here! Let's go!"),
-            "sweet_girl" => format!("{}来咯~主播加油哦💕", nickname),
-            "trump" => format!("I'm here, and this stream will be HUGE!"),
-            _ => format!("{}来了~", nickname),
-        }
+        PersonalityRegistry::current().greeting(personality, nickname)
     }
 
     /// 为每个员工启动事件循环
@@ -195,6 +458,10 @@ impl SimulationEngine {
         let is_running = self.is_running.clone();
         let memory = self.memory.clone();
         let gift_frequency = self.gift_frequency.clone();
+        let gift_catalog = self.gift_catalog.clone();
+        let danmaku_model = self.danmaku_model.clone();
+        let config_generation = self.config_generation.clone();
+        let generation = config_generation.load(Ordering::SeqCst);
 
         // 使用 tauri::async_runtime::spawn 替代 tokio::spawn
         tauri::async_runtime::spawn(async move {
@@ -211,13 +478,18 @@ impl SimulationEngine {
                     break;
                 }
 
+                // 配置已热重载, 由新的循环接管
+                if config_generation.load(Ordering::SeqCst) != generation {
+                    break;
+                }
+
                 // 70% 概率发弹幕, 30% 概率送礼物
                 if rand::random::<f64>() < 0.7 {
                     // 发送弹幕
-                    Self::send_danmaku(&app, &employee, &memory).await;
+                    Self::send_danmaku(&app, &employee, &memory, danmaku_model.as_ref()).await;
                 } else {
                     // 送礼物
-                    Self::send_gift(&app, &employee, &gift_frequency).await;
+                    Self::send_gift(&app, &employee, &gift_frequency, &gift_catalog).await;
                 }
             }
 
@@ -225,27 +497,80 @@ impl SimulationEngine {
         });
     }
 
+    /// 获取礼物频率
+    pub fn gift_frequency(&self) -> &str {
+        &self.gift_frequency
+    }
+
+    /// 获取礼物目录
+    pub fn gift_catalog(&self) -> &GiftCatalog {
+        &self.gift_catalog
+    }
+
+    /// 指定员工发送弹幕 (及可选礼物), 跳过随机触发, 走正常的事件与记忆流程
+    pub async fn employee_send(
+        app: &AppHandle,
+        employee: &EmployeeConfig,
+        memory: &Arc<MemoryManager>,
+        message: &str,
+        gift: Option<GiftRequest>,
+        gift_frequency: &str,
+        gift_catalog: &GiftCatalog,
+    ) {
+        if !message.trim().is_empty() {
+            Self::emit_danmaku(app, employee, memory, message.to_string());
+        }
+
+        let Some(gift) = gift else {
+            return;
+        };
+
+        match gift.gift_name {
+            Some(gift_name) => {
+                if !message.trim().is_empty() {
+                    sleep(Duration::from_millis(500)).await;
+                }
+                let count = gift.count.unwrap_or(1).max(1);
+                let event = SimulationEvent::new(employee.gift_event(gift_name.clone(), count));
+                Self::emit_event(app, event);
+                println!("🎁 [{}] 送出 {} x{}", employee.nickname, gift_name, count);
+            }
+            None => Self::send_gift(app, employee, gift_frequency, gift_catalog).await,
+        }
+    }
+
     /// 发送弹幕
-    async fn send_danmaku(app: &AppHandle, employee: &EmployeeConfig, memory: &Arc<MemoryManager>) {
+    async fn send_danmaku(
+        app: &AppHandle,
+        employee: &EmployeeConfig,
+        memory: &Arc<MemoryManager>,
+        model: Option<&ModelConfig>,
+    ) {
         let message = Self::generate_danmaku(
             &employee.personality,
             &employee.nickname,
             memory,
             &employee.id,
+            model,
         )
         .await;
 
+        Self::emit_danmaku(app, employee, memory, message);
+    }
+
+    /// 保存弹幕到记忆并发送事件
+    fn emit_danmaku(
+        app: &AppHandle,
+        employee: &EmployeeConfig,
+        memory: &Arc<MemoryManager>,
+        message: String,
+    ) {
         // 保存到记忆
         memory.add_message(&employee.id, "assistant", &message);
 
-        let event = SimulationEvent::new(EventType::Danmaku {
-            employee_id: employee.id.clone(),
-            nickname: employee.nickname.clone(),
-            message: message.clone(),
-            personality: employee.personality.clone(),
-        });
+        let event = SimulationEvent::new(employee.danmaku_event(message.clone()));
 
-        let _ = app.emit("simulation_event", event);
+        Self::emit_event(app, event);
         println!("💬 [{}] {}", employee.nickname, message);
     }
 
@@ -255,76 +580,38 @@ impl SimulationEngine {
         nickname: &str,
         memory: &Arc<MemoryManager>,
         employee_id: &str,
+        model: Option<&ModelConfig>,
     ) -> String {
-        // 这里可以调用 LLM 生成更智能的内容
-        // 暂时使用模板生成
-        let templates = Self::get_danmaku_templates(personality);
-        let index = rand::random::<usize>() % templates.len();
-
-        templates[index].to_string()
-    }
-
-    /// 获取弹幕模板 (根据性格)
-    fn get_danmaku_templates(personality: &str) -> Vec<&'static str> {
-        match personality {
-            "sunnyou_male" => vec![
-                "这波操作可以啊!",
-                "兄弟稳住,我看好你!",
-                "哈哈哈笑死我了",
-                "主播别怂,就是干!",
-                "这游戏有点东西啊",
-            ],
-            "funny_female" => vec![
-                "哈哈哈主播好搞笑~",
-                "这是什么神仙操作!",
-                "加油加油!你可以的!",
-                "笑不活了哈哈哈",
-                "主播太可爱了吧!",
-            ],
-            "kobe" => vec![
-                "Mamba Mentality! Keep going!",
-                "You got this! Focus!",
-                "Great move! Championship level!",
-                "Never give up!",
-                "That's what I'm talking about!",
-            ],
-            "sweet_girl" => vec![
-                "主播好厉害呀~",
-                "加油加油💕",
-                "好帅气的操作!",
-                "主播最棒了!",
-                "我会一直支持你的~",
-            ],
-            "trump" => vec![
-                "This is TREMENDOUS!",
-                "Nobody plays better than you!",
-                "HUGE victory coming!",
-                "You're doing a fantastic job!",
-                "Make gaming great again!",
-            ],
-            _ => vec!["666", "主播加油!", "这波可以", "nice!", "支持主播!"],
+        // 优先使用 LLM 生成, 未配置模型或调用失败时使用模板
+        if let Some(model) = model {
+            let history = memory.get_history(employee_id);
+            match danmaku::generate_with_llm(model, personality, nickname, &history).await {
+                Ok(message) => return message,
+                Err(e) => log::debug!("LLM 生成弹幕失败, 使用模板: {}", e),
+            }
         }
+
+        PersonalityRegistry::current().random_danmaku(personality)
     }
 
-    /// 发送礼物
-    async fn send_gift(app: &AppHandle, employee: &EmployeeConfig, gift_frequency: &str) {
+    /// 发送礼物 (按权重从礼物目录中随机选择)
+    async fn send_gift(
+        app: &AppHandle,
+        employee: &EmployeeConfig,
+        gift_frequency: &str,
+        gift_catalog: &GiftCatalog,
+    ) {
         let (min_count, max_count, min_combo, max_combo) = gift_frequency_to_params(gift_frequency);
 
         let combo = min_combo + (rand::random::<u32>() % (max_combo - min_combo + 1));
-        let gifts = vec!["🚀火箭", "🌹鲜花", "666"];
-        let gift_name = gifts[rand::random::<usize>() % gifts.len()];
+        let gift_name = gift_catalog.pick().label();
 
         for _ in 0..combo {
             let count = min_count + (rand::random::<u32>() % (max_count - min_count + 1));
 
-            let event = SimulationEvent::new(EventType::Gift {
-                employee_id: employee.id.clone(),
-                nickname: employee.nickname.clone(),
-                gift_name: gift_name.to_string(),
-                count,
-            });
+            let event = SimulationEvent::new(employee.gift_event(gift_name.clone(), count));
 
-            let _ = app.emit("simulation_event", event);
+            Self::emit_event(app, event);
             println!("🎁 [{}] 送出 {} x{}", employee.nickname, gift_name, count);
 
             // 连刷间隔 500ms
@@ -341,7 +628,7 @@ impl SimulationEngine {
         };
 
         if let Some(emp) = employee {
-            Self::send_gift(&self.app, &emp, &self.gift_frequency).await;
+            Self::send_gift(&self.app, &emp, &self.gift_frequency, &self.gift_catalog).await;
         }
     }
 
@@ -369,6 +656,7 @@ impl SimulationEngine {
             let app = self.app.clone();
             let emp = employee.clone();
             let memory = self.memory.clone();
+            let model = self.danmaku_model.clone();
             let msg = message.to_string();
 
             tauri::async_runtime::spawn(async move {
@@ -378,7 +666,7 @@ impl SimulationEngine {
                 memory.add_message(&emp.id, "user", &msg);
 
                 // 生成回复
-                Self::send_danmaku(&app, &emp, &memory).await;
+                Self::send_danmaku(&app, &emp, &memory, model.as_ref()).await;
             });
         }
     }
@@ -400,6 +688,15 @@ impl SimulationEngine {
             return;
         };
 
+        // 冷却 / 单飞检查, 持有许可直到分析结束
+        let _permit = match self.analysis_gate.acquire().await {
+            Ok(permit) => permit,
+            Err(reason) => {
+                Self::emit_analysis_skipped(&self.app, &reason, speech_text);
+                return;
+            }
+        };
+
         // 构建每个员工的上下文
         let employee_contexts: Vec<EmployeeContext> = self
             .employees
@@ -422,10 +719,12 @@ impl SimulationEngine {
             .collect();
 
         // 构建 AI 分析请求
+        let (screenshot_before, screenshot_after) =
+            Self::screenshots_for_analysis(screenshot_before, screenshot_after);
         let request = AIAnalysisRequest {
             streamer_speech: speech_text.to_string(),
-            screenshot_before: screenshot_before.to_string(),
-            screenshot_after: screenshot_after.to_string(),
+            screenshot_before,
+            screenshot_after,
             employees: employee_contexts,
             game_id: self.game_id.clone(),
         };
@@ -466,14 +765,9 @@ impl SimulationEngine {
                         // 发送弹幕
                         memory.add_message(&emp.id, "assistant", &content);
 
-                        let event = SimulationEvent::new(EventType::Danmaku {
-                            employee_id: emp.id.clone(),
-                            nickname: emp.nickname.clone(),
-                            message: content.clone(),
-                            personality: emp.personality.clone(),
-                        });
+                        let event = SimulationEvent::new(emp.danmaku_event(content.clone()));
 
-                        let _ = app.emit("simulation_event", event);
+                        Self::emit_event(&app, event);
                         // println!("[{}] {}", emp.nickname, content);
 
                         // 如果需要送礼物
@@ -481,14 +775,10 @@ impl SimulationEngine {
                             sleep(Duration::from_millis(500)).await;
 
                             let gift = gift_name.unwrap_or("🚀火箭".to_string());
-                            let event = SimulationEvent::new(EventType::Gift {
-                                employee_id: emp.id.clone(),
-                                nickname: emp.nickname.clone(),
-                                gift_name: gift.clone(),
-                                count: gift_count,
-                            });
-
-                            let _ = app.emit("simulation_event", event);
+                            let event =
+                                SimulationEvent::new(emp.gift_event(gift.clone(), gift_count));
+
+                            Self::emit_event(&app, event);
                             // println!("🎁 [{}] 送出 {} x{}", emp.nickname, gift, gift_count);
                         }
                     });
@@ -0,0 +1,142 @@
+/// 模拟事件日志
+///
+/// 配置了 `event_log_path` 时, 每个发送给前端的 SimulationEvent 都会追加写入 JSONL 文件,
+/// 写入在后台任务中进行, 不阻塞员工循环
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use super::events::SimulationEvent;
+
+/// 当前的日志写入通道
+struct EventSink {
+    path: PathBuf,
+    tx: mpsc::UnboundedSender<SimulationEvent>,
+}
+
+static EVENT_SINK: Mutex<Option<EventSink>> = Mutex::new(None);
+
+/// 设置事件日志路径 (None 或空字符串时关闭日志), 路径未变化时保持原有写入任务
+pub fn configure(path: Option<&str>) {
+    let path = path
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+
+    let mut sink = EVENT_SINK.lock().unwrap();
+    if sink.as_ref().map(|s| &s.path) == path.as_ref() {
+        return;
+    }
+
+    // 替换旧的通道后, 旧写入任务写完剩余事件即退出
+    *sink = path.map(|path| {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task_path = path.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = write_events(&task_path, rx).await {
+                log::error!("❌ 写入事件日志失败 ({}): {}", task_path.display(), e);
+            }
+        });
+        log::info!("📝 事件日志: {}", path.display());
+        EventSink { path, tx }
+    });
+}
+
+/// 记录一个事件 (未配置日志时忽略)
+pub fn record(event: &SimulationEvent) {
+    if let Some(sink) = EVENT_SINK.lock().unwrap().as_ref() {
+        let _ = sink.tx.send(event.clone());
+    }
+}
+
+/// 后台写入任务: 逐条追加为 JSONL, 通道关闭后退出
+async fn write_events(path: &Path, mut rx: mpsc::UnboundedReceiver<SimulationEvent>) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .context("无法打开事件日志文件")?;
+
+    while let Some(event) = rx.recv().await {
+        let mut line = serde_json::to_string(&event)?;
+        line.push('\n');
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+    }
+
+    Ok(())
+}
+
+/// 读取事件日志, 按写入顺序还原一次直播的所有事件 (跳过无法解析的行)
+pub fn replay_events(path: &Path) -> Result<Vec<SimulationEvent>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("无法读取事件日志: {}", path.display()))?;
+
+    let mut events = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(event) => events.push(event),
+            Err(e) => log::warn!("⚠️ 跳过事件日志第 {} 行: {}", index + 1, e),
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::EventType;
+
+    fn gift(count: u32) -> SimulationEvent {
+        SimulationEvent::new(EventType::Gift {
+            employee_id: "emp1".to_string(),
+            nickname: "小助手".to_string(),
+            gift_name: "🚀火箭".to_string(),
+            count,
+            avatar: None,
+            color: "#FF6B6B".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_write_and_replay() {
+        let path = std::env::temp_dir()
+            .join(format!("events_{}", uuid::Uuid::new_v4()))
+            .join("events.jsonl");
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(gift(1)).unwrap();
+        tx.send(gift(2)).unwrap();
+        drop(tx);
+        write_events(&path, rx).await.unwrap();
+
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"not json\n"))
+            .unwrap();
+
+        let events = replay_events(&path).unwrap();
+        let counts: Vec<u32> = events
+            .iter()
+            .map(|e| match &e.event_type {
+                EventType::Gift { count, .. } => *count,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(counts, vec![1, 2]);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -11,6 +11,8 @@ pub enum EventType {
         nickname: String,
         message: String,
         personality: String,
+        avatar: Option<String>,
+        color: String,
     },
     /// 礼物
     Gift {
@@ -18,15 +20,31 @@ pub enum EventType {
         nickname: String,
         gift_name: String,
         count: u32,
+        avatar: Option<String>,
+        color: String,
     },
     /// 打招呼
     Greeting {
         employee_id: String,
         nickname: String,
         message: String,
+        avatar: Option<String>,
+        color: String,
     },
 }
 
+/// 手动触发的礼物请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftRequest {
+    /// 礼物名称 (不填时随机选择并按礼物频率连刷)
+    #[serde(default)]
+    pub gift_name: Option<String>,
+    /// 数量 (默认 1)
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
 /// 模拟事件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationEvent {
@@ -66,3 +84,17 @@ pub fn gift_frequency_to_params(frequency: &str) -> (u32, u32, u32, u32) {
         _ => (2, 5, 1, 3),        // 默认中频
     }
 }
+
+/// 根据员工 ID 生成稳定的用户名颜色 (未配置颜色时使用)
+pub fn default_color_for(employee_id: &str) -> String {
+    const PALETTE: &[&str] = &[
+        "#FF6B6B", "#F7A440", "#FFD93D", "#6BCB77", "#4D96FF", "#9B5DE5", "#F15BB5", "#00BBF9",
+    ];
+
+    // FNV-1a, 保证同一员工每次启动颜色一致
+    let hash = employee_id.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+
+    PALETTE[(hash % PALETTE.len() as u64) as usize].to_string()
+}
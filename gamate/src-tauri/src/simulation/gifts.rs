@@ -0,0 +1,121 @@
+/// 礼物目录
+///
+/// 根据 `SimulationSettings.gifts` 按权重随机选择礼物, 并校验 AI 返回的礼物名称
+use crate::settings::{default_gifts, GiftConfig};
+
+#[derive(Debug, Clone)]
+pub struct GiftCatalog {
+    gifts: Vec<GiftConfig>,
+}
+
+impl GiftCatalog {
+    /// 创建礼物目录 (忽略名称为空的礼物, 全部无效时使用默认礼物)
+    pub fn new(gifts: Vec<GiftConfig>) -> Self {
+        let gifts: Vec<GiftConfig> = gifts
+            .into_iter()
+            .filter(|gift| !gift.name.trim().is_empty())
+            .collect();
+
+        Self {
+            gifts: if gifts.is_empty() {
+                default_gifts()
+            } else {
+                gifts
+            },
+        }
+    }
+
+    /// 权重最高的礼物 (权重相同时取靠前的)
+    pub fn primary(&self) -> &GiftConfig {
+        self.gifts.iter().fold(&self.gifts[0], |best, gift| {
+            if gift.weight > best.weight {
+                gift
+            } else {
+                best
+            }
+        })
+    }
+
+    /// 按权重随机选择一个礼物 (权重全为 0 时返回权重最高的礼物)
+    pub fn pick(&self) -> &GiftConfig {
+        let total: u64 = self.gifts.iter().map(|gift| gift.weight as u64).sum();
+        if total == 0 {
+            return self.primary();
+        }
+
+        let mut roll = rand::random::<u64>() % total;
+        for gift in &self.gifts {
+            let weight = gift.weight as u64;
+            if roll < weight {
+                return gift;
+            }
+            roll -= weight;
+        }
+
+        self.primary()
+    }
+
+    /// 校验礼物名称: 匹配目录中的名称或显示名称, 未知或为空时使用权重最高的礼物
+    pub fn resolve(&self, name: Option<&str>) -> String {
+        let name = name.map(str::trim).unwrap_or_default();
+        self.gifts
+            .iter()
+            .find(|gift| gift.label() == name || gift.name == name)
+            .unwrap_or_else(|| self.primary())
+            .label()
+    }
+
+    /// 所有礼物的显示名称
+    pub fn labels(&self) -> Vec<String> {
+        self.gifts.iter().map(GiftConfig::label).collect()
+    }
+}
+
+impl Default for GiftCatalog {
+    fn default() -> Self {
+        Self::new(default_gifts())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gift(name: &str, emoji: &str, weight: u32) -> GiftConfig {
+        GiftConfig {
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+            weight,
+            value: 1,
+        }
+    }
+
+    #[test]
+    fn test_default_catalog() {
+        let catalog = GiftCatalog::default();
+        assert_eq!(catalog.labels(), vec!["🚀火箭", "🌹鲜花", "666"]);
+        assert_eq!(catalog.resolve(Some("🌹鲜花")), "🌹鲜花");
+        assert_eq!(catalog.resolve(Some("鲜花")), "🌹鲜花");
+        assert_eq!(catalog.resolve(Some("💎钻石")), "🚀火箭");
+        assert_eq!(catalog.resolve(None), "🚀火箭");
+    }
+
+    #[test]
+    fn test_weighted_pick() {
+        let catalog = GiftCatalog::new(vec![
+            gift("灯牌", "💡", 0),
+            gift("鲜花", "🌹", 3),
+            gift("游艇", "🛥️", 1),
+        ]);
+        assert_eq!(catalog.primary().name, "鲜花");
+        assert_eq!(catalog.resolve(Some("钻石")), "🌹鲜花");
+
+        for _ in 0..200 {
+            assert_ne!(catalog.pick().name, "灯牌");
+        }
+
+        let catalog = GiftCatalog::new(vec![gift("灯牌", "💡", 0), gift(" ", "", 5)]);
+        assert_eq!(catalog.labels(), vec!["💡灯牌"]);
+        assert_eq!(catalog.pick().name, "灯牌");
+    }
+}
@@ -1,11 +1,26 @@
 /// AI 员工记忆管理
 ///
-/// 为每个 AI 员工维护独立的对话历史 (最多30条)
-use std::collections::HashMap;
+/// 为每个 AI 员工维护独立的对话历史 (超过上限时淘汰最早的消息)
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// 每个员工默认保留的消息条数
+pub const DEFAULT_MAX_HISTORY: usize = 50;
+
+/// 记忆文件名 (位于配置目录)
+pub const MEMORY_FILE_NAME: &str = "simulation_memory.json";
+
+/// 默认的记忆文件路径
+pub fn default_memory_path() -> Result<PathBuf> {
+    Ok(crate::settings::AppSettings::config_dir()?.join(MEMORY_FILE_NAME))
+}
+
 /// 对话记录
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: String, // "user" 或 "assistant"
     pub content: String,
@@ -14,16 +29,38 @@ pub struct Message {
 
 /// 记忆管理器
 pub struct MemoryManager {
-    /// 每个员工的对话历史: employee_id -> Vec<Message>
-    memories: Arc<Mutex<HashMap<String, Vec<Message>>>>,
-    max_messages: usize, // 最多保存的消息数量
+    /// 每个员工的对话历史: employee_id -> 消息环形缓冲
+    memories: Arc<Mutex<HashMap<String, VecDeque<Message>>>>,
+    max_history: AtomicUsize, // 每个员工最多保存的消息数量
 }
 
 impl MemoryManager {
     pub fn new() -> Self {
+        Self::with_max_history(DEFAULT_MAX_HISTORY)
+    }
+
+    pub fn with_max_history(max_history: usize) -> Self {
         Self {
             memories: Arc::new(Mutex::new(HashMap::new())),
-            max_messages: 30,
+            max_history: AtomicUsize::new(max_history.max(1)),
+        }
+    }
+
+    /// 修改消息上限 (立即裁剪已有的历史)
+    pub fn set_max_history(&self, max_history: usize) {
+        let max_history = max_history.max(1);
+        self.max_history.store(max_history, Ordering::Relaxed);
+
+        let mut memories = self.memories.lock().unwrap();
+        for history in memories.values_mut() {
+            Self::evict(history, max_history);
+        }
+    }
+
+    /// 淘汰最早的消息, 只保留最新的 max_history 条
+    fn evict(history: &mut VecDeque<Message>, max_history: usize) {
+        while history.len() > max_history {
+            history.pop_front();
         }
     }
 
@@ -32,7 +69,7 @@ impl MemoryManager {
         let mut memories = self.memories.lock().unwrap();
         let history = memories
             .entry(employee_id.to_string())
-            .or_insert_with(Vec::new);
+            .or_insert_with(VecDeque::new);
 
         let message = Message {
             role: role.to_string(),
@@ -43,21 +80,20 @@ impl MemoryManager {
                 .as_secs(),
         };
 
-        history.push(message);
-
-        // 保持最多 max_messages 条记录
-        if history.len() > self.max_messages {
-            history.drain(0..history.len() - self.max_messages);
-        }
+        history.push_back(message);
+        Self::evict(history, self.max_history.load(Ordering::Relaxed));
     }
 
-    /// 获取员工的对话历史
+    /// 获取员工的对话历史 (按时间顺序)
     pub fn get_history(&self, employee_id: &str) -> Vec<Message> {
         let memories = self.memories.lock().unwrap();
-        memories.get(employee_id).cloned().unwrap_or_default()
+        memories
+            .get(employee_id)
+            .map(|history| history.iter().cloned().collect())
+            .unwrap_or_default()
     }
 
-    /// 🔥 获取对话历史（用于 AI 分析）
+    /// 🔥 获取对话历史（用于 AI 分析, 最多 max_history 条）
     pub fn get_conversation_history(&self, employee_id: &str) -> Vec<Message> {
         self.get_history(employee_id)
     }
@@ -80,6 +116,37 @@ impl MemoryManager {
         context
     }
 
+    /// 将所有员工的对话记录保存为 JSON
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let json = {
+            let memories = self.memories.lock().unwrap();
+            serde_json::to_string_pretty(&*memories)?
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// 从 JSON 文件加载对话记录 (替换当前记忆), 文件不存在时不做任何改动
+    pub fn load_from(&self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let json = std::fs::read_to_string(path)?;
+        let mut loaded: HashMap<String, VecDeque<Message>> = serde_json::from_str(&json)?;
+        let max_history = self.max_history.load(Ordering::Relaxed);
+        for history in loaded.values_mut() {
+            Self::evict(history, max_history);
+        }
+
+        *self.memories.lock().unwrap() = loaded;
+        Ok(())
+    }
+
     /// 清空所有记忆
     pub fn clear_all(&self) {
         let mut memories = self.memories.lock().unwrap();
@@ -98,3 +165,49 @@ impl Default for MemoryManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("memory_{}.json", uuid::Uuid::new_v4()));
+
+        let memory = MemoryManager::new();
+        memory.add_message("emp1", "user", "开始游戏了");
+        memory.add_message("emp1", "assistant", "冲冲冲!");
+        memory.save_to(&path).unwrap();
+
+        let restored = MemoryManager::new();
+        restored.load_from(&path).unwrap();
+        let history = restored.get_history("emp1");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "冲冲冲!");
+
+        restored.clear_all();
+        assert!(restored.get_history("emp1").is_empty());
+
+        // 文件不存在时不改动当前记忆
+        let _ = std::fs::remove_file(&path);
+        memory.load_from(&path).unwrap();
+        assert_eq!(memory.get_history("emp1").len(), 2);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let memory = MemoryManager::new();
+        for i in 0..100 {
+            memory.add_message("emp1", "user", &format!("消息{}", i));
+        }
+
+        let history = memory.get_conversation_history("emp1");
+        assert_eq!(history.len(), DEFAULT_MAX_HISTORY);
+        let contents: Vec<String> = history.into_iter().map(|m| m.content).collect();
+        let expected: Vec<String> = (50..100).map(|i| format!("消息{}", i)).collect();
+        assert_eq!(contents, expected);
+
+        memory.set_max_history(10);
+        assert_eq!(memory.get_history("emp1")[0].content, "消息90");
+    }
+}
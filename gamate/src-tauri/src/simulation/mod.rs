@@ -1,11 +1,17 @@
 pub mod ai_analyzer;
+pub mod analysis_gate;
+pub mod danmaku;
 /// 直播间模拟系统
 ///
 /// 负责模拟直播间场景,包括 AI 员工发送弹幕、送礼物等
 pub mod engine;
+pub mod event_log;
 pub mod events;
+pub mod gifts;
 pub mod memory;
 
-pub use engine::SimulationEngine;
-pub use events::{EventType, SimulationEvent};
+pub use analysis_gate::{AnalysisGate, BusyPolicy, SkipReason};
+pub use engine::{ReloadSummary, SimulationEngine};
+pub use events::{EventType, GiftRequest, SimulationEvent};
+pub use gifts::GiftCatalog;
 pub use memory::MemoryManager;
@@ -1,5 +1,13 @@
+use futures::StreamExt;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 
 /// Steam 游戏基本信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +16,44 @@ pub struct SteamAppInfo {
     pub name: String,
 }
 
+/// Steam 应用列表接口
+const APP_LIST_URL: &str = "https://api.steampowered.com/ISteamApps/GetAppList/v2/";
+
+/// 应用列表缓存文件名
+pub const APP_LIST_CACHE_FILE: &str = "steam_app_list.json";
+
+/// 应用列表缓存有效期 (列表变化很少, 每天更新一次即可)
+pub const APP_LIST_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 下载应用列表的最大尝试次数
+const APP_LIST_MAX_ATTEMPTS: u32 = 3;
+
+/// 进程内缓存的 Steam 应用列表 (约二十万条, 避免每次搜索都读取缓存文件)
+static APP_LIST_CACHE: Lazy<Mutex<Option<CachedAppList>>> = Lazy::new(|| Mutex::new(None));
+
+/// 搜索结果默认数量
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// 游戏详情缓存目录名 (每个游戏一个 `{appid}.json`)
+pub const APP_DETAILS_CACHE_DIR: &str = "steam_app_details";
+
+/// 游戏详情缓存默认有效期 (小时)
+pub const DEFAULT_DETAILS_CACHE_TTL_HOURS: u64 = 7 * 24;
+
+/// 已缓存的应用列表
+#[derive(Debug, Clone)]
+pub struct CachedAppList {
+    pub apps: Arc<Vec<SteamAppInfo>>,
+    /// 列表下载时间
+    pub fetched_at: SystemTime,
+}
+
+impl CachedAppList {
+    fn is_fresh(&self) -> bool {
+        is_fresh(self.fetched_at)
+    }
+}
+
 /// Steam 应用列表响应
 #[derive(Debug, Deserialize)]
 pub struct SteamAppListResponse {
@@ -16,9 +62,134 @@ pub struct SteamAppListResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct SteamAppList {
+    /// 没有名称的条目 (工具、服务器等) 在反序列化时直接丢弃
+    #[serde(deserialize_with = "deserialize_named_apps")]
     pub apps: Vec<SteamAppInfo>,
 }
 
+/// 逐条反序列化应用列表, 跳过名称为空的条目
+fn deserialize_named_apps<'de, D>(deserializer: D) -> Result<Vec<SteamAppInfo>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct NamedApps;
+
+    impl<'de> serde::de::Visitor<'de> for NamedApps {
+        type Value = Vec<SteamAppInfo>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a list of Steam apps")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut apps = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(app) = seq.next_element::<SteamAppInfo>()? {
+                if !app.name.trim().is_empty() {
+                    apps.push(app);
+                }
+            }
+            Ok(apps)
+        }
+    }
+
+    deserializer.deserialize_seq(NamedApps)
+}
+
+/// 从缓存文件流式读取应用列表 (不把整个文件读入内存)
+pub fn read_app_list_file(path: &Path) -> Result<Vec<SteamAppInfo>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("打开应用列表缓存失败: {}", e))?;
+
+    let app_list: SteamAppListResponse = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("解析应用列表缓存失败: {}", e))?;
+
+    Ok(app_list.applist.apps)
+}
+
+/// 读取缓存文件 (不存在或损坏时为 None), 以文件修改时间作为下载时间
+async fn read_cache_file(path: &Path) -> Option<CachedAppList> {
+    let fetched_at = std::fs::metadata(path).ok()?.modified().ok()?;
+
+    let read_path = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || read_app_list_file(&read_path)).await {
+        Ok(Ok(apps)) => Some(CachedAppList {
+            apps: Arc::new(apps),
+            fetched_at,
+        }),
+        Ok(Err(e)) => {
+            log::warn!("⚠️ {}", e);
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+fn is_fresh(fetched_at: SystemTime) -> bool {
+    is_within_ttl(fetched_at, APP_LIST_CACHE_TTL)
+}
+
+fn is_within_ttl(fetched_at: SystemTime, ttl: Duration) -> bool {
+    // 时间在未来 (系统时钟被调整) 时视为新鲜
+    match SystemTime::now().duration_since(fetched_at) {
+        Ok(age) => age < ttl,
+        Err(_) => true,
+    }
+}
+
+/// 磁盘缓存中的游戏详情 (商店中不存在的游戏缓存为 None, 避免重复请求)
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedAppDetails {
+    /// 获取时间 (Unix 秒)
+    fetched_at: u64,
+    details: Option<SteamGameDetails>,
+}
+
+fn details_cache_path(cache_dir: &Path, appid: u32) -> PathBuf {
+    cache_dir.join(format!("{}.json", appid))
+}
+
+/// 读取未过期的详情缓存 (不存在、损坏或过期时为 None)
+fn read_details_cache(
+    cache_dir: &Path,
+    appid: u32,
+    cache_ttl_hours: u64,
+) -> Option<Option<SteamGameDetails>> {
+    let content = std::fs::read_to_string(details_cache_path(cache_dir, appid)).ok()?;
+    let cached: CachedAppDetails = serde_json::from_str(&content).ok()?;
+
+    let fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(cached.fetched_at);
+    let ttl = Duration::from_secs(cache_ttl_hours * 60 * 60);
+    is_within_ttl(fetched_at, ttl).then_some(cached.details)
+}
+
+/// 写入详情缓存 (失败只记录警告, 不影响获取结果)
+fn write_details_cache(cache_dir: &Path, appid: u32, details: &Option<SteamGameDetails>) {
+    let cached = CachedAppDetails {
+        fetched_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        details: details.clone(),
+    };
+
+    let result = std::fs::create_dir_all(cache_dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string(&cached).map_err(|e| e.to_string()))
+        .and_then(|content| {
+            // 先写临时文件再重命名, 避免并发读取到写了一半的文件
+            let path = details_cache_path(cache_dir, appid);
+            let temp_path = path.with_extension("json.tmp");
+            std::fs::write(&temp_path, content)
+                .and_then(|_| std::fs::rename(&temp_path, &path))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::warn!("⚠️ 写入 appid {} 的详情缓存失败: {}", appid, e);
+    }
+}
+
 /// Steam 游戏详细信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SteamGameDetails {
@@ -88,6 +259,42 @@ pub struct FilteredGameData {
     pub recommendations: u32,
 }
 
+/// 批量获取游戏详情的并发与限流参数
+#[derive(Debug, Clone)]
+pub struct BatchFetchOptions {
+    /// 同时进行的请求数
+    pub max_concurrent: usize,
+    /// 相邻两个请求发出的最小间隔 (商店 API 只有一个域名, 即按域名限流)
+    pub min_interval_ms: u64,
+    /// 被限流 (429) 后的最大重试次数
+    pub max_retries: u32,
+    /// 429 退避基础时长 (每次重试翻倍, 响应带 Retry-After 时以其为准)
+    pub backoff_base_ms: u64,
+    /// 详情磁盘缓存目录 (None 时不使用缓存)
+    pub cache_dir: Option<PathBuf>,
+    /// 详情缓存有效期 (小时)
+    pub cache_ttl_hours: u64,
+}
+
+impl Default for BatchFetchOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 5,
+            min_interval_ms: 300,
+            max_retries: 3,
+            backoff_base_ms: 5000,
+            cache_dir: None,
+            cache_ttl_hours: DEFAULT_DETAILS_CACHE_TTL_HOURS,
+        }
+    }
+}
+
+/// 获取详情失败的原因 (区分限流以便退避重试)
+enum DetailsError {
+    RateLimited(Option<Duration>),
+    Other(String),
+}
+
 /// Steam API 客户端
 pub struct SteamApiClient {
     client: reqwest::Client,
@@ -103,26 +310,204 @@ impl SteamApiClient {
         }
     }
 
-    /// 获取所有 Steam 应用列表
+    /// 获取所有 Steam 应用列表 (不使用缓存, 失败时重试)
     pub async fn get_app_list(&self) -> Result<Vec<SteamAppInfo>, String> {
-        let url = "https://api.steampowered.com/ISteamApps/GetAppList/v2/";
-        
+        let mut attempt = 1;
+        loop {
+            match self.request_app_list().await {
+                Ok(apps) => return Ok(apps),
+                Err(e) if attempt < APP_LIST_MAX_ATTEMPTS => {
+                    log::warn!("⚠️ 获取 Steam 应用列表失败 (第 {} 次): {}", attempt, e);
+                    tokio::time::sleep(Duration::from_secs(2u64 << attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn request_app_list(&self) -> Result<Vec<SteamAppInfo>, String> {
+        let body = self
+            .send_app_list_request()
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+
+        let app_list: SteamAppListResponse =
+            serde_json::from_slice(&body).map_err(|e| format!("解析响应失败: {}", e))?;
+
+        Ok(app_list.applist.apps)
+    }
+
+    async fn send_app_list_request(&self) -> Result<reqwest::Response, String> {
+        self.client
+            .get(APP_LIST_URL)
+            // 完整列表有十几 MB, 慢速网络下 30 秒不够
+            .timeout(Duration::from_secs(180))
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("请求失败: {}", e))
+    }
+
+    /// 下载应用列表到缓存文件 (边下载边写入), 校验通过后替换旧缓存
+    async fn download_app_list(&self, cache_path: &Path) -> Result<Vec<SteamAppInfo>, String> {
+        let temp_path = cache_path.with_extension("json.tmp");
+
+        let mut response = self.send_app_list_request().await?;
+        let mut file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(|e| format!("创建缓存文件失败: {}", e))?;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("读取响应失败: {}", e))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("写入缓存文件失败: {}", e))?;
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("写入缓存文件失败: {}", e))?;
+        drop(file);
+
+        let parse_path = temp_path.clone();
+        let apps = tokio::task::spawn_blocking(move || read_app_list_file(&parse_path))
+            .await
+            .map_err(|e| format!("解析任务失败: {}", e))?;
+        let apps = match apps {
+            Ok(apps) => apps,
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(e);
+            }
+        };
+
+        std::fs::rename(&temp_path, cache_path).map_err(|e| format!("保存缓存文件失败: {}", e))?;
+
+        Ok(apps)
+    }
+
+    /// 获取 Steam 应用列表 (先查内存, 再查缓存文件, 过期或 `force` 时重新下载)
+    ///
+    /// 下载失败时退回到已过期的缓存
+    pub async fn load_app_list(
+        &self,
+        cache_path: &Path,
+        force: bool,
+    ) -> Result<CachedAppList, String> {
+        let mut cache = APP_LIST_CACHE.lock().await;
+
+        if !force {
+            if let Some(cached) = cache.as_ref().filter(|c| c.is_fresh()) {
+                return Ok(cached.clone());
+            }
+
+            if let Some(cached) = read_cache_file(cache_path).await.filter(|c| c.is_fresh()) {
+                log::info!("📂 已从缓存加载 {} 个 Steam 应用", cached.apps.len());
+                *cache = Some(cached.clone());
+                return Ok(cached);
+            }
+        }
+
+        log::info!("🌐 正在下载 Steam 应用列表...");
+        let mut attempt = 1;
+        let result = loop {
+            match self.download_app_list(cache_path).await {
+                Ok(apps) => break Ok(apps),
+                Err(e) if attempt < APP_LIST_MAX_ATTEMPTS => {
+                    log::warn!("⚠️ 下载 Steam 应用列表失败 (第 {} 次): {}", attempt, e);
+                    tokio::time::sleep(Duration::from_secs(2u64 << attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        match result {
+            Ok(apps) => {
+                log::info!("✅ 已缓存 {} 个 Steam 应用", apps.len());
+                let cached = CachedAppList {
+                    apps: Arc::new(apps),
+                    fetched_at: SystemTime::now(),
+                };
+                *cache = Some(cached.clone());
+                Ok(cached)
+            }
+            Err(e) => {
+                let stale = match cache.clone() {
+                    Some(cached) => Some(cached),
+                    None => read_cache_file(cache_path).await,
+                };
+                let Some(stale) = stale else {
+                    return Err(e);
+                };
+
+                log::warn!("⚠️ 下载 Steam 应用列表失败, 使用过期缓存: {}", e);
+                *cache = Some(stale.clone());
+                Ok(stale)
+            }
+        }
+    }
+
+    /// 下载图片 (封面/图标), 返回 (数据, Content-Type)
+    pub async fn download_image(&self, url: &str) -> Result<(Vec<u8>, Option<String>), String> {
         let response = self.client
             .get(url)
             .send()
             .await
+            .map_err(|e| format!("请求失败: {}", e))?
+            .error_for_status()
             .map_err(|e| format!("请求失败: {}", e))?;
 
-        let app_list: SteamAppListResponse = response
-            .json()
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let bytes = response
+            .bytes()
             .await
-            .map_err(|e| format!("解析响应失败: {}", e))?;
+            .map_err(|e| format!("读取响应失败: {}", e))?;
 
-        Ok(app_list.applist.apps)
+        Ok((bytes.to_vec(), content_type))
     }
 
     /// 获取单个游戏的详细信息
     pub async fn get_app_details(&self, appid: u32) -> Result<Option<SteamGameDetails>, String> {
+        self.request_app_details(appid).await.map_err(|e| match e {
+            DetailsError::RateLimited(_) => "请求过于频繁 (429)".to_string(),
+            DetailsError::Other(e) => e,
+        })
+    }
+
+    /// 获取单个游戏的详细信息, `cache_dir` 下有未过期的缓存时直接返回
+    ///
+    /// 缓存未命中时请求商店 API 并写回缓存 (请求失败不写缓存)
+    pub async fn get_app_details_cached(
+        &self,
+        appid: u32,
+        cache_dir: &Path,
+        cache_ttl_hours: u64,
+    ) -> Result<Option<SteamGameDetails>, String> {
+        if let Some(details) = read_details_cache(cache_dir, appid, cache_ttl_hours) {
+            log::debug!("📂 使用 appid {} 的详情缓存", appid);
+            return Ok(details);
+        }
+
+        let details = self.get_app_details(appid).await?;
+        write_details_cache(cache_dir, appid, &details);
+        Ok(details)
+    }
+
+    async fn request_app_details(
+        &self,
+        appid: u32,
+    ) -> Result<Option<SteamGameDetails>, DetailsError> {
         let url = format!(
             "https://store.steampowered.com/api/appdetails?appids={}&cc=cn&l=schinese",
             appid
@@ -132,13 +517,26 @@ impl SteamApiClient {
             .get(&url)
             .send()
             .await
-            .map_err(|e| format!("请求失败: {}", e))?;
+            .map_err(|e| DetailsError::Other(format!("请求失败: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(DetailsError::RateLimited(retry_after));
+        }
 
-        let text = response.text().await.map_err(|e| format!("读取响应失败: {}", e))?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| DetailsError::Other(format!("读取响应失败: {}", e)))?;
         
         // Steam API 返回的格式是 {"appid": {"success": true, "data": {...}}}
         let mut response_map: HashMap<String, SteamAppDetailsResponse> = serde_json::from_str(&text)
-            .map_err(|e| format!("解析响应失败: {} (appid: {})", e, appid))?;
+            .map_err(|e| DetailsError::Other(format!("解析响应失败: {} (appid: {})", e, appid)))?;
 
         if let Some(app_response) = response_map.remove(&appid.to_string()) {
             if app_response.success {
@@ -149,33 +547,111 @@ impl SteamApiClient {
         Ok(None)
     }
 
-    /// 批量获取游戏详情（带延迟避免被限流）
+    /// 批量获取游戏详情（串行, 每个请求间隔 `delay_ms` 避免被限流）
     pub async fn get_batch_details(
         &self,
         appids: &[u32],
         delay_ms: u64,
     ) -> Vec<(u32, Option<SteamGameDetails>)> {
-        let mut results = Vec::new();
+        self.get_batch_details_with(
+            appids,
+            &BatchFetchOptions {
+                max_concurrent: 1,
+                min_interval_ms: delay_ms,
+                ..Default::default()
+            },
+        )
+        .await
+    }
 
-        for &appid in appids {
-            match self.get_app_details(appid).await {
-                Ok(details) => {
-                    results.push((appid, details));
-                }
-                Err(e) => {
-                    eprintln!("❌ 获取 appid {} 失败: {}", appid, e);
-                    results.push((appid, None));
-                }
-            }
+    /// 批量获取游戏详情（有限并发 + 请求间隔限流 + 429 退避重试）
+    ///
+    /// 结果顺序与 `appids` 一致, 获取失败的游戏详情为 None。
+    /// 设置了 `cache_dir` 时命中缓存的游戏不发请求, 新获取的详情写回缓存
+    pub async fn get_batch_details_with(
+        &self,
+        appids: &[u32],
+        options: &BatchFetchOptions,
+    ) -> Vec<(u32, Option<SteamGameDetails>)> {
+        let interval = Duration::from_millis(options.min_interval_ms);
+        // 下一个请求允许发出的时间 (所有并发请求共享, 429 时整体推后)
+        let next_slot = Mutex::new(Instant::now());
 
-            // 延迟避免被限流
-            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-        }
+        futures::stream::iter(appids.iter().copied())
+            .map(|appid| {
+                let next_slot = &next_slot;
+                async move {
+                    let cache_dir = options.cache_dir.as_deref();
+                    if let Some(details) = cache_dir
+                        .and_then(|dir| read_details_cache(dir, appid, options.cache_ttl_hours))
+                    {
+                        return (appid, details);
+                    }
+
+                    let mut attempt = 0;
+                    loop {
+                        wait_for_slot(next_slot, interval).await;
+
+                        match self.request_app_details(appid).await {
+                            Ok(details) => {
+                                if let Some(dir) = cache_dir {
+                                    write_details_cache(dir, appid, &details);
+                                }
+                                return (appid, details);
+                            }
+                            Err(DetailsError::RateLimited(retry_after))
+                                if attempt < options.max_retries =>
+                            {
+                                let backoff = retry_after.unwrap_or_else(|| {
+                                    Duration::from_millis(options.backoff_base_ms << attempt)
+                                });
+                                attempt += 1;
+                                log::warn!(
+                                    "⏳ Steam 商店 API 限流, {} 秒后重试 appid {} ({}/{})",
+                                    backoff.as_secs(),
+                                    appid,
+                                    attempt,
+                                    options.max_retries
+                                );
 
-        results
+                                let mut slot = next_slot.lock().await;
+                                *slot = (*slot).max(Instant::now() + backoff);
+                            }
+                            Err(DetailsError::RateLimited(_)) => {
+                                log::error!("❌ 获取 appid {} 失败: 多次被限流", appid);
+                                return (appid, None);
+                            }
+                            Err(DetailsError::Other(e)) => {
+                                log::error!("❌ 获取 appid {} 失败: {}", appid, e);
+                                return (appid, None);
+                            }
+                        }
+                    }
+                }
+            })
+            .buffered(options.max_concurrent.max(1))
+            .collect()
+            .await
     }
 }
 
+/// 等待下一个请求时间槽, 并预留下一个槽位
+async fn wait_for_slot(next_slot: &Mutex<Instant>, interval: Duration) {
+    let start = {
+        let mut slot = next_slot.lock().await;
+        let start = (*slot).max(Instant::now());
+        *slot = start + interval;
+        start
+    };
+
+    tokio::time::sleep_until(start).await;
+}
+
+/// Steam 商店封面图地址 (与 appdetails 返回的 header_image 相同)
+pub fn header_image_url(appid: u32) -> String {
+    format!("https://steamcdn-a.akamaihd.net/steam/apps/{}/header.jpg", appid)
+}
+
 /// 过滤规则
 pub struct FilterRules {
     /// 最低推荐数（点赞数）
@@ -251,10 +727,180 @@ pub fn filter_game(game: &SteamGameDetails, rules: &FilterRules) -> Option<Filte
     })
 }
 
+/// 按名称搜索应用 (不区分大小写, 忽略标点)
+///
+/// 排序: 完全匹配 > 前缀匹配 > 单词前缀 > 包含 > 包含全部关键词 > 按顺序包含所有字符,
+/// 同级按名称长度排序
+pub fn search_apps(apps: &[SteamAppInfo], query: &str, limit: usize) -> Vec<SteamAppInfo> {
+    let query = normalize_name(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u8, &SteamAppInfo)> = apps
+        .iter()
+        .filter_map(|app| match_score(&normalize_name(&app.name), &query).map(|score| (score, app)))
+        .collect();
+
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        a_score
+            .cmp(b_score)
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.appid.cmp(&b.appid))
+    });
+
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, app)| app.clone())
+        .collect()
+}
+
+/// 小写并把标点/空白折叠为单个空格
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 匹配等级 (越小越好), 不匹配时为 None
+fn match_score(name: &str, query: &str) -> Option<u8> {
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    if name.split(' ').any(|word| word.starts_with(query)) {
+        return Some(2);
+    }
+
+    let compact_name: String = name.chars().filter(|c| *c != ' ').collect();
+    let compact_query: String = query.chars().filter(|c| *c != ' ').collect();
+    if compact_name.contains(&compact_query) {
+        return Some(3);
+    }
+    if query.split(' ').all(|word| name.contains(word)) {
+        return Some(4);
+    }
+
+    // 模糊匹配: 查询的字符按顺序出现 (过短的查询不做模糊匹配, 避免大量噪音)
+    if compact_query.chars().count() >= 3 {
+        let mut chars = compact_name.chars();
+        if compact_query.chars().all(|q| chars.any(|c| c == q)) {
+            return Some(5);
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_search_apps_ranking() {
+        let app = |appid, name: &str| SteamAppInfo {
+            appid,
+            name: name.to_string(),
+        };
+        let apps = vec![
+            app(1, "Elden Ring Soundtrack"),
+            app(2, "ELDEN RING"),
+            app(3, "The Elden Chronicles"),
+            app(4, "Golden Ring"),
+            app(5, "Eldenring Fan Art"),
+            app(6, "Counter-Strike 2"),
+        ];
+
+        let ids = |query: &str| -> Vec<u32> {
+            search_apps(&apps, query, 10)
+                .into_iter()
+                .map(|a| a.appid)
+                .collect()
+        };
+
+        assert_eq!(ids("elden ring"), vec![2, 1, 5]);
+        assert_eq!(ids("elden"), vec![2, 5, 1, 3]);
+        // 忽略标点, 模糊匹配
+        assert_eq!(ids("counterstrike"), vec![6]);
+        assert_eq!(ids("cs2"), vec![6]);
+        assert!(ids("  ").is_empty());
+        assert_eq!(search_apps(&apps, "ring", 2).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_slot_spaces_requests() {
+        let next_slot = Mutex::new(Instant::now());
+        let interval = Duration::from_millis(20);
+
+        let start = Instant::now();
+        futures::future::join_all((0..3).map(|_| wait_for_slot(&next_slot, interval))).await;
+
+        // 3 个请求至少间隔 2 个 interval
+        assert!(start.elapsed() >= interval * 2);
+    }
+
+    #[test]
+    fn test_read_app_list_file_skips_unnamed_apps() {
+        let path =
+            std::env::temp_dir().join(format!("gamate_app_list_{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"applist":{"apps":[{"appid":10,"name":"Counter-Strike"},{"appid":11,"name":""},{"appid":12,"name":"  "},{"appid":570,"name":"Dota 2"}]}}"#,
+        )
+        .unwrap();
+
+        let apps = read_app_list_file(&path).unwrap();
+        assert_eq!(
+            apps.iter().map(|a| a.appid).collect::<Vec<_>>(),
+            vec![10, 570]
+        );
+
+        // 截断的文件视为损坏
+        std::fs::write(&path, r#"{"applist":{"apps":[{"appid":10,"#).unwrap();
+        assert!(read_app_list_file(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(is_fresh(SystemTime::now()));
+        assert!(!is_fresh(SystemTime::now() - APP_LIST_CACHE_TTL * 2));
+    }
+
+    #[test]
+    fn test_details_cache() {
+        let dir = std::env::temp_dir().join(format!("gamate_app_details_{}", uuid::Uuid::new_v4()));
+        let details: SteamGameDetails =
+            serde_json::from_str(r#"{"steam_appid":739630,"name":"Phasmophobia","type":"game"}"#)
+                .unwrap();
+
+        assert!(read_details_cache(&dir, 739630, 24).is_none());
+
+        write_details_cache(&dir, 739630, &Some(details));
+        let cached = read_details_cache(&dir, 739630, 24).unwrap().unwrap();
+        assert_eq!(cached.name, "Phasmophobia");
+
+        // 不存在的游戏也会缓存
+        write_details_cache(&dir, 1, &None);
+        assert!(matches!(read_details_cache(&dir, 1, 24), Some(None)));
+
+        // 过期的缓存视为未命中
+        let stale = CachedAppDetails {
+            fetched_at: 0,
+            details: None,
+        };
+        std::fs::write(
+            details_cache_path(&dir, 2),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+        assert!(read_details_cache(&dir, 2, 24).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[tokio::test]
     #[ignore] // 标记为 ignore，避免每次测试都调用 API
     async fn test_get_app_list() {
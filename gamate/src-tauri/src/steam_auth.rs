@@ -4,6 +4,7 @@
 /// 1. Steam OpenID 登录
 /// 2. 获取用户 Steam 库中的游戏列表
 /// 3. 获取用户基本信息
+/// 4. 获取游戏成就进度
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -65,6 +66,132 @@ struct OwnedGamesData {
     games: Option<Vec<OwnedGame>>,
 }
 
+/// 用户资料或游戏详情未公开时读取成就返回的错误 (界面据此提示用户修改隐私设置)
+pub const PROFILE_PRIVATE_ERROR: &str = "STEAM_PROFILE_PRIVATE";
+
+/// 玩家的单个成就进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Achievement {
+    /// 成就 API 名称 (与游戏成就架构中的 name 对应)
+    pub apiname: String,
+    pub achieved: bool,
+    /// 解锁时间 (Unix 秒, 未解锁为 0)
+    pub unlocktime: u64,
+    /// 显示名称 (按请求语言)
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// 成就接口返回的原始成就 (achieved 为 0/1)
+#[derive(Debug, Deserialize)]
+struct RawAchievement {
+    apiname: String,
+    achieved: u8,
+    #[serde(default)]
+    unlocktime: u64,
+    name: Option<String>,
+    description: Option<String>,
+}
+
+/// 获取玩家成就响应
+#[derive(Debug, Deserialize)]
+struct GetPlayerAchievementsResponse {
+    playerstats: PlayerStatsData,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlayerStatsData {
+    #[serde(default)]
+    success: bool,
+    error: Option<String>,
+    achievements: Option<Vec<RawAchievement>>,
+}
+
+/// 游戏成就架构 (显示名称和图标)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSchema {
+    pub game_name: String,
+    pub achievements: Vec<AchievementSchema>,
+}
+
+/// 单个成就的定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct AchievementSchema {
+    /// 成就 API 名称
+    pub name: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 已解锁图标
+    pub icon: String,
+    /// 未解锁 (灰色) 图标
+    pub icongray: String,
+    /// 隐藏成就 (接口返回 0/1)
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub hidden: bool,
+}
+
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(u8::deserialize(deserializer)? != 0)
+}
+
+/// 获取游戏架构响应 (没有成就的游戏缺少 availableGameStats)
+#[derive(Debug, Deserialize)]
+struct GetSchemaForGameResponse {
+    game: SchemaGameData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaGameData {
+    #[serde(default)]
+    game_name: String,
+    available_game_stats: Option<AvailableGameStats>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailableGameStats {
+    achievements: Option<Vec<AchievementSchema>>,
+}
+
+/// 解析玩家成就响应 (HTTP 状态为 403 时响应体同样是 playerstats)
+fn parse_player_achievements(text: &str) -> Result<Vec<Achievement>, String> {
+    let data: GetPlayerAchievementsResponse = serde_json::from_str(text).map_err(|e| {
+        format!(
+            "解析响应失败: {}。响应内容: {}",
+            e,
+            &text[..text.len().min(200)]
+        )
+    })?;
+    let stats = data.playerstats;
+
+    if !stats.success {
+        let error = stats.error.unwrap_or_default();
+        // 资料或游戏详情未公开时 Steam 返回 "Profile is not public"
+        if error.to_lowercase().contains("not public") {
+            return Err(PROFILE_PRIVATE_ERROR.to_string());
+        }
+        return Err(format!("Steam API 返回错误: {}", error));
+    }
+
+    Ok(stats
+        .achievements
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| Achievement {
+            apiname: a.apiname,
+            achieved: a.achieved != 0,
+            unlocktime: a.unlocktime,
+            name: a.name,
+            description: a.description,
+        })
+        .collect())
+}
+
 /// Steam Web API 客户端
 pub struct SteamAuthClient {
     api_key: String,
@@ -178,6 +305,75 @@ impl SteamAuthClient {
 
         Ok(data.response.games.unwrap_or_default())
     }
+
+    /// 获取玩家在某个游戏中的成就进度
+    ///
+    /// 资料未公开时返回 `PROFILE_PRIVATE_ERROR`; 游戏没有成就时返回错误
+    pub async fn get_player_achievements(
+        &self,
+        steamid: &str,
+        appid: u32,
+    ) -> Result<Vec<Achievement>, String> {
+        let url = format!(
+            "https://api.steampowered.com/ISteamUserStats/GetPlayerAchievements/v1/?key={}&steamid={}&appid={}&l=schinese",
+            self.api_key, steamid, appid
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {}", e))?;
+
+        // 资料未公开时状态码为 403, 但响应体仍包含错误原因, 因此先解析再看状态码
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+
+        match parse_player_achievements(&text) {
+            Err(e) if !status.is_success() && e != PROFILE_PRIVATE_ERROR => {
+                Err(format!("Steam API 返回错误状态码: {} ({})", status, e))
+            }
+            result => result,
+        }
+    }
+
+    /// 获取游戏的成就架构 (成就显示名称、描述和图标)
+    pub async fn get_game_schema(&self, appid: u32) -> Result<GameSchema, String> {
+        let url = format!(
+            "https://api.steampowered.com/ISteamUserStats/GetSchemaForGame/v2/?key={}&appid={}&l=schinese",
+            self.api_key, appid
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Steam API 返回错误状态码: {}", status));
+        }
+
+        let data: GetSchemaForGameResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("解析响应失败: {}", e))?;
+
+        Ok(GameSchema {
+            game_name: data.game.game_name,
+            achievements: data
+                .game
+                .available_game_stats
+                .and_then(|stats| stats.achievements)
+                .unwrap_or_default(),
+        })
+    }
 }
 
 /// Steam OpenID 认证帮助函数
@@ -201,50 +397,224 @@ pub mod openid {
         Ok(url.to_string())
     }
 
-    /// 从回调 URL 中提取 Steam ID
-    pub fn extract_steamid_from_callback(callback_url: &str) -> Result<String, String> {
-        let url = Url::parse(callback_url)
-            .map_err(|e| format!("URL 解析失败: {}", e))?;
+    /// Steam OpenID 端点
+    pub const STEAM_OP_ENDPOINT: &str = "https://steamcommunity.com/openid/login";
+
+    /// OpenID 2.0 命名空间
+    const OPENID_NS: &str = "http://specs.openid.net/auth/2.0";
 
-        let params: HashMap<String, String> = url
+    /// Steam 身份 URL 前缀 (后接 64 位 Steam ID)
+    const STEAM_ID_PREFIX: &str = "https://steamcommunity.com/openid/id/";
+
+    /// 必须被签名覆盖的字段 (不含 `openid.` 前缀)
+    const REQUIRED_SIGNED_FIELDS: [&str; 6] = [
+        "op_endpoint",
+        "claimed_id",
+        "identity",
+        "return_to",
+        "response_nonce",
+        "assoc_handle",
+    ];
+
+    /// 解析回调 URL 的查询参数
+    pub fn parse_callback_params(callback_url: &str) -> Result<HashMap<String, String>, String> {
+        let url = Url::parse(callback_url).map_err(|e| format!("URL 解析失败: {}", e))?;
+
+        Ok(url
             .query_pairs()
             .map(|(k, v)| (k.to_string(), v.to_string()))
-            .collect();
+            .collect())
+    }
 
-        // 验证 OpenID 响应
-        if params.get("openid.mode") != Some(&"id_res".to_string()) {
+    /// 从回调 URL 中提取 Steam ID (只做本地校验, 见 [`validate_callback_params`])
+    ///
+    /// 签名是否有效需要再调用 [`verify_openid_response`] 向 Steam 确认, 完整流程使用 [`verify_callback`]
+    pub fn extract_steamid_from_callback(callback_url: &str) -> Result<String, String> {
+        let params = parse_callback_params(callback_url)?;
+        validate_callback_params(&params, Some(callback_url))
+    }
+
+    /// 校验 OpenID 回调参数, 返回 Steam ID
+    ///
+    /// - 命名空间、模式和 `op_endpoint` 必须是 Steam 的
+    /// - `claimed_id` 必须是 Steam 身份 URL, 且与 `identity` 一致
+    /// - `openid.signed` 必须覆盖身份、端点、回调地址和 nonce, 且被签名的字段都存在
+    /// - 传入 `callback_url` 时, `return_to` 必须指向同一回调地址 (防止把其他站点的登录响应转发过来)
+    pub fn validate_callback_params(
+        params: &HashMap<String, String>,
+        callback_url: Option<&str>,
+    ) -> Result<String, String> {
+        let param = |key: &str| params.get(&format!("openid.{}", key)).map(String::as_str);
+
+        if param("ns") != Some(OPENID_NS) {
+            return Err("无效的 OpenID 响应: 命名空间不匹配".to_string());
+        }
+        if param("mode") != Some("id_res") {
             return Err("无效的 OpenID 响应".to_string());
         }
+        if param("op_endpoint") != Some(STEAM_OP_ENDPOINT) {
+            return Err("无效的 OpenID 响应: 不是 Steam 的登录端点".to_string());
+        }
+        if param("sig").is_none_or(str::is_empty) {
+            return Err("无效的 OpenID 响应: 缺少签名".to_string());
+        }
+
+        let signed: Vec<&str> = param("signed").unwrap_or_default().split(',').collect();
+        if let Some(missing) = REQUIRED_SIGNED_FIELDS.iter().find(|f| !signed.contains(f)) {
+            return Err(format!("无效的 OpenID 响应: 签名未覆盖 {}", missing));
+        }
+        if let Some(absent) = signed.iter().find(|f| param(f).is_none()) {
+            return Err(format!("无效的 OpenID 响应: 缺少被签名的字段 {}", absent));
+        }
+
+        let claimed_id = param("claimed_id").unwrap_or_default();
+        if param("identity") != Some(claimed_id) {
+            return Err("无效的 OpenID 响应: identity 与 claimed_id 不一致".to_string());
+        }
 
-        // 从 claimed_id 中提取 Steam ID
         // 格式: https://steamcommunity.com/openid/id/76561198XXXXXXXXX
-        if let Some(claimed_id) = params.get("openid.claimed_id") {
-            if let Some(steamid) = claimed_id.split('/').last() {
-                return Ok(steamid.to_string());
+        let steamid = claimed_id
+            .strip_prefix(STEAM_ID_PREFIX)
+            .filter(|id| id.len() == 17 && id.chars().all(|c| c.is_ascii_digit()))
+            .ok_or_else(|| "无法从回调中提取 Steam ID".to_string())?;
+
+        if let Some(callback_url) = callback_url {
+            let return_to = param("return_to").unwrap_or_default();
+            if !same_endpoint(return_to, callback_url) {
+                return Err("无效的 OpenID 响应: return_to 与回调地址不一致".to_string());
             }
         }
 
-        Err("无法从回调中提取 Steam ID".to_string())
+        Ok(steamid.to_string())
     }
 
-    /// 验证 OpenID 响应
+    /// 比较两个 URL 的协议、主机、端口和路径 (忽略查询参数)
+    fn same_endpoint(a: &str, b: &str) -> bool {
+        match (Url::parse(a), Url::parse(b)) {
+            (Ok(a), Ok(b)) => {
+                a.scheme() == b.scheme()
+                    && a.host_str() == b.host_str()
+                    && a.port_or_known_default() == b.port_or_known_default()
+                    && a.path() == b.path()
+            }
+            _ => false,
+        }
+    }
+
+    /// 完整校验登录回调: 本地校验参数后向 Steam 确认签名, 返回可信的 Steam ID
+    ///
+    /// Steam 对每个 nonce 只确认一次, 重放的回调会被拒绝
+    pub async fn verify_callback(callback_url: &str) -> Result<String, String> {
+        let params = parse_callback_params(callback_url)?;
+        let steamid = validate_callback_params(&params, Some(callback_url))?;
+
+        if !verify_openid_response(params).await? {
+            return Err("Steam 登录验证失败: 签名无效或已被使用".to_string());
+        }
+
+        Ok(steamid)
+    }
+
+    /// 向 Steam 验证 OpenID 响应签名
+    ///
+    /// 先做本地校验 (不校验 `return_to`), 只有被签名的参数集合合法时才请求 Steam
     pub async fn verify_openid_response(params: HashMap<String, String>) -> Result<bool, String> {
+        validate_callback_params(&params, None)?;
+
         let client = reqwest::Client::new();
-        
-        // 构建验证参数
-        let mut verify_params = params.clone();
-        verify_params.insert("openid.mode".to_string(), "check_authentication".to_string());
+
+        // 构建验证参数 (只回传 openid.* 参数)
+        let mut verify_params: HashMap<String, String> = params
+            .into_iter()
+            .filter(|(k, _)| k.starts_with("openid."))
+            .collect();
+        verify_params.insert(
+            "openid.mode".to_string(),
+            "check_authentication".to_string(),
+        );
 
         let response = client
-            .post("https://steamcommunity.com/openid/login")
+            .post(STEAM_OP_ENDPOINT)
             .form(&verify_params)
             .send()
             .await
             .map_err(|e| format!("验证请求失败: {}", e))?;
 
-        let body = response.text().await.map_err(|e| format!("读取响应失败: {}", e))?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+
+        Ok(is_valid_response(&body))
+    }
+
+    /// 解析 check_authentication 的 key:value 响应
+    fn is_valid_response(body: &str) -> bool {
+        body.lines()
+            .filter_map(|line| line.split_once(':'))
+            .any(|(key, value)| key.trim() == "is_valid" && value.trim() == "true")
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
 
-        Ok(body.contains("is_valid:true"))
+        /// 抓取的 Steam 登录回调 (签名已替换)
+        const VALID_CALLBACK: &str = "http://localhost:1420/auth/steam/callback?openid.ns=http%3A%2F%2Fspecs.openid.net%2Fauth%2F2.0&openid.mode=id_res&openid.op_endpoint=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Flogin&openid.claimed_id=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Fid%2F76561198123456789&openid.identity=https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Fid%2F76561198123456789&openid.return_to=http%3A%2F%2Flocalhost%3A1420%2Fauth%2Fsteam%2Fcallback&openid.response_nonce=2025-01-01T00%3A00%3A00Zabc123&openid.assoc_handle=1234567890&openid.signed=signed%2Cop_endpoint%2Cclaimed_id%2Cidentity%2Creturn_to%2Cresponse_nonce%2Cassoc_handle&openid.sig=W0v2b3JrZWQrc2lnbmF0dXJlPQ%3D%3D";
+
+        fn tampered(from: &str, to: &str) -> Result<String, String> {
+            assert!(VALID_CALLBACK.contains(from));
+            extract_steamid_from_callback(&VALID_CALLBACK.replacen(from, to, 1))
+        }
+
+        #[test]
+        fn test_validate_valid_callback() {
+            assert_eq!(
+                extract_steamid_from_callback(VALID_CALLBACK).unwrap(),
+                "76561198123456789"
+            );
+        }
+
+        #[test]
+        fn test_validate_tampered_callback() {
+            // claimed_id 被替换但 identity 未变
+            assert!(tampered(
+                "id%2F76561198123456789&openid.identity",
+                "id%2F76561198000000001&openid.identity"
+            )
+            .is_err());
+            // 签名不覆盖 claimed_id
+            assert!(tampered("%2Cclaimed_id", "").is_err());
+            // 伪造的 OpenID 端点
+            assert!(tampered(
+                "https%3A%2F%2Fsteamcommunity.com%2Fopenid%2Flogin",
+                "https%3A%2F%2Fevil.example%2Fopenid%2Flogin"
+            )
+            .is_err());
+            // 非 Steam 身份
+            assert!(tampered(
+                "openid.claimed_id=https%3A%2F%2Fsteamcommunity.com",
+                "openid.claimed_id=https%3A%2F%2Fevil.example"
+            )
+            .is_err());
+            // 转发给其他站点的回调
+            assert!(tampered("localhost%3A1420", "evil.example").is_err());
+            // 缺少签名
+            assert!(tampered("&openid.sig=W0v2b3JrZWQrc2lnbmF0dXJlPQ%3D%3D", "").is_err());
+            // 模式不是 id_res
+            assert!(tampered("mode=id_res", "mode=cancel").is_err());
+        }
+
+        #[test]
+        fn test_is_valid_response() {
+            assert!(is_valid_response(
+                "ns:http://specs.openid.net/auth/2.0\nis_valid:true\n"
+            ));
+            assert!(!is_valid_response(
+                "ns:http://specs.openid.net/auth/2.0\nis_valid:false\n"
+            ));
+            assert!(!is_valid_response("error:is_valid:true"));
+        }
     }
 }
 
@@ -265,10 +635,10 @@ mod tests {
 
     #[test]
     fn test_extract_steamid() {
+        // 只有 claimed_id, 没有签名的回调不可信
         let callback_url = "http://localhost:3000/auth/steam/callback?openid.ns=http://specs.openid.net/auth/2.0&openid.mode=id_res&openid.claimed_id=https://steamcommunity.com/openid/id/76561198123456789";
         
-        let steamid = openid::extract_steamid_from_callback(callback_url).unwrap();
-        assert_eq!(steamid, "76561198123456789");
+        assert!(openid::extract_steamid_from_callback(callback_url).is_err());
     }
 
     #[tokio::test]
@@ -310,4 +680,52 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_parse_player_achievements() {
+        let achievements = parse_player_achievements(
+            r#"{"playerstats":{"steamID":"1","gameName":"Phasmophobia","achievements":[{"apiname":"ACH_A","achieved":1,"unlocktime":1700000000},{"apiname":"ACH_B","achieved":0,"unlocktime":0}],"success":true}}"#,
+        )
+        .unwrap();
+        assert_eq!(achievements.len(), 2);
+        assert!(achievements[0].achieved);
+        assert!(!achievements[1].achieved);
+
+        let private = parse_player_achievements(
+            r#"{"playerstats":{"error":"Profile is not public","success":false}}"#,
+        );
+        assert_eq!(private.unwrap_err(), PROFILE_PRIVATE_ERROR);
+
+        let no_stats = parse_player_achievements(
+            r#"{"playerstats":{"error":"Requested app has no stats","success":false}}"#,
+        );
+        assert!(no_stats.unwrap_err().contains("no stats"));
+    }
+
+    #[tokio::test]
+    #[ignore] // 需要真实的 API Key
+    async fn test_get_player_achievements() {
+        let api_key = std::env::var("STEAM_API_KEY").expect("需要设置 STEAM_API_KEY 环境变量");
+        let client = SteamAuthClient::new(api_key);
+
+        let steamid = "76561197960287930";
+        // 传送门 2 - appid: 620
+        let appid = 620;
+
+        let schema = client.get_game_schema(appid).await.unwrap();
+        println!(
+            "✅ {} 共 {} 个成就",
+            schema.game_name,
+            schema.achievements.len()
+        );
+
+        match client.get_player_achievements(steamid, appid).await {
+            Ok(achievements) => {
+                let unlocked = achievements.iter().filter(|a| a.achieved).count();
+                println!("   已解锁 {}/{}", unlocked, achievements.len());
+            }
+            Err(e) if e == PROFILE_PRIVATE_ERROR => println!("   资料未公开"),
+            Err(e) => panic!("{}", e),
+        }
+    }
 }
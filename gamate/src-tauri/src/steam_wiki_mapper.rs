@@ -31,6 +31,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(500),
                 max_depth: Some(5),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // Elden Ring
@@ -45,6 +48,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(1000),
                 max_depth: Some(6),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // Baldur's Gate 3
@@ -59,6 +65,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(800),
                 max_depth: Some(5),
                 request_delay_ms: Some(600),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // CS2
@@ -73,6 +82,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(600),
                 max_depth: Some(5),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // Dota 2
@@ -87,6 +99,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(1000),
                 max_depth: Some(6),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // Terraria
@@ -101,6 +116,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(1200),
                 max_depth: Some(6),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
         // Minecraft (Java Edition)
@@ -115,6 +133,9 @@ fn get_known_game_wikis(appid: u32) -> Option<Vec<SkillConfig>> {
                 max_pages: Some(1500),
                 max_depth: Some(7),
                 request_delay_ms: Some(500),
+                user_agent: None,
+                headers: None,
+                cookies: None,
             },
         ]),
     ].into_iter().collect();
@@ -138,6 +159,9 @@ fn generate_generic_wiki_config(appid: u32, game_name: &str) -> Vec<SkillConfig>
             max_pages: Some(500),
             max_depth: Some(5),
             request_delay_ms: Some(600),
+            user_agent: None,
+            headers: None,
+            cookies: None,
         },
     ]
 }
@@ -5,11 +5,29 @@
 /// macOS: AVFoundation
 /// Linux: Speech Dispatcher
 ///
+/// 设置了 `tts.output_device` 时 (仅 Windows), 语音先合成为 WAV 再通过 cpal 播放到指定设备
+/// `tts.provider` 为 aliyun 时使用阿里云语音合成, 合成的 WAV 通过 cpal 播放
+/// 播报期间设置 `audio::echo_gate` 的播放标记, 持续监听据此忽略麦克风中的播报声音
+///
+use crate::aliyun_voice_service;
+use crate::audio::{echo_gate, player};
+use crate::settings::AppSettings;
 use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tts::Tts;
 
+/// 查询播报是否结束的间隔
+const SPEAKING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 等待引擎开始发声的最长时间
+const SPEAKING_START_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 引擎不支持查询播报状态时, 每个字估算的播报时长
+const ESTIMATED_SPEECH_PER_CHAR: Duration = Duration::from_millis(200);
+
 /// TTS 播报请求
 #[derive(Debug, Clone)]
 pub struct SpeakRequest {
@@ -17,10 +35,32 @@ pub struct SpeakRequest {
     pub interrupt: bool, // 是否打断当前播报
 }
 
+/// 输出到指定设备时使用的语音参数 (与 `tts` 引擎的设置保持同步)
+#[derive(Debug, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))]
+struct RoutedVoice {
+    rate: f32,
+    volume: f32,
+    voice: Option<String>,
+}
+
+impl Default for RoutedVoice {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            volume: 1.0,
+            voice: None,
+        }
+    }
+}
+
 /// TTS 引擎状态
 pub struct TtsEngine {
     tts: Arc<Mutex<Tts>>,
     queue_tx: mpsc::UnboundedSender<SpeakRequest>,
+    routed_voice: Arc<Mutex<RoutedVoice>>,
+    /// 取消指定设备上正在进行的播放
+    routed_cancel: Arc<AtomicBool>,
 }
 
 impl TtsEngine {
@@ -34,18 +74,77 @@ impl TtsEngine {
         log::info!("✅ TTS 引擎初始化成功");
 
         let tts = Arc::new(Mutex::new(tts));
+        let routed_voice = Arc::new(Mutex::new(RoutedVoice::default()));
+        let routed_cancel = Arc::new(AtomicBool::new(false));
 
         // 创建播报队列
         let (queue_tx, mut queue_rx) = mpsc::unbounded_channel::<SpeakRequest>();
 
         // 启动后台播报任务
         let tts_clone = Arc::clone(&tts);
+        let routed_voice_clone = Arc::clone(&routed_voice);
+        let routed_cancel_clone = Arc::clone(&routed_cancel);
         tokio::spawn(async move {
             log::info!("🎙️ TTS 播报队列已启动");
 
             while let Some(request) = queue_rx.recv().await {
                 log::debug!("📢 收到播报请求: {:?}", request);
 
+                // 阿里云语音合成: 合成完整音频后播放到输出设备
+                if let Some(device) = aliyun_provider_device() {
+                    let cancel = Arc::clone(&routed_cancel_clone);
+                    cancel.store(false, Ordering::Relaxed);
+
+                    let wav = match aliyun_voice_service::aliyun_synthesize_wav(
+                        &request.text,
+                        None,
+                        None,
+                    )
+                    .await
+                    {
+                        Ok(wav) => wav,
+                        Err(e) => {
+                            log::error!("❌ 阿里云语音合成失败: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let generation = echo_gate::begin_playback();
+                    let result = tokio::task::spawn_blocking(move || {
+                        player::play_wav_bytes(device.as_deref(), &wav, Some(&cancel))
+                    })
+                    .await;
+                    echo_gate::end_playback(generation);
+
+                    match result {
+                        Ok(Ok(())) => log::debug!("✅ 阿里云播报完成"),
+                        Ok(Err(e)) => log::error!("❌ 阿里云播报失败: {}", e),
+                        Err(e) => log::error!("❌ 播报任务异常: {}", e),
+                    }
+                    continue;
+                }
+
+                // 指定了输出设备时合成后播放到该设备 (逐条阻塞播放)
+                if let Some(device) = routed_output_device() {
+                    let voice = routed_voice_clone.lock().unwrap().clone();
+                    let cancel = Arc::clone(&routed_cancel_clone);
+                    cancel.store(false, Ordering::Relaxed);
+
+                    let generation = echo_gate::begin_playback();
+                    let result = tokio::task::spawn_blocking(move || {
+                        speak_to_device(&request.text, &voice, &device, &cancel)
+                    })
+                    .await;
+                    echo_gate::end_playback(generation);
+
+                    match result {
+                        Ok(Ok(())) => log::debug!("✅ 播报到指定设备完成"),
+                        Ok(Err(e)) => log::error!("❌ 播报到指定设备失败: {}", e),
+                        Err(e) => log::error!("❌ 播报任务异常: {}", e),
+                    }
+                    continue;
+                }
+
                 let mut tts = tts_clone.lock().unwrap();
 
                 // 如果需要打断,先停止当前播报
@@ -55,16 +154,19 @@ impl TtsEngine {
                     }
                 }
 
-                // 开始播报
+                // 开始播报 (发声前先设置播放标记)
+                let generation = echo_gate::begin_playback();
                 match tts.speak(&request.text, request.interrupt) {
                     Ok(_) => {
                         log::debug!(
                             "✅ 播报成功: {}",
                             &request.text[..request.text.len().min(50)]
                         );
+                        watch_speaking(Arc::clone(&tts_clone), generation, &request.text);
                     }
                     Err(e) => {
                         log::error!("❌ 播报失败: {}", e);
+                        echo_gate::end_playback(generation);
                     }
                 }
             }
@@ -72,11 +174,20 @@ impl TtsEngine {
             log::warn!("🛑 TTS 播报队列已关闭");
         });
 
-        Ok(Self { tts, queue_tx })
+        Ok(Self {
+            tts,
+            queue_tx,
+            routed_voice,
+            routed_cancel,
+        })
     }
 
     /// 播报文本 (异步,不阻塞)
     pub fn speak(&self, text: String, interrupt: bool) -> Result<()> {
+        if interrupt {
+            self.routed_cancel.store(true, Ordering::Relaxed);
+        }
+
         self.queue_tx
             .send(SpeakRequest { text, interrupt })
             .context("发送播报请求失败")?;
@@ -85,6 +196,8 @@ impl TtsEngine {
 
     /// 停止当前播报
     pub fn stop(&self) -> Result<()> {
+        self.routed_cancel.store(true, Ordering::Relaxed);
+
         let mut tts = self.tts.lock().unwrap();
         tts.stop().context("停止播报失败")?;
         Ok(())
@@ -92,6 +205,8 @@ impl TtsEngine {
 
     /// 设置语速 (0.0 - 10.0, 默认 1.0)
     pub fn set_rate(&self, rate: f32) -> Result<()> {
+        self.routed_voice.lock().unwrap().rate = rate;
+
         let mut tts = self.tts.lock().unwrap();
 
         // 尝试设置语速
@@ -109,6 +224,8 @@ impl TtsEngine {
 
     /// 设置音量 (0.0 - 1.0)
     pub fn set_volume(&self, volume: f32) -> Result<()> {
+        self.routed_voice.lock().unwrap().volume = volume;
+
         let mut tts = self.tts.lock().unwrap();
 
         // 尝试设置音量
@@ -151,6 +268,8 @@ impl TtsEngine {
             Ok(voices) => {
                 // 查找匹配的音色
                 if let Some(target_voice) = voices.iter().find(|v| v.name() == voice_name) {
+                    self.routed_voice.lock().unwrap().voice = Some(voice_name.to_string());
+
                     match tts.set_voice(target_voice) {
                         Ok(_) => {
                             log::info!("🎤 音色已设置为: {}", voice_name);
@@ -174,6 +293,119 @@ impl TtsEngine {
     }
 }
 
+/// 当前配置的输出设备 (未配置或平台不支持时为 None)
+fn routed_output_device() -> Option<String> {
+    let device = AppSettings::load()
+        .ok()
+        .and_then(|s| s.tts.output_device)
+        .filter(|d| !d.is_empty())?;
+
+    if cfg!(windows) {
+        Some(device)
+    } else {
+        log::warn!("⚠️ 当前平台不支持指定 TTS 输出设备, 使用系统默认设备");
+        None
+    }
+}
+
+/// 使用阿里云语音合成时返回 Some(输出设备名称, None 为系统默认设备)
+fn aliyun_provider_device() -> Option<Option<String>> {
+    let settings = AppSettings::load().ok()?.tts;
+    if settings.provider != "aliyun" {
+        return None;
+    }
+    Some(settings.output_device.filter(|d| !d.is_empty()))
+}
+
+/// 播报真正结束后清除本次播放的标记
+///
+/// `speak` 返回时引擎可能还没开始发声, 先等待 `is_speaking` 变为 true 再等待其变回 false
+/// (超过 `SPEAKING_START_TIMEOUT` 仍未开始视为已结束); 引擎不支持查询播报状态时按文本长度估算时长
+fn watch_speaking(tts: Arc<Mutex<Tts>>, generation: u64, text: &str) {
+    let estimated = ESTIMATED_SPEECH_PER_CHAR * text.chars().count() as u32;
+
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let mut speech_started = false;
+
+        loop {
+            tokio::time::sleep(SPEAKING_POLL_INTERVAL).await;
+            let speaking = tts.lock().unwrap().is_speaking();
+            match speaking {
+                Ok(true) => speech_started = true,
+                Ok(false) if speech_started || started.elapsed() >= SPEAKING_START_TIMEOUT => break,
+                Ok(false) => {}
+                Err(_) => {
+                    tokio::time::sleep(estimated.saturating_sub(started.elapsed())).await;
+                    break;
+                }
+            }
+        }
+
+        echo_gate::end_playback(generation);
+    });
+}
+
+/// 合成语音并播放到指定设备 (阻塞)
+#[cfg(windows)]
+fn speak_to_device(
+    text: &str,
+    voice: &RoutedVoice,
+    device: &str,
+    cancel: &AtomicBool,
+) -> Result<()> {
+    let wav = synthesize_wav(text, voice)?;
+    crate::audio::player::play_wav_bytes(Some(device), &wav, Some(cancel))
+}
+
+#[cfg(not(windows))]
+fn speak_to_device(
+    _text: &str,
+    _voice: &RoutedVoice,
+    _device: &str,
+    _cancel: &AtomicBool,
+) -> Result<()> {
+    anyhow::bail!("当前平台不支持指定 TTS 输出设备")
+}
+
+/// 使用 WinRT SpeechSynthesizer 合成 WAV 数据
+#[cfg(windows)]
+fn synthesize_wav(text: &str, voice: &RoutedVoice) -> Result<Vec<u8>> {
+    use windows::core::HSTRING;
+    use windows::Media::SpeechSynthesis::SpeechSynthesizer;
+    use windows::Storage::Streams::DataReader;
+
+    let synthesizer = SpeechSynthesizer::new()?;
+
+    // 音色名称与 `tts` crate 一致 (DisplayName)
+    if let Some(name) = &voice.voice {
+        let target = SpeechSynthesizer::AllVoices()?
+            .into_iter()
+            .find(|v| v.DisplayName().is_ok_and(|n| n.to_string() == *name));
+        if let Some(target) = target {
+            synthesizer.SetVoice(&target)?;
+        }
+    }
+
+    let options = synthesizer.Options()?;
+    options.SetSpeakingRate(voice.rate.clamp(0.5, 6.0) as f64)?;
+    options.SetAudioVolume(voice.volume.clamp(0.0, 1.0) as f64)?;
+
+    let stream = synthesizer
+        .SynthesizeTextToStreamAsync(&HSTRING::from(text))?
+        .get()
+        .context("语音合成失败")?;
+
+    let size = stream.Size()? as u32;
+    let reader = DataReader::CreateDataReader(&stream.GetInputStreamAt(0)?)?;
+    reader.LoadAsync(size)?.get()?;
+
+    let mut wav = vec![0u8; size as usize];
+    reader.ReadBytes(&mut wav)?;
+
+    Ok(wav)
+}
+
 /// 全局 TTS 引擎实例
 static mut TTS_ENGINE: Option<Arc<TtsEngine>> = None;
 static INIT: std::sync::Once = std::sync::Once::new();
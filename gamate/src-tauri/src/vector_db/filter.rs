@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+/// Qdrant payload 过滤条件
+///
+/// 序列化后即为 Qdrant 的 `filter` 对象, 例如只检索 "Bosses" 分类:
+/// `SearchFilter::new().must(FilterCondition::matches("categories", "Bosses"))`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// 必须全部满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub must: Vec<FilterCondition>,
+    /// 至少满足一个
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub should: Vec<FilterCondition>,
+    /// 必须全部不满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub must_not: Vec<FilterCondition>,
+}
+
+impl SearchFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn must(mut self, condition: FilterCondition) -> Self {
+        self.must.push(condition);
+        self
+    }
+
+    pub fn should(mut self, condition: FilterCondition) -> Self {
+        self.should.push(condition);
+        self
+    }
+
+    pub fn must_not(mut self, condition: FilterCondition) -> Self {
+        self.must_not.push(condition);
+        self
+    }
+
+    /// 没有任何条件 (等同于不过滤)
+    pub fn is_empty(&self) -> bool {
+        self.must.is_empty() && self.should.is_empty() && self.must_not.is_empty()
+    }
+}
+
+/// 单个 payload 字段条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterCondition {
+    /// payload 字段名 (如 categories, game_id, url)
+    pub key: String,
+    #[serde(flatten)]
+    pub condition: Condition,
+}
+
+impl FilterCondition {
+    /// 字段等于指定值 (数组字段包含该值即匹配)
+    pub fn matches(key: &str, value: impl Into<serde_json::Value>) -> Self {
+        Self {
+            key: key.to_string(),
+            condition: Condition::Match(MatchValue::Value {
+                value: value.into(),
+            }),
+        }
+    }
+
+    /// 字段等于其中任意一个值
+    pub fn any(key: &str, values: Vec<serde_json::Value>) -> Self {
+        Self {
+            key: key.to_string(),
+            condition: Condition::Match(MatchValue::Any { any: values }),
+        }
+    }
+
+    /// 字段包含指定文本 (没有全文索引时为子串匹配, 可用于 URL 前缀)
+    pub fn text(key: &str, text: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            condition: Condition::Match(MatchValue::Text {
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    /// 数值字段范围
+    pub fn range(key: &str, range: Range) -> Self {
+        Self {
+            key: key.to_string(),
+            condition: Condition::Range(range),
+        }
+    }
+}
+
+/// 条件类型 (序列化为 `"match": {...}` 或 `"range": {...}`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Match(MatchValue),
+    Range(Range),
+}
+
+/// 匹配条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MatchValue {
+    Value { value: serde_json::Value },
+    Any { any: Vec<serde_json::Value> },
+    Text { text: String },
+}
+
+/// 范围条件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Range {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gt: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gte: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lt: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lte: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_search_filter_json() {
+        let filter = SearchFilter::new()
+            .must(FilterCondition::matches("game_id", "elden_ring"))
+            .must(FilterCondition::matches("categories", "Bosses"))
+            .should(FilterCondition::any(
+                "categories",
+                vec![json!("Bosses"), json!("Enemies")],
+            ))
+            .should(FilterCondition::text(
+                "url",
+                "https://eldenring.wiki.fextralife.com/",
+            ))
+            .must_not(FilterCondition::range(
+                "timestamp",
+                Range {
+                    lt: Some(1700000000.0),
+                    ..Default::default()
+                },
+            ));
+
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({
+                "must": [
+                    { "key": "game_id", "match": { "value": "elden_ring" } },
+                    { "key": "categories", "match": { "value": "Bosses" } }
+                ],
+                "should": [
+                    { "key": "categories", "match": { "any": ["Bosses", "Enemies"] } },
+                    { "key": "url", "match": { "text": "https://eldenring.wiki.fextralife.com/" } }
+                ],
+                "must_not": [
+                    { "key": "timestamp", "range": { "lt": 1700000000.0 } }
+                ]
+            })
+        );
+
+        // 反序列化后保持一致
+        let parsed: SearchFilter =
+            serde_json::from_value(serde_json::to_value(&filter).unwrap()).unwrap();
+        assert_eq!(parsed, filter);
+
+        // 空过滤条件
+        assert!(SearchFilter::new().is_empty());
+        assert_eq!(
+            serde_json::to_value(SearchFilter::new()).unwrap(),
+            json!({})
+        );
+    }
+}
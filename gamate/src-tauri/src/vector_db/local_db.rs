@@ -130,27 +130,71 @@ impl LocalVectorDB {
             .into_iter()
             .take(limit)
             .map(|(score, entry)| super::SearchResult {
+                id: Some(entry.id),
                 score,
                 payload: entry.payload,
             })
             .collect())
     }
 
-    /// 获取集合信息
-    pub fn get_collection_info(&self) -> Result<super::CollectionInfo> {
+    /// 按条目 ID (payload 中的 `id`) 读取已存储的向量
+    pub fn get_vector_by_entry_id(&self, entry_id: &str) -> Result<Option<Vec<f32>>> {
+        if !self.collection_exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(self.collection_file())?;
+        let data: CollectionData = serde_json::from_str(&json)?;
+
+        Ok(data
+            .vectors
+            .into_iter()
+            .find(|entry| entry.payload.get("id").and_then(|v| v.as_str()) == Some(entry_id))
+            .map(|entry| entry.vector))
+    }
+
+    /// 读取集合中的所有点 (id, 向量, payload)
+    pub fn read_all_points(&self) -> Result<Vec<(u64, Vec<f32>, serde_json::Value)>> {
         if !self.collection_exists() {
-            return Ok(super::CollectionInfo {
-                vectors_count: 0,
-                points_count: 0,
-            });
+            return Err(anyhow::anyhow!("集合不存在"));
         }
 
         let json = std::fs::read_to_string(self.collection_file())?;
         let data: CollectionData = serde_json::from_str(&json)?;
 
+        Ok(data
+            .vectors
+            .into_iter()
+            .map(|entry| (entry.id, entry.vector, entry.payload))
+            .collect())
+    }
+
+    /// 集合中的点数量 (集合不存在时为 0)
+    pub fn point_count(&self) -> Result<u64> {
+        if !self.collection_exists() {
+            return Ok(0);
+        }
+
+        let json = std::fs::read_to_string(self.collection_file())?;
+        let data: CollectionData = serde_json::from_str(&json)?;
+
+        Ok(data.vectors.len() as u64)
+    }
+
+    /// 集合文件占用的字节数 (集合不存在时为 0)
+    pub fn size_bytes(&self) -> u64 {
+        std::fs::metadata(self.collection_file())
+            .map(|m| m.len())
+            .unwrap_or(0)
+    }
+
+    /// 获取集合信息
+    pub fn get_collection_info(&self) -> Result<super::CollectionInfo> {
+        let count = self.point_count()?;
+
         Ok(super::CollectionInfo {
-            vectors_count: data.vectors.len() as u64,
-            points_count: data.vectors.len() as u64,
+            vectors_count: count,
+            points_count: count,
         })
     }
 }
@@ -1,12 +1,16 @@
 mod ai_search;
+mod filter;
 mod local_db;
+mod retry;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 pub use ai_search::{AIDirectSearch, SearchResult as AISearchResult};
+pub use filter::{Condition, FilterCondition, MatchValue, Range, SearchFilter};
 pub use local_db::LocalVectorDB;
+pub use retry::RetryPolicy;
 
 /// 向量数据库模式
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,11 +28,16 @@ pub struct VectorDB {
     client: reqwest::Client,
     base_url: String,
     collection_name: String,
+    retry: RetryPolicy,
 }
 
 /// 搜索结果
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchResult {
+    /// 点 ID (Qdrant 返回字符串 UUID 时为空)
+    #[serde(default)]
+    pub id: Option<u64>,
+    /// 相似度 (分页读取时没有检索分数, 为 0)
     pub score: f32,
     pub payload: serde_json::Value,
 }
@@ -63,11 +72,21 @@ struct PointData {
     payload: serde_json::Map<String, serde_json::Value>,
 }
 
+/// 删除请求 (按 ID 列表或按过滤条件)
+#[derive(Serialize)]
+#[serde(untagged)]
+enum DeletePointsRequest {
+    Points { points: Vec<u64> },
+    Filter { filter: SearchFilter },
+}
+
 #[derive(Serialize)]
 struct SearchRequest {
     vector: Vec<f32>,
     limit: usize,
     with_payload: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<SearchFilter>,
 }
 
 #[derive(Deserialize)]
@@ -77,10 +96,29 @@ struct SearchResponse {
 
 #[derive(Deserialize)]
 struct SearchResultItem {
+    id: serde_json::Value,
     score: f32,
     payload: serde_json::Map<String, serde_json::Value>,
 }
 
+#[derive(Deserialize)]
+struct ScrollResponse {
+    result: ScrollResult,
+}
+
+#[derive(Deserialize)]
+struct ScrollResult {
+    points: Vec<ScrollPoint>,
+    next_page_offset: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ScrollPoint {
+    id: serde_json::Value,
+    payload: Option<serde_json::Map<String, serde_json::Value>>,
+    vector: Option<Vec<f32>>,
+}
+
 #[derive(Deserialize)]
 struct CollectionInfoResponse {
     result: CollectionInfoResult,
@@ -124,9 +162,52 @@ impl VectorDB {
             client,
             base_url,
             collection_name: collection_name.to_string(),
+            retry: RetryPolicy::default(),
         })
     }
 
+    /// 设置幂等请求的重试策略
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 发送请求, 5xx 和连接错误按重试策略重试
+    ///
+    /// `build` 每次重试都会重新构建请求; 返回的响应可能仍是非 2xx (4xx 或重试用尽), 由调用方处理
+    async fn send_with_retry(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let can_retry = attempt < self.retry.max_retries;
+            match build().send().await {
+                Ok(response) if can_retry && retry::is_retryable_status(response.status()) => {
+                    log::warn!(
+                        "⚠️ Qdrant 返回 HTTP {}, 第 {}/{} 次重试",
+                        response.status(),
+                        attempt + 1,
+                        self.retry.max_retries
+                    );
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if can_retry && retry::is_retryable_error(&e) => {
+                    log::warn!(
+                        "⚠️ Qdrant 请求失败: {}, 第 {}/{} 次重试",
+                        e,
+                        attempt + 1,
+                        self.retry.max_retries
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+
+            tokio::time::sleep(self.retry.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
     pub async fn collection_exists(&self) -> Result<bool> {
         let response = self
             .client
@@ -201,35 +282,85 @@ impl VectorDB {
             })
             .collect();
         let request = UpsertRequest { points };
+        let url = format!(
+            "{}/collections/{}/points",
+            self.base_url, self.collection_name
+        );
+        let response = self
+            .send_with_retry(|| self.client.put(&url).json(&request))
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("插入数据失败: {}", response.text().await?);
+        }
+        Ok(())
+    }
+
+    /// 按点 ID 批量删除 (ID 列表为空时不发送请求)
+    pub async fn delete_points(&self, ids: Vec<u64>) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let count = ids.len();
+        self.send_delete(DeletePointsRequest::Points { points: ids })
+            .await?;
+        log::info!(" 已删除 {} 个点", count);
+        Ok(())
+    }
+
+    /// 删除满足过滤条件的点
+    ///
+    /// 空条件在 Qdrant 中会匹配所有点, 这里直接拒绝, 清空集合请使用 `delete_collection`
+    pub async fn delete_points_by_filter(&self, filter: SearchFilter) -> Result<()> {
+        if filter.is_empty() {
+            anyhow::bail!("删除条件为空, 拒绝删除整个集合的数据");
+        }
+        self.send_delete(DeletePointsRequest::Filter { filter })
+            .await?;
+        log::info!(" 已按条件删除数据");
+        Ok(())
+    }
+
+    async fn send_delete(&self, request: DeletePointsRequest) -> Result<()> {
         let response = self
             .client
-            .put(format!(
-                "{}/collections/{}/points",
+            .post(format!(
+                "{}/collections/{}/points/delete",
                 self.base_url, self.collection_name
             ))
             .json(&request)
             .send()
             .await?;
         if !response.status().is_success() {
-            anyhow::bail!("插入数据失败: {}", response.text().await?);
+            let status = response.status();
+            anyhow::bail!("删除数据失败 (HTTP {}): {}", status, response.text().await?);
         }
         Ok(())
     }
 
     pub async fn search(&self, query_vector: Vec<f32>, limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_filtered(query_vector, limit, SearchFilter::default())
+            .await
+    }
+
+    /// 按 payload 条件过滤后检索 (空条件等同于 `search`)
+    pub async fn search_filtered(
+        &self,
+        query_vector: Vec<f32>,
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
         let request = SearchRequest {
             vector: query_vector,
             limit,
             with_payload: true,
+            filter: (!filter.is_empty()).then_some(filter),
         };
+        let url = format!(
+            "{}/collections/{}/points/search",
+            self.base_url, self.collection_name
+        );
         let response = self
-            .client
-            .post(format!(
-                "{}/collections/{}/points/search",
-                self.base_url, self.collection_name
-            ))
-            .json(&request)
-            .send()
+            .send_with_retry(|| self.client.post(&url).json(&request))
             .await?;
         if !response.status().is_success() {
             anyhow::bail!("搜索失败: {}", response.text().await?);
@@ -239,6 +370,7 @@ impl VectorDB {
             .result
             .into_iter()
             .map(|item| SearchResult {
+                id: item.id.as_u64(),
                 score: item.score,
                 payload: serde_json::to_value(item.payload).unwrap_or_default(),
             })
@@ -246,15 +378,108 @@ impl VectorDB {
         Ok(results)
     }
 
-    pub async fn get_collection_info(&self) -> Result<CollectionInfo> {
+    /// 按条目 ID (payload 中的 `id`) 读取已存储的向量
+    pub async fn get_vector_by_entry_id(&self, entry_id: &str) -> Result<Option<Vec<f32>>> {
+        let request = serde_json::json!({
+            "filter": {
+                "must": [{ "key": "id", "match": { "value": entry_id } }]
+            },
+            "limit": 1,
+            "with_payload": false,
+            "with_vector": true,
+        });
+        let url = format!(
+            "{}/collections/{}/points/scroll",
+            self.base_url, self.collection_name
+        );
         let response = self
-            .client
-            .get(format!(
-                "{}/collections/{}",
-                self.base_url, self.collection_name
-            ))
-            .send()
+            .send_with_retry(|| self.client.post(&url).json(&request))
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("读取向量失败: {}", response.text().await?);
+        }
+        let scroll: ScrollResponse = response.json().await?;
+        Ok(scroll
+            .result
+            .points
+            .into_iter()
+            .next()
+            .and_then(|point| point.vector))
+    }
+
+    /// 分页列出集合中的点 (只返回 payload, 不返回向量), 返回下一页的 offset
+    ///
+    /// 从 `offset: None` 开始, 把返回的 offset 传入下一次调用, 返回 `None` 时表示已经读完
+    pub async fn scroll_points(
+        &self,
+        offset: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<SearchResult>, Option<u64>)> {
+        let (points, next_offset) = self.scroll(offset, limit, false).await?;
+        let results = points
+            .into_iter()
+            .map(|point| SearchResult {
+                id: point.id.as_u64(),
+                score: 0.0,
+                payload: serde_json::Value::Object(point.payload.unwrap_or_default()),
+            })
+            .collect();
+        Ok((results, next_offset))
+    }
+
+    /// 分页读取集合中的点 (id, 向量, payload), 返回下一页的 offset
+    pub async fn scroll_points_with_vectors(
+        &self,
+        offset: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<(u64, Vec<f32>, serde_json::Value)>, Option<u64>)> {
+        let (points, next_offset) = self.scroll(offset, limit, true).await?;
+        let points = points
+            .into_iter()
+            .filter_map(|point| {
+                Some((
+                    point.id.as_u64()?,
+                    point.vector?,
+                    serde_json::Value::Object(point.payload.unwrap_or_default()),
+                ))
+            })
+            .collect();
+        Ok((points, next_offset))
+    }
+
+    /// 调用 Qdrant scroll 接口
+    async fn scroll(
+        &self,
+        offset: Option<u64>,
+        limit: usize,
+        with_vector: bool,
+    ) -> Result<(Vec<ScrollPoint>, Option<u64>)> {
+        let mut request = serde_json::json!({
+            "limit": limit,
+            "with_payload": true,
+            "with_vector": with_vector,
+        });
+        if let Some(offset) = offset {
+            request["offset"] = serde_json::json!(offset);
+        }
+        let url = format!(
+            "{}/collections/{}/points/scroll",
+            self.base_url, self.collection_name
+        );
+        let response = self
+            .send_with_retry(|| self.client.post(&url).json(&request))
             .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("读取数据失败: {}", response.text().await?);
+        }
+        let scroll: ScrollResponse = response.json().await?;
+        let next_offset = scroll.result.next_page_offset.and_then(|v| v.as_u64());
+        Ok((scroll.result.points, next_offset))
+    }
+
+    pub async fn get_collection_info(&self) -> Result<CollectionInfo> {
+        let url = format!("{}/collections/{}", self.base_url, self.collection_name);
+        let response = self.send_with_retry(|| self.client.get(&url)).await?;
         if !response.status().is_success() {
             anyhow::bail!("获取集合信息失败: {}", response.text().await?);
         }
@@ -265,4 +490,208 @@ impl VectorDB {
             points_count,
         })
     }
+
+    /// 估算集合占用的空间 (字节)
+    ///
+    /// Qdrant 不直接提供集合的磁盘占用, 按抽样点的向量和 payload 大小乘以点数量估算
+    pub async fn estimate_size_bytes(&self) -> Result<u64> {
+        let info = self.get_collection_info().await?;
+        if info.points_count == 0 {
+            return Ok(0);
+        }
+
+        let (sample, _) = self.scroll_points_with_vectors(None, 64).await?;
+        if sample.is_empty() {
+            return Ok(0);
+        }
+
+        let sample_bytes: usize = sample
+            .iter()
+            .map(|(_, vector, payload)| {
+                vector.len() * std::mem::size_of::<f32>() + payload.to_string().len()
+            })
+            .sum();
+
+        Ok(sample_bytes as u64 * info.points_count / sample.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// 按顺序返回给定状态码的简易 HTTP 服务 (之后都返回 200), 返回地址和请求计数
+    async fn mock_qdrant(statuses: Vec<u16>, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                read_request(&mut stream).await;
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses.get(index).copied().unwrap_or(200);
+                let body = if status == 200 {
+                    body
+                } else {
+                    r#"{"status":{"error":"Service Unavailable"}}"#
+                };
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        (format!("http://{}", addr), count)
+    }
+
+    /// 读完一个 HTTP 请求 (请求头 + content-length 长度的请求体)
+    async fn read_request(stream: &mut tokio::net::TcpStream) {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut chunk).await.unwrap_or(0);
+            if n == 0 {
+                return;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+
+            let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let headers = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+            let length = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + length {
+                return;
+            }
+        }
+    }
+
+    fn mock_db(base_url: String) -> VectorDB {
+        VectorDB {
+            client: reqwest::Client::new(),
+            base_url,
+            collection_name: "mock".to_string(),
+            retry: RetryPolicy::new(3, 1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_server_error() {
+        let (url, count) = mock_qdrant(
+            vec![503, 503],
+            r#"{"result":{"points_count":5,"vectors_count":5}}"#,
+        )
+        .await;
+        let info = mock_db(url).get_collection_info().await.unwrap();
+        assert_eq!(info.points_count, 5);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        let (url, count) = mock_qdrant(
+            vec![503, 503],
+            r#"{"result":[{"id":7,"score":0.9,"payload":{"title":"Boss"}}]}"#,
+        )
+        .await;
+        let results = mock_db(url).search(vec![0.1, 0.2], 1).await.unwrap();
+        assert_eq!(results[0].id, Some(7));
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_client_error() {
+        // 4xx 直接失败
+        let (url, count) = mock_qdrant(vec![404], "{}").await;
+        assert!(mock_db(url).get_collection_info().await.is_err());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // 重试次数用尽后返回错误
+        let (url, count) = mock_qdrant(vec![503; 10], "{}").await;
+        let db = mock_db(url).with_retry(RetryPolicy::new(2, 1));
+        assert!(db.get_collection_info().await.is_err());
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_delete_request_json() {
+        let by_ids = DeletePointsRequest::Points {
+            points: vec![1, 2, 3],
+        };
+        assert_eq!(
+            serde_json::to_value(&by_ids).unwrap(),
+            serde_json::json!({ "points": [1, 2, 3] })
+        );
+
+        let by_filter = DeletePointsRequest::Filter {
+            filter: SearchFilter::new().must(FilterCondition::matches(
+                "url",
+                "https://wiki.example.com/Removed",
+            )),
+        };
+        assert_eq!(
+            serde_json::to_value(&by_filter).unwrap(),
+            serde_json::json!({
+                "filter": {
+                    "must": [
+                        { "key": "url", "match": { "value": "https://wiki.example.com/Removed" } }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // 需要本地 Qdrant 服务运行
+    async fn test_scroll_points() {
+        let collection = format!("gamate_scroll_test_{}", uuid::Uuid::new_v4().simple());
+        let db = VectorDB::new("http://localhost:6333", &collection)
+            .await
+            .unwrap();
+        db.create_collection(4).await.unwrap();
+
+        let points = (1..=5)
+            .map(|id| {
+                (
+                    id,
+                    vec![id as f32, 1.0, 0.0, 0.0],
+                    serde_json::json!({ "title": format!("条目 {}", id) }),
+                )
+            })
+            .collect();
+        db.upsert_points(points).await.unwrap();
+
+        let mut seen = Vec::new();
+        let mut offset = None;
+        loop {
+            let (page, next_offset) = db.scroll_points(offset, 2).await.unwrap();
+            assert!(page.len() <= 2);
+            seen.extend(page.iter().filter_map(|p| p.id));
+            assert!(page.iter().all(|p| p.payload.get("title").is_some()));
+
+            match next_offset {
+                Some(next) => {
+                    // offset 向前推进
+                    assert!(offset.is_none_or(|prev| next > prev));
+                    offset = Some(next);
+                }
+                None => break,
+            }
+        }
+
+        db.delete_collection().await.unwrap();
+        seen.sort();
+        assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+    }
 }
@@ -0,0 +1,59 @@
+use crate::settings::VectorDBSettings;
+use std::time::Duration;
+
+/// Qdrant 请求重试策略
+///
+/// 只用于幂等请求 (检索、读取、upsert): 5xx 和连接错误按指数退避加随机抖动重试, 4xx 直接失败
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多重试次数 (0 表示不重试)
+    pub max_retries: u32,
+    /// 首次重试前的等待时间, 之后每次翻倍
+    pub base_delay: Duration,
+}
+
+/// 单次退避等待的上限
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, base_delay_ms: u64) -> Self {
+        Self {
+            max_retries,
+            base_delay: Duration::from_millis(base_delay_ms),
+        }
+    }
+
+    /// 使用设置中的 `retry_max` / `retry_base_ms`
+    pub fn from_settings(settings: &VectorDBSettings) -> Self {
+        Self::new(settings.retry_max, settings.retry_base_ms)
+    }
+
+    /// 第 `attempt` 次重试 (从 0 开始) 前的等待时间: base * 2^attempt, 再加最多 50% 的抖动
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_DELAY);
+        let jitter = backoff.mul_f64(rand::random::<f64>() * 0.5);
+        backoff + jitter
+    }
+}
+
+/// 状态码是否值得重试
+pub(super) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 请求错误是否值得重试 (连接失败、超时)
+pub(super) fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
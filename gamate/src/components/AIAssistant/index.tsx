@@ -9,7 +9,7 @@ import {
   Select,
   Tabs,
 } from "antd";
-import { SendOutlined, ClearOutlined } from "@ant-design/icons";
+import { SendOutlined, ClearOutlined, StopOutlined } from "@ant-design/icons";
 import { Image as ImageIcon, BookOpen } from "lucide-react";
 import { motion } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
@@ -24,6 +24,9 @@ import { DEFAULT_GAME } from "../../types/game";
 
 const { TextArea } = Input;
 
+// AI 回复超时时间 (秒), 模型卡住时结束等待
+const AI_RESPONSE_TIMEOUT_SECS = 120;
+
 // 清理 Markdown 标记，用于 TTS 播报 (与 ConversationArea 中的函数一致)
 const cleanMarkdownForTTS = (text: string): string => {
   // 检查是否包含简化播报标记
@@ -178,6 +181,22 @@ const AIAssistant: React.FC = () => {
     };
   }, []);
 
+  // 监听 AI 助手的定时截图, 作为当前游戏的最新画面
+  useEffect(() => {
+    const unlisten = listen<{ gameId: string; screenshot: { data: string } }>(
+      "ai_assistant_screenshot",
+      (event) => {
+        if (event.payload.gameId === currentGame) {
+          updateContext(event.payload.screenshot.data);
+        }
+      },
+    );
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [currentGame]);
+
   // 监听语音识别完成事件 (从 VoiceChatPanel 触发)
   useEffect(() => {
     // 防止重复注册（React Strict Mode 会执行两次）
@@ -262,6 +281,7 @@ const AIAssistant: React.FC = () => {
         // 3. 调用 AI 生成回复
         const response = await invoke<{
           content: string;
+          finish_reason?: "stop" | "refusal" | "empty" | "fallback";
           wiki_references?: Array<{
             title: string;
             content: string;
@@ -271,9 +291,13 @@ const AIAssistant: React.FC = () => {
           message: recognizedText,
           gameId: currentGame,
           screenshot,
+          timeoutSecs: AI_RESPONSE_TIMEOUT_SECS,
         });
         // 4. 添加 AI 回复到对话历史
         receiveAIResponse(response.content, response.wiki_references);
+        if (response.finish_reason === "refusal") {
+          antdMessage.warning("AI 拒绝回答了这个问题，可以换个问法试试");
+        }
 
         // 通知 HUD: AI 回答准备好了
         try {
@@ -382,8 +406,12 @@ const AIAssistant: React.FC = () => {
         });
       } catch (error) {
         console.error("截图失败:", error);
+        // 使用定时截图的最新画面代替
+        screenshot = latestScreenshot ?? undefined;
         antdMessage.warning({
-          content: "截图失败,将以纯文本模式发送",
+          content: screenshot
+            ? "截图失败,将使用最近一次定时截图"
+            : "截图失败,将以纯文本模式发送",
           key: "screenshot",
           duration: 2,
         });
@@ -398,6 +426,7 @@ const AIAssistant: React.FC = () => {
       // 调用后端 RAG 生成 AI 回复
       const response = await invoke<{
         content: string;
+        finish_reason?: "stop" | "refusal" | "empty" | "fallback";
         wiki_references?: Array<{
           title: string;
           content: string;
@@ -407,9 +436,13 @@ const AIAssistant: React.FC = () => {
         message: userMessage,
         gameId: currentGame,
         screenshot,
+        timeoutSecs: AI_RESPONSE_TIMEOUT_SECS,
       });
       // 添加 AI 回复
       receiveAIResponse(response.content, response.wiki_references);
+      if (response.finish_reason === "refusal") {
+        antdMessage.warning("AI 拒绝回答了这个问题，可以换个问法试试");
+      }
 
       // TTS 播报 AI 回复
       try {
@@ -610,15 +643,28 @@ const AIAssistant: React.FC = () => {
                     autoSize={{ minRows: 2, maxRows: 4 }}
                     disabled={!currentGame || isThinking}
                   />
-                  <Button
-                    type="primary"
-                    icon={<SendOutlined />}
-                    onClick={handleSend}
-                    loading={isThinking}
-                    disabled={!currentGame || !inputValue.trim()}
-                  >
-                    发送
-                  </Button>
+                  {isThinking ? (
+                    <Button
+                      danger
+                      icon={<StopOutlined />}
+                      onClick={() =>
+                        invoke("cancel_ai_response").catch((e) =>
+                          console.error("取消 AI 回复失败:", e),
+                        )
+                      }
+                    >
+                      停止
+                    </Button>
+                  ) : (
+                    <Button
+                      type="primary"
+                      icon={<SendOutlined />}
+                      onClick={handleSend}
+                      disabled={!currentGame || !inputValue.trim()}
+                    >
+                      发送
+                    </Button>
+                  )}
                 </div>
               </div>
             </Card>
@@ -48,6 +48,7 @@ const GameLibrary: React.FC = () => {
   const [skillModalVisible, setSkillModalVisible] = useState(false);
   const [downloadProgress, setDownloadProgress] = useState(0);
   const [isDownloading, setIsDownloading] = useState(false);
+  const [exportMarkdown, setExportMarkdown] = useState(false);
   const [games, setGames] = useState<Game[]>([]);
   const [skillConfigs, setSkillConfigs] = useState<GameSkillConfig[]>([]);
   const [loading, setLoading] = useState(true);
@@ -150,6 +151,30 @@ const GameLibrary: React.FC = () => {
           // 不影响主流程,只记录错误
         }
       }
+
+      // 游戏库中有已配置技能库的游戏时, 提示添加到我的游戏
+      if (library && library.length > 0) {
+        try {
+          const matched = await invoke<string[]>("match_steam_library_games");
+          if (matched.length > 0) {
+            Modal.confirm({
+              title: "发现已配置的游戏",
+              content: `Steam 游戏库中有 ${matched.length} 个游戏已有技能库配置 (${matched.join(", ")}), 是否添加到我的游戏?`,
+              okText: "添加",
+              cancelText: "暂不",
+              onOk: async () => {
+                const updatedGameIds = await invoke<string[]>("add_selected_games", {
+                  gameIds: matched,
+                });
+                setSelectedGameIds(updatedGameIds);
+                message.success(`已添加 ${matched.length} 个游戏`);
+              },
+            });
+          }
+        } catch (matchError) {
+          console.error("匹配 Steam 游戏失败:", matchError);
+        }
+      }
     } catch (error) {
       console.error("加载 Steam 游戏库失败:", error);
       message.error("加载 Steam 游戏库失败");
@@ -390,6 +415,10 @@ const GameLibrary: React.FC = () => {
           timestamp,
           storagePath,
           githubToken: null,
+          userAgent: skillConfig.userAgent ?? null,
+          headers: skillConfig.headers ?? null,
+          cookies: skillConfig.cookies ?? null,
+          outputFormats: exportMarkdown ? ["jsonl", "markdown"] : null,
         },
       });
 
@@ -738,6 +767,16 @@ const GameLibrary: React.FC = () => {
                 size="middle"
                 style={{ width: "100%" }}
               >
+                <Space>
+                  <Switch
+                    size="small"
+                    checked={exportMarkdown}
+                    onChange={setExportMarkdown}
+                  />
+                  <Tooltip title="每个页面额外保存为 pages 目录下的 .md 文件, 方便直接查看或用其他工具处理">
+                    <Text type="secondary">同时导出 Markdown 页面</Text>
+                  </Tooltip>
+                </Space>
                 {skillConfigs.map((config) => {
                   const badge = getStatusBadge(config.status);
                   return (
@@ -19,6 +19,7 @@ interface HudState {
 interface ListenerState {
   vad_state: "Idle" | "Speaking" | "Processing";
   is_listening: boolean;
+  paused?: boolean;
   recording_duration: number;
   buffer_size: number;
   last_transcription: string | null;
@@ -161,6 +162,19 @@ export const HudOverlay: React.FC = () => {
         });
         unlistenFunctions.push(unlistenSpeechEnded);
 
+        // 实时字幕 (识别中间结果)
+        const unlistenAsrPartial = await listen<string>(
+          "asr_partial",
+          (event) => {
+            setState((prev) => ({
+              ...prev,
+              aiStatus: `识别中: ${event.payload}`,
+              statusColor: "#faad14", // 橙色
+            }));
+          },
+        );
+        unlistenFunctions.push(unlistenAsrPartial);
+
         // 识别完成 (语音转文字完成)
         const unlistenRecognizeRequest = await listen(
           "aliyun_recognize_request",
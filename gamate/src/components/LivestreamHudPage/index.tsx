@@ -5,6 +5,7 @@ import {
   PhoneOutlined,
   PlayCircleOutlined,
   PauseCircleOutlined,
+  CameraOutlined,
 } from "@ant-design/icons";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
@@ -58,6 +59,8 @@ export const LivestreamHudPage: React.FC = () => {
   const [onMicEmployees, setOnMicEmployees] = useState<Set<string>>(new Set());
   const [isLivestreaming, setIsLivestreaming] = useState(false);
   const [isSmartCaptureRunning, setIsSmartCaptureRunning] = useState(false);
+  const [isIntervalCaptureRunning, setIsIntervalCaptureRunning] = useState(false);
+  const [intervalCaptureIdle, setIntervalCaptureIdle] = useState(false);
   const eventListenerRef = useRef<UnlistenFn | null>(null);
   const smartCaptureListenerRef = useRef<UnlistenFn | null>(null);
   
@@ -205,6 +208,52 @@ export const LivestreamHudPage: React.FC = () => {
         }
       });
 
+      // 语音识别完成后: 带上双截图触发 AI 分析
+      const analyzeSpeech = async (result: string) => {
+        // 容错处理：即使截图缺失也进行 AI 分析
+        const hasBeforeScreenshot = !!currentScreenshotBefore;
+        const hasAfterScreenshot = !!currentScreenshotAfter;
+        const bothMissing = !hasBeforeScreenshot && !hasAfterScreenshot;
+      
+        // 记录截图缺失情况
+        if (bothMissing) {
+          screenshotErrorCountRef.current += 1;
+        
+          // 连续2次双截图都缺失，提示已切换为纯文本分析（仅提示一次）
+          if (screenshotErrorCountRef.current === 2) {
+            message.warning("截图不可用，AI 将仅根据语音内容互动", 5);
+          }
+        } else {
+          // 有截图就重置错误计数
+          screenshotErrorCountRef.current = 0;
+        }
+      
+        // 使用空字符串代替缺失的截图
+        const beforeScreenshot = currentScreenshotBefore || "";
+        const afterScreenshot = currentScreenshotAfter || "";
+      
+        try {
+          await invoke("trigger_ai_analysis", {
+            request: {
+              speech_text: result,
+              screenshot_before: beforeScreenshot,
+              screenshot_after: afterScreenshot,
+            },
+          });
+        } catch (error) {
+          console.error("AI 分析调用失败:", error);
+          // 如果是因为直播已停止，不显示错误
+          const errorMsg = String(error);
+          if (!errorMsg.includes("已忽略此请求")) {
+            message.error(`AI 分析失败: ${error}`, 3);
+          }
+        }
+
+        // 清空截图
+        currentScreenshotBefore = null;
+        currentScreenshotAfter = null;
+      };
+
       // 监听阿里云识别请求（需要调用 ASR）
       const unlistenRecognize = await listen("livestream_recognize_request", async (event: any) => {
         // 防止重复处理同一个识别请求
@@ -239,60 +288,7 @@ export const LivestreamHudPage: React.FC = () => {
 
           message.success(`识别: ${result}`, 3);
 
-          // 容错处理：即使截图缺失也进行 AI 分析
-          const hasBeforeScreenshot = !!currentScreenshotBefore;
-          const hasAfterScreenshot = !!currentScreenshotAfter;
-          const bothMissing = !hasBeforeScreenshot && !hasAfterScreenshot;
-          
-          // 记录截图缺失情况
-          if (bothMissing) {
-            screenshotErrorCountRef.current += 1;
-            
-            // 连续2次双截图都缺失，停止直播
-            if (screenshotErrorCountRef.current >= 2) {
-              message.error("截图系统异常（连续2次双截图缺失），已自动停止直播", 5);
-              
-              try {
-                await invoke("stop_livestream_simulation");
-                await invoke("stop_smart_capture");
-                setIsLivestreaming(false);
-                setIsSmartCaptureRunning(false);
-              } catch (e) {
-                console.error("停止直播失败:", e);
-              }
-              
-              isProcessingRecognitionRef.current = false;
-              return;
-            }
-          } else {
-            // 有截图就重置错误计数
-            screenshotErrorCountRef.current = 0;
-          }
-          
-          // 使用空字符串代替缺失的截图
-          const beforeScreenshot = currentScreenshotBefore || "";
-          const afterScreenshot = currentScreenshotAfter || "";
-          
-          try {
-            await invoke("trigger_ai_analysis", {
-              request: {
-                speech_text: result,
-                screenshot_before: beforeScreenshot,
-                screenshot_after: afterScreenshot,
-              },
-            });
-          } catch (error) {
-            console.error("AI 分析调用失败:", error);
-            // 如果是因为直播已停止，不显示错误
-            const errorMsg = String(error);
-            if (!errorMsg.includes("已忽略此请求")) {
-              message.error(`AI 分析失败: ${error}`, 3);
-            }
-          }
-
-          // 清空截图
-          currentScreenshotBefore = null;
-          currentScreenshotAfter = null;
+          await analyzeSpeech(result);
         } catch (error) {
           console.error("❌ 识别失败:", error);
           message.error(`识别失败: ${error}`, 3);
@@ -306,9 +302,25 @@ export const LivestreamHudPage: React.FC = () => {
         }
       });
 
+      // 监听本地语音识别结果（Windows / Whisper 后端）
+      const unlistenTranscribed = await listen<string>("livestream_transcribed", async (event) => {
+        if (isProcessingRecognitionRef.current) {
+          return;
+        }
+
+        isProcessingRecognitionRef.current = true;
+        try {
+          message.success(`识别: ${event.payload}`, 3);
+          await analyzeSpeech(event.payload);
+        } finally {
+          isProcessingRecognitionRef.current = false;
+        }
+      });
+
       smartCaptureListenerRef.current = () => {
         unlistenCapture();
         unlistenRecognize();
+        unlistenTranscribed();
       };
     };
 
@@ -393,6 +405,17 @@ export const LivestreamHudPage: React.FC = () => {
   }, [setCurrentGame]);
 
   // 处理游戏切换
+  // 监听定时截图的每一帧 (显示当前使用的是活跃还是闲置间隔)
+  useEffect(() => {
+    const unlisten = listen<{ idle: boolean }>("ai_assistant_screenshot", (event) => {
+      setIntervalCaptureIdle(event.payload.idle);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const handleGameChange = async (gameId: string) => {
     setCurrentGame(gameId);
     try {
@@ -432,6 +455,16 @@ export const LivestreamHudPage: React.FC = () => {
         setIsLivestreaming(false);
         message.info("直播已停止");
 
+        // 停止定时截图
+        if (isIntervalCaptureRunning) {
+          try {
+            await invoke("stop_ai_assistant");
+            setIsIntervalCaptureRunning(false);
+          } catch (error) {
+            console.error("❌ 停止定时截图失败:", error);
+          }
+        }
+
         // 停止智能截图
         if (isSmartCaptureRunning) {
           try {
@@ -498,6 +531,35 @@ export const LivestreamHudPage: React.FC = () => {
     }
   };
 
+  // 开始/停止定时截图 (主播很少说话时按间隔截图交给 AI)
+  const handleToggleIntervalCapture = async () => {
+    try {
+      if (isIntervalCaptureRunning) {
+        await invoke("stop_ai_assistant");
+        setIsIntervalCaptureRunning(false);
+        message.info("定时截图已停止");
+      } else {
+        if (!currentGame) {
+          message.warning("请先选择游戏");
+          return;
+        }
+        const state = await invoke<{ screenshot_enabled: boolean }>("start_ai_assistant", {
+          gameId: currentGame,
+        });
+        if (!state.screenshot_enabled) {
+          message.warning("截图功能未启用, 请先在设置中启用");
+          return;
+        }
+        setIntervalCaptureIdle(false);
+        setIsIntervalCaptureRunning(true);
+        message.success("定时截图已启动");
+      }
+    } catch (error) {
+      console.error("切换定时截图失败:", error);
+      message.error(`操作失败: ${error}`);
+    }
+  };
+
   return (
     <div className="livestream-hud-page">
       {/* 头部信息 */}
@@ -641,6 +703,19 @@ export const LivestreamHudPage: React.FC = () => {
         >
           {isLivestreaming ? "停止直播" : "开始直播"}
         </Button>
+        <Tooltip title="按固定间隔截图, 画面变化时交给 AI 互动 (适合很少说话的游戏)">
+          <Button
+            block
+            style={{ marginTop: 8 }}
+            type={isIntervalCaptureRunning ? "primary" : "default"}
+            icon={<CameraOutlined />}
+            onClick={handleToggleIntervalCapture}
+          >
+            {isIntervalCaptureRunning
+              ? `停止定时截图${intervalCaptureIdle ? " (闲置)" : ""}`
+              : "定时截图"}
+          </Button>
+        </Tooltip>
       </div>
     </div>
   );
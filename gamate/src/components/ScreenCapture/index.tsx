@@ -89,7 +89,11 @@ const ScreenCapture: React.FC = () => {
       const displayList = await invoke<DisplayInfo[]>("list_displays");
       setDisplays(displayList);
       if (displayList.length > 0) {
-        setSelectedDisplay(displayList[0].id);
+        // 优先使用设置中保存的显示器
+        const settings = await invoke<any>("get_app_settings");
+        const savedId = settings.screenshot?.capture_display_id;
+        const saved = displayList.find((d) => d.id === savedId);
+        setSelectedDisplay(saved ? saved.id : displayList[0].id);
       }
     } catch (error) {
       console.error("获取显示器列表失败:", error);
@@ -97,6 +101,16 @@ const ScreenCapture: React.FC = () => {
     }
   };
 
+  const handleDisplayChange = async (displayId: number) => {
+    setSelectedDisplay(displayId);
+    try {
+      await invoke("set_capture_display", { displayId });
+    } catch (error) {
+      console.error("设置截图显示器失败:", error);
+      message.error(`设置截图显示器失败: ${error}`);
+    }
+  };
+
   const loadWindows = async () => {
     try {
       const windowList = await invoke<WindowInfo[]>("list_windows_command");
@@ -319,7 +333,7 @@ const ScreenCapture: React.FC = () => {
                 <Text strong>显示器</Text>
                 <Select
                   value={selectedDisplay}
-                  onChange={setSelectedDisplay}
+                  onChange={handleDisplayChange}
                   style={{ width: "100%", marginTop: 8 }}
                   disabled={isCapturing}
                   options={displays.map((d) => ({
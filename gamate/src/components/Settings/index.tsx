@@ -37,6 +37,44 @@ import "./styles.scss";
 
 const { Title, Text, Paragraph } = Typography;
 
+interface OutputDeviceInfo {
+  name: string;
+  isDefault: boolean;
+}
+
+interface InputDeviceInfo {
+  name: string;
+  is_default: boolean;
+  default_sample_rate: number;
+}
+
+interface SelfTestStage {
+  name: string;
+  status: "passed" | "failed" | "skipped";
+  message: string;
+  durationMs: number;
+}
+
+interface SelfTestReport {
+  passed: boolean;
+  mode: string;
+  stages: SelfTestStage[];
+}
+
+const SELF_TEST_STAGE_LABELS: Record<string, string> = {
+  crawl: "爬取",
+  embed: "Embedding",
+  import: "导入",
+  search: "检索",
+  cleanup: "清理",
+};
+
+const SELF_TEST_STATUS_ICONS: Record<SelfTestStage["status"], string> = {
+  passed: "✅",
+  failed: "❌",
+  skipped: "⏭️",
+};
+
 interface WindowInfo {
   id: number;
   title: string;
@@ -53,11 +91,50 @@ const SettingsPanel: React.FC = () => {
   const [saving, setSaving] = useState(false);
   const [settings, setSettings] = useState<AppSettings | null>(null);
   const [windows, setWindows] = useState<WindowInfo[]>([]);
+  const [outputDevices, setOutputDevices] = useState<OutputDeviceInfo[]>([]);
+  const [inputDevices, setInputDevices] = useState<InputDeviceInfo[]>([]);
   const [hudPreviewVisible, setHudPreviewVisible] = useState(false); // HUD 预览状态
+  const [selfTesting, setSelfTesting] = useState(false);
+
+  // 运行完整流程自检 (使用已保存的设置)
+  const runSelfTest = async () => {
+    setSelfTesting(true);
+    try {
+      const report = await invoke<SelfTestReport>("run_self_test");
+      Modal[report.passed ? "success" : "error"]({
+        title: report.passed ? "自检通过" : "自检未通过",
+        width: 560,
+        content: (
+          <div>
+            <Text type="secondary">向量数据库模式: {report.mode}</Text>
+            {report.stages.map((stage) => (
+              <div key={stage.name} style={{ marginTop: 8 }}>
+                {SELF_TEST_STATUS_ICONS[stage.status]}{" "}
+                <Text strong>
+                  {SELF_TEST_STAGE_LABELS[stage.name] || stage.name}
+                </Text>{" "}
+                <Text type="secondary">({stage.durationMs} ms)</Text>
+                <div>
+                  <Text type={stage.status === "failed" ? "danger" : undefined}>
+                    {stage.message}
+                  </Text>
+                </div>
+              </div>
+            ))}
+          </div>
+        ),
+      });
+    } catch (error) {
+      message.error(`${error}`);
+    } finally {
+      setSelfTesting(false);
+    }
+  };
 
   useEffect(() => {
     loadSettings();
     loadWindows();
+    loadOutputDevices();
     checkHudPreview();
   }, []);
 
@@ -80,6 +157,26 @@ const SettingsPanel: React.FC = () => {
     }
   };
 
+  const loadInputDevices = async () => {
+    try {
+      const devices = await invoke<InputDeviceInfo[]>(
+        "list_audio_input_devices",
+      );
+      setInputDevices(devices);
+    } catch (error) {
+      console.error("获取输入设备列表失败:", error);
+    }
+  };
+
+  const loadOutputDevices = async () => {
+    try {
+      const devices = await invoke<OutputDeviceInfo[]>("list_output_devices");
+      setOutputDevices(devices);
+    } catch (error) {
+      console.error("获取输出设备列表失败:", error);
+    }
+  };
+
   const loadSettings = async () => {
     setLoading(true);
     try {
@@ -200,6 +297,65 @@ const SettingsPanel: React.FC = () => {
                   </Select>
                 </Form.Item>
 
+                <Form.Item
+                  label="语音输入设备"
+                  name={["general", "input_device"]}
+                  tooltip="语音对话和直播间监听使用的麦克风 (例如耳麦、采集卡、虚拟声卡); 留空使用系统默认设备, 修改后重新开始监听生效"
+                >
+                  <Select
+                    allowClear
+                    placeholder="系统默认设备"
+                    onDropdownVisibleChange={(open) => {
+                      if (open) loadInputDevices();
+                    }}
+                  >
+                    {inputDevices.map((device) => (
+                      <Select.Option key={device.name} value={device.name}>
+                        {device.is_default
+                          ? `${device.name} (默认, ${device.default_sample_rate} Hz)`
+                          : `${device.name} (${device.default_sample_rate} Hz)`}
+                      </Select.Option>
+                    ))}
+                  </Select>
+                </Form.Item>
+
+                <Form.Item
+                  label="语音识别后端"
+                  name={["general", "stt_backend"]}
+                  tooltip="语音对话使用的识别服务; Whisper 为本地离线识别, 需要使用 whisper 特性编译, 修改后重新开始监听生效"
+                >
+                  <Select>
+                    <Select.Option value="aliyun">阿里云 (在线)</Select.Option>
+                    <Select.Option value="whisper">Whisper (本地)</Select.Option>
+                    <Select.Option value="windows">Windows 语音识别</Select.Option>
+                  </Select>
+                </Form.Item>
+
+                <Form.Item
+                  label="Whisper 模型路径"
+                  name={["general", "whisper_model_path"]}
+                  tooltip="ggml 格式的 Whisper 模型文件, 例如 ggml-base.bin"
+                >
+                  <Input placeholder="C:\models\ggml-base.bin" />
+                </Form.Item>
+
+                <Form.Item
+                  label="保存调试音频"
+                  name={["general", "save_debug_audio"]}
+                  valuePropName="checked"
+                  tooltip="将送去识别的语音片段保存为 WAV 文件, 用于排查识别问题"
+                >
+                  <Switch checkedChildren="开启" unCheckedChildren="关闭" />
+                </Form.Item>
+
+                <Form.Item
+                  label="调试音频目录"
+                  name={["general", "debug_audio_dir"]}
+                  tooltip="留空时保存到配置目录下的 debug_audio 文件夹"
+                >
+                  <Input placeholder="留空使用默认目录" />
+                </Form.Item>
+
                 <Divider />
 
                 <Form.Item
@@ -269,6 +425,33 @@ const SettingsPanel: React.FC = () => {
                   style={{ marginTop: 8 }}
                 />
               </Card>
+
+              <Card type="inner" title="本地 API" style={{ marginTop: 16 }}>
+                <Form.Item
+                  label="启动时开启"
+                  name={["apiServer", "enabled"]}
+                  valuePropName="checked"
+                  tooltip="在 127.0.0.1 上提供 HTTP 接口 (搜索、问答、游戏列表、爬取), 供 Stream Deck、脚本等外部工具调用"
+                >
+                  <Switch />
+                </Form.Item>
+
+                <Form.Item
+                  label="端口"
+                  name={["apiServer", "port"]}
+                  tooltip="修改后重启应用生效"
+                >
+                  <InputNumber min={1024} max={65535} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="访问令牌"
+                  name={["apiServer", "token"]}
+                  tooltip="请求时携带 Authorization: Bearer <令牌>; 留空则在首次启动时自动生成"
+                >
+                  <Input.Password placeholder="首次启动时自动生成" />
+                </Form.Item>
+              </Card>
             </Tabs.TabPane>
 
             {/* AI 模型设置 */}
@@ -488,6 +671,26 @@ const SettingsPanel: React.FC = () => {
                     );
                   }}
                 </Form.Item>
+
+                <Form.Item
+                  label="全局系统提示词前言"
+                  name={["aiModels", "globalSystemPreamble"]}
+                  tooltip="放在所有 AI 对话的系统提示词最前面, 对所有角色生效"
+                >
+                  <Input.TextArea
+                    rows={3}
+                    placeholder="例如: 回答尽量简洁; 不要剧透主线剧情; 始终使用中文回答"
+                  />
+                </Form.Item>
+
+                <Form.Item
+                  label="直播间模拟也使用前言"
+                  name={["aiModels", "preambleInSimulation"]}
+                  valuePropName="checked"
+                  tooltip="模拟分析要求 AI 返回 JSON, 前言可能影响输出格式"
+                >
+                  <Switch />
+                </Form.Item>
               </Card>
 
               {/* Embedding 模型 */}
@@ -521,6 +724,7 @@ const SettingsPanel: React.FC = () => {
                       const apiBaseMap: Record<string, string> = {
                         openai: "https://api.openai.com/v1",
                         local: "http://localhost:11434/v1",
+                        ollama: "http://localhost:11434",
                         azure: "https://your-resource.openai.azure.com",
                       };
 
@@ -532,7 +736,7 @@ const SettingsPanel: React.FC = () => {
                       }
 
                       // 如果是本地模型,清空 API Key
-                      if (value === "local") {
+                      if (value === "local" || value === "ollama") {
                         form.setFieldValue(
                           ["aiModels", "embedding", "apiKey"],
                           null,
@@ -543,6 +747,9 @@ const SettingsPanel: React.FC = () => {
                     <Select.Option value="local">
                       本地模型 (Ollama)
                     </Select.Option>
+                    <Select.Option value="ollama">
+                      Ollama (原生 API)
+                    </Select.Option>
                     <Select.Option value="openai">OpenAI</Select.Option>
                     <Select.Option value="azure">Azure OpenAI</Select.Option>
                     <Select.Option value="custom">自定义</Select.Option>
@@ -713,6 +920,87 @@ const SettingsPanel: React.FC = () => {
                 >
                   <InputNumber min={100} max={4000} style={{ width: "100%" }} />
                 </Form.Item>
+
+                <Form.Item
+                  label="上下文窗口 (Token)"
+                  name={["aiModels", "contextTokens"]}
+                  tooltip="模型能接收的最大 Token 数, 提示词超出时自动裁剪知识库条目和对话历史"
+                >
+                  <InputNumber min={1024} max={1000000} step={1024} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  noStyle
+                  shouldUpdate={(prev, cur) =>
+                    prev.aiModels?.multimodal?.provider !==
+                    cur.aiModels?.multimodal?.provider
+                  }
+                >
+                  {({ getFieldValue }) =>
+                    getFieldValue(["aiModels", "multimodal", "provider"]) ===
+                      "local" && (
+                      <>
+                        <Divider>模型常驻 (Ollama)</Divider>
+
+                        <Form.Item
+                          label="保持模型加载"
+                          name={["aiModels", "ollamaKeepAlive", "enabled"]}
+                          valuePropName="checked"
+                          tooltip="定期预热模型, 避免空闲后第一次提问需要重新加载。会一直占用显存"
+                        >
+                          <Switch />
+                        </Form.Item>
+
+                        <Form.Item
+                          label="保留时长"
+                          name={["aiModels", "ollamaKeepAlive", "keepAlive"]}
+                          tooltip='每次请求后模型保留在内存中的时长, 如 "10m" "1h", "-1" 表示一直保留'
+                        >
+                          <Input placeholder="10m" />
+                        </Form.Item>
+
+                        <Form.Item
+                          label="预热间隔 (分钟)"
+                          name={[
+                            "aiModels",
+                            "ollamaKeepAlive",
+                            "pingIntervalMinutes",
+                          ]}
+                        >
+                          <InputNumber
+                            min={1}
+                            max={120}
+                            style={{ width: "100%" }}
+                          />
+                        </Form.Item>
+
+                        <Form.Item label="立即加载">
+                          <Button
+                            onClick={async () => {
+                              try {
+                                const model = await invoke<string>(
+                                  "preload_model",
+                                  {
+                                    model: getFieldValue([
+                                      "aiModels",
+                                      "multimodal",
+                                      "modelName",
+                                    ]),
+                                  },
+                                );
+                                message.success(`模型 ${model} 已加载`);
+                              } catch (error) {
+                                message.error(`${error}`);
+                              }
+                            }}
+                          >
+                            预加载模型
+                          </Button>
+                        </Form.Item>
+                      </>
+                    )
+                  }
+                </Form.Item>
               </Card>
 
               {/* 向量数据库配置 */}
@@ -765,6 +1053,9 @@ const SettingsPanel: React.FC = () => {
                     <Select.Option value="ai_direct">
                       🤖 AI 直接检索
                     </Select.Option>
+                    <Select.Option value="hybrid">
+                      🔀 混合检索 (向量 + 关键词)
+                    </Select.Option>
                   </Select>
                 </Form.Item>
 
@@ -798,6 +1089,29 @@ const SettingsPanel: React.FC = () => {
                           >
                             <Input placeholder="http://localhost:6333" />
                           </Form.Item>
+                          <Form.Item
+                            label="失败重试次数"
+                            name={["aiModels", "vectorDb", "retryMax"]}
+                            tooltip="Qdrant 返回 5xx 或连接失败时重试, 4xx 错误不重试"
+                          >
+                            <InputNumber
+                              min={0}
+                              max={10}
+                              style={{ width: "100%" }}
+                            />
+                          </Form.Item>
+                          <Form.Item
+                            label="重试等待 (毫秒)"
+                            name={["aiModels", "vectorDb", "retryBaseMs"]}
+                            tooltip="首次重试前的等待时间, 之后每次翻倍"
+                          >
+                            <InputNumber
+                              min={50}
+                              max={10000}
+                              step={100}
+                              style={{ width: "100%" }}
+                            />
+                          </Form.Item>
                           <Form.Item>
                             <Button
                               type="dashed"
@@ -844,9 +1158,131 @@ const SettingsPanel: React.FC = () => {
                       );
                     }
 
+                    if (mode === "hybrid") {
+                      return (
+                        <>
+                          <Form.Item
+                            label="向量数据库"
+                            name={["aiModels", "vectorDb", "hybridVectorMode"]}
+                            tooltip="混合检索中语义检索部分使用的数据库, 关键词检索使用导入时保存的 JSONL"
+                          >
+                            <Select>
+                              <Select.Option value="local">
+                                🏠 本地文件型
+                              </Select.Option>
+                              <Select.Option value="qdrant">
+                                🚀 Qdrant 服务器
+                              </Select.Option>
+                            </Select>
+                          </Form.Item>
+                          <Form.Item
+                            label="存储路径"
+                            name={["aiModels", "vectorDb", "localStoragePath"]}
+                            tooltip="向量数据与关键词检索数据的存储目录"
+                          >
+                            <Input placeholder="./data/vector_db" />
+                          </Form.Item>
+                          <Form.Item
+                            label="Qdrant 地址"
+                            name={["aiModels", "vectorDb", "qdrantUrl"]}
+                            tooltip="向量数据库为 Qdrant 时使用"
+                          >
+                            <Input placeholder="http://localhost:6333" />
+                          </Form.Item>
+                          <Form.Item
+                            label="向量结果权重"
+                            name={["aiModels", "vectorDb", "hybridWeight"]}
+                            tooltip="融合排名时语义检索结果的权重 (0-1), 其余为关键词检索的权重。精确的物品名查询较多时调低"
+                          >
+                            <InputNumber
+                              min={0}
+                              max={1}
+                              step={0.1}
+                              style={{ width: "100%" }}
+                            />
+                          </Form.Item>
+                        </>
+                      );
+                    }
+
                     return null;
                   }}
                 </Form.Item>
+
+                <Form.Item
+                  label="导入批大小"
+                  name={["aiModels", "vectorDb", "importBatchSize"]}
+                  tooltip="每次请求 Embedding 的条目数。本地慢速模型超时时调小, 在线 API 可调大以减少请求次数"
+                >
+                  <InputNumber min={1} max={1000} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="分块大小 (字符)"
+                  name={["aiModels", "vectorDb", "chunkSize"]}
+                  tooltip="长页面按此长度切分为多个分块分别生成 Embedding, 避免超出模型输入上限被截断。0 表示不分块, 修改后需重新导入"
+                >
+                  <InputNumber min={0} max={6000} step={100} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="分块重叠 (字符)"
+                  name={["aiModels", "vectorDb", "chunkOverlap"]}
+                  tooltip="相邻分块之间重复的字符数, 避免关键信息被切断。最多为分块大小的一半"
+                >
+                  <InputNumber min={0} max={3000} step={50} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Divider>检索 (RAG)</Divider>
+
+                <Form.Item
+                  label="检索条目数"
+                  name={["aiModels", "rag", "topK"]}
+                  tooltip="每次提问从知识库检索的 Wiki 条目数, 开启重排时作为候选数量"
+                >
+                  <InputNumber min={1} max={50} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="上下文预算 (Token)"
+                  name={["aiModels", "rag", "contextTokenBudget"]}
+                  tooltip="注入提示词的知识库内容上限, 超出时丢弃相关度最低的条目"
+                >
+                  <InputNumber min={100} max={100000} step={100} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="最低相关度"
+                  name={["aiModels", "rag", "minScore"]}
+                  tooltip="低于该分数 (0-1) 的检索结果不作为参考资料"
+                >
+                  <InputNumber min={0} max={1} step={0.05} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="重排检索结果"
+                  name={["aiModels", "rag", "rerank"]}
+                  valuePropName="checked"
+                  tooltip="用对话模型对检索结果重新打分排序, 会增加一次模型请求"
+                >
+                  <Switch />
+                </Form.Item>
+
+                <Form.Item
+                  label="重排保留条目数"
+                  name={["aiModels", "rag", "rerankTopM"]}
+                >
+                  <InputNumber min={1} max={20} style={{ width: "100%" }} />
+                </Form.Item>
+
+                <Form.Item
+                  label="完整流程自检"
+                  tooltip="用内置的测试页面依次测试爬取、Embedding、导入和检索, 结束后删除测试数据。使用已保存的设置"
+                >
+                  <Button loading={selfTesting} onClick={runSelfTest}>
+                    🧪 运行自检
+                  </Button>
+                </Form.Item>
               </Card>
             </Tabs.TabPane>
 
@@ -1152,6 +1588,64 @@ const SettingsPanel: React.FC = () => {
                   />
                 </Form.Item>
 
+                <Form.Item
+                  label="闲置判定时间 (秒)"
+                  name={["screenshot", "idleThresholdSeconds"]}
+                  tooltip="键盘和鼠标无输入超过该时间后切换为闲置模式"
+                  rules={[
+                    {
+                      type: "number",
+                      min: 5,
+                      max: 3600,
+                      message: "时间必须在 5-3600 秒之间",
+                    },
+                  ]}
+                >
+                  <InputNumber
+                    min={5}
+                    max={3600}
+                    style={{ width: "100%" }}
+                    addonAfter="秒"
+                  />
+                </Form.Item>
+
+                <Form.Item
+                  label="语音闲置判定时间 (秒)"
+                  name={["screenshot", "speechIdleSeconds"]}
+                  tooltip="定时截图时，超过该时间未检测到语音后切换为闲置间隔"
+                  rules={[
+                    {
+                      type: "number",
+                      min: 5,
+                      max: 3600,
+                      message: "时间必须在 5-3600 秒之间",
+                    },
+                  ]}
+                >
+                  <InputNumber
+                    min={5}
+                    max={3600}
+                    style={{ width: "100%" }}
+                    addonAfter="秒"
+                  />
+                </Form.Item>
+
+                <Form.Item
+                  label="画面变化阈值"
+                  name={["screenshot", "minScreenDiff"]}
+                  tooltip="前后截图差异低于该值时跳过画面分析，只发送语音；0 表示关闭"
+                  rules={[
+                    {
+                      type: "number",
+                      min: 0,
+                      max: 1,
+                      message: "阈值必须在 0-1 之间",
+                    },
+                  ]}
+                >
+                  <InputNumber min={0} max={1} step={0.01} style={{ width: "100%" }} />
+                </Form.Item>
+
                 {/* <Form.Item
                   label="截图质量"
                   name={["screenshot", "quality"]}
@@ -1368,6 +1862,15 @@ const SettingsPanel: React.FC = () => {
                   <Switch />
                 </Form.Item>
 
+                <Form.Item
+                  label="播报时屏蔽语音输入"
+                  name={["tts", "echoSuppression"]}
+                  valuePropName="checked"
+                  tooltip="播报期间忽略麦克风输入,避免 AI 识别到自己的声音并回复自己"
+                >
+                  <Switch />
+                </Form.Item>
+
                 <Form.Item
                   label="语速"
                   name={["tts", "rate"]}
@@ -1408,6 +1911,26 @@ const SettingsPanel: React.FC = () => {
                   />
                 </Form.Item>
 
+                <Form.Item
+                  label="输出设备"
+                  name={["tts", "outputDevice"]}
+                  tooltip="将语音播报输出到指定设备 (例如虚拟声卡), 以便接入直播混音; 留空使用系统默认设备"
+                >
+                  <Select
+                    allowClear
+                    placeholder="系统默认设备"
+                    onDropdownVisibleChange={(open) => {
+                      if (open) loadOutputDevices();
+                    }}
+                  >
+                    {outputDevices.map((device) => (
+                      <Select.Option key={device.name} value={device.name}>
+                        {device.isDefault ? `${device.name} (默认)` : device.name}
+                      </Select.Option>
+                    ))}
+                  </Select>
+                </Form.Item>
+
                 <Form.Item
                   label="提供商"
                   name={["tts", "provider"]}
@@ -1526,6 +2049,27 @@ const SettingsPanel: React.FC = () => {
                             >
                               测试播报
                             </Button>
+                            <Button
+                              onClick={async () => {
+                                try {
+                                  const { invoke } =
+                                    await import("@tauri-apps/api/core");
+                                  const result = await invoke<string>(
+                                    "test_tts_output",
+                                    {
+                                      device:
+                                        getFieldValue(["tts", "outputDevice"]) ||
+                                        null,
+                                    }
+                                  );
+                                  message.success(result);
+                                } catch (error: any) {
+                                  message.error(`测试失败: ${error}`);
+                                }
+                              }}
+                            >
+                              测试输出设备
+                            </Button>
                             <Button
                               onClick={async () => {
                                 try {
@@ -1,4 +1,4 @@
-import React, { useEffect } from "react";
+import React, { useEffect, useState } from "react";
 import {
   Card,
   Select,
@@ -32,6 +32,13 @@ const PERSONALITY_OPTIONS: { value: AIPersonality; label: string }[] = [
   { value: "trump", label: "特朗普" },
 ];
 
+// 后端返回的角色 (内置 + 自定义)
+interface PersonalityInfo {
+  id: string;
+  description: string;
+  builtin: boolean;
+}
+
 // 频率选项
 const FREQUENCY_OPTIONS: { value: FrequencyLevel; label: string }[] = [
   { value: "high", label: "高" },
@@ -52,6 +59,16 @@ export const SimulationPanel: React.FC = () => {
   } = useSimulationStore();
 
   const livestream = config.livestream!;
+  const [customPersonalities, setCustomPersonalities] = useState<
+    PersonalityInfo[]
+  >([]);
+
+  // 加载用户自定义角色
+  useEffect(() => {
+    invoke<PersonalityInfo[]>("list_personalities")
+      .then((list) => setCustomPersonalities(list.filter((p) => !p.builtin)))
+      .catch((error) => console.error("加载角色列表失败:", error));
+  }, []);
 
   // 加载保存的配置
   useEffect(() => {
@@ -342,6 +359,11 @@ export const SimulationPanel: React.FC = () => {
                         {opt.label}
                       </Select.Option>
                     ))}
+                    {customPersonalities.map((p) => (
+                      <Select.Option key={p.id} value={p.id}>
+                        {p.description}
+                      </Select.Option>
+                    ))}
                   </Select>
                 </div>
 
@@ -27,13 +27,19 @@ import {
   CheckCircle,
   Calendar,
   HardDrive,
+  Upload,
 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { motion } from "framer-motion";
 import { getGameById } from "../../services/configService";
 import { getSkillLibraryConfig } from "../../services/settingsService";
-import type { DownloadedSkillLibrary } from "../../types/skillLibrary";
+import type {
+  CrawlVersion,
+  DownloadedSkillLibrary,
+  TotalKnowledgeBaseSize,
+  VersionContentDiff,
+} from "../../types/skillLibrary";
 import type { Game } from "../../types/game";
 import "./styles.scss";
 
@@ -53,9 +59,19 @@ const SkillDatabase: React.FC = () => {
   const [validating, setValidating] = useState(false);
   const [loading, setLoading] = useState(true);
   const [gameCache, setGameCache] = useState<Map<string, Game>>(new Map());
+  // 版本历史: gameId -> (timestamp -> 版本记录)
+  const [crawlHistory, setCrawlHistory] = useState<
+    Map<string, Map<number, CrawlVersion>>
+  >(new Map());
   const [downloadedLibraries, setDownloadedLibraries] = useState<
     DownloadedSkillLibrary[]
   >([]);
+  const [knowledgeBaseSize, setKnowledgeBaseSize] =
+    useState<TotalKnowledgeBaseSize | null>(null);
+  // 版本内容变化弹窗
+  const [contentDiff, setContentDiff] = useState<VersionContentDiff | null>(
+    null,
+  );
 
   const basicCardStyle: CSSProperties = {
     height: "100%",
@@ -115,8 +131,33 @@ const SkillDatabase: React.FC = () => {
       setGameCache(cache);
     };
 
+    // 加载每个游戏的版本历史 (用于展示版本变化)
+    const loadCrawlHistory = async () => {
+      const history = new Map<string, Map<number, CrawlVersion>>();
+      const uniqueGameIds = Array.from(
+        new Set(downloadedLibraries.map((lib) => lib.gameId)),
+      );
+
+      for (const gameId of uniqueGameIds) {
+        try {
+          const versions = await invoke<CrawlVersion[]>("get_crawl_history", {
+            gameId,
+          });
+          history.set(
+            gameId,
+            new Map(versions.map((version) => [version.timestamp, version])),
+          );
+        } catch (error) {
+          console.error(`加载 ${gameId} 版本历史失败:`, error);
+        }
+      }
+
+      setCrawlHistory(history);
+    };
+
     if (downloadedLibraries.length > 0) {
       loadGameCache();
+      loadCrawlHistory();
     }
   }, [downloadedLibraries]);
 
@@ -165,6 +206,15 @@ const SkillDatabase: React.FC = () => {
     }
   };
 
+  // 打开配置弹窗时统计知识库占用空间
+  useEffect(() => {
+    if (!configModalVisible) return;
+
+    invoke<TotalKnowledgeBaseSize>("total_knowledge_base_size")
+      .then(setKnowledgeBaseSize)
+      .catch((error) => console.error("获取知识库占用空间失败:", error));
+  }, [configModalVisible]);
+
   // 格式化文件大小
   const formatSize = (bytes: number): string => {
     if (bytes === 0) return "0 B";
@@ -290,9 +340,79 @@ const SkillDatabase: React.FC = () => {
 
   // 处理切换活跃版本
   const handleSetActive = (library: DownloadedSkillLibrary) => {
-    // 注意: 活跃版本现在由后端管理,不再使用前端 store
-    // TODO: 可能需要调用后端 API 来设置活跃版本
-    message.success(`已切换到版本: ${formatTimestamp(library.timestamp)}`);
+    Modal.confirm({
+      title: "切换活跃版本",
+      content: `确定要将 ${library.gameName} 回滚到 ${formatTimestamp(library.timestamp)} 的版本吗？之后导入知识库时将使用该版本。`,
+      okText: "确认切换",
+      cancelText: "取消",
+      onOk: async () => {
+        try {
+          await invoke("rollback_skill_library", {
+            gameId: library.gameId,
+            timestamp: library.timestamp,
+          });
+
+          // 重新扫描以刷新活跃状态
+          const libraries = await invoke<any[]>("scan_downloaded_libraries");
+          setDownloadedLibraries(libraries);
+
+          message.success(
+            `已切换到版本: ${formatTimestamp(library.timestamp)}`,
+          );
+        } catch (error) {
+          console.error("切换版本失败:", error);
+          message.error(`切换版本失败: ${error}`);
+        }
+      },
+    });
+  };
+
+  // 导入外部 JSONL (其他用户分享的知识库) 作为新版本
+  const handleImportExternal = async (gameId: string) => {
+    try {
+      const selectedPath = await open({
+        multiple: false,
+        title: "选择 Wiki JSONL 文件",
+        filters: [{ name: "JSONL", extensions: ["jsonl"] }],
+      });
+      if (!selectedPath || typeof selectedPath !== "string") return;
+
+      message.loading({ content: "正在校验并导入...", key: "import-external", duration: 0 });
+      const result = await invoke<{ totalEntries: number; message: string }>(
+        "import_external_jsonl",
+        { gameId, path: selectedPath },
+      );
+
+      const libraries = await invoke<any[]>("scan_downloaded_libraries");
+      setDownloadedLibraries(libraries);
+
+      message.success({
+        content: `已导入 ${result.totalEntries} 个条目`,
+        key: "import-external",
+      });
+    } catch (error) {
+      console.error("导入外部知识库失败:", error);
+      message.error({ content: `${error}`, key: "import-external" });
+    }
+  };
+
+  // 查看相对上一版本的内容变化
+  const handleShowContentDiff = async (
+    gameId: string,
+    previousTimestamp: number,
+    timestamp: number,
+  ) => {
+    try {
+      const diff = await invoke<VersionContentDiff>("diff_crawl_versions", {
+        gameId,
+        tsA: previousTimestamp,
+        tsB: timestamp,
+      });
+      setContentDiff(diff);
+    } catch (error) {
+      console.error("比较版本失败:", error);
+      message.error(`比较版本失败: ${error}`);
+    }
   };
 
   // 打开存储目录
@@ -485,6 +605,17 @@ const SkillDatabase: React.FC = () => {
                       <Tag color="blue">{libraries.length} 个版本</Tag>
                     </Space>
                   }
+                  extra={
+                    <Tooltip title="导入其他来源的 Wiki JSONL 作为新版本">
+                      <Button
+                        size="small"
+                        icon={<Upload size={16} />}
+                        onClick={() => handleImportExternal(gameId)}
+                      >
+                        导入 JSONL
+                      </Button>
+                    </Tooltip>
+                  }
                 >
                   <Space
                     direction="vertical"
@@ -561,6 +692,46 @@ const SkillDatabase: React.FC = () => {
                                     </Space>
                                   </Tooltip>
 
+                                  {(() => {
+                                    const version = crawlHistory
+                                      .get(library.gameId)
+                                      ?.get(library.timestamp);
+                                    const diff = version?.diff;
+                                    if (!diff) return null;
+                                    const previousTimestamp =
+                                      version?.previousTimestamp;
+                                    return (
+                                      <Tooltip
+                                        title={
+                                          previousTimestamp
+                                            ? "相对上一版本的变化 (点击查看详情)"
+                                            : "相对上一版本的变化"
+                                        }
+                                      >
+                                        <Space
+                                          size={4}
+                                          style={{
+                                            cursor: previousTimestamp
+                                              ? "pointer"
+                                              : undefined,
+                                          }}
+                                          onClick={() =>
+                                            previousTimestamp &&
+                                            handleShowContentDiff(
+                                              library.gameId,
+                                              previousTimestamp,
+                                              library.timestamp,
+                                            )
+                                          }
+                                        >
+                                          <Tag color="green">+{diff.added}</Tag>
+                                          <Tag color="blue">~{diff.updated}</Tag>
+                                          <Tag color="red">-{diff.removed}</Tag>
+                                        </Space>
+                                      </Tooltip>
+                                    );
+                                  })()}
+
                                   <Tooltip title="存储路径">
                                     <Space size={4}>
                                       <FolderOpen size={14} />
@@ -734,11 +905,106 @@ const SkillDatabase: React.FC = () => {
             )}
             format={() => `${formatSize(totalSize)} / 5 GB`}
           />
+          {knowledgeBaseSize && (
+            <Text type="secondary" style={{ fontSize: 12 }}>
+              知识库 ({knowledgeBaseSize.mode}):{" "}
+              {knowledgeBaseSize.estimated ? "约 " : ""}
+              {formatSize(knowledgeBaseSize.totalBytes)}, 共{" "}
+              {knowledgeBaseSize.games.length} 个游戏
+            </Text>
+          )}
           <Text type="secondary" style={{ fontSize: 12 }}>
             建议定期清理不需要的历史版本以节省空间
           </Text>
         </Space>
       </Modal>
+
+      {/* 版本内容变化弹窗 */}
+      <Modal
+        title={
+          contentDiff &&
+          `${formatTimestamp(contentDiff.fromTimestamp)} → ${formatTimestamp(contentDiff.toTimestamp)}`
+        }
+        open={contentDiff !== null}
+        onCancel={() => setContentDiff(null)}
+        footer={null}
+        width={720}
+      >
+        {contentDiff && (
+          <Space direction="vertical" style={{ width: "100%" }}>
+            <Space>
+              <Tag color="green">新增 {contentDiff.added.length}</Tag>
+              <Tag color="blue">更新 {contentDiff.updated.length}</Tag>
+              <Tag color="red">删除 {contentDiff.removed.length}</Tag>
+              <Text type="secondary">未变化 {contentDiff.unchanged}</Text>
+            </Space>
+
+            <div style={{ maxHeight: 480, overflowY: "auto" }}>
+              {contentDiff.added.length > 0 && (
+                <>
+                  <Divider orientation="left">新增条目</Divider>
+                  {contentDiff.added.map((entry) => (
+                    <Paragraph key={entry.url || entry.title}>
+                      <Text type="success">+ {entry.title}</Text>
+                    </Paragraph>
+                  ))}
+                </>
+              )}
+
+              {contentDiff.updated.length > 0 && (
+                <>
+                  <Divider orientation="left">更新条目</Divider>
+                  {contentDiff.updated.map((entry) => (
+                    <div key={entry.url || entry.title}>
+                      <Text strong>
+                        {entry.previousTitle
+                          ? `${entry.previousTitle} → ${entry.title}`
+                          : entry.title}
+                      </Text>
+                      {entry.removedLines.map((line, i) => (
+                        <Paragraph
+                          key={`-${i}`}
+                          type="danger"
+                          style={{ margin: 0, fontSize: 12 }}
+                          ellipsis={{ rows: 2, tooltip: line }}
+                        >
+                          - {line}
+                        </Paragraph>
+                      ))}
+                      {entry.addedLines.map((line, i) => (
+                        <Paragraph
+                          key={`+${i}`}
+                          type="success"
+                          style={{ margin: 0, fontSize: 12 }}
+                          ellipsis={{ rows: 2, tooltip: line }}
+                        >
+                          + {line}
+                        </Paragraph>
+                      ))}
+                      {entry.truncated && (
+                        <Text type="secondary" style={{ fontSize: 12 }}>
+                          ……变化过多, 仅显示部分
+                        </Text>
+                      )}
+                    </div>
+                  ))}
+                </>
+              )}
+
+              {contentDiff.removed.length > 0 && (
+                <>
+                  <Divider orientation="left">删除条目</Divider>
+                  {contentDiff.removed.map((entry) => (
+                    <Paragraph key={entry.url || entry.title}>
+                      <Text type="danger">- {entry.title}</Text>
+                    </Paragraph>
+                  ))}
+                </>
+              )}
+            </div>
+          </Space>
+        )}
+      </Modal>
     </div>
   );
 };
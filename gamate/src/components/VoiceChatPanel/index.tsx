@@ -20,6 +20,7 @@ interface VadConfig {
 interface ListenerState {
   vad_state: "Idle" | "Speaking" | "Processing";
   is_listening: boolean;
+  paused?: boolean;
   recording_duration: number;
   buffer_size: number;
   last_transcription: string | null;
@@ -408,6 +409,7 @@ export const VoiceChatPanel: React.FC = () => {
   const getStateText = () => {
     if (!listenerState) return "未初始化";
     if (!listenerState.is_listening) return "等待你说话";
+    if (listenerState.paused) return "监听已暂停";
 
     switch (listenerState.vad_state) {
       case "Idle":
@@ -32,9 +32,10 @@ interface WikiSearchResult {
 }
 
 interface VectorDBStats {
-  exists: boolean;
-  vectorsCount: number;
-  pointsCount: number;
+  status: "notImported" | "ready" | "unavailable";
+  exists: boolean | null;
+  vectorsCount: number | null;
+  pointsCount: number | null;
   gameId: string;
 }
 
@@ -158,8 +159,19 @@ const WikiSearch: React.FC<WikiSearchProps> = ({ gameId }) => {
                 ></DatabaseOutlined>
                 数据库状态:
               </Text>
-              <Tag color="green">已加载</Tag>
-              <Text type="secondary">共 {stats.pointsCount} 条数据</Text>
+              {stats.status === "ready" && (
+                <>
+                  <Tag color="green">已加载</Tag>
+                  <Text type="secondary">共 {stats.pointsCount} 条数据</Text>
+                </>
+              )}
+              {stats.status === "notImported" && <Tag>未导入</Tag>}
+              {stats.status === "unavailable" && (
+                <>
+                  <Tag color="orange">统计不可用</Tag>
+                  <Text type="secondary">无法连接向量数据库或读取数据</Text>
+                </>
+              )}
             </Space>
           </Card>
         )
@@ -3,7 +3,7 @@
  * 从后端加载配置，替代硬编码数据
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
 import { Game, GameSkillConfig, SkillSource, SkillStatus } from '../types/game';
 
 interface GameConfigResponse {
@@ -29,10 +29,34 @@ interface GameConfigResponse {
       max_pages?: number;
       max_depth?: number;
       request_delay_ms?: number;
+      user_agent?: string;
+      headers?: Record<string, string>;
+      cookies?: Record<string, string>;
     }>;
   }>;
 }
 
+/**
+ * 本地缓存的图片路径转换为可在 WebView 中加载的地址, 远程地址保持不变
+ */
+function resolveImageSrc(src: string): string {
+  if (!src || /^(https?:|data:|asset:)/.test(src) || !/[\\/]/.test(src)) {
+    return src;
+  }
+  return convertFileSrc(src);
+}
+
+/**
+ * 下载游戏图片到本地缓存并更新 games.toml, 返回本地路径
+ */
+export async function cacheGameImage(
+  gameId: string,
+  url: string,
+  kind: 'icon' | 'banner' = 'banner'
+): Promise<string> {
+  return invoke<string>('cache_game_image', { gameId, url, kind });
+}
+
 /**
  * 从后端加载游戏配置
  */
@@ -47,8 +71,8 @@ export async function loadGamesConfig(): Promise<{
       id: g.id,
       name: g.name,
       nameEn: g.name_en,
-      icon: g.icon,
-      banner: g.banner,
+      icon: resolveImageSrc(g.icon),
+      banner: g.banner ? resolveImageSrc(g.banner) : undefined,
       description: g.description,
       category: g.category as any,
       tags: g.tags,
@@ -68,6 +92,9 @@ export async function loadGamesConfig(): Promise<{
           description: skill.description,
           version: skill.version,
           source: skill.source_type as SkillSource,
+          userAgent: skill.user_agent,
+          headers: skill.headers,
+          cookies: skill.cookies,
           status: SkillStatus.NotDownloaded,
           statistics: {
             totalEntries: 0,
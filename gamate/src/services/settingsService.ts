@@ -12,6 +12,11 @@ export interface AppSettings {
     language: string;
     theme: string;
     hud_mode?: boolean;
+    input_device?: string | null;
+    stt_backend?: string;
+    whisper_model_path?: string | null;
+    save_debug_audio?: boolean;
+    debug_audio_dir?: string | null;
   };
   skillLibrary: {
     storageBasePath: string;
@@ -44,10 +49,32 @@ export interface AppSettings {
       maxTokens: number;
     };
     aiPersonality: string;
+    globalSystemPreamble?: string | null;
+    preambleInSimulation?: boolean;
+    contextTokens?: number;
+    ollamaKeepAlive?: {
+      enabled: boolean;
+      keepAlive: string;
+      pingIntervalMinutes: number;
+    };
     vectorDb: {
       mode: string;
       qdrantUrl: string | null;
       localStoragePath: string | null;
+      retryMax?: number;
+      retryBaseMs?: number;
+      importBatchSize?: number | null;
+      chunkSize?: number;
+      chunkOverlap?: number;
+      hybridVectorMode?: string;
+      hybridWeight?: number;
+    };
+    rag?: {
+      topK: number;
+      contextTokenBudget: number;
+      minScore: number;
+      rerank: boolean;
+      rerankTopM: number;
     };
   };
   screenshot?: {
@@ -57,6 +84,9 @@ export interface AppSettings {
     targetWindowName: string | null;
     activeIntervalSeconds: number;
     idleIntervalSeconds: number;
+    idleThresholdSeconds: number;
+    speechIdleSeconds: number;
+    minScreenDiff: number;
     quality: number;
     targetSizeKb: number;
     autoSendToAi: boolean;
@@ -71,6 +101,13 @@ export interface AppSettings {
     rate: number;
     volume: number;
     autoSpeak: boolean;
+    outputDevice: string | null;
+    echoSuppression?: boolean;
+  };
+  apiServer?: {
+    enabled: boolean;
+    port: number;
+    token: string;
   };
 }
 
@@ -80,6 +117,11 @@ interface BackendSettings {
     language: string;
     theme: string;
     hud_mode?: boolean;
+    input_device?: string | null;
+    stt_backend?: string;
+    whisper_model_path?: string | null;
+    save_debug_audio?: boolean;
+    debug_audio_dir?: string | null;
   };
   skill_library: {
     storage_base_path: string;
@@ -112,10 +154,32 @@ interface BackendSettings {
       max_tokens: number;
     };
     ai_personality: string;
+    global_system_preamble?: string | null;
+    preamble_in_simulation?: boolean;
+    context_tokens?: number;
+    ollama_keep_alive?: {
+      enabled: boolean;
+      keep_alive: string;
+      ping_interval_minutes: number;
+    };
     vector_db: {
       mode: string;
       qdrant_url: string | null;
       local_storage_path: string | null;
+      retry_max?: number;
+      retry_base_ms?: number;
+      import_batch_size?: number | null;
+      chunk_size?: number;
+      chunk_overlap?: number;
+      hybrid_vector_mode?: string;
+      hybrid_weight?: number;
+    };
+    rag?: {
+      top_k: number;
+      context_token_budget: number;
+      min_score: number;
+      rerank: boolean;
+      rerank_top_m: number;
     };
   };
   screenshot?: {
@@ -125,6 +189,9 @@ interface BackendSettings {
     target_window_name: string | null;
     active_interval_seconds: number;
     idle_interval_seconds: number;
+    idle_threshold_seconds: number;
+    speech_idle_seconds: number;
+    min_screen_diff: number;
     quality: number;
     target_size_kb: number;
     auto_send_to_ai: boolean;
@@ -139,6 +206,13 @@ interface BackendSettings {
     rate: number;
     volume: number;
     auto_speak: boolean;
+    output_device: string | null;
+    echo_suppression?: boolean;
+  };
+  api_server?: {
+    enabled: boolean;
+    port: number;
+    token: string;
   };
 }
 
@@ -181,10 +255,32 @@ export function transformBackendToFrontend(data: BackendSettings): AppSettings {
         maxTokens: data.ai_models?.multimodal?.max_tokens || 1000,
       },
       aiPersonality: data.ai_models?.ai_personality || "sunnyou_male",
+      globalSystemPreamble: data.ai_models?.global_system_preamble || null,
+      preambleInSimulation: data.ai_models?.preamble_in_simulation || false,
+      contextTokens: data.ai_models?.context_tokens ?? 8192,
+      ollamaKeepAlive: {
+        enabled: data.ai_models?.ollama_keep_alive?.enabled || false,
+        keepAlive: data.ai_models?.ollama_keep_alive?.keep_alive || "10m",
+        pingIntervalMinutes: data.ai_models?.ollama_keep_alive?.ping_interval_minutes || 4,
+      },
       vectorDb: {
         mode: data.ai_models?.vector_db?.mode || "local",
         qdrantUrl: data.ai_models?.vector_db?.qdrant_url || "http://localhost:6333",
         localStoragePath: data.ai_models?.vector_db?.local_storage_path || "./data/vector_db",
+        retryMax: data.ai_models?.vector_db?.retry_max ?? 3,
+        retryBaseMs: data.ai_models?.vector_db?.retry_base_ms ?? 500,
+        importBatchSize: data.ai_models?.vector_db?.import_batch_size ?? 50,
+        chunkSize: data.ai_models?.vector_db?.chunk_size ?? 1000,
+        chunkOverlap: data.ai_models?.vector_db?.chunk_overlap ?? 150,
+        hybridVectorMode: data.ai_models?.vector_db?.hybrid_vector_mode || "local",
+        hybridWeight: data.ai_models?.vector_db?.hybrid_weight ?? 0.5,
+      },
+      rag: {
+        topK: data.ai_models?.rag?.top_k ?? 3,
+        contextTokenBudget: data.ai_models?.rag?.context_token_budget ?? 1500,
+        minScore: data.ai_models?.rag?.min_score ?? 0.2,
+        rerank: data.ai_models?.rag?.rerank || false,
+        rerankTopM: data.ai_models?.rag?.rerank_top_m ?? 3,
       },
     },
     screenshot: data.screenshot
@@ -195,6 +291,9 @@ export function transformBackendToFrontend(data: BackendSettings): AppSettings {
           targetWindowName: data.screenshot.target_window_name || null,
           activeIntervalSeconds: data.screenshot.active_interval_seconds,
           idleIntervalSeconds: data.screenshot.idle_interval_seconds,
+          idleThresholdSeconds: data.screenshot.idle_threshold_seconds ?? 60,
+          speechIdleSeconds: data.screenshot.speech_idle_seconds ?? 30,
+          minScreenDiff: data.screenshot.min_screen_diff ?? 0.03,
           quality: data.screenshot.quality,
           targetSizeKb: data.screenshot.target_size_kb,
           autoSendToAi: data.screenshot.auto_send_to_ai,
@@ -211,6 +310,15 @@ export function transformBackendToFrontend(data: BackendSettings): AppSettings {
           rate: data.tts.rate || 1.0,
           volume: data.tts.volume || 0.8,
           autoSpeak: data.tts.auto_speak !== false,
+          outputDevice: data.tts.output_device || null,
+          echoSuppression: data.tts.echo_suppression !== false,
+        }
+      : undefined,
+    apiServer: data.api_server
+      ? {
+          enabled: data.api_server.enabled,
+          port: data.api_server.port || 17891,
+          token: data.api_server.token || "",
         }
       : undefined,
   };
@@ -253,10 +361,32 @@ export function transformFrontendToBackend(values: AppSettings): BackendSettings
         max_tokens: values.aiModels.multimodal.maxTokens || 1000,
       },
       ai_personality: values.aiModels.aiPersonality || "sunnyou_male",
+      global_system_preamble: values.aiModels.globalSystemPreamble?.trim() || null,
+      preamble_in_simulation: values.aiModels.preambleInSimulation || false,
+      context_tokens: values.aiModels.contextTokens || 8192,
+      ollama_keep_alive: {
+        enabled: values.aiModels.ollamaKeepAlive?.enabled || false,
+        keep_alive: values.aiModels.ollamaKeepAlive?.keepAlive?.trim() || "10m",
+        ping_interval_minutes: values.aiModels.ollamaKeepAlive?.pingIntervalMinutes || 4,
+      },
       vector_db: {
         mode: values.aiModels.vectorDb.mode,
         qdrant_url: values.aiModels.vectorDb.qdrantUrl || null,
         local_storage_path: values.aiModels.vectorDb.localStoragePath || null,
+        retry_max: values.aiModels.vectorDb.retryMax ?? 3,
+        retry_base_ms: values.aiModels.vectorDb.retryBaseMs ?? 500,
+        import_batch_size: values.aiModels.vectorDb.importBatchSize || null,
+        chunk_size: values.aiModels.vectorDb.chunkSize ?? 1000,
+        chunk_overlap: values.aiModels.vectorDb.chunkOverlap ?? 150,
+        hybrid_vector_mode: values.aiModels.vectorDb.hybridVectorMode || "local",
+        hybrid_weight: values.aiModels.vectorDb.hybridWeight ?? 0.5,
+      },
+      rag: {
+        top_k: values.aiModels.rag?.topK ?? 3,
+        context_token_budget: values.aiModels.rag?.contextTokenBudget ?? 1500,
+        min_score: values.aiModels.rag?.minScore ?? 0.2,
+        rerank: values.aiModels.rag?.rerank || false,
+        rerank_top_m: values.aiModels.rag?.rerankTopM ?? 3,
       },
     },
     screenshot: values.screenshot
@@ -267,6 +397,9 @@ export function transformFrontendToBackend(values: AppSettings): BackendSettings
           target_window_name: values.screenshot.targetWindowName || null,
           active_interval_seconds: values.screenshot.activeIntervalSeconds,
           idle_interval_seconds: values.screenshot.idleIntervalSeconds,
+          idle_threshold_seconds: values.screenshot.idleThresholdSeconds,
+          speech_idle_seconds: values.screenshot.speechIdleSeconds,
+          min_screen_diff: values.screenshot.minScreenDiff,
           quality: values.screenshot.quality,
           target_size_kb: values.screenshot.targetSizeKb,
           auto_send_to_ai: values.screenshot.autoSendToAi,
@@ -283,6 +416,15 @@ export function transformFrontendToBackend(values: AppSettings): BackendSettings
           rate: values.tts.rate || 1.0,
           volume: values.tts.volume || 0.8,
           auto_speak: values.tts.autoSpeak !== false,
+          output_device: values.tts.outputDevice || null,
+          echo_suppression: values.tts.echoSuppression !== false,
+        }
+      : undefined,
+    api_server: values.apiServer
+      ? {
+          enabled: values.apiServer.enabled,
+          port: values.apiServer.port || 17891,
+          token: values.apiServer.token || "",
         }
       : undefined,
   };
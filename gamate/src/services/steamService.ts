@@ -3,7 +3,13 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { SteamUser, OwnedGame } from '../types/steam';
+import type {
+  SteamUser,
+  OwnedGame,
+  SteamAppInfo,
+  SteamAppListStatus,
+  GameSetupSuggestion,
+} from '../types/steam';
 
 /**
  * Steam 服务类
@@ -91,6 +97,58 @@ export class SteamService {
     return await invoke<string>('verify_steam_login', { callbackUrl });
   }
 
+  /**
+   * 根据最常玩的游戏获取知识库配置建议
+   * @param limit 最多返回数量 (默认 10)
+   * @param steamid Steam ID, 为空时使用当前登录用户
+   */
+  static async suggestGamesForSetup(limit?: number, steamid?: string): Promise<GameSetupSuggestion[]> {
+    return await invoke<GameSetupSuggestion[]>('suggest_games_for_setup', { steamid, limit });
+  }
+
+  /**
+   * 将已获取的 Steam 游戏库匹配到游戏配置, 返回尚未添加到已选游戏的游戏 ID
+   */
+  static async matchLibraryGames(): Promise<string[]> {
+    return await invoke<string[]>('match_steam_library_games');
+  }
+
+  /**
+   * 把游戏添加到已选游戏
+   * @param gameIds 游戏配置 ID
+   * @returns 更新后的已选游戏
+   */
+  static async addSelectedGames(gameIds: string[]): Promise<string[]> {
+    return await invoke<string[]>('add_selected_games', { gameIds });
+  }
+
+  /**
+   * 按名称搜索 Steam 应用 (首次搜索会下载 Steam 应用列表)
+   * @param query 游戏名称
+   * @param limit 最多返回数量 (默认 20)
+   */
+  static async searchGames(query: string, limit?: number): Promise<SteamAppInfo[]> {
+    return await invoke<SteamAppInfo[]>('search_steam_games', { query, limit });
+  }
+
+  /**
+   * 刷新 Steam 应用列表缓存
+   * @param force 为 true 时忽略缓存有效期强制重新下载
+   */
+  static async refreshAppList(force: boolean = false): Promise<SteamAppListStatus> {
+    return await invoke<SteamAppListStatus>('refresh_steam_app_list', { force });
+  }
+
+  /**
+   * 把选中的 Steam 应用添加到游戏配置 (games.toml)
+   * @param apps 搜索结果中选中的应用
+   */
+  static async addGamesToConfig(apps: SteamAppInfo[]): Promise<void> {
+    await invoke('save_steam_games_to_config', {
+      steamGames: apps.map((app) => ({ appid: app.appid, name: app.name })),
+    });
+  }
+
   /**
    * Steam 登录 - 在当前窗口跳转
    * @returns Promise 在回调页面处理后 resolve
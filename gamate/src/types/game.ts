@@ -58,6 +58,9 @@ export interface GameSkillConfig {
   description: string;
   version: string;
   source: SkillSource;
+  userAgent?: string; // 自定义 User-Agent
+  headers?: Record<string, string>; // 自定义请求头
+  cookies?: Record<string, string>; // 预置 Cookie
   status: SkillStatus;
   statistics: SkillStatistics;
   createdAt: string;
@@ -10,7 +10,8 @@ export type AIPersonality =
   | 'funny_female'   // 搞笑女
   | 'kobe'           // Kobe
   | 'sweet_girl'     // 甜妹
-  | 'trump';         // 特朗普
+  | 'trump'          // 特朗普
+  | (string & {});   // 自定义角色 ID
 
 /** 直播间配置 */
 export interface LivestreamConfig {
@@ -29,3 +29,81 @@ export interface DownloadedSkillLibrary {
   };
   status: 'active' | 'outdated' | 'error';
 }
+
+/**
+ * 技能库版本相对上一版本的变化
+ */
+export interface CrawlDiff {
+  added: number;
+  updated: number;
+  removed: number;
+}
+
+/**
+ * 技能库版本历史记录
+ */
+export interface CrawlVersion {
+  timestamp: number; // Unix 时间戳（秒）
+  storagePath: string;
+  sourceType?: string;
+  sourceUrl?: string;
+  totalEntries: number;
+  totalBytes: number;
+  diff?: CrawlDiff; // 旧版本没有记录时为空
+  previousTimestamp?: number;
+  crawlerVersion?: string;
+  configSnapshot?: Record<string, unknown>;
+}
+
+/**
+ * 条目摘要
+ */
+export interface EntrySummary {
+  title: string;
+  url: string;
+}
+
+/**
+ * 内容有变化的条目
+ */
+export interface UpdatedEntry {
+  title: string;
+  url: string;
+  previousTitle?: string; // 标题有变化时为旧标题
+  addedLines: string[];
+  removedLines: string[];
+  truncated: boolean; // 变化行数超过上限被截断
+}
+
+/**
+ * 两个版本之间的内容变化
+ */
+export interface VersionContentDiff {
+  fromTimestamp: number;
+  toTimestamp: number;
+  added: EntrySummary[];
+  removed: EntrySummary[];
+  updated: UpdatedEntry[];
+  unchanged: number;
+}
+
+/**
+ * 单个游戏知识库占用空间
+ */
+export interface KnowledgeBaseSize {
+  gameId: string;
+  mode: string; // local / qdrant / ai_direct
+  bytes: number;
+  estimated: boolean; // Qdrant 按抽样估算
+  path?: string;
+}
+
+/**
+ * 所有已导入游戏的知识库占用空间
+ */
+export interface TotalKnowledgeBaseSize {
+  mode: string;
+  totalBytes: number;
+  estimated: boolean;
+  games: KnowledgeBaseSize[];
+}
@@ -60,6 +60,46 @@ export interface OwnedGame {
   has_community_visible_stats?: boolean;
 }
 
+/**
+ * 游戏配置建议 (玩家常玩且已支持的游戏)
+ */
+export interface GameSetupSuggestion {
+  /** Steam 应用 ID */
+  appid: number;
+  /** Steam 上的游戏名称 */
+  name: string;
+  /** 匹配到的游戏配置 ID */
+  gameId: string;
+  /** 游戏配置中的名称 */
+  gameName: string;
+  /** 总游戏时长（分钟） */
+  playtimeForever: number;
+  /** 最近两周游戏时长（分钟） */
+  playtime2weeks?: number | null;
+  /** 可下载的技能库数量 */
+  skillConfigCount: number;
+}
+
+/**
+ * Steam 应用 (搜索结果)
+ */
+export interface SteamAppInfo {
+  /** 应用 ID */
+  appid: number;
+  /** 应用名称 */
+  name: string;
+}
+
+/**
+ * Steam 应用列表缓存状态
+ */
+export interface SteamAppListStatus {
+  /** 应用数量 */
+  total: number;
+  /** 列表下载时间 (Unix 秒) */
+  updatedAt: number;
+}
+
 /**
  * Steam 游戏库响应
  */